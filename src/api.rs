@@ -0,0 +1,375 @@
+//! Library facade.
+//!
+//! [`Rlm`] bundles a storage backend and an embedder behind a small API for
+//! programs that use rlm-rs as a library rather than through the CLI.
+
+use crate::chunking::{
+    ChunkerMetadata, DEFAULT_MIN_CHUNK_SIZE, create_chunker, default_size_and_overlap,
+};
+use crate::core::{Buffer, Chunk};
+use crate::embedding::{Embedder, create_embedder, default_embed_workers};
+use crate::error::{Result, StorageError};
+use crate::search::{SearchConfig, SearchResult, embed_buffer_chunks_with_workers, hybrid_search};
+use crate::storage::{SqliteStorage, Storage};
+use std::path::Path;
+
+/// Options for loading content into a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Chunking strategy name (see [`crate::available_strategies`]).
+    pub chunker: String,
+    /// Target chunk size, in tokens for the `token` chunker and characters
+    /// otherwise. `None` uses the chunker's default (see
+    /// [`default_size_and_overlap`]).
+    pub chunk_size: Option<usize>,
+    /// Overlap between consecutive chunks, in the same unit as
+    /// `chunk_size`. `None` uses the chunker's default.
+    pub overlap: Option<usize>,
+    /// Chunks smaller than this are merged into their predecessor (0
+    /// disables merging).
+    pub min_chunk_size: usize,
+    /// Whether to generate embeddings for the new chunks.
+    pub embed: bool,
+    /// Maximum number of concurrent embedding batches.
+    pub embed_workers: usize,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            chunker: "semantic".to_string(),
+            chunk_size: None,
+            overlap: None,
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            embed: true,
+            embed_workers: default_embed_workers(),
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Creates options with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the chunking strategy.
+    #[must_use]
+    pub fn with_chunker(mut self, chunker: &str) -> Self {
+        self.chunker = chunker.to_string();
+        self
+    }
+
+    /// Sets the chunk size and overlap.
+    #[must_use]
+    pub const fn with_chunk_size(mut self, chunk_size: usize, overlap: usize) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self.overlap = Some(overlap);
+        self
+    }
+
+    /// Sets the minimum chunk size; 0 disables merging small chunks.
+    #[must_use]
+    pub const fn with_min_chunk_size(mut self, min_chunk_size: usize) -> Self {
+        self.min_chunk_size = min_chunk_size;
+        self
+    }
+
+    /// Enables or disables embedding generation.
+    #[must_use]
+    pub const fn with_embed(mut self, embed: bool) -> Self {
+        self.embed = embed;
+        self
+    }
+
+    /// Sets the maximum number of concurrent embedding batches.
+    #[must_use]
+    pub const fn with_embed_workers(mut self, workers: usize) -> Self {
+        self.embed_workers = workers;
+        self
+    }
+}
+
+/// Outcome of loading a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadResult {
+    /// ID of the new buffer.
+    pub buffer_id: i64,
+    /// Number of chunks created.
+    pub chunk_count: usize,
+    /// Number of chunks embedded.
+    pub embedded_count: usize,
+}
+
+/// High-level handle for using rlm-rs as a library.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::{FallbackEmbedder, LoadOptions, Rlm, SearchConfig, SqliteStorage};
+///
+/// let storage = SqliteStorage::in_memory().unwrap();
+/// let embedder = Box::new(FallbackEmbedder::new(rlm_rs::DEFAULT_DIMENSIONS));
+/// let mut rlm = Rlm::with_embedder(storage, embedder).unwrap();
+///
+/// let loaded = rlm
+///     .load_buffer_with("notes.txt", "Rust is a systems language.", &LoadOptions::new())
+///     .unwrap();
+/// assert_eq!(loaded.chunk_count, 1);
+///
+/// let results = rlm
+///     .search("systems", &SearchConfig::new().with_semantic(false))
+///     .unwrap();
+/// let chunk = rlm.get_chunk(results[0].chunk_id).unwrap().unwrap();
+/// assert!(chunk.content.contains("Rust"));
+/// ```
+pub struct Rlm {
+    storage: SqliteStorage,
+    embedder: Box<dyn Embedder>,
+}
+
+impl Rlm {
+    /// Opens (and initializes or migrates) the database at `path` with the
+    /// default embedder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or initialized, or
+    /// the embedder cannot be created.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_embedder(SqliteStorage::open(path)?, create_embedder()?)
+    }
+
+    /// Creates an in-memory instance with the default embedder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database or embedder cannot be created.
+    pub fn in_memory() -> Result<Self> {
+        Self::with_embedder(SqliteStorage::in_memory()?, create_embedder()?)
+    }
+
+    /// Wraps an existing storage backend and embedder.
+    ///
+    /// The storage is initialized (or migrated) if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if initialization fails.
+    pub fn with_embedder(mut storage: SqliteStorage, embedder: Box<dyn Embedder>) -> Result<Self> {
+        storage.init()?;
+        Ok(Self { storage, embedder })
+    }
+
+    /// Returns the underlying storage.
+    #[must_use]
+    pub const fn storage(&self) -> &SqliteStorage {
+        &self.storage
+    }
+
+    /// Returns the underlying storage mutably.
+    pub const fn storage_mut(&mut self) -> &mut SqliteStorage {
+        &mut self.storage
+    }
+
+    /// Loads `content` into a new buffer named `name` with default options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if chunking, storage or embedding fails.
+    pub fn load_buffer(&mut self, name: &str, content: &str) -> Result<LoadResult> {
+        self.load_buffer_with(name, content, &LoadOptions::default())
+    }
+
+    /// Loads `content` into a new buffer named `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunker is unknown or chunking, storage or
+    /// embedding fails.
+    pub fn load_buffer_with(
+        &mut self,
+        name: &str,
+        content: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadResult> {
+        let buffer = Buffer::from_named(name.to_string(), content.to_string());
+        let embedder = options.embed.then_some(self.embedder.as_ref());
+        ingest_buffer(&mut self.storage, embedder, buffer, options)
+    }
+
+    /// Searches stored chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails.
+    pub fn search(&self, query: &str, config: &SearchConfig) -> Result<Vec<SearchResult>> {
+        hybrid_search(&self.storage, self.embedder.as_ref(), query, config)
+    }
+
+    /// Gets a chunk by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    pub fn get_chunk(&self, id: i64) -> Result<Option<Chunk>> {
+        self.storage.get_chunk(id)
+    }
+}
+
+/// Stores `buffer`, chunks it, optionally embeds the chunks, and records
+/// the chunk count and the buffer in the saved context.
+///
+/// Shared by [`Rlm`] and the CLI load commands.
+pub(crate) fn ingest_buffer(
+    storage: &mut SqliteStorage,
+    embedder: Option<&dyn Embedder>,
+    mut buffer: Buffer,
+    options: &LoadOptions,
+) -> Result<LoadResult> {
+    let chunker = create_chunker(&options.chunker)?;
+
+    buffer.compute_hash();
+    let buffer_id = storage.add_buffer(&buffer)?;
+
+    let (default_size, default_overlap) = default_size_and_overlap(&options.chunker);
+    let mut meta = ChunkerMetadata::with_size_and_overlap(
+        options.chunk_size.unwrap_or(default_size),
+        options.overlap.unwrap_or(default_overlap),
+    )
+    .min_chunk_size(options.min_chunk_size);
+    if let Some(source) = buffer.source.as_deref().and_then(Path::to_str) {
+        meta = meta.source(source);
+    }
+    let chunks = chunker.chunk(buffer_id, &buffer.content, Some(&meta))?;
+    store_chunks(storage, embedder, buffer_id, &chunks, options.embed_workers)
+}
+
+/// Stores `buffer` with chunks that were already built, such as chunks
+/// read back from an export, instead of running a chunker.
+///
+/// Each chunk's `buffer_id` is set to the new buffer's ID. Otherwise works
+/// like [`ingest_buffer`].
+pub(crate) fn ingest_chunked_buffer(
+    storage: &mut SqliteStorage,
+    embedder: Option<&dyn Embedder>,
+    mut buffer: Buffer,
+    mut chunks: Vec<Chunk>,
+    embed_workers: usize,
+) -> Result<LoadResult> {
+    buffer.compute_hash();
+    let buffer_id = storage.add_buffer(&buffer)?;
+    for chunk in &mut chunks {
+        chunk.buffer_id = buffer_id;
+    }
+    store_chunks(storage, embedder, buffer_id, &chunks, embed_workers)
+}
+
+/// Stores the chunks of a newly added buffer, optionally embeds them, and
+/// records the chunk count and the buffer in the saved context.
+fn store_chunks(
+    storage: &mut SqliteStorage,
+    embedder: Option<&dyn Embedder>,
+    buffer_id: i64,
+    chunks: &[Chunk],
+    embed_workers: usize,
+) -> Result<LoadResult> {
+    storage.add_chunks(buffer_id, chunks)?;
+
+    let embedded_count = match embedder {
+        Some(embedder) => {
+            embed_buffer_chunks_with_workers(storage, embedder, buffer_id, embed_workers)?
+        }
+        None => 0,
+    };
+
+    let mut updated_buffer =
+        storage
+            .get_buffer(buffer_id)?
+            .ok_or_else(|| StorageError::BufferNotFound {
+                identifier: buffer_id.to_string(),
+            })?;
+    updated_buffer.set_chunk_count(chunks.len());
+    storage.update_buffer(&updated_buffer)?;
+
+    if let Some(mut context) = storage.load_context()? {
+        context.add_buffer(buffer_id);
+        storage.save_context(&context)?;
+    }
+
+    Ok(LoadResult {
+        buffer_id,
+        chunk_count: chunks.len(),
+        embedded_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::{DEFAULT_DIMENSIONS, FallbackEmbedder};
+
+    fn setup() -> Rlm {
+        let storage = SqliteStorage::in_memory().unwrap();
+        Rlm::with_embedder(storage, Box::new(FallbackEmbedder::new(DEFAULT_DIMENSIONS))).unwrap()
+    }
+
+    #[test]
+    fn test_load_buffer_chunks_and_embeds() {
+        let mut rlm = setup();
+        let content = "First paragraph about parsing.\n\nSecond paragraph about storage.";
+        let loaded = rlm.load_buffer("doc.md", content).unwrap();
+
+        assert!(loaded.chunk_count >= 1);
+        assert_eq!(loaded.embedded_count, loaded.chunk_count);
+
+        let buffer = rlm.storage().get_buffer(loaded.buffer_id).unwrap().unwrap();
+        assert_eq!(buffer.name.as_deref(), Some("doc.md"));
+        assert_eq!(buffer.metadata.chunk_count, Some(loaded.chunk_count));
+    }
+
+    #[test]
+    fn test_load_buffer_without_embedding() {
+        let mut rlm = setup();
+        let options = LoadOptions::new()
+            .with_chunker("fixed")
+            .with_chunk_size(20, 0)
+            .with_embed(false);
+        let loaded = rlm
+            .load_buffer_with(
+                "plain.txt",
+                "0123456789012345678901234567890123456789",
+                &options,
+            )
+            .unwrap();
+
+        assert!(loaded.chunk_count >= 2);
+        assert_eq!(loaded.embedded_count, 0);
+        assert_eq!(rlm.storage().embedding_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_load_buffer_unknown_chunker() {
+        let mut rlm = setup();
+        let options = LoadOptions::new().with_chunker("bogus");
+        assert!(rlm.load_buffer_with("x", "content", &options).is_err());
+        assert!(rlm.storage().list_buffers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_and_get_chunk() {
+        let mut rlm = setup();
+        rlm.load_buffer("a.txt", "The quick brown fox").unwrap();
+        rlm.load_buffer("b.txt", "Rust ownership and borrowing")
+            .unwrap();
+
+        let config = SearchConfig::new().with_semantic(false);
+        let results = rlm.search("ownership", &config).unwrap();
+        assert_eq!(results.len(), 1);
+
+        let chunk = rlm.get_chunk(results[0].chunk_id).unwrap().unwrap();
+        assert!(chunk.content.contains("borrowing"));
+        assert!(rlm.get_chunk(9999).unwrap().is_none());
+    }
+}
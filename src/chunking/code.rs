@@ -1,8 +1,14 @@
 //! Code-aware chunking strategy.
 //!
 //! Chunks source code at natural boundaries (functions, classes, methods)
-//! using regex-based pattern matching for multiple languages.
-
+//! for multiple languages. With the `tree-sitter` feature, item extents come
+//! from a syntax tree built by the language's tree-sitter grammar. Without
+//! it, or when the text does not parse cleanly, a small lexer finds them by
+//! tracking brace depth (skipping strings and comments) or, for Python and
+//! Ruby, indentation. Either way items are never split mid-body.
+
+#[cfg(feature = "tree-sitter")]
+use crate::chunking::syntax;
 use crate::chunking::traits::{ChunkMetadata, Chunker};
 use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP};
 use crate::core::Chunk;
@@ -13,8 +19,21 @@ use std::sync::OnceLock;
 
 /// Code-aware chunker that splits at function/class boundaries.
 ///
-/// Supports multiple programming languages and falls back to
-/// line-based chunking for unknown languages.
+/// For supported languages, the text is divided into top-level items
+/// (functions, types, impls, classes) with their doc comments and
+/// attributes. Consecutive items are packed into chunks up to `chunk_size`
+/// without ever splitting one. An oversized container (impl, trait, class,
+/// module) is split at its member boundaries instead; an oversized function
+/// is kept whole. Overlap is not applied to these structural chunks.
+///
+/// Each chunk's custom metadata lists the items it contains, plus the
+/// enclosing item for members of a split container, e.g.
+/// `{"symbols":["parse","validate"],"parent":"Parser"}`.
+///
+/// Items are found with tree-sitter when the `tree-sitter` feature is
+/// enabled, and with a brace/indentation lexer otherwise or when the text has
+/// syntax errors. Unknown languages, and text without recognizable items,
+/// fall back to size-based chunking that prefers boundary lines.
 ///
 /// # Supported Languages
 ///
@@ -246,6 +265,117 @@ impl CodeChunker {
 
         target.min(current_end)
     }
+
+    /// Packs items into pieces of at most `chunk_size` bytes without
+    /// splitting any item.
+    ///
+    /// Oversized containers are split at their members (recursively);
+    /// oversized functions are kept whole and oversized gaps are split by
+    /// size.
+    fn pack_items(
+        &self,
+        text: &str,
+        items: Vec<CodeItem>,
+        parent: Option<&str>,
+        chunk_size: usize,
+        out: &mut Vec<Piece>,
+    ) {
+        let mut pending: Option<Piece> = None;
+
+        for item in items {
+            if item.range.len() > chunk_size {
+                out.extend(pending.take());
+                match item.symbol {
+                    Some(name) if !item.members.is_empty() => {
+                        self.pack_items(text, item.members, Some(name.as_str()), chunk_size, out);
+                    }
+                    Some(name) => out.push(Piece {
+                        range: item.range,
+                        symbols: vec![name],
+                        parent: parent.map(String::from),
+                    }),
+                    None => self.split_gap(text, item.range, parent, chunk_size, out),
+                }
+                continue;
+            }
+
+            match pending.as_mut() {
+                Some(piece) if piece.range.len() + item.range.len() <= chunk_size => {
+                    piece.range.end = item.range.end;
+                    piece.symbols.extend(item.symbol);
+                }
+                _ => {
+                    out.extend(pending.take());
+                    pending = Some(Piece {
+                        range: item.range,
+                        symbols: item.symbol.into_iter().collect(),
+                        parent: parent.map(String::from),
+                    });
+                }
+            }
+        }
+
+        out.extend(pending);
+    }
+
+    /// Splits oversized code between items by size.
+    fn split_gap(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        parent: Option<&str>,
+        chunk_size: usize,
+        out: &mut Vec<Piece>,
+    ) {
+        let offset = range.start;
+        let sub = self.chunk_at_boundaries(0, &text[range], &[], chunk_size, 0);
+        out.extend(sub.into_iter().map(|c| Piece {
+            range: offset + c.byte_range.start..offset + c.byte_range.end,
+            symbols: Vec::new(),
+            parent: parent.map(String::from),
+        }));
+    }
+
+    /// Converts packed pieces into chunks with symbol metadata.
+    fn build_chunks(
+        &self,
+        buffer_id: i64,
+        text: &str,
+        pieces: Vec<Piece>,
+        max_chunks: usize,
+    ) -> Vec<Chunk> {
+        let mut chunks = Vec::with_capacity(pieces.len());
+
+        for piece in pieces {
+            let content = &text[piece.range.clone()];
+            if content.trim().is_empty() {
+                continue;
+            }
+
+            let mut chunk = Chunk::with_strategy(
+                buffer_id,
+                content.to_string(),
+                piece.range,
+                chunks.len(),
+                self.name(),
+            );
+            chunk.set_token_count(chunk.estimate_tokens());
+            if !piece.symbols.is_empty() || piece.parent.is_some() {
+                let mut custom = serde_json::json!({ "symbols": piece.symbols });
+                if let Some(parent) = piece.parent {
+                    custom["parent"] = serde_json::Value::String(parent);
+                }
+                chunk.metadata.custom = Some(custom.to_string());
+            }
+            chunks.push(chunk);
+
+            if max_chunks > 0 && chunks.len() >= max_chunks {
+                break;
+            }
+        }
+
+        chunks
+    }
 }
 
 impl Chunker for CodeChunker {
@@ -273,6 +403,30 @@ impl Chunker for CodeChunker {
         // Detect language
         let lang = Self::detect_language(metadata);
 
+        let max_chunks = metadata.map_or(0, |m| m.max_chunks);
+
+        #[cfg(feature = "tree-sitter")]
+        if let Some(items) = lang
+            .grammar()
+            .and_then(|grammar| syntax::parse_items(text, grammar))
+        {
+            let mut pieces = Vec::new();
+            self.pack_items(text, items, None, chunk_size, &mut pieces);
+            return Ok(self.build_chunks(buffer_id, text, pieces, max_chunks));
+        }
+
+        // Lexer-based fallback
+        if lang != Language::Unknown {
+            let lines = scan_lines(text, lang);
+            let segments = find_segments(text, &lines, 0..lines.len(), lang, 0);
+            if segments.iter().any(|s| s.symbol.is_some()) {
+                let items = segment_items(text, &lines, segments, lang, chunk_size);
+                let mut pieces = Vec::new();
+                self.pack_items(text, items, None, chunk_size, &mut pieces);
+                return Ok(self.build_chunks(buffer_id, text, pieces, max_chunks));
+            }
+        }
+
         // Find code structure boundaries
         let boundaries = self.find_boundaries(text, lang);
 
@@ -323,6 +477,34 @@ impl Language {
         }
     }
 
+    /// Returns the tree-sitter grammar for the language.
+    #[cfg(feature = "tree-sitter")]
+    const fn grammar(self) -> Option<&'static syntax::Grammar> {
+        match self {
+            Self::Rust => Some(&syntax::RUST),
+            Self::Python => Some(&syntax::PYTHON),
+            Self::JavaScript => Some(&syntax::JAVASCRIPT),
+            Self::TypeScript => Some(&syntax::TYPESCRIPT),
+            Self::Go => Some(&syntax::GO),
+            Self::Java => Some(&syntax::JAVA),
+            Self::C => Some(&syntax::C),
+            Self::Cpp => Some(&syntax::CPP),
+            Self::Ruby => Some(&syntax::RUBY),
+            Self::Php => Some(&syntax::PHP),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Returns true if item extents follow indentation rather than braces.
+    const fn uses_indentation(self) -> bool {
+        matches!(self, Self::Python | Self::Ruby)
+    }
+
+    /// Returns true if `#` starts a line comment.
+    const fn hash_comments(self) -> bool {
+        matches!(self, Self::Python | Self::Ruby | Self::Php)
+    }
+
     /// Returns regex patterns for detecting code boundaries.
     fn boundary_patterns(self) -> Vec<BoundaryPattern> {
         match self {
@@ -416,16 +598,17 @@ enum BoundaryPattern {
     GenericFunction,
 }
 
+/// Returns a lazily compiled static regex.
+macro_rules! static_regex {
+    ($name:ident, $pattern:expr) => {{
+        static $name: OnceLock<Regex> = OnceLock::new();
+        $name.get_or_init(|| Regex::new($pattern).expect("valid regex"))
+    }};
+}
+
 impl BoundaryPattern {
     /// Returns the compiled regex for this pattern.
     fn regex(self) -> &'static Regex {
-        macro_rules! static_regex {
-            ($name:ident, $pattern:expr) => {{
-                static $name: OnceLock<Regex> = OnceLock::new();
-                $name.get_or_init(|| Regex::new($pattern).expect("valid regex"))
-            }};
-        }
-
         match self {
             // Rust
             Self::RustFn => static_regex!(
@@ -522,6 +705,491 @@ impl BoundaryPattern {
     }
 }
 
+/// Layout of one source line used for structural chunking.
+#[derive(Debug, Clone, Copy)]
+struct LineInfo {
+    /// Byte offset of the line start.
+    start: usize,
+    /// Byte offset just past the line, including its newline.
+    end: usize,
+    /// Brace depth at the start of the line.
+    depth: usize,
+    /// Highest brace depth reached on the line.
+    max_depth: usize,
+    /// Brace depth at the end of the line.
+    end_depth: usize,
+    /// Width of the leading whitespace.
+    indent: usize,
+    /// Whether the line has no code of its own: empty, whitespace only, or
+    /// starting inside a multi-line string.
+    blank: bool,
+}
+
+/// An item, or the code between items, as a line-aligned byte range.
+///
+/// Both the tree-sitter parser and the lexer describe the text as a
+/// contiguous list of these, so one packer serves both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CodeItem {
+    /// Bytes covered, including attached comments and attributes.
+    pub(crate) range: Range<usize>,
+    /// Item name, or `None` for code between items.
+    pub(crate) symbol: Option<String>,
+    /// Members covering `range`, for containers with named members.
+    pub(crate) members: Vec<Self>,
+}
+
+impl CodeItem {
+    /// Creates an item for the code between items.
+    pub(crate) const fn gap(range: Range<usize>) -> Self {
+        Self {
+            range,
+            symbol: None,
+            members: Vec::new(),
+        }
+    }
+}
+
+/// A run of lines that is either one item or the code between items.
+#[derive(Debug, Clone)]
+struct Segment {
+    /// Line indices covered.
+    lines: Range<usize>,
+    /// Line holding the item's declaration (equal to `lines.start` for gaps).
+    header: usize,
+    /// Item name, or `None` for code between items.
+    symbol: Option<String>,
+}
+
+/// A packed byte range that becomes one chunk.
+#[derive(Debug, Clone)]
+struct Piece {
+    range: Range<usize>,
+    symbols: Vec<String>,
+    parent: Option<String>,
+}
+
+/// Lexer state while scanning for braces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Code,
+    LineComment,
+    BlockComment,
+    Str(u8),
+    /// Python triple-quoted string closed by three of the delimiter.
+    TripleStr(u8),
+    /// Rust raw string closed by a quote and this many `#`s.
+    RawStr(usize),
+}
+
+/// Returns the number of `#`s if a Rust raw string (`r"`, `r#"`, `br#"`)
+/// opens at the `r` at `i`.
+fn raw_string_hashes(bytes: &[u8], i: usize) -> Option<usize> {
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let prefix_start = match i.checked_sub(1).map(|p| bytes[p]) {
+        Some(b'b') => i - 1,
+        _ => i,
+    };
+    if prefix_start > 0 && is_ident(bytes[prefix_start - 1]) {
+        return None;
+    }
+    let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+    (bytes.get(i + 1 + hashes) == Some(&b'"')).then_some(hashes)
+}
+
+/// Returns true if a Rust char literal (as opposed to a lifetime) opens at
+/// the quote at `i`.
+fn rust_char_literal(text: &str, i: usize) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.get(i + 1) == Some(&b'\\') {
+        return true;
+    }
+    text.get(i + 1..)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| bytes.get(i + 1 + c.len_utf8()) == Some(&b'\''))
+}
+
+/// Splits `text` into lines, recording brace depth outside strings and
+/// comments.
+fn scan_lines(text: &str, lang: Language) -> Vec<LineInfo> {
+    let bytes = text.as_bytes();
+    let mut lines = Vec::new();
+    let mut state = ScanState::Code;
+    let mut depth = 0usize;
+    let mut line_start = 0;
+    let mut line_depth = 0;
+    let mut line_max = 0;
+    let mut line_inside = false;
+
+    let mut push_line =
+        |start: usize, end: usize, depth: usize, max: usize, end_depth: usize, inside: bool| {
+            let line = &text[start..end];
+            let trimmed = line.trim_start();
+            lines.push(LineInfo {
+                start,
+                end,
+                depth,
+                max_depth: max,
+                end_depth,
+                indent: line.len() - trimmed.len(),
+                blank: inside || trimmed.trim_end().is_empty(),
+            });
+        };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let next = bytes.get(i + 1).copied();
+
+        match state {
+            ScanState::Code => match b {
+                b'/' if next == Some(b'/') => {
+                    state = ScanState::LineComment;
+                    i += 1;
+                }
+                b'/' if next == Some(b'*') => {
+                    state = ScanState::BlockComment;
+                    i += 1;
+                }
+                b'#' if lang.hash_comments() => state = ScanState::LineComment,
+                b'"' | b'\''
+                    if lang == Language::Python
+                        && next == Some(b)
+                        && bytes.get(i + 2) == Some(&b) =>
+                {
+                    state = ScanState::TripleStr(b);
+                    i += 2;
+                }
+                b'r' if lang == Language::Rust => {
+                    if let Some(hashes) = raw_string_hashes(bytes, i) {
+                        state = ScanState::RawStr(hashes);
+                        i += hashes + 1;
+                    }
+                }
+                b'"' | b'`' => state = ScanState::Str(b),
+                // In Rust a quote is a char literal only in forms like 'x' or '\n';
+                // otherwise it starts a lifetime
+                b'\'' if lang != Language::Rust || rust_char_literal(text, i) => {
+                    state = ScanState::Str(b);
+                }
+                b'{' => {
+                    depth += 1;
+                    line_max = line_max.max(depth);
+                }
+                b'}' => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+            ScanState::LineComment => {}
+            ScanState::BlockComment => {
+                if b == b'*' && next == Some(b'/') {
+                    state = ScanState::Code;
+                    i += 1;
+                }
+            }
+            ScanState::Str(delim) => {
+                if b == b'\\' && next != Some(b'\n') {
+                    i += 1;
+                } else if b == delim {
+                    state = ScanState::Code;
+                }
+            }
+            ScanState::TripleStr(delim) => {
+                if b == b'\\' && next != Some(b'\n') {
+                    i += 1;
+                } else if b == delim && next == Some(delim) && bytes.get(i + 2) == Some(&delim) {
+                    state = ScanState::Code;
+                    i += 2;
+                }
+            }
+            ScanState::RawStr(hashes) => {
+                if b == b'"'
+                    && bytes.len() > i + hashes
+                    && bytes[i + 1..=i + hashes].iter().all(|&h| h == b'#')
+                {
+                    state = ScanState::Code;
+                    i += hashes;
+                }
+            }
+        }
+
+        if b == b'\n' {
+            push_line(line_start, i + 1, line_depth, line_max, depth, line_inside);
+            line_start = i + 1;
+            line_depth = depth;
+            line_max = depth;
+            if matches!(state, ScanState::LineComment | ScanState::Str(b'\'')) {
+                state = ScanState::Code;
+            }
+            line_inside = matches!(
+                state,
+                ScanState::Str(_) | ScanState::TripleStr(_) | ScanState::RawStr(_)
+            );
+        }
+        i += 1;
+    }
+
+    if line_start < bytes.len() {
+        push_line(
+            line_start,
+            bytes.len(),
+            line_depth,
+            line_max,
+            depth,
+            line_inside,
+        );
+    }
+
+    lines
+}
+
+/// Keywords that look like calls but never name an item.
+const CONTROL_KEYWORDS: &[&str] = &[
+    "if", "else", "for", "foreach", "while", "switch", "match", "catch", "return", "loop", "do",
+    "try", "sizeof", "new", "elif", "unless", "until", "with",
+];
+
+/// Returns the name of the item declared on `line`, if it declares one.
+fn item_name(line: &str, lang: Language) -> Option<String> {
+    if !lang
+        .boundary_patterns()
+        .iter()
+        .any(|pattern| pattern.regex().is_match(line))
+    {
+        return None;
+    }
+
+    let name_patterns = [
+        static_regex!(
+            IMPL_NAME,
+            r"^\s*(?:unsafe\s+)?impl(?:<[^>]*>)?\s+(?:[^{]*?\bfor\s+)?&?(?:mut\s+)?([A-Za-z_]\w*)"
+        ),
+        static_regex!(
+            KEYWORD_NAME,
+            r"\b(?:fn|struct|enum|union|trait|mod|def|class|function\*?|func(?:\s*\([^)]*\))?|interface|module|namespace|type)\s+\*?\s*([A-Za-z_$][\w$]*)"
+        ),
+        static_regex!(ASSIGN_NAME, r"\b(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*="),
+        static_regex!(CALL_NAME, r"([A-Za-z_$][\w$]*)\s*\("),
+    ];
+
+    name_patterns
+        .iter()
+        .find_map(|re| re.captures(line))
+        .and_then(|caps| caps.get(1))
+        .map(regex::Match::as_str)
+        .filter(|name| !CONTROL_KEYWORDS.contains(name))
+        .map(String::from)
+}
+
+/// Returns true if `line` declares an item whose members can be split out.
+fn is_container(line: &str) -> bool {
+    static_regex!(
+        CONTAINER,
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|abstract|final|public|private|protected|static|unsafe)\s+)*(?:impl|trait|mod|class|module|namespace|interface)\b"
+    )
+    .is_match(line)
+}
+
+/// Returns true if `trimmed` is a comment or attribute line that belongs to
+/// the item below it.
+fn is_attached(trimmed: &str, lang: Language) -> bool {
+    let comment = if lang.uses_indentation() {
+        trimmed.starts_with('#')
+    } else {
+        (trimmed.starts_with("//") && !trimmed.starts_with("//!"))
+            || trimmed.starts_with("/*")
+            || trimmed.starts_with('*')
+    };
+    comment || trimmed.starts_with("#[") || trimmed.starts_with('@')
+}
+
+/// Returns true if `line` sits at nesting `level` (brace depth or indent).
+const fn at_level(line: &LineInfo, lang: Language, level: usize) -> bool {
+    if lang.uses_indentation() {
+        line.indent == level
+    } else {
+        line.depth == level
+    }
+}
+
+/// Finds the line index just past the item declared on line `start`.
+fn item_end(
+    text: &str,
+    lines: &[LineInfo],
+    start: usize,
+    limit: usize,
+    lang: Language,
+    level: usize,
+) -> usize {
+    if lang.uses_indentation() {
+        for (j, line) in lines.iter().enumerate().take(limit).skip(start + 1) {
+            let trimmed = text[line.start..line.end].trim();
+            // Closing brackets of a multi-line signature or literal
+            if line.blank || line.indent > level || trimmed.starts_with([')', ']', '}']) {
+                continue;
+            }
+            if lang == Language::Ruby && trimmed == "end" {
+                return j + 1;
+            }
+            return j;
+        }
+        return limit;
+    }
+
+    let mut opened = false;
+    for (j, line) in lines.iter().enumerate().take(limit).skip(start) {
+        opened |= line.max_depth > level;
+        if opened && line.end_depth <= level {
+            return j + 1;
+        }
+        if !opened && text[line.start..line.end].trim_end().ends_with(';') {
+            return j + 1;
+        }
+    }
+    limit
+}
+
+/// Divides lines `range` into items at nesting `level` and the gaps
+/// between them, covering every line in order.
+fn find_segments(
+    text: &str,
+    lines: &[LineInfo],
+    range: Range<usize>,
+    lang: Language,
+    level: usize,
+) -> Vec<Segment> {
+    let mut items: Vec<Segment> = Vec::new();
+    let mut i = range.start;
+
+    while i < range.end {
+        let line = &lines[i];
+        let name = if !line.blank && at_level(line, lang, level) {
+            item_name(&text[line.start..line.end], lang)
+        } else {
+            None
+        };
+
+        let Some(name) = name else {
+            i += 1;
+            continue;
+        };
+
+        let end = item_end(text, lines, i, range.end, lang, level);
+
+        // Pull in doc comments and attributes directly above the item
+        let floor = items.last().map_or(range.start, |item| item.lines.end);
+        let mut first = i;
+        while first > floor {
+            let above = &lines[first - 1];
+            let trimmed = text[above.start..above.end].trim();
+            if above.blank || !at_level(above, lang, level) || !is_attached(trimmed, lang) {
+                break;
+            }
+            first -= 1;
+        }
+
+        items.push(Segment {
+            lines: first..end,
+            header: i,
+            symbol: Some(name),
+        });
+        i = end.max(i + 1);
+    }
+
+    let mut segments = Vec::with_capacity(items.len() * 2 + 1);
+    let mut cursor = range.start;
+    for item in items {
+        if item.lines.start > cursor {
+            segments.push(Segment {
+                lines: cursor..item.lines.start,
+                header: cursor,
+                symbol: None,
+            });
+        }
+        cursor = item.lines.end;
+        segments.push(item);
+    }
+    if cursor < range.end {
+        segments.push(Segment {
+            lines: cursor..range.end,
+            header: cursor,
+            symbol: None,
+        });
+    }
+
+    segments
+}
+
+/// Converts lexer segments into [`CodeItem`]s.
+///
+/// Members are only looked up for containers larger than `chunk_size`, the
+/// only ones the packer splits.
+fn segment_items(
+    text: &str,
+    lines: &[LineInfo],
+    segments: Vec<Segment>,
+    lang: Language,
+    chunk_size: usize,
+) -> Vec<CodeItem> {
+    segments
+        .into_iter()
+        .map(|segment| {
+            let range = lines[segment.lines.start].start..lines[segment.lines.end - 1].end;
+            let Some(symbol) = segment.symbol.clone() else {
+                return CodeItem::gap(range);
+            };
+            let members = if range.len() > chunk_size {
+                nested_level(text, lines, &segment, lang)
+                    .map(|level| {
+                        find_segments(
+                            text,
+                            lines,
+                            segment.header + 1..segment.lines.end,
+                            lang,
+                            level,
+                        )
+                    })
+                    .filter(|members| members.iter().any(|m| m.symbol.is_some()))
+                    .map_or_else(Vec::new, |mut members| {
+                        // The container's header joins its first member
+                        members[0].lines.start = segment.lines.start;
+                        segment_items(text, lines, members, lang, chunk_size)
+                    })
+            } else {
+                Vec::new()
+            };
+            CodeItem {
+                range,
+                symbol: Some(symbol),
+                members,
+            }
+        })
+        .collect()
+}
+
+/// Returns the nesting level of a container item's members, or `None` if
+/// the item is not a container.
+fn nested_level(
+    text: &str,
+    lines: &[LineInfo],
+    segment: &Segment,
+    lang: Language,
+) -> Option<usize> {
+    let header = &lines[segment.header];
+    if !is_container(&text[header.start..header.end]) {
+        return None;
+    }
+
+    if lang.uses_indentation() {
+        lines[segment.header + 1..segment.lines.end]
+            .iter()
+            .find(|line| !line.blank)
+            .map(|line| line.indent)
+            .filter(|&indent| indent > header.indent)
+    } else {
+        Some(header.depth + 1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -652,6 +1320,250 @@ export async function fetchData() {
         assert!(!chunks.is_empty());
     }
 
+    fn symbols(chunk: &Chunk) -> Vec<String> {
+        chunk
+            .metadata
+            .custom
+            .as_deref()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+            .and_then(|v| v["symbols"].as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|s| s.as_str().map(String::from))
+            .collect()
+    }
+
+    fn parent(chunk: &Chunk) -> Option<String> {
+        chunk
+            .metadata
+            .custom
+            .as_deref()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+            .and_then(|v| v["parent"].as_str().map(String::from))
+    }
+
+    fn rust_fn(name: &str, body_lines: usize) -> String {
+        let mut f = format!("/// Docs for {name}.\npub fn {name}(x: usize) -> usize {{\n");
+        for i in 0..body_lines {
+            f.push_str(&format!("    let v{i} = x + {i}; // {{ not a brace\n"));
+        }
+        f.push_str(&format!("    x // end {name}\n}}\n\n"));
+        f
+    }
+
+    #[test]
+    fn test_rust_functions_not_split_mid_body() {
+        let names = ["alpha", "beta", "gamma", "delta", "epsilon"];
+        let code: String = names
+            .iter()
+            .zip([2, 8, 3, 12, 1])
+            .map(|(name, lines)| rust_fn(name, lines))
+            .collect();
+
+        let chunker = CodeChunker::with_size(300);
+        let meta = ChunkMetadata::with_size(300).content_type("rs");
+        let chunks = chunker.chunk(1, &code, Some(&meta)).unwrap();
+        assert!(chunks.len() > 1);
+
+        for name in names {
+            let holders: Vec<&Chunk> = chunks
+                .iter()
+                .filter(|c| c.content.contains(&format!("fn {name}(")))
+                .collect();
+            assert_eq!(holders.len(), 1, "{name} appears in one chunk");
+            assert!(
+                holders[0]
+                    .content
+                    .contains(&format!("/// Docs for {name}."))
+            );
+            assert!(holders[0].content.contains(&format!("// end {name}\n}}")));
+            assert!(symbols(holders[0]).contains(&name.to_string()));
+        }
+
+        // Oversized functions are kept whole rather than split
+        let delta = chunks
+            .iter()
+            .find(|c| c.content.contains("fn delta("))
+            .unwrap();
+        assert!(delta.content.len() > 300);
+
+        // Chunks cover the text contiguously
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].byte_range.end, pair[1].byte_range.start);
+        }
+    }
+
+    #[test]
+    fn test_rust_oversized_impl_split_at_methods() {
+        let mut code = String::from("#[derive(Debug)]\nstruct Parser;\n\nimpl Parser {\n");
+        for name in ["parse", "validate", "finish"] {
+            code.push_str(&format!(
+                "    /// {name} docs.\n    pub fn {name}(&self) -> bool {{\n"
+            ));
+            for i in 0..6 {
+                code.push_str(&format!("        let _x{i} = \"}}\"; // brace in string\n"));
+            }
+            code.push_str("        true\n    }\n\n");
+        }
+        code.push_str("}\n");
+
+        let chunker = CodeChunker::with_size(400);
+        let meta = ChunkMetadata::with_size(400).source("src/parser.rs");
+        let chunks = chunker.chunk(1, &code, Some(&meta)).unwrap();
+
+        let first = &chunks[0];
+        assert!(
+            first
+                .content
+                .starts_with("#[derive(Debug)]\nstruct Parser;")
+        );
+        assert_eq!(symbols(first), vec!["Parser".to_string()]);
+
+        for name in ["parse", "validate", "finish"] {
+            let chunk = chunks
+                .iter()
+                .find(|c| c.content.contains(&format!("fn {name}(")))
+                .unwrap();
+            assert!(chunk.content.contains(&format!("/// {name} docs.")));
+            assert!(chunk.content.contains("        true\n    }"));
+            assert_eq!(symbols(chunk), vec![name.to_string()]);
+            assert_eq!(parent(chunk).as_deref(), Some("Parser"));
+        }
+        assert!(chunks[1].content.starts_with("impl Parser {\n"));
+        assert!(chunks.last().unwrap().content.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_rust_lifetimes_and_char_literals() {
+        let code =
+            "fn first<'a>(s: &'a str) -> char {\n    '{'\n}\n\nfn second() -> char {\n    '}'\n}\n";
+        let chunker = CodeChunker::with_size(60);
+        let meta = ChunkMetadata::with_size(60).content_type("rs");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(symbols(&chunks[0]), vec!["first".to_string()]);
+        assert!(chunks[0].content.ends_with("'{'\n}\n\n"));
+        assert_eq!(symbols(&chunks[1]), vec!["second".to_string()]);
+    }
+
+    #[test]
+    fn test_rust_raw_strings_and_multibyte_chars() {
+        let code = "fn first() -> &'static str {\n    r#\"{\"}\"#\n}\n\nfn second() -> [char; 2] {\n    ['\u{e9}','{']\n}\n\nfn third() -> &'static [u8] {\n    br\"\\\"\n}\n";
+        let chunker = CodeChunker::with_size(50);
+        let meta = ChunkMetadata::with_size(50).content_type("rs");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(symbols(&chunks[0]), vec!["first".to_string()]);
+        assert!(chunks[0].content.ends_with("r#\"{\"}\"#\n}\n\n"));
+        assert_eq!(symbols(&chunks[1]), vec!["second".to_string()]);
+        assert!(chunks[1].content.ends_with("['\u{e9}','{']\n}\n\n"));
+        assert_eq!(symbols(&chunks[2]), vec!["third".to_string()]);
+    }
+
+    #[test]
+    fn test_scan_lines_string_states() {
+        let lines = scan_lines("let s = r##\"a\"# {\"##;\n{\n", Language::Rust);
+        assert_eq!(lines[0].end_depth, 0);
+        assert_eq!(lines[1].end_depth, 1);
+
+        let lines = scan_lines("x = '''it's {\n}'''\ny = {\n", Language::Python);
+        assert_eq!(lines[0].end_depth, 0);
+        assert!(lines[1].blank);
+        assert_eq!(lines[2].end_depth, 1);
+    }
+
+    #[test]
+    fn test_python_docstrings_do_not_end_items() {
+        let code = "def first():\n    \"\"\"Docs.\n\ndef not_an_item():\n\"\"\"\n    return 1\n\n\ndef second():\n    return 2\n";
+        let chunker = CodeChunker::with_size(100);
+        let meta = ChunkMetadata::with_size(100).content_type("py");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        let first = chunks
+            .iter()
+            .find(|c| c.content.contains("def first"))
+            .unwrap();
+        assert!(first.content.contains("    return 1\n"));
+        assert!(symbols(first).contains(&"first".to_string()));
+        assert!(
+            chunks
+                .iter()
+                .all(|c| !symbols(c).contains(&"not_an_item".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_python_items_follow_indentation() {
+        let code = "import os\n\n@decorator\ndef first(a,\n          b):\n    if a:\n        return b\n\n    return a\n\n\nclass Thing:\n    def method(self):\n        pass\n";
+        let chunker = CodeChunker::with_size(60);
+        let meta = ChunkMetadata::with_size(60).content_type("py");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        let first = chunks
+            .iter()
+            .find(|c| c.content.contains("def first"))
+            .unwrap();
+        assert!(first.content.starts_with("@decorator\n"));
+        assert!(first.content.contains("    return a\n"));
+        assert_eq!(symbols(first), vec!["first".to_string()]);
+
+        let thing = chunks
+            .iter()
+            .find(|c| c.content.contains("class Thing"))
+            .unwrap();
+        assert!(thing.content.contains("        pass"));
+        assert_eq!(symbols(thing), vec!["Thing".to_string()]);
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_javascript_template_literals() {
+        let code = "function render(items) {\n  return `<ul>${items.map((i) => `<li>${i}</li>`).join(\"\")}</ul>`;\n}\n\nfunction other() {\n  return \"}\";\n}\n";
+        let chunker = CodeChunker::with_size(100);
+        let meta = ChunkMetadata::with_size(100).content_type("js");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(symbols(&chunks[0]), vec!["render".to_string()]);
+        assert!(chunks[0].content.ends_with("</ul>`;\n}\n\n"));
+        assert_eq!(symbols(&chunks[1]), vec!["other".to_string()]);
+    }
+
+    #[test]
+    fn test_syntax_errors_use_lexer() {
+        let code = "fn first() {\n    let x = ;\n}\n\nfn second() {}\n";
+        let chunker = CodeChunker::with_size(30);
+        let meta = ChunkMetadata::with_size(30).content_type("rs");
+        let chunks = chunker.chunk(1, code, Some(&meta)).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(symbols(&chunks[0]), vec!["first".to_string()]);
+        assert_eq!(symbols(&chunks[1]), vec!["second".to_string()]);
+    }
+
+    #[test]
+    fn test_item_name_extraction() {
+        assert_eq!(
+            item_name("pub(crate) fn load(path: &Path) {", Language::Rust).as_deref(),
+            Some("load")
+        );
+        assert_eq!(
+            item_name("impl<T: Clone> Display for Wrapper<T> {", Language::Rust).as_deref(),
+            Some("Wrapper")
+        );
+        assert_eq!(
+            item_name("func (s *Server) Start() error {", Language::Go).as_deref(),
+            Some("Start")
+        );
+        assert_eq!(
+            item_name("    } else if (ready) {", Language::JavaScript),
+            None
+        );
+        assert_eq!(item_name("let x = 1;", Language::Rust), None);
+    }
+
     #[test]
     fn test_boundary_patterns_rust() {
         let patterns = Language::Rust.boundary_patterns();
@@ -4,7 +4,7 @@
 //! Respects UTF-8 character boundaries to avoid splitting multi-byte characters.
 
 use crate::chunking::traits::{ChunkMetadata, Chunker};
-use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP, MAX_CHUNK_SIZE};
+use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP, MAX_CHUNK_SIZE, merge_small_chunks};
 use crate::core::Chunk;
 use crate::error::{ChunkingError, Result};
 
@@ -222,7 +222,8 @@ impl Chunker for FixedChunker {
             index += 1;
         }
 
-        Ok(chunks)
+        let min_chunk_size = metadata.map_or(0, |meta| meta.min_chunk_size);
+        Ok(merge_small_chunks(text, chunks, min_chunk_size, chunk_size))
     }
 
     fn name(&self) -> &'static str {
@@ -449,6 +450,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fixed_chunker_merges_small_tail() {
+        let chunker = FixedChunker::new().line_aware(false);
+        let text = "A".repeat(210);
+
+        let meta = ChunkMetadata::with_size_and_overlap(100, 0).min_chunk_size(20);
+        let chunks = chunker.chunk(1, &text, Some(&meta)).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].byte_range, 100..210);
+
+        // Merging is opt-in
+        let meta = ChunkMetadata::with_size_and_overlap(100, 0);
+        let chunks = chunker.chunk(1, &text, Some(&meta)).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].size(), 10);
+    }
+
     #[test]
     fn test_fixed_chunker_force_progress_edge_case() {
         // Test edge case where end <= start requiring forced progress (line 183)
@@ -0,0 +1,530 @@
+//! Markdown-aware chunking strategy.
+//!
+//! Splits Markdown documents at heading boundaries, never cuts through fenced
+//! code blocks or tables, and records the enclosing heading path of each
+//! chunk in its custom metadata.
+
+use crate::chunking::semantic::SemanticChunker;
+use crate::chunking::traits::{ChunkMetadata, Chunker};
+use crate::chunking::{DEFAULT_CHUNK_SIZE, MAX_CHUNK_SIZE};
+use crate::core::Chunk;
+use crate::error::{ChunkingError, Result};
+use std::ops::Range;
+
+/// Kind of a Markdown block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    Heading,
+    Paragraph,
+    Fence,
+    Table,
+}
+
+/// A contiguous run of lines forming one Markdown block.
+#[derive(Debug, Clone)]
+struct Block {
+    kind: BlockKind,
+    range: Range<usize>,
+}
+
+impl Block {
+    /// Blocks that must never be split internally.
+    const fn is_atomic(&self) -> bool {
+        matches!(self.kind, BlockKind::Fence | BlockKind::Table)
+    }
+}
+
+/// A heading and the blocks that follow it up to the next heading.
+#[derive(Debug, Clone)]
+struct Section {
+    blocks: Vec<Block>,
+    heading_path: Vec<String>,
+}
+
+impl Section {
+    fn range(&self) -> Range<usize> {
+        let start = self.blocks.first().map_or(0, |b| b.range.start);
+        let end = self.blocks.last().map_or(start, |b| b.range.end);
+        start..end
+    }
+}
+
+/// Parses a heading line, returning its level and title.
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line.trim_start();
+    let level = trimmed.bytes().take_while(|&b| b == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t', '\r', '\n']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim().to_string();
+    Some((level, title))
+}
+
+/// Returns the fence character and length if the line opens a code fence.
+fn parse_fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start();
+    let fence_char = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|&c| c == fence_char).count();
+    (len >= 3).then_some((fence_char, len))
+}
+
+/// Returns true if the line closes a fence opened with `fence`.
+fn closes_fence(line: &str, fence: (char, usize)) -> bool {
+    let trimmed = line.trim();
+    let len = trimmed.chars().take_while(|&c| c == fence.0).count();
+    len >= fence.1 && trimmed.len() == len * fence.0.len_utf8()
+}
+
+/// Splits Markdown text into blocks that together cover the whole input.
+fn parse_blocks(text: &str) -> Vec<(Block, Option<(usize, String)>)> {
+    let mut blocks: Vec<(Block, Option<(usize, String)>)> = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let range = offset..offset + line.len();
+        offset = range.end;
+
+        if let Some(open) = fence {
+            if let Some(block) = current.as_mut() {
+                block.range.end = range.end;
+            }
+            if closes_fence(line, open) {
+                fence = None;
+                blocks.extend(current.take().map(|b| (b, None)));
+            }
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            // Blank lines attach to the preceding block so ranges stay contiguous.
+            if let Some(mut block) = current.take() {
+                block.range.end = range.end;
+                blocks.push((block, None));
+            } else if let Some((last, _)) = blocks.last_mut() {
+                last.range.end = range.end;
+            } else {
+                blocks.push((
+                    Block {
+                        kind: BlockKind::Paragraph,
+                        range,
+                    },
+                    None,
+                ));
+            }
+            continue;
+        }
+
+        if let Some(open) = parse_fence(line) {
+            blocks.extend(current.take().map(|b| (b, None)));
+            fence = Some(open);
+            current = Some(Block {
+                kind: BlockKind::Fence,
+                range,
+            });
+            continue;
+        }
+
+        if let Some(heading) = parse_heading(line) {
+            blocks.extend(current.take().map(|b| (b, None)));
+            blocks.push((
+                Block {
+                    kind: BlockKind::Heading,
+                    range,
+                },
+                Some(heading),
+            ));
+            continue;
+        }
+
+        let kind = if line.trim_start().starts_with('|') {
+            BlockKind::Table
+        } else {
+            BlockKind::Paragraph
+        };
+
+        match current.as_mut() {
+            Some(block) if block.kind == kind => block.range.end = range.end,
+            _ => {
+                blocks.extend(current.take().map(|b| (b, None)));
+                current = Some(Block { kind, range });
+            }
+        }
+    }
+
+    blocks.extend(current.map(|b| (b, None)));
+    blocks
+}
+
+/// Groups blocks into heading-delimited sections with their heading paths.
+fn build_sections(text: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+
+    for (block, heading) in parse_blocks(text) {
+        if let Some((level, title)) = heading {
+            while stack.last().is_some_and(|(l, _)| *l >= level) {
+                stack.pop();
+            }
+            stack.push((level, title));
+            sections.push(Section {
+                blocks: vec![block],
+                heading_path: stack.iter().map(|(_, t)| t.clone()).collect(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.blocks.push(block);
+        } else {
+            sections.push(Section {
+                blocks: vec![block],
+                heading_path: Vec::new(),
+            });
+        }
+    }
+
+    sections
+}
+
+/// Markdown chunker that respects document structure.
+///
+/// Sections are merged up to `chunk_size`; a section larger than
+/// `chunk_size` is sub-split on paragraph boundaries. Fenced code blocks and
+/// tables are kept whole even when they exceed `chunk_size`. Overlap is not
+/// applied, since chunks follow structural boundaries.
+///
+/// Each chunk's custom metadata holds its heading path, e.g.
+/// `{"heading_path":["Guide","Install"]}`. A chunk packing several sections
+/// holds the path they share, so `## Install` and `## Usage` under
+/// `# Guide` give `["Guide"]`.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::chunking::{Chunker, MarkdownChunker};
+///
+/// let chunker = MarkdownChunker::with_size(40);
+/// let text = "# Title\n\nIntro text.\n\n## Part\n\nMore text here.\n";
+/// let chunks = chunker.chunk(1, text, None).unwrap();
+/// assert!(!chunks.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MarkdownChunker {
+    /// Target chunk size in characters.
+    chunk_size: usize,
+}
+
+impl Default for MarkdownChunker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownChunker {
+    /// Creates a new Markdown chunker with default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Creates a Markdown chunker with a custom chunk size.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Target size for each chunk in characters.
+    #[must_use]
+    pub const fn with_size(chunk_size: usize) -> Self {
+        Self { chunk_size }
+    }
+
+    /// Splits an oversized section on block boundaries.
+    fn split_section(
+        text: &str,
+        section: &Section,
+        chunk_size: usize,
+    ) -> Result<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+        let mut pending: Option<Range<usize>> = None;
+
+        for block in &section.blocks {
+            let len = block.range.len();
+
+            if len > chunk_size {
+                ranges.extend(pending.take());
+                if block.is_atomic() {
+                    ranges.push(block.range.clone());
+                } else {
+                    let slice = &text[block.range.clone()];
+                    let sub = SemanticChunker::with_size(chunk_size)
+                        .min_chunk_size(0)
+                        .chunk(0, slice, None)?;
+                    ranges.extend(sub.into_iter().map(|c| {
+                        block.range.start + c.byte_range.start..block.range.start + c.byte_range.end
+                    }));
+                }
+                continue;
+            }
+
+            match pending.as_mut() {
+                Some(range) if range.len() + len <= chunk_size => range.end = block.range.end,
+                _ => {
+                    ranges.extend(pending.take());
+                    pending = Some(block.range.clone());
+                }
+            }
+        }
+
+        ranges.extend(pending);
+        Ok(ranges)
+    }
+}
+
+impl Chunker for MarkdownChunker {
+    fn chunk(
+        &self,
+        buffer_id: i64,
+        text: &str,
+        metadata: Option<&ChunkMetadata>,
+    ) -> Result<Vec<Chunk>> {
+        let chunk_size = metadata.map_or(self.chunk_size, |meta| meta.chunk_size);
+
+        if chunk_size == 0 {
+            return Err(ChunkingError::InvalidConfig {
+                reason: "chunk_size must be > 0".to_string(),
+            }
+            .into());
+        }
+        if chunk_size > MAX_CHUNK_SIZE {
+            return Err(ChunkingError::ChunkTooLarge {
+                size: chunk_size,
+                max: MAX_CHUNK_SIZE,
+            }
+            .into());
+        }
+
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // (range, heading path) for each output chunk
+        let mut pieces: Vec<(Range<usize>, Vec<String>)> = Vec::new();
+        let mut pending: Option<(Range<usize>, Vec<String>)> = None;
+
+        for section in build_sections(text) {
+            let range = section.range();
+
+            if range.len() > chunk_size {
+                pieces.extend(pending.take());
+                for sub in Self::split_section(text, &section, chunk_size)? {
+                    pieces.push((sub, section.heading_path.clone()));
+                }
+                continue;
+            }
+
+            match pending.as_mut() {
+                Some((pending_range, pending_path))
+                    if pending_range.len() + range.len() <= chunk_size =>
+                {
+                    pending_range.end = range.end;
+                    let shared = pending_path
+                        .iter()
+                        .zip(&section.heading_path)
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    pending_path.truncate(shared);
+                }
+                _ => {
+                    pieces.extend(pending.take());
+                    pending = Some((range, section.heading_path));
+                }
+            }
+        }
+        pieces.extend(pending);
+
+        let mut chunks = Vec::with_capacity(pieces.len());
+        for (range, heading_path) in pieces {
+            let mut chunk = Chunk::with_strategy(
+                buffer_id,
+                text[range.clone()].to_string(),
+                range,
+                chunks.len(),
+                self.name(),
+            );
+            chunk.set_token_count(chunk.estimate_tokens());
+            if !heading_path.is_empty() {
+                chunk.metadata.custom =
+                    Some(serde_json::json!({ "heading_path": heading_path }).to_string());
+            }
+            chunks.push(chunk);
+
+            if let Some(meta) = metadata
+                && meta.max_chunks > 0
+                && chunks.len() >= meta.max_chunks
+            {
+                break;
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn description(&self) -> &'static str {
+        "Markdown chunking at heading boundaries, keeping code fences and tables intact"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading_path(chunk: &Chunk) -> Vec<String> {
+        chunk
+            .metadata
+            .custom
+            .as_deref()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+            .and_then(|v| serde_json::from_value(v["heading_path"].clone()).ok())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_markdown_chunker_empty_text() {
+        let chunks = MarkdownChunker::new().chunk(1, "", None).unwrap();
+        assert!(chunks.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_chunker_covers_input() {
+        let text = "Preamble.\n\n# A\n\nText a.\n\n## B\n\nText b.\n\n\n# C\n\nText c.\n";
+        let chunks = MarkdownChunker::with_size(20).chunk(1, text, None).unwrap();
+        assert_eq!(chunks.first().unwrap().byte_range.start, 0);
+        assert_eq!(chunks.last().unwrap().byte_range.end, text.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].byte_range.end, pair[1].byte_range.start);
+        }
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.index, i);
+            assert_eq!(&text[chunk.byte_range.clone()], chunk.content);
+        }
+    }
+
+    #[test]
+    fn test_markdown_chunker_nested_heading_paths() {
+        let text = "# Guide\n\nIntro paragraph.\n\n## Install\n\nRun the installer.\n\n### Linux\n\nUse the package.\n\n## Usage\n\nCall the tool.\n";
+        let chunks = MarkdownChunker::with_size(40).chunk(1, text, None).unwrap();
+
+        let linux = chunks
+            .iter()
+            .find(|c| c.content.contains("Use the package"))
+            .unwrap();
+        assert_eq!(heading_path(linux), vec!["Guide", "Install", "Linux"]);
+
+        let usage = chunks
+            .iter()
+            .find(|c| c.content.contains("Call the tool"))
+            .unwrap();
+        assert_eq!(heading_path(usage), vec!["Guide", "Usage"]);
+
+        // Chunks break at heading boundaries
+        for chunk in &chunks {
+            assert!(chunk.content.starts_with('#'));
+        }
+    }
+
+    #[test]
+    fn test_markdown_chunker_merged_sections_share_path() {
+        let text = "# Guide\n\n## Install\n\nRun it.\n\n## Usage\n\nCall it.\n";
+        let chunks = MarkdownChunker::with_size(200)
+            .chunk(1, text, None)
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(heading_path(&chunks[0]), vec!["Guide"]);
+
+        let text = "## Install\n\nRun it.\n\n## Usage\n\nCall it.\n";
+        let chunks = MarkdownChunker::with_size(200)
+            .chunk(1, text, None)
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert!(heading_path(&chunks[0]).is_empty());
+        assert!(chunks[0].metadata.custom.is_none());
+    }
+
+    #[test]
+    fn test_markdown_chunker_never_splits_fence() {
+        let code = "let x = 1;\n".repeat(50);
+        let text = format!("# Code\n\nSome intro.\n\n```rust\n{code}```\n\nAfter the fence.\n");
+        let chunks = MarkdownChunker::with_size(100)
+            .chunk(1, &text, None)
+            .unwrap();
+
+        let fence_chunks: Vec<_> = chunks
+            .iter()
+            .filter(|c| c.content.contains("let x = 1;"))
+            .collect();
+        assert_eq!(fence_chunks.len(), 1);
+        assert!(fence_chunks[0].content.contains("```rust"));
+        assert!(fence_chunks[0].content.trim_end().ends_with("```"));
+        assert_eq!(heading_path(fence_chunks[0]), vec!["Code"]);
+    }
+
+    #[test]
+    fn test_markdown_chunker_fence_hides_headings() {
+        let text = "# Real\n\n```\n# not a heading\n```\n";
+        let chunks = MarkdownChunker::with_size(1000)
+            .chunk(1, text, None)
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(heading_path(&chunks[0]), vec!["Real"]);
+    }
+
+    #[test]
+    fn test_markdown_chunker_keeps_table_whole() {
+        let rows = "| a | b |\n".repeat(20);
+        let text = format!("# Data\n\nBefore.\n\n| h1 | h2 |\n|----|----|\n{rows}\nAfter.\n");
+        let chunks = MarkdownChunker::with_size(60)
+            .chunk(1, &text, None)
+            .unwrap();
+        let table_chunks: Vec<_> = chunks.iter().filter(|c| c.content.contains('|')).collect();
+        assert_eq!(table_chunks.len(), 1);
+        assert!(table_chunks[0].content.contains("| h1 | h2 |"));
+    }
+
+    #[test]
+    fn test_markdown_chunker_subsplits_long_section() {
+        let para = "This is a sentence in a long paragraph. ".repeat(10);
+        let text = format!("# Long\n\n{para}\n\n{para}\n");
+        let chunks = MarkdownChunker::with_size(200)
+            .chunk(1, &text, None)
+            .unwrap();
+        assert!(chunks.len() > 2);
+        for chunk in &chunks {
+            assert!(chunk.size() <= 200);
+            assert_eq!(heading_path(chunk), vec!["Long"]);
+        }
+    }
+
+    #[test]
+    fn test_markdown_chunker_strategy_name() {
+        let chunks = MarkdownChunker::new().chunk(1, "# Hi\n", None).unwrap();
+        assert_eq!(chunks[0].metadata.strategy, Some("markdown".to_string()));
+    }
+
+    #[test]
+    fn test_parse_heading() {
+        assert_eq!(
+            parse_heading("## Title ##\n"),
+            Some((2, "Title".to_string()))
+        );
+        assert_eq!(parse_heading("#hashtag"), None);
+        assert_eq!(parse_heading("####### seven"), None);
+    }
+}
@@ -6,20 +6,33 @@
 //! - **Fixed**: Simple character-based chunking with configurable size and overlap
 //! - **Semantic**: Unicode-aware chunking respecting sentence/paragraph boundaries
 //! - **Code**: Language-aware chunking at function/class boundaries
+//! - **Markdown**: Heading-aware chunking that keeps code fences and tables intact
+//! - **JSON/YAML**: Structure-aware chunking by array elements and object entries
 //! - **Parallel**: Orchestrator for parallel chunk processing
+//! - **Token**: Token-aware chunking with sizes measured in tokens
 
 pub mod code;
 pub mod fixed;
+pub mod markdown;
 pub mod parallel;
 pub mod semantic;
+pub mod structured;
+#[cfg(feature = "tree-sitter")]
+mod syntax;
+pub mod token;
 pub mod traits;
 
 pub use code::CodeChunker;
 pub use fixed::FixedChunker;
+pub use markdown::MarkdownChunker;
 pub use parallel::ParallelChunker;
 pub use semantic::SemanticChunker;
+pub use structured::{JsonChunker, YamlChunker};
+pub use token::TokenChunker;
 pub use traits::{ChunkMetadata as ChunkerMetadata, Chunker};
 
+use crate::core::Chunk;
+
 /// Default chunk size in characters (~750 tokens at 4 chars/token).
 /// Sized for granular semantic search with embeddings.
 pub const DEFAULT_CHUNK_SIZE: usize = 3_000;
@@ -30,6 +43,10 @@ pub const DEFAULT_OVERLAP: usize = 500;
 /// Maximum allowed chunk size (50k chars, ~12.5k tokens).
 pub const MAX_CHUNK_SIZE: usize = 50_000;
 
+/// Default minimum chunk size in characters used by [`crate::LoadOptions`]
+/// and the CLI's `--min-chunk-size` (see [`ChunkMetadata::min_chunk_size`]).
+pub const DEFAULT_MIN_CHUNK_SIZE: usize = 100;
+
 /// Creates the default chunker (semantic).
 #[must_use]
 pub const fn default_chunker() -> SemanticChunker {
@@ -40,7 +57,8 @@ pub const fn default_chunker() -> SemanticChunker {
 ///
 /// # Arguments
 ///
-/// * `name` - Chunker strategy name: "fixed", "semantic", "code", or "parallel".
+/// * `name` - Chunker strategy name: "fixed", "semantic", "code", "markdown",
+///   "json", "yaml", "parallel", or "token".
 ///
 /// # Returns
 ///
@@ -54,7 +72,11 @@ pub fn create_chunker(name: &str) -> crate::error::Result<Box<dyn Chunker>> {
         "fixed" => Ok(Box::new(FixedChunker::new())),
         "semantic" => Ok(Box::new(SemanticChunker::new())),
         "code" | "ast" => Ok(Box::new(CodeChunker::new())),
+        "markdown" | "md" => Ok(Box::new(MarkdownChunker::new())),
+        "json" => Ok(Box::new(JsonChunker::new())),
+        "yaml" | "yml" => Ok(Box::new(YamlChunker::new())),
         "parallel" => Ok(Box::new(ParallelChunker::new(SemanticChunker::new()))),
+        "token" => Ok(Box::new(TokenChunker::new())),
         _ => Err(crate::error::ChunkingError::UnknownStrategy {
             name: name.to_string(),
         }
@@ -62,10 +84,81 @@ pub fn create_chunker(name: &str) -> crate::error::Result<Box<dyn Chunker>> {
     }
 }
 
+/// Returns the default chunk size and overlap for a chunking strategy.
+///
+/// The `token` strategy measures both in tokens
+/// ([`token::DEFAULT_TOKEN_CHUNK_SIZE`], [`token::DEFAULT_TOKEN_OVERLAP`]);
+/// every other strategy measures them in characters ([`DEFAULT_CHUNK_SIZE`],
+/// [`DEFAULT_OVERLAP`]).
+#[must_use]
+pub fn default_size_and_overlap(name: &str) -> (usize, usize) {
+    if name.eq_ignore_ascii_case("token") {
+        (
+            token::DEFAULT_TOKEN_CHUNK_SIZE,
+            token::DEFAULT_TOKEN_OVERLAP,
+        )
+    } else {
+        (DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP)
+    }
+}
+
+/// Merges undersized chunks into their predecessor.
+///
+/// Any chunk after the first that is smaller than `min_size` is folded into
+/// the preceding chunk, extending its byte range, and the result is
+/// re-indexed. The threshold is capped at half of `chunk_size` so chunks
+/// produced at the configured size are never merged.
+///
+/// # Arguments
+///
+/// * `text` - The source text the chunks were produced from.
+/// * `chunks` - Chunks in buffer order.
+/// * `min_size` - Minimum chunk size in bytes (0 disables merging).
+/// * `chunk_size` - Target chunk size used to produce the chunks.
+#[must_use]
+pub fn merge_small_chunks(
+    text: &str,
+    chunks: Vec<Chunk>,
+    min_size: usize,
+    chunk_size: usize,
+) -> Vec<Chunk> {
+    let threshold = min_size.min(chunk_size / 2);
+    if threshold == 0 || chunks.len() < 2 {
+        return chunks;
+    }
+
+    let mut merged: Vec<Chunk> = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        if chunk.size() < threshold
+            && let Some(prev) = merged.last_mut()
+            && chunk.byte_range.end > prev.byte_range.end
+            && let Some(content) = text.get(prev.byte_range.start..chunk.byte_range.end)
+        {
+            prev.content = content.to_string();
+            prev.byte_range.end = chunk.byte_range.end;
+            if prev.metadata.token_count.is_some() {
+                prev.set_token_count(prev.estimate_tokens());
+            }
+            if prev.metadata.content_hash.is_some() {
+                prev.compute_hash();
+            }
+            continue;
+        }
+        merged.push(chunk);
+    }
+
+    for (index, chunk) in merged.iter_mut().enumerate() {
+        chunk.index = index;
+    }
+    merged
+}
+
 /// Lists available chunking strategy names.
 #[must_use]
 pub fn available_strategies() -> Vec<&'static str> {
-    vec!["fixed", "semantic", "code", "parallel"]
+    vec![
+        "fixed", "semantic", "code", "markdown", "json", "yaml", "parallel", "token",
+    ]
 }
 
 #[cfg(test)]
@@ -97,6 +190,21 @@ mod tests {
         assert_eq!(chunker.name(), "parallel");
     }
 
+    #[test]
+    fn test_default_size_and_overlap() {
+        assert_eq!(
+            default_size_and_overlap("token"),
+            (
+                token::DEFAULT_TOKEN_CHUNK_SIZE,
+                token::DEFAULT_TOKEN_OVERLAP
+            )
+        );
+        assert_eq!(
+            default_size_and_overlap("semantic"),
+            (DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP)
+        );
+    }
+
     #[test]
     fn test_create_chunker_unknown() {
         let result = create_chunker("unknown");
@@ -112,11 +220,65 @@ mod tests {
     #[test]
     fn test_available_strategies() {
         let strategies = available_strategies();
-        assert_eq!(strategies.len(), 4);
+        assert_eq!(strategies.len(), 8);
         assert!(strategies.contains(&"fixed"));
         assert!(strategies.contains(&"semantic"));
         assert!(strategies.contains(&"code"));
         assert!(strategies.contains(&"parallel"));
+        assert!(strategies.contains(&"markdown"));
+        assert!(strategies.contains(&"json"));
+        assert!(strategies.contains(&"yaml"));
+        assert!(strategies.contains(&"token"));
+    }
+
+    #[test]
+    fn test_create_chunker_markdown() {
+        let chunker = create_chunker("markdown").unwrap();
+        assert_eq!(chunker.name(), "markdown");
+        let chunker = create_chunker("md").unwrap();
+        assert_eq!(chunker.name(), "markdown");
+    }
+
+    #[test]
+    fn test_create_chunker_token() {
+        let chunker = create_chunker("token").unwrap();
+        assert_eq!(chunker.name(), "token");
+    }
+
+    #[test]
+    fn test_create_chunker_structured() {
+        assert_eq!(create_chunker("json").unwrap().name(), "json");
+        assert_eq!(create_chunker("yaml").unwrap().name(), "yaml");
+        assert_eq!(create_chunker("yml").unwrap().name(), "yaml");
+    }
+
+    #[test]
+    fn test_merge_small_chunks_folds_tail() {
+        let text = format!("{}{}", "A".repeat(200), "B".repeat(10));
+        let chunks = vec![
+            Chunk::new(1, text[0..100].to_string(), 0..100, 0),
+            Chunk::new(1, text[100..200].to_string(), 100..200, 1),
+            Chunk::new(1, text[200..210].to_string(), 200..210, 2),
+        ];
+        let merged = merge_small_chunks(&text, chunks, 50, 100);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].byte_range, 100..210);
+        assert_eq!(merged[1].index, 1);
+        assert_eq!(merged[1].content, &text[100..210]);
+    }
+
+    #[test]
+    fn test_merge_small_chunks_disabled_or_capped() {
+        let text = "0123456789";
+        let chunks = vec![
+            Chunk::new(1, text[0..5].to_string(), 0..5, 0),
+            Chunk::new(1, text[5..10].to_string(), 5..10, 1),
+        ];
+        // Disabled
+        assert_eq!(merge_small_chunks(text, chunks.clone(), 0, 5).len(), 2);
+        // Full-size chunks are never merged even with a large minimum
+        assert_eq!(merge_small_chunks(text, chunks, 100, 5).len(), 2);
     }
 
     #[test]
@@ -4,10 +4,10 @@
 //! boundaries using the `unicode-segmentation` crate.
 
 use crate::chunking::traits::{ChunkMetadata, Chunker};
-use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP, MAX_CHUNK_SIZE};
+use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP, MAX_CHUNK_SIZE, merge_small_chunks};
 use crate::core::Chunk;
 use crate::error::{ChunkingError, Result};
-use crate::io::find_char_boundary;
+use crate::io::{PAGE_BREAK, find_char_boundary};
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Semantic chunker that respects sentence and paragraph boundaries.
@@ -81,7 +81,8 @@ impl SemanticChunker {
 
     /// Finds the best boundary near the target position.
     ///
-    /// Prefers paragraph breaks > sentence breaks > word breaks > character breaks.
+    /// Prefers page breaks > paragraph breaks > sentence breaks > word breaks >
+    /// character breaks.
     fn find_best_boundary(&self, text: &str, target_pos: usize) -> usize {
         if target_pos >= text.len() {
             return text.len();
@@ -98,6 +99,14 @@ impl SemanticChunker {
 
         let search_region = &text[search_start..search_end];
 
+        // Priority 0: Page break (form feed between extracted PDF pages)
+        if let Some(pos) = search_region.rfind(PAGE_BREAK) {
+            let boundary = search_start + pos + PAGE_BREAK.len_utf8();
+            if boundary > search_start {
+                return boundary;
+            }
+        }
+
         // Priority 1: Paragraph break (double newline)
         if let Some(pos) = search_region.rfind("\n\n") {
             let boundary = search_start + pos + 2;
@@ -293,7 +302,9 @@ impl Chunker for SemanticChunker {
             chunks.push(merged);
         }
 
-        Ok(chunks)
+        // Fold any remaining undersized chunks configured via metadata
+        let min_chunk_size = metadata.map_or(0, |meta| meta.min_chunk_size);
+        Ok(merge_small_chunks(text, chunks, min_chunk_size, chunk_size))
     }
 
     fn name(&self) -> &'static str {
@@ -367,6 +378,18 @@ mod tests {
         assert!(!chunks.is_empty());
     }
 
+    #[test]
+    fn test_semantic_chunker_prefers_page_break() {
+        let chunker = SemanticChunker::with_size(100);
+        let page_one = format!("{}Delta epsilon.", "Alpha beta gamma. ".repeat(4));
+        let text = format!("{page_one}\u{c}Hi.\n\n{}", "Filler words here. ".repeat(6));
+        let chunks = chunker.chunk(1, &text, None).unwrap();
+
+        // The paragraph break after the page break is ignored
+        assert_eq!(chunks[0].byte_range.end, page_one.len() + 1);
+        assert!(chunks[1].content.starts_with("Hi."));
+    }
+
     #[test]
     fn test_semantic_chunker_unicode() {
         let chunker = SemanticChunker::with_size(20);
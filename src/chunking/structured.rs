@@ -0,0 +1,656 @@
+//! Structure-aware chunking for JSON and YAML documents.
+//!
+//! Emits one chunk per top-level array element or object entry, merging
+//! small entries up to `chunk_size` and descending into nested keys when an
+//! entry is too large. Each chunk records its location as a JSONPath-style
+//! expression in its custom metadata (e.g. `{"json_path":"$.items[3]"}`).
+
+use crate::chunking::semantic::SemanticChunker;
+use crate::chunking::traits::{ChunkMetadata, Chunker};
+use crate::chunking::{DEFAULT_CHUNK_SIZE, MAX_CHUNK_SIZE};
+use crate::core::Chunk;
+use crate::error::{ChunkingError, Result};
+use std::ops::Range;
+
+/// A single entry (array element or object member) within a document.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Byte range of the entry, including its key for object members.
+    range: Range<usize>,
+    /// Object key, or `None` for array elements.
+    key: Option<String>,
+    /// Position of the entry within its parent.
+    index: usize,
+    /// Byte offset where the entry's value (or nested content) begins.
+    value_start: usize,
+}
+
+/// Returns the path of a child entry below `parent`.
+fn child_path(parent: &str, entry: &Entry) -> String {
+    match &entry.key {
+        Some(key) if is_identifier(key) => format!("{parent}.{key}"),
+        Some(key) => format!("{parent}['{}']", key.replace('\'', "\\'")),
+        None => format!("{parent}[{}]", entry.index),
+    }
+}
+
+/// Returns the path covering a group of sibling entries.
+fn group_path(parent: &str, group: &[Entry]) -> String {
+    match group {
+        [] => parent.to_string(),
+        [single] => child_path(parent, single),
+        [first, .., last] if first.key.is_none() => {
+            format!("{parent}[{}:{}]", first.index, last.index + 1)
+        }
+        _ => {
+            let keys: Vec<String> = group
+                .iter()
+                .filter_map(|e| e.key.as_ref())
+                .map(|k| format!("'{}'", k.replace('\'', "\\'")))
+                .collect();
+            format!("{parent}[{}]", keys.join(","))
+        }
+    }
+}
+
+/// Returns true if `key` can be written in dot notation.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Validates chunk size shared by both structured chunkers.
+fn validate_size(chunk_size: usize) -> Result<()> {
+    if chunk_size == 0 {
+        return Err(ChunkingError::InvalidConfig {
+            reason: "chunk_size must be > 0".to_string(),
+        }
+        .into());
+    }
+    if chunk_size > MAX_CHUNK_SIZE {
+        return Err(ChunkingError::ChunkTooLarge {
+            size: chunk_size,
+            max: MAX_CHUNK_SIZE,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Groups sibling entries up to `chunk_size`, descending into oversized ones.
+///
+/// `descend` returns the nested entries of an oversized entry, or `None`
+/// if the entry cannot be split further.
+fn group_entries<F>(
+    entries: Vec<Entry>,
+    parent: &str,
+    chunk_size: usize,
+    descend: &F,
+    out: &mut Vec<(Range<usize>, String)>,
+) where
+    F: Fn(&Entry) -> Option<Vec<Entry>>,
+{
+    let mut group: Vec<Entry> = Vec::new();
+
+    let flush = |group: &mut Vec<Entry>, out: &mut Vec<(Range<usize>, String)>| {
+        if let (Some(first), Some(last)) = (group.first(), group.last()) {
+            out.push((first.range.start..last.range.end, group_path(parent, group)));
+        }
+        group.clear();
+    };
+
+    for entry in entries {
+        if entry.range.len() > chunk_size {
+            flush(&mut group, out);
+            let path = child_path(parent, &entry);
+            match descend(&entry) {
+                Some(children) if !children.is_empty() => {
+                    group_entries(children, &path, chunk_size, descend, out);
+                }
+                _ => out.push((entry.range, path)),
+            }
+            continue;
+        }
+
+        if group
+            .first()
+            .is_some_and(|first| entry.range.end - first.range.start > chunk_size)
+        {
+            flush(&mut group, out);
+        }
+        group.push(entry);
+    }
+
+    flush(&mut group, out);
+}
+
+/// Builds chunks from `(range, path)` pieces.
+fn build_chunks(
+    buffer_id: i64,
+    text: &str,
+    pieces: Vec<(Range<usize>, String)>,
+    strategy: &str,
+    metadata: Option<&ChunkMetadata>,
+) -> Vec<Chunk> {
+    let mut chunks = Vec::with_capacity(pieces.len());
+    for (range, path) in pieces {
+        let mut chunk = Chunk::with_strategy(
+            buffer_id,
+            text[range.clone()].to_string(),
+            range,
+            chunks.len(),
+            strategy,
+        );
+        chunk.set_token_count(chunk.estimate_tokens());
+        chunk.metadata.custom = Some(serde_json::json!({ "json_path": path }).to_string());
+        chunks.push(chunk);
+
+        if let Some(meta) = metadata
+            && meta.max_chunks > 0
+            && chunks.len() >= meta.max_chunks
+        {
+            break;
+        }
+    }
+    chunks
+}
+
+/// Falls back to semantic chunking for input that cannot be parsed.
+fn fallback_chunks(
+    buffer_id: i64,
+    text: &str,
+    chunk_size: usize,
+    strategy: &str,
+    metadata: Option<&ChunkMetadata>,
+) -> Result<Vec<Chunk>> {
+    let mut chunks = SemanticChunker::with_size(chunk_size).chunk(buffer_id, text, metadata)?;
+    for chunk in &mut chunks {
+        chunk.metadata.strategy = Some(strategy.to_string());
+    }
+    Ok(chunks)
+}
+
+// ==================== JSON ====================
+
+/// Returns the end offset of the JSON string starting at `start`.
+fn json_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the end offset of the JSON value starting at `start`.
+fn json_value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    match *bytes.get(start)? {
+        b'"' => json_string_end(bytes, start),
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            let mut i = start;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'"' => {
+                        i = json_string_end(bytes, i)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth = depth.checked_sub(1)?;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            None
+        }
+        _ => {
+            let mut i = start;
+            while i < bytes.len()
+                && !matches!(bytes[i], b',' | b'}' | b']')
+                && !bytes[i].is_ascii_whitespace()
+            {
+                i += 1;
+            }
+            Some(i)
+        }
+    }
+}
+
+/// Skips JSON whitespace and separators starting at `i`.
+fn json_skip(bytes: &[u8], mut i: usize, end: usize, separators: &[u8]) -> usize {
+    while i < end && (bytes[i].is_ascii_whitespace() || separators.contains(&bytes[i])) {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the entries of the JSON container whose value starts at `start`.
+fn json_entries(bytes: &[u8], start: usize) -> Option<Vec<Entry>> {
+    let is_object = match *bytes.get(start)? {
+        b'{' => true,
+        b'[' => false,
+        _ => return None,
+    };
+    let end = json_value_end(bytes, start)? - 1;
+    let mut entries = Vec::new();
+    let mut i = start + 1;
+
+    loop {
+        i = json_skip(bytes, i, end, b",");
+        if i >= end {
+            break;
+        }
+        let entry_start = i;
+        let key = if is_object {
+            let key_end = json_string_end(bytes, i)?;
+            let key: String = serde_json::from_slice(&bytes[i..key_end]).ok()?;
+            i = json_skip(bytes, key_end, end, b":");
+            Some(key)
+        } else {
+            None
+        };
+        let value_start = i;
+        let value_end = json_value_end(bytes, value_start)?;
+        entries.push(Entry {
+            range: entry_start..value_end,
+            key,
+            index: entries.len(),
+            value_start,
+        });
+        i = value_end;
+    }
+
+    Some(entries)
+}
+
+/// Chunker that splits JSON documents along their structure.
+///
+/// Each top-level array element or object member becomes a chunk; small
+/// siblings are merged up to `chunk_size` and oversized ones are split by
+/// their nested entries. Single-element chunks are valid JSON on their own.
+/// Input that is not valid JSON falls back to semantic chunking.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::chunking::{Chunker, JsonChunker};
+///
+/// let chunker = JsonChunker::with_size(20);
+/// let text = r#"[{"id": 1}, {"id": 2}, {"id": 3}]"#;
+/// let chunks = chunker.chunk(1, text, None).unwrap();
+/// assert!(chunks.len() > 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonChunker {
+    /// Target chunk size in characters.
+    chunk_size: usize,
+}
+
+impl Default for JsonChunker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonChunker {
+    /// Creates a new JSON chunker with default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Creates a JSON chunker with a custom chunk size.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Target size for each chunk in characters.
+    #[must_use]
+    pub const fn with_size(chunk_size: usize) -> Self {
+        Self { chunk_size }
+    }
+}
+
+impl Chunker for JsonChunker {
+    fn chunk(
+        &self,
+        buffer_id: i64,
+        text: &str,
+        metadata: Option<&ChunkMetadata>,
+    ) -> Result<Vec<Chunk>> {
+        let chunk_size = metadata.map_or(self.chunk_size, |meta| meta.chunk_size);
+        validate_size(chunk_size)?;
+
+        if text.trim().is_empty() {
+            return Ok(vec![]);
+        }
+
+        if serde_json::from_str::<serde::de::IgnoredAny>(text).is_err() {
+            return fallback_chunks(buffer_id, text, chunk_size, self.name(), metadata);
+        }
+
+        let bytes = text.as_bytes();
+        let root = json_skip(bytes, 0, bytes.len(), b"");
+        let Some(entries) = json_entries(bytes, root) else {
+            // Top-level scalar: a single chunk
+            let end = json_value_end(bytes, root).unwrap_or(bytes.len());
+            let pieces = vec![(root..end, "$".to_string())];
+            return Ok(build_chunks(buffer_id, text, pieces, self.name(), metadata));
+        };
+
+        let mut pieces = Vec::new();
+        let descend = |entry: &Entry| json_entries(bytes, entry.value_start);
+        group_entries(entries, "$", chunk_size, &descend, &mut pieces);
+
+        Ok(build_chunks(buffer_id, text, pieces, self.name(), metadata))
+    }
+
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn description(&self) -> &'static str {
+        "JSON chunking by array elements and object entries"
+    }
+}
+
+// ==================== YAML ====================
+
+/// Returns the indentation width of a line.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Returns true if the line carries content (not blank, comment, or marker).
+fn is_content_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---" && trimmed != "..."
+}
+
+/// Parses the key of a YAML mapping line (`key: value`).
+fn yaml_key(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    let colon = trimmed.find(": ").or_else(|| {
+        trimmed
+            .strip_suffix(':')
+            .map(str::len)
+            .filter(|_| !trimmed.starts_with('-'))
+    })?;
+    let key = trimmed[..colon].trim().trim_matches(['"', '\'']);
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+/// Returns the entries of YAML lines in `range` at their shallowest indent.
+///
+/// `skip_first` excludes the first line (an entry header) from the scan; it
+/// is folded into the first child entry so ranges remain contiguous.
+fn yaml_entries(text: &str, range: Range<usize>, skip_first: bool) -> Vec<Entry> {
+    let mut lines: Vec<(usize, &str)> = Vec::new();
+    let mut offset = range.start;
+    for line in text[range.clone()].split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+
+    let body = if skip_first {
+        lines.get(1..).unwrap_or_default()
+    } else {
+        &lines[..]
+    };
+    let Some(base) = body
+        .iter()
+        .filter(|(_, l)| is_content_line(l))
+        .map(|(_, l)| indent_of(l))
+        .min()
+    else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut seq_index = 0;
+    for &(start, line) in body {
+        if is_content_line(line) && indent_of(line) == base {
+            let trimmed = line.trim_start();
+            let (key, index) = if trimmed == "-" || trimmed.starts_with("- ") {
+                seq_index += 1;
+                (None, seq_index - 1)
+            } else {
+                (yaml_key(line), entries.len())
+            };
+            if let Some(last) = entries.last_mut() {
+                last.range.end = start;
+            }
+            entries.push(Entry {
+                range: start..range.end,
+                key,
+                index,
+                value_start: start,
+            });
+        }
+    }
+
+    // Leading comments, blank lines, or an entry header join the first entry.
+    if let Some(first) = entries.first_mut() {
+        first.range.start = range.start;
+    }
+
+    entries
+}
+
+/// Chunker that splits YAML documents along their indentation structure.
+///
+/// Works like [`JsonChunker`]: top-level mapping entries or sequence items
+/// become chunks, merged up to `chunk_size`, with oversized entries split by
+/// their nested keys. Comments and blank lines stay attached to the entry
+/// they precede.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::chunking::{Chunker, YamlChunker};
+///
+/// let chunker = YamlChunker::with_size(20);
+/// let text = "name: demo\nversion: 1\nitems:\n  - a\n  - b\n";
+/// let chunks = chunker.chunk(1, text, None).unwrap();
+/// assert!(!chunks.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct YamlChunker {
+    /// Target chunk size in characters.
+    chunk_size: usize,
+}
+
+impl Default for YamlChunker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl YamlChunker {
+    /// Creates a new YAML chunker with default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Creates a YAML chunker with a custom chunk size.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Target size for each chunk in characters.
+    #[must_use]
+    pub const fn with_size(chunk_size: usize) -> Self {
+        Self { chunk_size }
+    }
+}
+
+impl Chunker for YamlChunker {
+    fn chunk(
+        &self,
+        buffer_id: i64,
+        text: &str,
+        metadata: Option<&ChunkMetadata>,
+    ) -> Result<Vec<Chunk>> {
+        let chunk_size = metadata.map_or(self.chunk_size, |meta| meta.chunk_size);
+        validate_size(chunk_size)?;
+
+        if text.trim().is_empty() {
+            return Ok(vec![]);
+        }
+
+        let entries = yaml_entries(text, 0..text.len(), false);
+        if entries.is_empty() {
+            return fallback_chunks(buffer_id, text, chunk_size, self.name(), metadata);
+        }
+
+        let mut pieces = Vec::new();
+        let descend = |entry: &Entry| Some(yaml_entries(text, entry.range.clone(), true));
+        group_entries(entries, "$", chunk_size, &descend, &mut pieces);
+
+        Ok(build_chunks(buffer_id, text, pieces, self.name(), metadata))
+    }
+
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn description(&self) -> &'static str {
+        "YAML chunking by top-level entries and nested keys"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_path(chunk: &Chunk) -> String {
+        let custom: serde_json::Value =
+            serde_json::from_str(chunk.metadata.custom.as_deref().unwrap()).unwrap();
+        custom["json_path"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_json_chunker_array_of_objects() {
+        let items: Vec<String> = (0..50)
+            .map(|i| format!(r#"{{"id": {i}, "name": "item-{i}", "tags": ["a", "b"]}}"#))
+            .collect();
+        let text = format!("[\n  {}\n]\n", items.join(",\n  "));
+        let chunks = JsonChunker::with_size(200).chunk(1, &text, None).unwrap();
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.size() <= 200);
+            assert_eq!(&text[chunk.byte_range.clone()], chunk.content);
+            assert!(json_path(chunk).starts_with("$["));
+        }
+
+        // Chunks never split an element mid-object
+        let total: usize = chunks
+            .iter()
+            .map(|c| c.content.matches(r#""id""#).count())
+            .sum();
+        assert_eq!(total, 50);
+    }
+
+    #[test]
+    fn test_json_chunker_single_elements_are_valid_json() {
+        let text = r#"[{"a": "xxxxxxxxxxxxxxxxxxxx"}, {"b": "yyyyyyyyyyyyyyyyyyyy"}]"#;
+        let chunks = JsonChunker::with_size(30).chunk(1, text, None).unwrap();
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            assert!(serde_json::from_str::<serde_json::Value>(&chunk.content).is_ok());
+        }
+        assert_eq!(json_path(&chunks[0]), "$[0]");
+        assert_eq!(json_path(&chunks[1]), "$[1]");
+    }
+
+    #[test]
+    fn test_json_chunker_merges_small_elements() {
+        let text = "[1, 2, 3, 4, 5, 6, 7, 8]";
+        let chunks = JsonChunker::with_size(10).chunk(1, text, None).unwrap();
+        assert!(chunks.len() > 1);
+        assert_eq!(json_path(&chunks[0]), "$[0:4]");
+    }
+
+    #[test]
+    fn test_json_chunker_deeply_nested_object() {
+        let filler = "z".repeat(80);
+        let text = format!(
+            r#"{{"config": {{"server": {{"host": "{filler}", "port": "{filler}"}}, "client": {{"retries": 3}}}}, "name": "demo"}}"#
+        );
+        let chunks = JsonChunker::with_size(100).chunk(1, &text, None).unwrap();
+        let paths: Vec<String> = chunks.iter().map(json_path).collect();
+
+        assert!(paths.contains(&"$.config.server.host".to_string()));
+        assert!(paths.contains(&"$.config.server.port".to_string()));
+        assert!(paths.contains(&"$.config.client".to_string()));
+        assert!(paths.contains(&"$.name".to_string()));
+    }
+
+    #[test]
+    fn test_json_chunker_quoted_keys_and_escapes() {
+        let text = r#"{"a key": "va\"l}", "b": [1, {"c": "]"}]}"#;
+        let chunks = JsonChunker::with_size(12).chunk(1, text, None).unwrap();
+        let paths: Vec<String> = chunks.iter().map(json_path).collect();
+        assert!(paths.contains(&"$['a key']".to_string()));
+    }
+
+    #[test]
+    fn test_json_chunker_invalid_falls_back() {
+        let text = "not json at all. ".repeat(10);
+        let chunks = JsonChunker::with_size(50).chunk(1, &text, None).unwrap();
+        assert!(!chunks.is_empty());
+        assert_eq!(chunks[0].metadata.strategy, Some("json".to_string()));
+    }
+
+    #[test]
+    fn test_json_chunker_scalar_and_empty() {
+        let chunks = JsonChunker::new().chunk(1, "  42 ", None).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "42");
+        assert!(JsonChunker::new().chunk(1, "", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_yaml_chunker_top_level_entries() {
+        let text = "# header\nname: demo\nversion: 1\nitems:\n  - a\n  - b\n";
+        let chunks = YamlChunker::with_size(25).chunk(1, text, None).unwrap();
+        let paths: Vec<String> = chunks.iter().map(json_path).collect();
+        assert!(chunks[0].content.starts_with("# header"));
+        assert!(paths.iter().any(|p| p.contains("items")));
+        assert_eq!(chunks.last().unwrap().byte_range.end, text.len());
+    }
+
+    #[test]
+    fn test_yaml_chunker_nested_split() {
+        let long = "x".repeat(60);
+        let text = format!("server:\n  host: {long}\n  port: {long}\nname: demo\n");
+        let chunks = YamlChunker::with_size(80).chunk(1, &text, None).unwrap();
+        let paths: Vec<String> = chunks.iter().map(json_path).collect();
+        assert!(paths.contains(&"$.server.host".to_string()));
+        assert!(paths.contains(&"$.server.port".to_string()));
+        assert!(chunks[0].content.starts_with("server:"));
+    }
+
+    #[test]
+    fn test_yaml_chunker_sequence() {
+        let text = "- one\n- two\n- three\n";
+        let chunks = YamlChunker::with_size(6).chunk(1, text, None).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(json_path(&chunks[2]), "$[2]");
+    }
+}
@@ -0,0 +1,490 @@
+//! Syntax-tree item discovery for the code chunker.
+//!
+//! Parses source text with a tree-sitter grammar and lists its top-level
+//! items (functions, types, impls, classes) as line-aligned byte ranges,
+//! including the doc comments and attributes directly above each one.
+//! Containers (impls, traits, classes, modules) also list their members so
+//! the chunker can split an oversized container between them.
+
+use crate::chunking::code::CodeItem;
+use std::ops::Range;
+use tree_sitter::{Node, Parser};
+
+/// Node kinds the code chunker needs from one tree-sitter grammar.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Grammar {
+    /// Loads the tree-sitter language.
+    language: fn() -> tree_sitter::Language,
+    /// Kinds that are items in their own right.
+    items: &'static [&'static str],
+    /// Kinds wrapping an item, with the field holding it (`None` searches
+    /// the named children).
+    wrappers: &'static [(&'static str, Option<&'static str>)],
+    /// Item kinds whose members can be split out, with the field holding
+    /// them.
+    containers: &'static [(&'static str, &'static str)],
+    /// Kinds whose children are listed as if they were siblings, such as
+    /// preprocessor conditionals.
+    transparent: &'static [&'static str],
+    /// Comment and attribute kinds that attach to the item below them.
+    attached: &'static [&'static str],
+}
+
+/// Rust items.
+pub(crate) const RUST: Grammar = Grammar {
+    language: || tree_sitter_rust::LANGUAGE.into(),
+    items: &[
+        "function_item",
+        "function_signature_item",
+        "struct_item",
+        "enum_item",
+        "union_item",
+        "trait_item",
+        "impl_item",
+        "mod_item",
+        "const_item",
+        "static_item",
+        "type_item",
+        "macro_definition",
+    ],
+    wrappers: &[],
+    containers: &[
+        ("impl_item", "body"),
+        ("trait_item", "body"),
+        ("mod_item", "body"),
+    ],
+    transparent: &[],
+    attached: &["line_comment", "block_comment", "attribute_item"],
+};
+
+/// Python items.
+pub(crate) const PYTHON: Grammar = Grammar {
+    language: || tree_sitter_python::LANGUAGE.into(),
+    items: &["function_definition", "class_definition"],
+    wrappers: &[("decorated_definition", Some("definition"))],
+    containers: &[("class_definition", "body")],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// JavaScript (including JSX) items.
+pub(crate) const JAVASCRIPT: Grammar = Grammar {
+    language: || tree_sitter_javascript::LANGUAGE.into(),
+    items: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "class_declaration",
+        "method_definition",
+        "variable_declarator",
+    ],
+    wrappers: &[
+        ("export_statement", Some("declaration")),
+        ("lexical_declaration", None),
+        ("variable_declaration", None),
+    ],
+    containers: &[("class_declaration", "body")],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// TypeScript items.
+pub(crate) const TYPESCRIPT: Grammar = Grammar {
+    language: || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+    items: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "function_signature",
+        "class_declaration",
+        "abstract_class_declaration",
+        "interface_declaration",
+        "type_alias_declaration",
+        "enum_declaration",
+        "internal_module",
+        "module",
+        "method_definition",
+        "method_signature",
+        "abstract_method_signature",
+        "variable_declarator",
+    ],
+    wrappers: &[
+        ("export_statement", Some("declaration")),
+        ("lexical_declaration", None),
+        ("variable_declaration", None),
+        ("ambient_declaration", None),
+        ("expression_statement", None),
+    ],
+    containers: &[
+        ("class_declaration", "body"),
+        ("abstract_class_declaration", "body"),
+        ("interface_declaration", "body"),
+        ("internal_module", "body"),
+        ("module", "body"),
+    ],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// Go items.
+pub(crate) const GO: Grammar = Grammar {
+    language: || tree_sitter_go::LANGUAGE.into(),
+    items: &["function_declaration", "method_declaration", "type_spec"],
+    wrappers: &[("type_declaration", None)],
+    containers: &[],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// Java items.
+pub(crate) const JAVA: Grammar = Grammar {
+    language: || tree_sitter_java::LANGUAGE.into(),
+    items: &[
+        "class_declaration",
+        "interface_declaration",
+        "enum_declaration",
+        "record_declaration",
+        "annotation_type_declaration",
+        "method_declaration",
+        "constructor_declaration",
+    ],
+    wrappers: &[],
+    containers: &[
+        ("class_declaration", "body"),
+        ("interface_declaration", "body"),
+        ("enum_declaration", "body"),
+        ("record_declaration", "body"),
+    ],
+    transparent: &[],
+    attached: &["line_comment", "block_comment"],
+};
+
+/// C items.
+pub(crate) const C: Grammar = Grammar {
+    language: || tree_sitter_c::LANGUAGE.into(),
+    items: &[
+        "function_definition",
+        "declaration",
+        "type_definition",
+        "struct_specifier",
+        "union_specifier",
+        "enum_specifier",
+    ],
+    wrappers: &[],
+    containers: &[],
+    transparent: &[
+        "preproc_ifdef",
+        "preproc_if",
+        "preproc_else",
+        "preproc_elif",
+        "linkage_specification",
+        "declaration_list",
+    ],
+    attached: &["comment"],
+};
+
+/// C++ items.
+pub(crate) const CPP: Grammar = Grammar {
+    language: || tree_sitter_cpp::LANGUAGE.into(),
+    items: &[
+        "function_definition",
+        "declaration",
+        "type_definition",
+        "alias_declaration",
+        "class_specifier",
+        "struct_specifier",
+        "union_specifier",
+        "enum_specifier",
+        "namespace_definition",
+    ],
+    wrappers: &[("template_declaration", None)],
+    containers: &[
+        ("class_specifier", "body"),
+        ("struct_specifier", "body"),
+        ("namespace_definition", "body"),
+    ],
+    transparent: &[
+        "preproc_ifdef",
+        "preproc_if",
+        "preproc_else",
+        "preproc_elif",
+        "linkage_specification",
+        "declaration_list",
+    ],
+    attached: &["comment"],
+};
+
+/// Ruby items.
+pub(crate) const RUBY: Grammar = Grammar {
+    language: || tree_sitter_ruby::LANGUAGE.into(),
+    items: &["method", "singleton_method", "class", "module"],
+    wrappers: &[],
+    containers: &[("class", "body"), ("module", "body")],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// PHP items.
+pub(crate) const PHP: Grammar = Grammar {
+    language: || tree_sitter_php::LANGUAGE_PHP.into(),
+    items: &[
+        "function_definition",
+        "class_declaration",
+        "interface_declaration",
+        "trait_declaration",
+        "enum_declaration",
+        "namespace_definition",
+        "method_declaration",
+    ],
+    wrappers: &[],
+    containers: &[
+        ("class_declaration", "body"),
+        ("interface_declaration", "body"),
+        ("trait_declaration", "body"),
+        ("enum_declaration", "body"),
+        ("namespace_definition", "body"),
+    ],
+    transparent: &[],
+    attached: &["comment"],
+};
+
+/// Splits `text` into items and the gaps between them.
+///
+/// The items cover the text contiguously. Returns `None` when the text does
+/// not parse cleanly or has no named items, so the caller can fall back to
+/// the lexer.
+pub(crate) fn parse_items(text: &str, grammar: &Grammar) -> Option<Vec<CodeItem>> {
+    let mut parser = Parser::new();
+    parser.set_language(&(grammar.language)()).ok()?;
+    let tree = parser.parse(text, None)?;
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+
+    let items = collect(grammar, text, root, 0..text.len());
+    items.iter().any(|i| i.symbol.is_some()).then_some(items)
+}
+
+/// Lists the items among `parent`'s children, filling `range` with gaps.
+fn collect(grammar: &Grammar, text: &str, parent: Node<'_>, range: Range<usize>) -> Vec<CodeItem> {
+    let mut children = Vec::new();
+    flatten(grammar, parent, &mut children);
+
+    let mut items = Vec::new();
+    let mut cursor = range.start;
+    // Byte range of the comments and attributes directly above the next node
+    let mut header: Option<Range<usize>> = None;
+
+    for child in children {
+        if grammar.attached.contains(&child.kind()) && !is_inner_doc(text, child) {
+            header = match header {
+                Some(h) if adjacent(text, h.end, child.start_byte()) => {
+                    Some(h.start..child.end_byte())
+                }
+                _ => Some(child.start_byte()..child.end_byte()),
+            };
+            continue;
+        }
+
+        let start = header
+            .take()
+            .filter(|h| adjacent(text, h.end, child.start_byte()))
+            .map_or(child.start_byte(), |h| h.start);
+        let Some(item) = item_node(grammar, child) else {
+            continue;
+        };
+        let Some(symbol) = item_name(text, item) else {
+            continue;
+        };
+
+        let start = line_start(text, start).max(cursor);
+        let end = line_end(text, child.end_byte()).min(range.end);
+        if end <= start {
+            continue;
+        }
+        if start > cursor {
+            items.push(CodeItem::gap(cursor..start));
+        }
+        items.push(CodeItem {
+            members: members(grammar, text, item, start..end),
+            range: start..end,
+            symbol: Some(symbol),
+        });
+        cursor = end;
+    }
+
+    if cursor < range.end {
+        items.push(CodeItem::gap(cursor..range.end));
+    }
+    items
+}
+
+/// Collects the named children of `node`, descending into transparent kinds.
+fn flatten<'t>(grammar: &Grammar, node: Node<'t>, out: &mut Vec<Node<'t>>) {
+    let mut walker = node.walk();
+    for child in node.named_children(&mut walker) {
+        if grammar.transparent.contains(&child.kind()) {
+            flatten(grammar, child, out);
+        } else {
+            out.push(child);
+        }
+    }
+}
+
+/// Lists the members of a container item, or nothing for other items.
+fn members(grammar: &Grammar, text: &str, item: Node<'_>, range: Range<usize>) -> Vec<CodeItem> {
+    let Some(&(_, field)) = grammar
+        .containers
+        .iter()
+        .find(|(kind, _)| *kind == item.kind())
+    else {
+        return Vec::new();
+    };
+    let Some(body) = item.child_by_field_name(field) else {
+        return Vec::new();
+    };
+
+    let members = collect(grammar, text, body, range);
+    if members.iter().any(|m| m.symbol.is_some()) {
+        members
+    } else {
+        Vec::new()
+    }
+}
+
+/// Returns the item `node` holds, looking inside wrappers (exports,
+/// decorators), or `None` if it holds no item.
+fn item_node<'t>(grammar: &Grammar, node: Node<'t>) -> Option<Node<'t>> {
+    let Some(&(_, field)) = grammar
+        .wrappers
+        .iter()
+        .find(|(kind, _)| *kind == node.kind())
+    else {
+        return grammar.items.contains(&node.kind()).then_some(node);
+    };
+
+    match field {
+        Some(field) => node
+            .child_by_field_name(field)
+            .and_then(|inner| item_node(grammar, inner)),
+        None => {
+            let mut walker = node.walk();
+            node.named_children(&mut walker)
+                .find_map(|child| item_node(grammar, child))
+        }
+    }
+}
+
+/// Extracts the name of an item node.
+fn item_name(text: &str, item: Node<'_>) -> Option<String> {
+    let name = match item.kind() {
+        // `impl<T> Display for Wrapper<T>` is named after `Wrapper`
+        "impl_item" => item
+            .child_by_field_name("type")
+            .map(|ty| ty.child_by_field_name("type").unwrap_or(ty)),
+        _ => item
+            .child_by_field_name("name")
+            .or_else(|| declarator_name(item)),
+    }?;
+
+    name.utf8_text(text.as_bytes()).ok().map(String::from)
+}
+
+/// Follows C-style declarators (`*parse(int argc)`) down to the identifier.
+fn declarator_name(node: Node<'_>) -> Option<Node<'_>> {
+    let mut last = node.child_by_field_name("declarator")?;
+    while let Some(inner) = last.child_by_field_name("declarator") {
+        last = inner;
+    }
+    Some(last.child_by_field_name("name").unwrap_or(last))
+}
+
+/// Returns true for Rust inner doc comments, which document the enclosing
+/// module rather than the next item.
+fn is_inner_doc(text: &str, node: Node<'_>) -> bool {
+    let source = &text[node.start_byte()..];
+    source.starts_with("//!") || source.starts_with("/*!")
+}
+
+/// Returns true if only whitespace without a blank line separates `end`
+/// from `start`.
+fn adjacent(text: &str, end: usize, start: usize) -> bool {
+    let between = &text[end..start.max(end)];
+    let newlines = between.matches('\n').count() + usize::from(text[..end].ends_with('\n'));
+    between.trim().is_empty() && newlines <= 1
+}
+
+/// Returns the offset of the start of the line containing `pos`.
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Returns the offset just past the line containing the byte before `pos`.
+fn line_end(text: &str, pos: usize) -> usize {
+    if pos == 0 || text[..pos].ends_with('\n') {
+        return pos;
+    }
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(items: &[CodeItem]) -> Vec<&str> {
+        items.iter().filter_map(|i| i.symbol.as_deref()).collect()
+    }
+
+    fn assert_contiguous(items: &[CodeItem], range: Range<usize>) {
+        assert_eq!(items.first().unwrap().range.start, range.start);
+        assert_eq!(items.last().unwrap().range.end, range.end);
+        for pair in items.windows(2) {
+            assert_eq!(pair[0].range.end, pair[1].range.start);
+        }
+    }
+
+    #[test]
+    fn test_rust_items_and_members() {
+        let code = "//! Crate docs.\n\nuse std::fmt;\n\n/// A wrapper.\n#[derive(Debug)]\nstruct Wrapper<T>(T);\n\nimpl<T: fmt::Debug> fmt::Display for Wrapper<T> {\n    /// Formats it.\n    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {\n        write!(f, \"{{}}\")\n    }\n}\n\nmacro_rules! wrap {\n    ($x:expr) => { Wrapper($x) };\n}\n";
+        let items = parse_items(code, &RUST).unwrap();
+
+        assert_contiguous(&items, 0..code.len());
+        assert_eq!(names(&items), vec!["Wrapper", "Wrapper", "wrap"]);
+
+        let wrapper = items.iter().find(|i| i.symbol.is_some()).unwrap();
+        assert!(code[wrapper.range.clone()].starts_with("/// A wrapper.\n#[derive(Debug)]"));
+        assert!(items[0].symbol.is_none());
+        assert!(code[items[0].range.clone()].starts_with("//! Crate docs."));
+
+        let imp = items.iter().find(|i| !i.members.is_empty()).unwrap();
+        assert_contiguous(&imp.members, imp.range.clone());
+        assert_eq!(names(&imp.members), vec!["fmt"]);
+        let fmt = imp.members.iter().find(|m| m.symbol.is_some()).unwrap();
+        assert!(code[fmt.range.clone()].starts_with("    /// Formats it.\n"));
+    }
+
+    #[test]
+    fn test_python_decorated_class_members() {
+        let code = "import os\n\n\n@dataclass\nclass Point:\n    x: int\n\n    @property\n    def norm(self):\n        return abs(self.x)\n";
+        let items = parse_items(code, &PYTHON).unwrap();
+
+        assert_eq!(names(&items), vec!["Point"]);
+        let point = items.iter().find(|i| i.symbol.is_some()).unwrap();
+        assert!(code[point.range.clone()].starts_with("@dataclass\n"));
+        assert_eq!(names(&point.members), vec!["norm"]);
+    }
+
+    #[test]
+    fn test_c_header_guard_is_transparent() {
+        let code = "#ifndef UTIL_H\n#define UTIL_H\n\n/* Parses it. */\nint *parse(const char *s);\n\nstruct point { int x; };\n\n#endif\n";
+        let items = parse_items(code, &C).unwrap();
+
+        assert_contiguous(&items, 0..code.len());
+        assert_eq!(names(&items), vec!["parse", "point"]);
+    }
+
+    #[test]
+    fn test_syntax_errors_fall_back() {
+        assert!(parse_items("fn broken( {\n", &RUST).is_none());
+        assert!(parse_items("use std::fmt;\n", &RUST).is_none());
+    }
+}
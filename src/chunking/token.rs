@@ -0,0 +1,412 @@
+//! Token-aware chunking strategy.
+//!
+//! Splits text on tokenizer boundaries so that `chunk_size` and `overlap`
+//! are measured in tokens rather than bytes. Tokens come from the
+//! `cl100k_base` BPE encoding (via `tiktoken-rs`). Byte ranges still map
+//! exactly onto the source text, so `chunk get` and `peek` work unchanged.
+
+use crate::chunking::traits::{ChunkMetadata, Chunker};
+use crate::chunking::{DEFAULT_OVERLAP, MAX_CHUNK_SIZE};
+use crate::core::Chunk;
+use crate::error::{ChunkingError, Result};
+use std::ops::Range;
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+
+/// Default chunk size in tokens (matches `DEFAULT_CHUNK_SIZE` at ~4 chars/token).
+pub const DEFAULT_TOKEN_CHUNK_SIZE: usize = 750;
+
+/// Default overlap in tokens.
+pub const DEFAULT_TOKEN_OVERLAP: usize = DEFAULT_OVERLAP / 4;
+
+/// A run of tokens that starts and ends on character boundaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TokenSpan {
+    /// Byte range of the span in the source text.
+    range: Range<usize>,
+    /// Number of tokens in the span.
+    cost: usize,
+}
+
+/// Returns the shared `cl100k_base` encoder, loading it on first use.
+fn encoder() -> Result<&'static CoreBPE> {
+    static ENCODER: OnceLock<std::result::Result<CoreBPE, String>> = OnceLock::new();
+
+    ENCODER
+        .get_or_init(|| tiktoken_rs::cl100k_base().map_err(|e| e.to_string()))
+        .as_ref()
+        .map_err(|reason| ChunkingError::Tokenizer(reason.clone()).into())
+}
+
+/// Splits text into token spans.
+///
+/// Each span is usually a single token. A token can end in the middle of a
+/// multi-byte character (common for CJK text and emoji); such tokens are
+/// grouped with the following ones until the span ends on a character
+/// boundary, so every span can be sliced out of `text`.
+fn tokenize(text: &str) -> Result<Vec<TokenSpan>> {
+    let bpe = encoder()?;
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut cost = 0;
+
+    for token in bpe.encode_ordinary(text) {
+        end += bpe
+            .decode_bytes(&[token])
+            .map_err(|e| ChunkingError::Tokenizer(e.to_string()))?
+            .len();
+        cost += 1;
+        if text.is_char_boundary(end) {
+            spans.push(TokenSpan {
+                range: start..end,
+                cost,
+            });
+            start = end;
+            cost = 0;
+        }
+    }
+
+    if end != text.len() {
+        return Err(ChunkingError::Tokenizer(format!(
+            "tokens cover {end} of {} bytes",
+            text.len()
+        ))
+        .into());
+    }
+
+    Ok(spans)
+}
+
+/// Counts `cl100k_base` tokens in text.
+///
+/// # Errors
+///
+/// Returns an error if the tokenizer cannot be loaded.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::chunking::token::count_tokens;
+///
+/// assert_eq!(count_tokens("").unwrap(), 0);
+/// assert_eq!(count_tokens("Hello, world!").unwrap(), 4);
+/// ```
+pub fn count_tokens(text: &str) -> Result<usize> {
+    Ok(encoder()?.encode_ordinary(text).len())
+}
+
+/// Chunker that measures chunk size and overlap in tokens.
+///
+/// Chunks always end on a token boundary, and no chunk exceeds the
+/// configured token budget when encoded on its own, unless a single
+/// character needs more tokens than the budget.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::chunking::{Chunker, TokenChunker};
+/// use rlm_rs::chunking::token::count_tokens;
+///
+/// let chunker = TokenChunker::with_size(16);
+/// let text = "The quick brown fox jumps over the lazy dog. ".repeat(10);
+/// let chunks = chunker.chunk(1, &text, None).unwrap();
+/// for chunk in &chunks {
+///     assert!(count_tokens(&chunk.content).unwrap() <= 16);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenChunker {
+    /// Target chunk size in tokens.
+    chunk_size: usize,
+    /// Overlap between consecutive chunks in tokens.
+    overlap: usize,
+}
+
+impl Default for TokenChunker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenChunker {
+    /// Creates a new token chunker with default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_TOKEN_CHUNK_SIZE,
+            overlap: DEFAULT_TOKEN_OVERLAP,
+        }
+    }
+
+    /// Creates a token chunker with custom size and no overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Maximum tokens per chunk.
+    #[must_use]
+    pub const fn with_size(chunk_size: usize) -> Self {
+        Self {
+            chunk_size,
+            overlap: 0,
+        }
+    }
+
+    /// Creates a token chunker with custom size and overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Maximum tokens per chunk.
+    /// * `overlap` - Tokens shared between consecutive chunks.
+    #[must_use]
+    pub const fn with_size_and_overlap(chunk_size: usize, overlap: usize) -> Self {
+        Self {
+            chunk_size,
+            overlap,
+        }
+    }
+}
+
+impl Chunker for TokenChunker {
+    fn chunk(
+        &self,
+        buffer_id: i64,
+        text: &str,
+        metadata: Option<&ChunkMetadata>,
+    ) -> Result<Vec<Chunk>> {
+        let (chunk_size, overlap) = metadata.map_or((self.chunk_size, self.overlap), |meta| {
+            (meta.chunk_size, meta.overlap)
+        });
+
+        if chunk_size == 0 {
+            return Err(ChunkingError::InvalidConfig {
+                reason: "chunk_size must be > 0".to_string(),
+            }
+            .into());
+        }
+        if chunk_size > MAX_CHUNK_SIZE {
+            return Err(ChunkingError::ChunkTooLarge {
+                size: chunk_size,
+                max: MAX_CHUNK_SIZE,
+            }
+            .into());
+        }
+        if overlap >= chunk_size {
+            return Err(ChunkingError::OverlapTooLarge {
+                overlap,
+                size: chunk_size,
+            }
+            .into());
+        }
+
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let spans = tokenize(text)?;
+        let mut chunks = Vec::new();
+        let mut first = 0;
+
+        while first < spans.len() {
+            // Extend while the token budget allows; always take at least one span.
+            let mut last = first;
+            let mut tokens = spans[first].cost;
+            while last + 1 < spans.len() && tokens + spans[last + 1].cost <= chunk_size {
+                last += 1;
+                tokens += spans[last].cost;
+            }
+
+            // Encoded on its own, a chunk can merge differently at its edges
+            // than it did inside the full text, so confirm the real count.
+            let start = spans[first].range.start;
+            tokens = count_tokens(&text[start..spans[last].range.end])?;
+            while tokens > chunk_size && last > first {
+                last -= 1;
+                tokens = count_tokens(&text[start..spans[last].range.end])?;
+            }
+            let end = spans[last].range.end;
+            let mut chunk = Chunk::with_strategy(
+                buffer_id,
+                text[start..end].to_string(),
+                start..end,
+                chunks.len(),
+                self.name(),
+            );
+            chunk.set_token_count(tokens);
+            if !chunks.is_empty() && overlap > 0 {
+                chunk.set_has_overlap(true);
+            }
+            chunks.push(chunk);
+
+            if let Some(meta) = metadata
+                && meta.max_chunks > 0
+                && chunks.len() >= meta.max_chunks
+            {
+                break;
+            }
+
+            if last + 1 >= spans.len() {
+                break;
+            }
+
+            // Step back over up to `overlap` tokens, always making progress.
+            let mut next = last + 1;
+            let mut overlap_tokens = 0;
+            while next > first + 1 && overlap_tokens + spans[next - 1].cost <= overlap {
+                next -= 1;
+                overlap_tokens += spans[next].cost;
+            }
+            first = next;
+        }
+
+        Ok(chunks)
+    }
+
+    fn name(&self) -> &'static str {
+        "token"
+    }
+
+    fn description(&self) -> &'static str {
+        "Token-aware chunking with sizes measured in cl100k_base tokens"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_chunker_empty_text() {
+        let chunker = TokenChunker::with_size(10);
+        assert!(chunker.chunk(1, "", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_covers_input() {
+        let text = "Hello, world! 12345 世界 🎉\n\tend";
+        let spans = tokenize(text).unwrap();
+        let mut pos = 0;
+        for span in &spans {
+            assert_eq!(span.range.start, pos);
+            assert!(span.cost > 0);
+            pos = span.range.end;
+        }
+        assert_eq!(pos, text.len());
+    }
+
+    #[test]
+    fn test_tokenize_groups_partial_characters() {
+        let text = "日本語のテキスト🎉";
+        let spans = tokenize(text).unwrap();
+        let total: usize = spans.iter().map(|span| span.cost).sum();
+        assert_eq!(total, count_tokens(text).unwrap());
+        for span in &spans {
+            assert!(text.is_char_boundary(span.range.start));
+            assert!(text.is_char_boundary(span.range.end));
+        }
+    }
+
+    #[test]
+    fn test_count_tokens_matches_cl100k_base() {
+        let bpe = encoder().unwrap();
+        assert_eq!(
+            bpe.encode_ordinary("Hello, world!"),
+            vec![9906, 11, 1917, 0]
+        );
+        assert_eq!(
+            bpe.encode_ordinary("tiktoken is great!"),
+            vec![83, 1609, 5963, 374, 2294, 0]
+        );
+
+        for (text, expected) in [
+            ("", 0),
+            ("Hello, world!", 4),
+            ("tiktoken is great!", 6),
+            ("The quick brown fox jumps over the lazy dog.", 10),
+            ("12345678", 3),
+        ] {
+            assert_eq!(count_tokens(text).unwrap(), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_token_chunker_respects_budget_mixed_input() {
+        let bpe = encoder().unwrap();
+        let text =
+            "Mixed ASCII text with 日本語の文章 and emoji 🎉🎉 plus numbers 1234567. ".repeat(40);
+        for size in [5, 16, 64] {
+            let chunker = TokenChunker::with_size(size);
+            let chunks = chunker.chunk(1, &text, None).unwrap();
+            assert!(chunks.len() > 1);
+            for chunk in &chunks {
+                let tokens = bpe.encode_ordinary(&chunk.content).len();
+                assert!(tokens <= size, "{tokens} > {size}: {:?}", chunk.content);
+                assert_eq!(&text[chunk.byte_range.clone()], chunk.content);
+                assert_eq!(chunk.metadata.token_count, Some(tokens));
+            }
+            assert_eq!(chunks.last().unwrap().byte_range.end, text.len());
+        }
+    }
+
+    #[test]
+    fn test_token_chunker_respects_budget_cjk_input() {
+        let bpe = encoder().unwrap();
+        let text = "東京は日本の首都です。人口は約千四百万人です。".repeat(30);
+        let chunks = TokenChunker::with_size(20).chunk(1, &text, None).unwrap();
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(bpe.encode_ordinary(&chunk.content).len() <= 20);
+        }
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].byte_range.end, pair[1].byte_range.start);
+        }
+    }
+
+    #[test]
+    fn test_token_chunker_contiguous_without_overlap() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta ".repeat(10);
+        let chunks = TokenChunker::with_size(8).chunk(1, &text, None).unwrap();
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].byte_range.end, pair[1].byte_range.start);
+        }
+        assert_eq!(chunks.last().unwrap().byte_range.end, text.len());
+    }
+
+    #[test]
+    fn test_token_chunker_with_overlap() {
+        let text = "one two three four five six seven eight nine ten ".repeat(5);
+        let chunks = TokenChunker::with_size_and_overlap(10, 3)
+            .chunk(1, &text, None)
+            .unwrap();
+        assert!(chunks.len() > 1);
+        for pair in chunks.windows(2) {
+            assert!(pair[1].byte_range.start < pair[0].byte_range.end);
+            assert!(pair[1].byte_range.start > pair[0].byte_range.start);
+        }
+        assert!(chunks[1].metadata.has_overlap);
+    }
+
+    #[test]
+    fn test_token_chunker_metadata_override() {
+        let text = "word ".repeat(100);
+        let meta = ChunkMetadata::with_size_and_overlap(10, 0);
+        let chunks = TokenChunker::new().chunk(1, &text, Some(&meta)).unwrap();
+        assert!(chunks.len() > 1);
+    }
+
+    #[test]
+    fn test_token_chunker_invalid_config() {
+        assert!(TokenChunker::with_size(0).chunk(1, "text", None).is_err());
+        assert!(
+            TokenChunker::with_size_and_overlap(5, 5)
+                .chunk(1, "text", None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_token_chunker_strategy_name() {
+        let chunks = TokenChunker::new().chunk(1, "Hello", None).unwrap();
+        assert_eq!(chunks[0].metadata.strategy, Some("token".to_string()));
+    }
+}
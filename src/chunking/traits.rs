@@ -120,6 +120,9 @@ pub struct ChunkMetadata {
 
     /// Maximum chunks to produce (0 = unlimited).
     pub max_chunks: usize,
+
+    /// Chunks smaller than this are merged into their predecessor (0 = disabled).
+    pub min_chunk_size: usize,
 }
 
 impl ChunkMetadata {
@@ -189,6 +192,13 @@ impl ChunkMetadata {
         self.max_chunks = max;
         self
     }
+
+    /// Sets the minimum chunk size below which chunks are merged.
+    #[must_use]
+    pub const fn min_chunk_size(mut self, size: usize) -> Self {
+        self.min_chunk_size = size;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +212,8 @@ mod tests {
         assert_eq!(meta.overlap, super::super::DEFAULT_OVERLAP);
         assert!(meta.preserve_lines);
         assert!(!meta.preserve_sentences);
+        assert_eq!(meta.min_chunk_size, 0);
+        assert_eq!(ChunkMetadata::default().min_chunk_size, 0);
     }
 
     #[test]
@@ -210,7 +222,8 @@ mod tests {
             .source("test.txt")
             .content_type("txt")
             .preserve_sentences(true)
-            .max_chunks(10);
+            .max_chunks(10)
+            .min_chunk_size(50);
 
         assert_eq!(meta.chunk_size, 1000);
         assert_eq!(meta.overlap, 100);
@@ -218,6 +231,7 @@ mod tests {
         assert_eq!(meta.content_type, Some("txt".to_string()));
         assert!(meta.preserve_sentences);
         assert_eq!(meta.max_chunks, 10);
+        assert_eq!(meta.min_chunk_size, 50);
     }
 
     #[test]
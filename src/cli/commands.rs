@@ -11,21 +11,33 @@
 #![allow(clippy::redundant_closure_for_method_calls)]
 #![allow(clippy::if_not_else)]
 
-use crate::chunking::{ChunkerMetadata, create_chunker};
+use crate::api::{LoadOptions, LoadResult, ingest_buffer, ingest_chunked_buffer};
+use crate::chunking::{
+    ChunkerMetadata, DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP, create_chunker, default_size_and_overlap,
+};
 use crate::cli::output::{
-    GrepMatch, OutputFormat, format_buffer, format_buffer_list, format_chunk_indices,
-    format_grep_matches, format_peek, format_status, format_write_chunks_result,
+    ColorChoice, FormatChoice, GrepMatch, OutputFormat, TextStyle, format_buffer,
+    format_buffer_list, format_chunk_indices, format_grep_matches, format_ndjson, format_peek,
+    format_status, format_write_chunks_result,
+};
+use crate::cli::parser::{
+    BufferCommands, ChunkCommands, Cli, Commands, StorageCommands, TagCommands,
 };
-use crate::cli::parser::{ChunkCommands, Cli, Commands};
-use crate::core::{Buffer, Context, ContextValue};
+use crate::core::{Buffer, Chunk, Context, ContextValue, LineDiff};
 use crate::embedding::create_embedder;
 use crate::error::{CommandError, Result, StorageError};
-use crate::io::{read_file, write_file};
-use crate::search::{SearchConfig, SearchResult, embed_buffer_chunks, hybrid_search};
-use crate::storage::{SqliteStorage, Storage};
-use regex::RegexBuilder;
+use crate::io::{
+    DEFAULT_HTTP_TIMEOUT, DEFAULT_MAX_DOWNLOAD_SIZE, fetch_url, is_pdf, is_url, read_file,
+    read_file_content, read_pdf, write_file,
+};
+use crate::search::{
+    ContextGroup, FusionMethod, MMR_CANDIDATE_FACTOR, SearchConfig, SearchResult, SimilarityMetric,
+    expand_context, hybrid_search, mmr_rerank,
+};
+use crate::storage::{CURRENT_SCHEMA_VERSION, SqliteStorage, Storage};
+use regex::{Regex, RegexBuilder};
 use std::fmt::Write as FmtWrite;
-use std::io::{self, Read, Write as IoWrite};
+use std::io::{self, BufRead, IsTerminal, Read, Write as IoWrite};
 
 /// Executes the CLI command.
 ///
@@ -40,21 +52,46 @@ use std::io::{self, Read, Write as IoWrite};
 /// # Errors
 ///
 /// Returns an error if the command fails to execute.
-#[allow(clippy::too_many_lines)]
 pub fn execute(cli: &Cli) -> Result<String> {
-    let format = OutputFormat::parse(&cli.format);
+    let choice = match (FormatChoice::parse(&cli.format), &cli.command) {
+        // Exports emit stored content or their own `--as` serialization,
+        // never a result to convert
+        (choice, Commands::ExportBuffers { .. }) => FormatChoice::Native(choice.output_format()),
+        (choice, _) => choice,
+    };
+    execute_as(cli, choice.output_format())
+        .map(|output| choice.convert(output))
+        .map_err(|e| match e {
+            crate::Error::Command(CommandError::NoResults(output)) => {
+                CommandError::NoResults(choice.convert(output)).into()
+            }
+            e => e,
+        })
+}
+
+/// Executes the CLI command with the given output format.
+#[allow(clippy::too_many_lines)]
+fn execute_as(cli: &Cli, format: OutputFormat) -> Result<String> {
+    let style = TextStyle::detect(ColorChoice::parse(&cli.color), format);
     let db_path = cli.get_db_path();
 
     match &cli.command {
         Commands::Init { force } => cmd_init(&db_path, *force, format),
-        Commands::Status => cmd_status(&db_path, format),
+        Commands::Status => cmd_status(&db_path, format, style),
         Commands::Reset { yes } => cmd_reset(&db_path, *yes, format),
+        Commands::Migrate { dry_run } => cmd_migrate(&db_path, *dry_run, format),
+        Commands::Doctor { check_embedder } => cmd_doctor(&db_path, *check_embedder, format),
+        Commands::Backup { path } => cmd_backup(&db_path, path, format),
+        Commands::Restore { path, force } => cmd_restore(&db_path, path, *force, format),
         Commands::Load {
             file,
             name,
             chunker,
             chunk_size,
             overlap,
+            min_chunk_size,
+            dedup,
+            update_if_exists,
         } => cmd_load(
             &db_path,
             file,
@@ -62,13 +99,40 @@ pub fn execute(cli: &Cli) -> Result<String> {
             chunker,
             *chunk_size,
             *overlap,
+            *min_chunk_size,
+            DuplicatePolicy::new(*dedup, *update_if_exists),
             format,
         ),
         Commands::ListBuffers => cmd_list_buffers(&db_path, format),
         Commands::ShowBuffer { buffer, chunks } => {
             cmd_show_buffer(&db_path, buffer, *chunks, format)
         }
-        Commands::DeleteBuffer { buffer, yes } => cmd_delete_buffer(&db_path, buffer, *yes, format),
+        Commands::BufferStats { buffer } => cmd_buffer_stats(&db_path, buffer, format),
+        Commands::DeleteBuffer { buffer, yes, trash } => {
+            cmd_delete_buffer(&db_path, buffer, *yes, *trash, format)
+        }
+        Commands::MergeBuffers {
+            sources,
+            name,
+            chunker,
+            chunk_size,
+            overlap,
+            min_chunk_size,
+            no_embed,
+        } => cmd_merge_buffers(
+            &db_path,
+            sources,
+            name,
+            chunker,
+            *chunk_size,
+            *overlap,
+            *min_chunk_size,
+            !*no_embed,
+            format,
+        ),
+        Commands::DiffBuffers { a, b, context } => {
+            cmd_diff_buffers(&db_path, a, b, *context, format)
+        }
         Commands::Peek { buffer, start, end } => cmd_peek(&db_path, buffer, *start, *end, format),
         Commands::Grep {
             buffer,
@@ -76,9 +140,10 @@ pub fn execute(cli: &Cli) -> Result<String> {
             max_matches,
             window,
             ignore_case,
+            all,
         } => cmd_grep(
             &db_path,
-            buffer,
+            if *all { None } else { buffer.as_deref() },
             pattern,
             *max_matches,
             *window,
@@ -95,6 +160,7 @@ pub fn execute(cli: &Cli) -> Result<String> {
             out_dir,
             chunk_size,
             overlap,
+            min_chunk_size,
             prefix,
         } => cmd_write_chunks(
             &db_path,
@@ -102,6 +168,7 @@ pub fn execute(cli: &Cli) -> Result<String> {
             out_dir,
             *chunk_size,
             *overlap,
+            *min_chunk_size,
             prefix,
             format,
         ),
@@ -112,22 +179,40 @@ pub fn execute(cli: &Cli) -> Result<String> {
             buffer,
             content,
             embed,
+            incremental,
             strategy,
             chunk_size,
             overlap,
+            min_chunk_size,
         } => cmd_update_buffer(
             &db_path,
             buffer,
             content.as_deref(),
             *embed,
+            *incremental,
             strategy,
             *chunk_size,
             *overlap,
+            *min_chunk_size,
             format,
         ),
-        Commands::ExportBuffers { output, pretty } => {
-            cmd_export_buffers(&db_path, output.as_deref(), *pretty, format)
-        }
+        Commands::ExportBuffers {
+            output,
+            buffer,
+            as_format,
+            pretty,
+        } => cmd_export_buffers(
+            &db_path,
+            output.as_deref(),
+            buffer.as_deref(),
+            *pretty,
+            ExportFormat::resolve(as_format.as_deref(), format),
+        ),
+        Commands::ImportBuffers {
+            file,
+            embed,
+            on_conflict,
+        } => cmd_import_buffers(&db_path, file, *embed, on_conflict == "rename", format),
         Commands::Variable {
             name,
             value,
@@ -138,26 +223,46 @@ pub fn execute(cli: &Cli) -> Result<String> {
             value,
             delete,
         } => cmd_global(&db_path, name, value.as_deref(), *delete, format),
+        Commands::Vars { scope } => cmd_vars(&db_path, scope, format),
         Commands::Search {
             query,
             top_k,
+            offset,
             threshold,
             mode,
             rrf_k,
+            fusion,
+            alpha,
+            metric,
+            mmr_lambda,
             buffer,
+            tag,
             preview,
             preview_len,
+            context,
+            enrich,
+            fail_on_empty,
         } => cmd_search(
             &db_path,
             query,
             *top_k,
+            *offset,
             *threshold,
             mode,
             *rrf_k,
+            fusion,
+            *alpha,
+            metric,
+            *mmr_lambda,
             buffer.as_deref(),
+            tag.as_deref(),
             *preview,
             *preview_len,
+            *context,
+            *enrich,
+            *fail_on_empty,
             format,
+            style,
         ),
         Commands::Aggregate {
             buffer,
@@ -165,6 +270,7 @@ pub fn execute(cli: &Cli) -> Result<String> {
             group_by,
             sort_by,
             output_buffer,
+            embed,
         } => cmd_aggregate(
             &db_path,
             buffer.as_deref(),
@@ -172,6 +278,7 @@ pub fn execute(cli: &Cli) -> Result<String> {
             group_by,
             sort_by,
             output_buffer.as_deref(),
+            *embed,
             format,
         ),
         Commands::Dispatch {
@@ -181,6 +288,7 @@ pub fn execute(cli: &Cli) -> Result<String> {
             query,
             mode,
             threshold,
+            manifest,
         } => cmd_dispatch(
             &db_path,
             buffer,
@@ -189,31 +297,54 @@ pub fn execute(cli: &Cli) -> Result<String> {
             query.as_deref(),
             mode,
             *threshold,
+            manifest.as_deref(),
             format,
         ),
         Commands::Chunk(chunk_cmd) => match chunk_cmd {
-            ChunkCommands::Get { id, metadata } => cmd_chunk_get(&db_path, *id, *metadata, format),
+            ChunkCommands::Get { ids, metadata } => cmd_chunk_get(&db_path, ids, *metadata, format),
             ChunkCommands::List {
                 buffer,
                 preview,
                 preview_len,
-            } => cmd_chunk_list(&db_path, buffer, *preview, *preview_len, format),
-            ChunkCommands::Embed { buffer, force } => {
-                cmd_chunk_embed(&db_path, buffer, *force, format)
-            }
+            } => cmd_chunk_list(&db_path, buffer, *preview, *preview_len, format, style),
+            ChunkCommands::Embed {
+                buffer,
+                force,
+                discard_other_dimensions,
+            } => cmd_chunk_embed(&db_path, buffer, *force, *discard_other_dimensions, format),
+            ChunkCommands::Embedding { id } => cmd_chunk_embedding(&db_path, *id, format),
             ChunkCommands::Status => cmd_chunk_status(&db_path, format),
+            ChunkCommands::Delete { id, yes } => cmd_chunk_delete(&db_path, *id, *yes, format),
+        },
+        Commands::Buffer(BufferCommands::Tag(tag_cmd)) => match tag_cmd {
+            TagCommands::Add { buffer, tags } => cmd_tag_add(&db_path, buffer, tags, format),
+            TagCommands::Remove { buffer, tags } => cmd_tag_remove(&db_path, buffer, tags, format),
+            TagCommands::List { buffer } => cmd_tag_list(&db_path, buffer.as_deref(), format),
+        },
+        Commands::Buffer(BufferCommands::Trash) => cmd_buffer_trash(&db_path, format),
+        Commands::Buffer(BufferCommands::Restore { id }) => {
+            cmd_buffer_restore(&db_path, *id, format)
+        }
+        Commands::Buffer(BufferCommands::EmptyTrash { yes }) => {
+            cmd_buffer_empty_trash(&db_path, *yes, format)
+        }
+        Commands::Storage(storage_cmd) => match storage_cmd {
+            StorageCommands::Vacuum { analyze } => cmd_storage_vacuum(&db_path, *analyze, format),
         },
     }
 }
 
 /// Opens storage and ensures it's initialized.
 fn open_storage(db_path: &std::path::Path) -> Result<SqliteStorage> {
-    let storage = SqliteStorage::open(db_path)?;
+    let mut storage = SqliteStorage::open(db_path)?;
 
     if !storage.is_initialized()? {
         return Err(StorageError::NotInitialized.into());
     }
 
+    // Upgrade databases created by older releases
+    storage.migrate()?;
+
     Ok(storage)
 }
 
@@ -237,6 +368,53 @@ fn resolve_buffer(storage: &SqliteStorage, identifier: &str) -> Result<Buffer> {
     .into())
 }
 
+/// Asks the user to type `expected` before a destructive operation.
+///
+/// Without `--yes`, the prompt is shown only when stdin and stderr are
+/// terminals; otherwise the command is refused so scripts never block.
+fn confirm(yes: bool, expected: &str, refusal: &str) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    if !(io::stdin().is_terminal() && io::stderr().is_terminal()) {
+        return Err(CommandError::ExecutionFailed(refusal.to_string()).into());
+    }
+
+    let prompt = format!("Type '{expected}' to confirm: ");
+    if confirm_with(
+        &mut io::stdin().lock(),
+        &mut io::stderr(),
+        &prompt,
+        expected,
+    )? {
+        Ok(())
+    } else {
+        Err(
+            CommandError::ExecutionFailed("Confirmation did not match; aborted.".to_string())
+                .into(),
+        )
+    }
+}
+
+/// Writes `prompt` and reads one line, returning whether it equals
+/// `expected` (ignoring surrounding whitespace).
+fn confirm_with(
+    input: &mut impl BufRead,
+    output: &mut impl IoWrite,
+    prompt: &str,
+    expected: &str,
+) -> Result<bool> {
+    write!(output, "{prompt}")
+        .and_then(|()| output.flush())
+        .map_err(|e| CommandError::ExecutionFailed(format!("cannot write prompt: {e}")))?;
+
+    let mut answer = String::new();
+    input
+        .read_line(&mut answer)
+        .map_err(|e| CommandError::ExecutionFailed(format!("cannot read confirmation: {e}")))?;
+    Ok(answer.trim() == expected)
+}
+
 // ==================== Command Implementations ====================
 
 fn cmd_init(db_path: &std::path::Path, force: bool, _format: OutputFormat) -> Result<String> {
@@ -277,21 +455,18 @@ fn cmd_init(db_path: &std::path::Path, force: bool, _format: OutputFormat) -> Re
     ))
 }
 
-fn cmd_status(db_path: &std::path::Path, format: OutputFormat) -> Result<String> {
+fn cmd_status(db_path: &std::path::Path, format: OutputFormat, style: TextStyle) -> Result<String> {
     let storage = open_storage(db_path)?;
     let stats = storage.stats()?;
-    Ok(format_status(&stats, format))
+    Ok(format_status(&stats, format, style))
 }
 
 fn cmd_reset(db_path: &std::path::Path, yes: bool, _format: OutputFormat) -> Result<String> {
-    if !yes {
-        // In a real implementation, we'd prompt the user
-        // For now, require --yes flag
-        return Err(CommandError::ExecutionFailed(
-            "Use --yes to confirm reset. This will delete all data.".to_string(),
-        )
-        .into());
-    }
+    confirm(
+        yes,
+        "reset",
+        "Use --yes to confirm reset. This will delete all data.",
+    )?;
 
     let mut storage = open_storage(db_path)?;
     storage.reset()?;
@@ -303,77 +478,711 @@ fn cmd_reset(db_path: &std::path::Path, yes: bool, _format: OutputFormat) -> Res
     Ok("RLM state reset successfully.\n".to_string())
 }
 
-fn cmd_load(
+fn cmd_migrate(db_path: &std::path::Path, dry_run: bool, format: OutputFormat) -> Result<String> {
+    let mut storage = SqliteStorage::open(db_path)?;
+    if !storage.is_initialized()? {
+        return Err(StorageError::NotInitialized.into());
+    }
+
+    let from_version = storage.stats()?.schema_version;
+    let migrations = if dry_run {
+        storage.pending_migrations()?
+    } else {
+        storage.migrate()?
+    };
+
+    match format {
+        OutputFormat::Text => {
+            if migrations.is_empty() {
+                return Ok(format!(
+                    "Database is up to date (schema version {CURRENT_SCHEMA_VERSION})\n"
+                ));
+            }
+            let mut output = String::new();
+            let verb = if dry_run { "Pending" } else { "Applied" };
+            let _ = writeln!(output, "{verb} migrations:");
+            for migration in &migrations {
+                let _ = writeln!(
+                    output,
+                    "  v{} -> v{}: {}",
+                    migration.from_version, migration.to_version, migration.description
+                );
+            }
+            if !dry_run {
+                let _ = writeln!(
+                    output,
+                    "Schema version: {from_version} -> {CURRENT_SCHEMA_VERSION}"
+                );
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "dry_run": dry_run,
+                "from_version": from_version,
+                "current_version": CURRENT_SCHEMA_VERSION,
+                "migrations": migrations.iter().map(|m| serde_json::json!({
+                    "from_version": m.from_version,
+                    "to_version": m.to_version,
+                    "description": m.description,
+                })).collect::<Vec<_>>(),
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+/// Outcome of a `doctor` check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// A single `doctor` check result.
+struct DoctorCheck {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Runs the database checks, stopping at the first one that fails.
+///
+/// Returns the stored embedding dimensions when the database is usable.
+fn doctor_database_checks(
     db_path: &std::path::Path,
-    file: &std::path::Path,
-    name: Option<&str>,
-    chunker_name: &str,
-    chunk_size: usize,
-    overlap: usize,
+    checks: &mut Vec<DoctorCheck>,
+) -> Option<Vec<usize>> {
+    let init_hint = "Run `rlm-cli init` to create the database";
+    if !db_path.exists() {
+        checks.push(DoctorCheck::problem(
+            "database",
+            CheckStatus::Fail,
+            format!("{} not found", db_path.display()),
+            init_hint,
+        ));
+        return None;
+    }
+
+    let storage = match SqliteStorage::open_read_only(db_path) {
+        Ok(storage) => storage,
+        Err(e) => {
+            checks.push(DoctorCheck::problem(
+                "database",
+                CheckStatus::Fail,
+                format!("cannot open {}: {e}", db_path.display()),
+                "Check the path and file permissions, or pass --db-path",
+            ));
+            return None;
+        }
+    };
+    checks.push(DoctorCheck::pass(
+        "database",
+        format!("found {}", db_path.display()),
+    ));
+
+    if !storage.is_initialized().unwrap_or(false) {
+        checks.push(DoctorCheck::problem(
+            "initialized",
+            CheckStatus::Fail,
+            "database has no RLM schema",
+            init_hint,
+        ));
+        return None;
+    }
+    checks.push(DoctorCheck::pass("initialized", "schema present"));
+
+    match storage.validate() {
+        Ok(version) if version < CURRENT_SCHEMA_VERSION => {
+            checks.push(DoctorCheck::problem(
+                "schema",
+                CheckStatus::Warn,
+                format!("version {version}, current is {CURRENT_SCHEMA_VERSION}"),
+                "Run `rlm-cli migrate` (databases are also upgraded when next written)",
+            ));
+        }
+        Ok(version) => checks.push(DoctorCheck::pass("schema", format!("version {version}"))),
+        Err(e) => {
+            checks.push(DoctorCheck::problem(
+                "schema",
+                CheckStatus::Fail,
+                e.to_string(),
+                "Restore a backup or re-create the database with `rlm-cli init --force`",
+            ));
+            return None;
+        }
+    }
+
+    storage.embedding_dimensions().ok()
+}
+
+fn cmd_doctor(
+    db_path: &std::path::Path,
+    check_embedder: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut checks = Vec::new();
+    let stored_dimensions = doctor_database_checks(db_path, &mut checks);
+
+    match create_embedder() {
+        Ok(embedder) => {
+            checks.push(DoctorCheck::pass(
+                "embedder",
+                format!(
+                    "{} ({} dimensions)",
+                    embedder.model_name(),
+                    embedder.dimensions()
+                ),
+            ));
+
+            if let Some(stored) = &stored_dimensions {
+                let other: Vec<String> = stored
+                    .iter()
+                    .filter(|d| **d != embedder.dimensions())
+                    .map(ToString::to_string)
+                    .collect();
+                if other.is_empty() {
+                    checks.push(DoctorCheck::pass(
+                        "embeddings",
+                        "stored embeddings match the embedder",
+                    ));
+                } else {
+                    checks.push(DoctorCheck::problem(
+                        "embeddings",
+                        CheckStatus::Warn,
+                        format!(
+                            "some stored embeddings have {} dimensions, the embedder produces {}",
+                            other.join(", "),
+                            embedder.dimensions()
+                        ),
+                        "Run `rlm-cli chunk embed <buffer> --force` to re-embed those buffers",
+                    ));
+                }
+            }
+
+            if check_embedder {
+                match embedder.embed("rlm doctor embedding probe") {
+                    Ok(vector) if crate::search::is_usable_embedding(&vector) => {
+                        checks.push(DoctorCheck::pass("embedding model", "probe text embedded"));
+                    }
+                    Ok(_) => checks.push(DoctorCheck::problem(
+                        "embedding model",
+                        CheckStatus::Fail,
+                        "probe embedding is all zeros or NaN",
+                        "Re-download the model by clearing the fastembed cache",
+                    )),
+                    Err(e) => checks.push(DoctorCheck::problem(
+                        "embedding model",
+                        CheckStatus::Fail,
+                        e.to_string(),
+                        "Check network access for the first model download",
+                    )),
+                }
+            }
+        }
+        Err(e) => checks.push(DoctorCheck::problem(
+            "embedder",
+            CheckStatus::Fail,
+            e.to_string(),
+            "Rebuild without the fastembed-embeddings feature to use the hash fallback",
+        )),
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    let warned = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Warn)
+        .count();
+
+    match format {
+        OutputFormat::Text => {
+            let mut output = String::new();
+            for check in &checks {
+                let _ = writeln!(
+                    output,
+                    "[{}] {}: {}",
+                    check.status.as_str(),
+                    check.name,
+                    check.detail
+                );
+                if let Some(hint) = &check.hint {
+                    let _ = writeln!(output, "       hint: {hint}");
+                }
+            }
+            let _ = writeln!(
+                output,
+                "\n{} passed, {warned} warnings, {failed} failed",
+                checks.len() - failed - warned
+            );
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "ok": failed == 0,
+                "checks": checks.iter().map(|c| serde_json::json!({
+                    "name": c.name,
+                    "status": c.status.as_str(),
+                    "detail": c.detail,
+                    "hint": c.hint,
+                })).collect::<Vec<_>>(),
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_backup(
+    db_path: &std::path::Path,
+    dest: &std::path::Path,
+    format: OutputFormat,
+) -> Result<String> {
+    // Not `open_storage`: the backup keeps the schema version as it is, so
+    // a database can be saved before a new release upgrades it
+    let storage = SqliteStorage::open(db_path)?;
+    if !storage.is_initialized()? {
+        return Err(StorageError::NotInitialized.into());
+    }
+    let schema_version = storage.validate()?;
+    storage.backup_to(dest)?;
+    let (buffer_count, chunk_count) = storage.row_counts()?;
+    let size = std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Backed up {buffer_count} buffer(s), {chunk_count} chunk(s) to {} ({size} bytes)\n",
+            dest.display()
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "path": dest.to_string_lossy(),
+                "size": size,
+                "buffer_count": buffer_count,
+                "chunk_count": chunk_count,
+                "schema_version": schema_version,
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_restore(
+    db_path: &std::path::Path,
+    source: &std::path::Path,
+    force: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    if !source.exists() {
+        return Err(CommandError::InvalidArgument(format!(
+            "backup file not found: {}",
+            source.display()
+        ))
+        .into());
+    }
+    if db_path.exists() && !force {
+        return Err(CommandError::ExecutionFailed(
+            "Use --force to replace the existing database.".to_string(),
+        )
+        .into());
+    }
+
+    // Validate, then snapshot the backup next to the target so the final
+    // swap is a rename on the same filesystem
+    let staged = db_path.with_extension("restore-tmp");
+    if staged.exists() {
+        std::fs::remove_file(&staged).map_err(|e| StorageError::Database(e.to_string()))?;
+    }
+    if let Some(parent) = db_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).map_err(|e| StorageError::Database(e.to_string()))?;
+    }
+    {
+        let backup = SqliteStorage::open_read_only(source)?;
+        backup.validate()?;
+        backup.backup_to(&staged)?;
+    }
+
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = std::path::PathBuf::from(sidecar);
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar).map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+    }
+    std::fs::rename(&staged, db_path).map_err(|e| StorageError::Database(e.to_string()))?;
+
+    let storage = open_storage(db_path)?;
+    let stats = storage.stats()?;
+
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Restored {} buffer(s), {} chunk(s) from {}\n",
+            stats.buffer_count,
+            stats.chunk_count,
+            source.display()
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "path": source.to_string_lossy(),
+                "buffer_count": stats.buffer_count,
+                "chunk_count": stats.chunk_count,
+                "schema_version": stats.schema_version,
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_storage_vacuum(
+    db_path: &std::path::Path,
+    analyze: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+    let stats = storage.vacuum(analyze)?;
+
+    match format {
+        OutputFormat::Text => {
+            let mut output = String::new();
+            let _ = writeln!(
+                output,
+                "Vacuumed database: {} -> {} bytes ({} reclaimed)",
+                stats.size_before,
+                stats.size_after,
+                stats.reclaimed()
+            );
+            if stats.cache_pruned > 0 {
+                let _ = writeln!(
+                    output,
+                    "Pruned {} unused embedding cache entries.",
+                    stats.cache_pruned
+                );
+            }
+            if stats.analyzed {
+                output.push_str("Query planner statistics refreshed.\n");
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "size_before": stats.size_before,
+                "size_after": stats.size_after,
+                "reclaimed": stats.reclaimed(),
+                "analyzed": stats.analyzed,
+                "cache_pruned": stats.cache_pruned,
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+/// Trims tags and rejects empty ones.
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>> {
+    tags.iter()
+        .map(|tag| -> Result<String> {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                Err(CommandError::InvalidArgument("tags must not be empty".to_string()).into())
+            } else {
+                Ok(tag.to_string())
+            }
+        })
+        .collect()
+}
+
+fn cmd_tag_add(
+    db_path: &std::path::Path,
+    identifier: &str,
+    tags: &[String],
     format: OutputFormat,
 ) -> Result<String> {
+    let tags = normalize_tags(tags)?;
     let mut storage = open_storage(db_path)?;
+    let buffer_id = resolve_buffer_id(&storage, identifier)?;
+    let added = storage.add_buffer_tags(buffer_id, &tags)?;
+    let all_tags = storage.get_buffer_tags(buffer_id)?;
 
-    // Read file content
-    let content = read_file(file)?;
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Added {added} tags to buffer {identifier} (now: {})\n",
+            all_tags.join(", ")
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "buffer_id": buffer_id,
+                "added": added,
+                "tags": all_tags,
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
 
-    // Create buffer
-    let buffer_name = name
-        .map(String::from)
-        .or_else(|| file.file_name().and_then(|n| n.to_str()).map(String::from));
+fn cmd_tag_remove(
+    db_path: &std::path::Path,
+    identifier: &str,
+    tags: &[String],
+    format: OutputFormat,
+) -> Result<String> {
+    let tags = normalize_tags(tags)?;
+    let mut storage = open_storage(db_path)?;
+    let buffer_id = resolve_buffer_id(&storage, identifier)?;
+    let removed = storage.remove_buffer_tags(buffer_id, &tags)?;
+    let all_tags = storage.get_buffer_tags(buffer_id)?;
 
-    let mut buffer = Buffer::from_file(file.to_path_buf(), content.clone());
-    buffer.name = buffer_name;
-    buffer.compute_hash();
+    match format {
+        OutputFormat::Text => Ok(format!("Removed {removed} tags from buffer {identifier}\n")),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "buffer_id": buffer_id,
+                "removed": removed,
+                "tags": all_tags,
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
 
-    // Add buffer to storage
-    let buffer_id = storage.add_buffer(&buffer)?;
+fn cmd_tag_list(
+    db_path: &std::path::Path,
+    identifier: Option<&str>,
+    format: OutputFormat,
+) -> Result<String> {
+    let storage = open_storage(db_path)?;
 
-    // Chunk the content
-    let chunker = create_chunker(chunker_name)?;
-    let meta = ChunkerMetadata::with_size_and_overlap(chunk_size, overlap);
-    let chunks = chunker.chunk(buffer_id, &content, Some(&meta))?;
+    if let Some(identifier) = identifier {
+        let buffer_id = resolve_buffer_id(&storage, identifier)?;
+        let tags = storage.get_buffer_tags(buffer_id)?;
+        return match format {
+            OutputFormat::Text => {
+                if tags.is_empty() {
+                    Ok(format!("Buffer {identifier} has no tags\n"))
+                } else {
+                    Ok(tags.iter().map(|tag| format!("{tag}\n")).collect())
+                }
+            }
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                let json = serde_json::json!({
+                    "buffer_id": buffer_id,
+                    "tags": tags,
+                });
+                Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+            }
+        };
+    }
 
-    // Store chunks
-    storage.add_chunks(buffer_id, &chunks)?;
+    let tags = storage.list_tags()?;
+    match format {
+        OutputFormat::Text => {
+            if tags.is_empty() {
+                return Ok("No tags\n".to_string());
+            }
+            let mut output = String::new();
+            for (tag, count) in &tags {
+                let _ = writeln!(output, "{tag:<30} {count} buffers");
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json: Vec<_> = tags
+                .iter()
+                .map(|(tag, count)| serde_json::json!({ "tag": tag, "buffer_count": count }))
+                .collect();
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
 
-    // Generate embeddings for semantic search (automatic during load)
-    let embedder = create_embedder()?;
-    let embedded_count = embed_buffer_chunks(&mut storage, embedder.as_ref(), buffer_id)?;
+/// What `load` does when a live buffer already holds identical content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DuplicatePolicy {
+    /// Load a new buffer regardless.
+    Load,
+    /// Report the existing buffer and load nothing.
+    Skip,
+    /// Give the existing buffer the new name and source instead of loading.
+    Update,
+}
 
-    // Update buffer with chunk count
-    let mut updated_buffer =
-        storage
-            .get_buffer(buffer_id)?
-            .ok_or_else(|| StorageError::BufferNotFound {
-                identifier: buffer_id.to_string(),
-            })?;
-    updated_buffer.set_chunk_count(chunks.len());
-    storage.update_buffer(&updated_buffer)?;
+impl DuplicatePolicy {
+    /// Maps the `--dedup` and `--update-if-exists` flags.
+    const fn new(dedup: bool, update_if_exists: bool) -> Self {
+        if update_if_exists {
+            Self::Update
+        } else if dedup {
+            Self::Skip
+        } else {
+            Self::Load
+        }
+    }
+}
 
-    // Update context
-    if let Some(mut context) = storage.load_context()? {
-        context.add_buffer(buffer_id);
-        storage.save_context(&context)?;
+#[allow(clippy::too_many_arguments)]
+fn cmd_load(
+    db_path: &std::path::Path,
+    file: &std::path::Path,
+    name: Option<&str>,
+    chunker_name: &str,
+    chunk_size: Option<usize>,
+    overlap: Option<usize>,
+    min_chunk_size: usize,
+    duplicates: DuplicatePolicy,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+
+    let (mut buffer, source) = match file.to_str().filter(|source| is_url(source)) {
+        Some(url) => {
+            let download = fetch_url(url, DEFAULT_MAX_DOWNLOAD_SIZE, DEFAULT_HTTP_TIMEOUT)?;
+            let mut buffer = Buffer::from_content(download.content);
+            buffer.name = download.file_name;
+            buffer.metadata.content_type = download.content_type;
+            buffer.metadata.encoding = Some(download.encoding.to_string());
+            (buffer, download.url)
+        }
+        None => {
+            // Read file content, extracting text from PDFs and decompressing
+            // gzip/zstd files
+            let buffer = if is_pdf(file) {
+                let pdf = read_pdf(file)?;
+                let mut buffer = Buffer::from_file(file.to_path_buf(), pdf.content);
+                buffer.metadata.page_offsets = Some(pdf.page_offsets);
+                buffer
+            } else {
+                let read = read_file_content(file)?;
+                let mut buffer = Buffer::from_file(file.to_path_buf(), read.content);
+                buffer.metadata.compressed_size = read.compressed_size;
+                buffer.metadata.encoding = Some(read.encoding.to_string());
+                buffer
+            };
+            (buffer, file.to_string_lossy().to_string())
+        }
+    };
+    let size = buffer.size();
+
+    if let Some(name) = name {
+        buffer.name = Some(name.to_string());
     }
+    let buffer_name = buffer.name.clone();
+
+    if duplicates != DuplicatePolicy::Load
+        && let Some((existing_id, existing_name)) =
+            storage.find_buffer_by_content(&buffer.content)?
+    {
+        let updated = duplicates == DuplicatePolicy::Update;
+        let name = if updated {
+            let mut existing =
+                storage
+                    .get_buffer(existing_id)?
+                    .ok_or_else(|| StorageError::BufferNotFound {
+                        identifier: existing_id.to_string(),
+                    })?;
+            if buffer_name.is_some() {
+                existing.name.clone_from(&buffer_name);
+            }
+            if buffer.source.is_some() {
+                existing.source = buffer.source;
+            }
+            storage.update_buffer(&existing)?;
+            existing.name
+        } else {
+            existing_name
+        };
+
+        return Ok(match format {
+            OutputFormat::Text => format!(
+                "Content of {source} already loaded as buffer {} (ID: {existing_id}); {}\n",
+                name.as_deref().unwrap_or("unnamed"),
+                if updated {
+                    "updated name and source"
+                } else {
+                    "skipped"
+                },
+            ),
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                let result = serde_json::json!({
+                    "buffer_id": existing_id,
+                    "name": name,
+                    "created": false,
+                    "updated": updated,
+                    "size": size,
+                    "source": source
+                });
+                serde_json::to_string_pretty(&result).unwrap_or_default()
+            }
+        });
+    }
+
+    // Chunk, store and embed (embedding is automatic during load)
+    let options = LoadOptions {
+        chunk_size,
+        overlap,
+        ..LoadOptions::new()
+            .with_chunker(chunker_name)
+            .with_min_chunk_size(min_chunk_size)
+    };
+    let embedder = create_embedder()?;
+    let loaded = ingest_buffer(&mut storage, Some(embedder.as_ref()), buffer, &options)?;
 
     match format {
         OutputFormat::Text => Ok(format!(
             "Loaded buffer {} (ID: {}) with {} chunks ({} embedded) from {}\n",
-            updated_buffer.name.as_deref().unwrap_or("unnamed"),
-            buffer_id,
-            chunks.len(),
-            embedded_count,
-            file.display()
+            buffer_name.as_deref().unwrap_or("unnamed"),
+            loaded.buffer_id,
+            loaded.chunk_count,
+            loaded.embedded_count,
+            source
         )),
         OutputFormat::Json | OutputFormat::Ndjson => {
             let result = serde_json::json!({
-                "buffer_id": buffer_id,
-                "name": updated_buffer.name,
-                "chunk_count": chunks.len(),
-                "embedded_count": embedded_count,
-                "size": content.len(),
-                "source": file.to_string_lossy()
+                "buffer_id": loaded.buffer_id,
+                "name": buffer_name,
+                "created": true,
+                "chunk_count": loaded.chunk_count,
+                "embedded_count": loaded.embedded_count,
+                "size": size,
+                "source": source
             });
             Ok(serde_json::to_string_pretty(&result).unwrap_or_default())
         }
@@ -395,41 +1204,351 @@ fn cmd_show_buffer(
     let storage = open_storage(db_path)?;
     let buffer = resolve_buffer(&storage, identifier)?;
 
-    let chunks = if show_chunks {
-        Some(storage.get_chunks(buffer.id.unwrap_or(0))?)
+    let chunks = if show_chunks {
+        Some(storage.get_chunks(buffer.id.unwrap_or(0))?)
+    } else {
+        None
+    };
+
+    Ok(format_buffer(&buffer, chunks.as_deref(), format))
+}
+
+/// Size and chunking statistics for a buffer.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+struct BufferStats {
+    buffer_id: i64,
+    name: Option<String>,
+    bytes: usize,
+    lines: usize,
+    estimated_tokens: usize,
+    chunk_count: usize,
+    min_chunk_size: usize,
+    max_chunk_size: usize,
+    mean_chunk_size: f64,
+    embedded_chunks: usize,
+    embedding_coverage: f64,
+}
+
+impl BufferStats {
+    /// Computes statistics from a buffer, its chunks and the number of
+    /// chunks that have embeddings.
+    #[allow(clippy::cast_precision_loss)]
+    fn compute(buffer: &mut Buffer, chunks: &[crate::core::Chunk], embedded_chunks: usize) -> Self {
+        let sizes = chunks.iter().map(crate::core::Chunk::size);
+        let total: usize = sizes.clone().sum();
+        let per_chunk = |total: usize| {
+            if chunks.is_empty() {
+                0.0
+            } else {
+                total as f64 / chunks.len() as f64
+            }
+        };
+
+        Self {
+            buffer_id: buffer.id.unwrap_or(0),
+            name: buffer.name.clone(),
+            bytes: buffer.size(),
+            lines: buffer.line_count(),
+            estimated_tokens: crate::core::estimate_tokens_for_text(&buffer.content),
+            chunk_count: chunks.len(),
+            min_chunk_size: sizes.clone().min().unwrap_or(0),
+            max_chunk_size: sizes.max().unwrap_or(0),
+            mean_chunk_size: per_chunk(total),
+            embedded_chunks,
+            embedding_coverage: per_chunk(embedded_chunks),
+        }
+    }
+}
+
+fn cmd_buffer_stats(
+    db_path: &std::path::Path,
+    identifier: &str,
+    format: OutputFormat,
+) -> Result<String> {
+    let storage = open_storage(db_path)?;
+    let mut buffer = resolve_buffer(&storage, identifier)?;
+    let buffer_id = buffer.id.unwrap_or(0);
+    let chunks = storage.get_chunks(buffer_id)?;
+    let embedded = storage.get_embedding_stats(buffer_id)?.embedded_chunks;
+    let stats = BufferStats::compute(&mut buffer, &chunks, embedded);
+
+    match format {
+        OutputFormat::Text => {
+            let mut output = String::new();
+            let _ = writeln!(
+                output,
+                "Buffer: {} (ID: {})",
+                stats.name.as_deref().unwrap_or("unnamed"),
+                stats.buffer_id
+            );
+            let _ = writeln!(output, "  Bytes:            {}", stats.bytes);
+            let _ = writeln!(output, "  Lines:            {}", stats.lines);
+            let _ = writeln!(output, "  Tokens (approx):  {}", stats.estimated_tokens);
+            let _ = writeln!(output, "  Chunks:           {}", stats.chunk_count);
+            if stats.chunk_count > 0 {
+                let _ = writeln!(
+                    output,
+                    "  Chunk size:       min {} / max {} / mean {:.1} bytes",
+                    stats.min_chunk_size, stats.max_chunk_size, stats.mean_chunk_size
+                );
+            }
+            let _ = writeln!(
+                output,
+                "  Embedded:         {}/{} ({:.0}%)",
+                stats.embedded_chunks,
+                stats.chunk_count,
+                stats.embedding_coverage * 100.0
+            );
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            Ok(serde_json::to_string_pretty(&stats).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_delete_buffer(
+    db_path: &std::path::Path,
+    identifier: &str,
+    yes: bool,
+    trash: bool,
+    _format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+    let buffer = resolve_buffer(&storage, identifier)?;
+    let buffer_id = buffer.id.unwrap_or(0);
+    let buffer_name = buffer.name.unwrap_or_else(|| format!("{buffer_id}"));
+
+    // Trashing is reversible, so it needs no confirmation
+    if trash {
+        storage.trash_buffer(buffer_id)?;
+    } else {
+        confirm(yes, &buffer_name, "Use --yes to confirm deletion.")?;
+        storage.delete_buffer(buffer_id)?;
+    }
+
+    // Update context
+    if let Some(mut context) = storage.load_context()? {
+        context.remove_buffer(buffer_id);
+        storage.save_context(&context)?;
+    }
+
+    if trash {
+        Ok(format!(
+            "Moved buffer to trash: {buffer_name} (restore with `buffer restore {buffer_id}`)\n"
+        ))
+    } else {
+        Ok(format!("Deleted buffer: {buffer_name}\n"))
+    }
+}
+
+fn cmd_buffer_trash(db_path: &std::path::Path, format: OutputFormat) -> Result<String> {
+    let storage = open_storage(db_path)?;
+    let trashed = storage.list_trashed_buffers()?;
+
+    match format {
+        OutputFormat::Text => {
+            if trashed.is_empty() {
+                return Ok("Trash is empty\n".to_string());
+            }
+            let mut output = String::new();
+            for (id, name, _) in &trashed {
+                let _ = writeln!(output, "{id:<6} {}", name.as_deref().unwrap_or("-"));
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json: Vec<_> = trashed
+                .iter()
+                .map(|(id, name, deleted_at)| {
+                    serde_json::json!({ "id": id, "name": name, "deleted_at": deleted_at })
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_buffer_restore(db_path: &std::path::Path, id: i64, format: OutputFormat) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+    let Some((_, name, _)) = storage
+        .list_trashed_buffers()?
+        .into_iter()
+        .find(|(trashed_id, _, _)| *trashed_id == id)
+    else {
+        return Err(
+            CommandError::InvalidArgument(format!("buffer {id} is not in the trash")).into(),
+        );
+    };
+    if let Some(name) = &name
+        && storage.find_buffer_id(name)?.is_some()
+    {
+        return Err(CommandError::ExecutionFailed(format!(
+            "cannot restore buffer {id}: a buffer named '{name}' already exists"
+        ))
+        .into());
+    }
+
+    storage.restore_buffer(id)?;
+    if let Some(mut context) = storage.load_context()? {
+        context.add_buffer(id);
+        storage.save_context(&context)?;
+    }
+
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Restored buffer: {}\n",
+            name.unwrap_or_else(|| id.to_string())
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({ "id": id, "name": name, "restored": true });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_buffer_empty_trash(
+    db_path: &std::path::Path,
+    yes: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+    confirm(
+        yes,
+        "empty-trash",
+        "Use --yes to confirm emptying the trash.",
+    )?;
+    let deleted = storage.empty_trash()?;
+
+    match format {
+        OutputFormat::Text => Ok(format!("Permanently deleted {deleted} trashed buffers\n")),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({ "deleted": deleted });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_merge_buffers(
+    db_path: &std::path::Path,
+    sources: &[String],
+    name: &str,
+    chunker_name: &str,
+    chunk_size: Option<usize>,
+    overlap: Option<usize>,
+    min_chunk_size: usize,
+    embed: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+
+    // Resolve all sources before creating anything
+    let buffers = sources
+        .iter()
+        .map(|identifier| resolve_buffer(&storage, identifier))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut content = String::new();
+    let mut source_ids = Vec::with_capacity(buffers.len());
+    for (i, source) in buffers.iter().enumerate() {
+        if i > 0 {
+            let label = source.name.as_deref().unwrap_or(sources[i].as_str());
+            let _ = write!(content, "\n\n--- merged from: {label} ---\n\n");
+        }
+        content.push_str(&source.content);
+        source_ids.push(source.id);
+    }
+
+    let size = content.len();
+    let buffer = Buffer::from_named(name.to_string(), content);
+    let options = LoadOptions {
+        chunk_size,
+        overlap,
+        ..LoadOptions::new()
+            .with_chunker(chunker_name)
+            .with_min_chunk_size(min_chunk_size)
+    };
+    let embedder = if embed {
+        Some(create_embedder()?)
     } else {
         None
     };
+    let LoadResult {
+        buffer_id,
+        chunk_count,
+        embedded_count,
+    } = ingest_buffer(&mut storage, embedder.as_deref(), buffer, &options)?;
 
-    Ok(format_buffer(&buffer, chunks.as_deref(), format))
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Merged {} buffers into {name} (ID: {buffer_id}) with {chunk_count} chunks ({embedded_count} embedded)\n",
+            buffers.len(),
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let result = serde_json::json!({
+                "buffer_id": buffer_id,
+                "name": name,
+                "sources": source_ids,
+                "chunk_count": chunk_count,
+                "embedded_count": embedded_count,
+                "size": size
+            });
+            Ok(serde_json::to_string_pretty(&result).unwrap_or_default())
+        }
+    }
 }
 
-fn cmd_delete_buffer(
+fn cmd_diff_buffers(
     db_path: &std::path::Path,
-    identifier: &str,
-    yes: bool,
-    _format: OutputFormat,
+    old_identifier: &str,
+    new_identifier: &str,
+    context: usize,
+    format: OutputFormat,
 ) -> Result<String> {
-    if !yes {
-        return Err(
-            CommandError::ExecutionFailed("Use --yes to confirm deletion.".to_string()).into(),
-        );
-    }
-
-    let mut storage = open_storage(db_path)?;
-    let buffer = resolve_buffer(&storage, identifier)?;
-    let buffer_id = buffer.id.unwrap_or(0);
-    let buffer_name = buffer.name.unwrap_or_else(|| format!("{buffer_id}"));
-
-    storage.delete_buffer(buffer_id)?;
+    let storage = open_storage(db_path)?;
+    let old = resolve_buffer(&storage, old_identifier)?;
+    let new = resolve_buffer(&storage, new_identifier)?;
+    let old_name = old
+        .name
+        .clone()
+        .unwrap_or_else(|| old_identifier.to_string());
+    let new_name = new
+        .name
+        .clone()
+        .unwrap_or_else(|| new_identifier.to_string());
+
+    let diff = LineDiff::new(&old.content, &new.content);
+    let summary = diff.summary();
 
-    // Update context
-    if let Some(mut context) = storage.load_context()? {
-        context.remove_buffer(buffer_id);
-        storage.save_context(&context)?;
+    match format {
+        OutputFormat::Text => {
+            if diff.is_identical() {
+                return Ok(format!(
+                    "Buffers '{old_name}' and '{new_name}' are identical\n"
+                ));
+            }
+            Ok(diff.unified(&old_name, &new_name, context))
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let hunks = diff.hunks(context);
+            let json = serde_json::json!({
+                "a": { "id": old.id, "name": old_name },
+                "b": { "id": new.id, "name": new_name },
+                "identical": diff.is_identical(),
+                "added": summary.added,
+                "removed": summary.removed,
+                "changed": summary.changed,
+                "hunks": hunks.iter().map(|h| serde_json::json!({
+                    "old_start": h.old_start,
+                    "old_len": h.old_len,
+                    "new_start": h.new_start,
+                    "new_len": h.new_len
+                })).collect::<Vec<_>>()
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
     }
-
-    Ok(format!("Deleted buffer: {buffer_name}\n"))
 }
 
 fn cmd_peek(
@@ -449,9 +1568,19 @@ fn cmd_peek(
     Ok(format_peek(content, start, end, format))
 }
 
+/// Bytes of buffer content scanned per storage read in `grep`.
+const GREP_READ_SIZE: usize = 1 << 20;
+
+/// Longest match `grep` is guaranteed to report intact. Each read extends
+/// this far past the scanned range so matches crossing it are not cut.
+const GREP_MAX_MATCH_LEN: usize = 64 * 1024;
+
+/// Greps one buffer, or every buffer when `identifier` is `None`.
+///
+/// `max_matches` caps the total across all searched buffers.
 fn cmd_grep(
     db_path: &std::path::Path,
-    identifier: &str,
+    identifier: Option<&str>,
     pattern: &str,
     max_matches: usize,
     window: usize,
@@ -459,41 +1588,183 @@ fn cmd_grep(
     format: OutputFormat,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
-    let buffer = resolve_buffer(&storage, identifier)?;
 
     let regex = RegexBuilder::new(pattern)
         .case_insensitive(ignore_case)
         .build()
         .map_err(|e| CommandError::InvalidArgument(format!("Invalid regex: {e}")))?;
 
-    let mut matches = Vec::new();
-    for m in regex.find_iter(&buffer.content) {
-        if matches.len() >= max_matches {
-            break;
+    let matches = if let Some(identifier) = identifier {
+        let buffer_id = resolve_buffer_id(&storage, identifier)?;
+        grep_buffer(
+            &storage,
+            buffer_id,
+            &regex,
+            window,
+            max_matches,
+            GREP_READ_SIZE,
+        )?
+    } else {
+        let mut matches = Vec::new();
+        for (buffer_id, buffer_name) in storage.list_buffer_names()? {
+            if matches.len() >= max_matches {
+                break;
+            }
+            let limit = max_matches - matches.len();
+            matches.extend(
+                grep_buffer(&storage, buffer_id, &regex, window, limit, GREP_READ_SIZE)?
+                    .into_iter()
+                    .map(|m| GrepMatch {
+                        buffer_id: Some(buffer_id),
+                        buffer_name: buffer_name.clone(),
+                        ..m
+                    }),
+            );
+        }
+        matches
+    };
+
+    Ok(format_grep_matches(&matches, pattern, format))
+}
+
+/// Resolves a buffer ID or name to an ID without loading the content.
+fn resolve_buffer_id(storage: &SqliteStorage, identifier: &str) -> Result<i64> {
+    if let Ok(id) = identifier.parse::<i64>()
+        && storage.buffer_content_len(id)?.is_some()
+    {
+        return Ok(id);
+    }
+
+    storage.find_buffer_id(identifier)?.ok_or_else(|| {
+        StorageError::BufferNotFound {
+            identifier: identifier.to_string(),
         }
+        .into()
+    })
+}
+
+/// Finds up to `limit` regex matches in a buffer with `window` bytes of
+/// context on each side.
+///
+/// Content is read from storage `read_size` bytes at a time, so peak memory
+/// is bounded by the read size rather than the buffer size. Matches and
+/// snippets are the same as scanning the whole content with
+/// [`Regex::find_iter`], except that matches longer than
+/// [`GREP_MAX_MATCH_LEN`] bytes may be cut short.
+fn grep_buffer(
+    storage: &SqliteStorage,
+    buffer_id: i64,
+    regex: &Regex,
+    window: usize,
+    limit: usize,
+    read_size: usize,
+) -> Result<Vec<GrepMatch>> {
+    let total =
+        storage
+            .buffer_content_len(buffer_id)?
+            .ok_or_else(|| StorageError::BufferNotFound {
+                identifier: buffer_id.to_string(),
+            })?;
+    // Extra bytes so trimming a split character never eats into context
+    let pad = window.saturating_add(4);
+
+    let mut matches = Vec::new();
+    // Absolute position the next search starts from
+    let mut next = 0;
+    // End of the previous match; an empty match may not start there
+    let mut last_end = None;
+    let mut scan_start = 0;
+
+    loop {
+        // Matches starting in [scan_start, scan_end) are reported from this read
+        let scan_end = (scan_start + read_size).min(total);
+        let read_start = scan_start.saturating_sub(pad);
+        let read_end = scan_end
+            .saturating_add(GREP_MAX_MATCH_LEN)
+            .saturating_add(pad)
+            .min(total);
+        let bytes = storage.read_buffer_content(buffer_id, read_start, read_end - read_start)?;
+        let (base, text) = utf8_window(&bytes, read_start, read_end < total)?;
+
+        let mut pos = (next - base).min(text.len());
+        while matches.len() < limit {
+            let Some(m) = regex.find_at(text, pos) else {
+                break;
+            };
+            let offset = base + m.start();
+            if offset >= scan_end && scan_end < total {
+                break;
+            }
+            if m.is_empty() && last_end == Some(offset) {
+                // Same rule as find_iter: skip ahead one character
+                let Some(c) = text[m.start()..].chars().next() else {
+                    break;
+                };
+                pos = m.start() + c.len_utf8();
+                continue;
+            }
 
-        let start = m.start().saturating_sub(window);
-        let end = (m.end() + window).min(buffer.content.len());
+            let start = m.start().saturating_sub(window);
+            let end = m.end().saturating_add(window).min(text.len());
 
-        // Find valid UTF-8 boundaries
-        let start = crate::io::find_char_boundary(&buffer.content, start);
-        let end = crate::io::find_char_boundary(&buffer.content, end);
+            // Find valid UTF-8 boundaries
+            let start = crate::io::find_char_boundary(text, start);
+            let end = crate::io::find_char_boundary(text, end);
 
-        matches.push(GrepMatch {
-            offset: m.start(),
-            matched: m.as_str().to_string(),
-            snippet: buffer.content[start..end].to_string(),
-        });
+            matches.push(GrepMatch {
+                offset,
+                matched: m.as_str().to_string(),
+                snippet: text[start..end].to_string(),
+                buffer_id: None,
+                buffer_name: None,
+            });
+            last_end = Some(base + m.end());
+            pos = m.end();
+        }
+
+        if scan_end >= total || matches.len() >= limit {
+            break;
+        }
+        // Nothing else starts before scan_end, so resume there at the latest
+        next = (base + pos).max(scan_end);
+        scan_start = scan_end;
     }
 
-    Ok(format_grep_matches(&matches, pattern, format))
+    Ok(matches)
+}
+
+/// Decodes bytes read from `offset` in a buffer, dropping a character split
+/// at the start and, if `truncated`, one split at the end.
+///
+/// Returns the absolute offset of the decoded text along with it.
+fn utf8_window(bytes: &[u8], offset: usize, truncated: bool) -> Result<(usize, &str)> {
+    let head = if offset == 0 {
+        0
+    } else {
+        bytes.iter().take_while(|&&b| (b & 0xC0) == 0x80).count()
+    };
+    let bytes = &bytes[head..];
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if truncated && e.error_len().is_none() => {
+            // Split character at the end of the read
+            std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(e) => {
+            return Err(CommandError::ExecutionFailed(format!(
+                "buffer content is not valid UTF-8: {e}"
+            ))
+            .into());
+        }
+    };
+    Ok((offset + head, text))
 }
 
 fn cmd_chunk_indices(
     db_path: &std::path::Path,
     identifier: &str,
-    chunk_size: usize,
-    overlap: usize,
+    chunk_size: Option<usize>,
+    overlap: Option<usize>,
     format: OutputFormat,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
@@ -501,6 +1772,8 @@ fn cmd_chunk_indices(
 
     let content_len = buffer.content.len();
     let mut indices = Vec::new();
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    let overlap = overlap.unwrap_or(DEFAULT_OVERLAP);
 
     if chunk_size == 0 || overlap >= chunk_size {
         return Err(
@@ -523,12 +1796,14 @@ fn cmd_chunk_indices(
     Ok(format_chunk_indices(&indices, format))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_write_chunks(
     db_path: &std::path::Path,
     identifier: &str,
     out_dir: &std::path::Path,
-    chunk_size: usize,
-    overlap: usize,
+    chunk_size: Option<usize>,
+    overlap: Option<usize>,
+    min_chunk_size: usize,
     prefix: &str,
     format: OutputFormat,
 ) -> Result<String> {
@@ -538,7 +1813,11 @@ fn cmd_write_chunks(
 
     // Create chunker and chunk the content
     let chunker = create_chunker("semantic")?;
-    let meta = ChunkerMetadata::with_size_and_overlap(chunk_size, overlap);
+    let meta = ChunkerMetadata::with_size_and_overlap(
+        chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+        overlap.unwrap_or(DEFAULT_OVERLAP),
+    )
+    .min_chunk_size(min_chunk_size);
     let chunks = chunker.chunk(buffer_id, &buffer.content, Some(&meta))?;
 
     // Store chunks in SQLite
@@ -616,9 +1895,11 @@ fn cmd_update_buffer(
     identifier: &str,
     content: Option<&str>,
     embed: bool,
+    incremental: bool,
     strategy: &str,
-    chunk_size: usize,
-    overlap: usize,
+    chunk_size: Option<usize>,
+    overlap: Option<usize>,
+    min_chunk_size: usize,
     format: OutputFormat,
 ) -> Result<String> {
     let mut storage = open_storage(db_path)?;
@@ -644,8 +1925,11 @@ fn cmd_update_buffer(
     // Get old chunk count for comparison
     let old_chunk_count = storage.chunk_count(buffer_id)?;
 
-    // Delete existing chunks (this cascades to embeddings)
-    storage.delete_chunks(buffer_id)?;
+    // Delete existing chunks (this cascades to embeddings) unless they are
+    // reconciled with the new chunks below
+    if !incremental {
+        storage.delete_chunks(buffer_id)?;
+    }
 
     // Update buffer content
     let updated_buffer = Buffer {
@@ -659,10 +1943,23 @@ fn cmd_update_buffer(
 
     // Re-chunk the content
     let chunker = create_chunker(strategy)?;
-    let meta = ChunkerMetadata::with_size_and_overlap(chunk_size, overlap);
+    let (default_size, default_overlap) = default_size_and_overlap(strategy);
+    let mut meta = ChunkerMetadata::with_size_and_overlap(
+        chunk_size.unwrap_or(default_size),
+        overlap.unwrap_or(default_overlap),
+    )
+    .min_chunk_size(min_chunk_size);
+    if let Some(source) = buffer.source.as_deref().and_then(std::path::Path::to_str) {
+        meta = meta.source(source);
+    }
     let chunks = chunker.chunk(buffer_id, &new_content, Some(&meta))?;
     let new_chunk_count = chunks.len();
-    storage.add_chunks(buffer_id, &chunks)?;
+    let reuse = if incremental {
+        Some(storage.replace_chunks_incremental(buffer_id, &chunks)?)
+    } else {
+        storage.add_chunks(buffer_id, &chunks)?;
+        None
+    };
 
     // Optionally embed the new chunks
     let embed_result = if embed {
@@ -689,6 +1986,12 @@ fn cmd_update_buffer(
                 "Chunks: {} -> {} (using {} strategy)\n",
                 old_chunk_count, new_chunk_count, strategy
             ));
+            if let Some(reuse) = reuse {
+                output.push_str(&format!(
+                    "Kept {} unchanged chunks ({} added, {} removed)\n",
+                    reuse.reused, reuse.added, reuse.removed
+                ));
+            }
             if let Some(ref result) = embed_result {
                 output.push_str(&format!(
                     "Embedded {} chunks using model '{}'\n",
@@ -705,6 +2008,9 @@ fn cmd_update_buffer(
                 "old_chunk_count": old_chunk_count,
                 "new_chunk_count": new_chunk_count,
                 "strategy": strategy,
+                "reused_chunks": reuse.map(|r| r.reused),
+                "added_chunks": reuse.map(|r| r.added),
+                "removed_chunks": reuse.map(|r| r.removed),
                 "embedded": embed_result.as_ref().map(|r| serde_json::json!({
                     "count": r.embedded_count,
                     "model": r.model_name
@@ -726,24 +2032,77 @@ struct AnalystFinding {
     summary: Option<String>,
     #[serde(default)]
     follow_up: Vec<String>,
+    /// Optional numeric confidence (0.0-1.0) used to rank within a relevance level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    confidence: Option<f32>,
 }
 
 /// Relevance level for sorting.
+///
+/// `critical` ranks above `high` for finding sets where `high` dominates.
 fn relevance_order(relevance: &str) -> u8 {
     match relevance.to_lowercase().as_str() {
-        "high" => 0,
-        "medium" => 1,
-        "low" => 2,
-        "none" => 3,
-        _ => 4,
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        "none" => 4,
+        _ => 5,
+    }
+}
+
+/// Orders confidence descending, with findings lacking a confidence last.
+fn confidence_order(a: Option<f32>, b: Option<f32>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
+/// Sorts findings by `sort_by` (`relevance`, `confidence`, `chunk_id`,
+/// `findings_count`); unknown keys keep the input order.
+///
+/// Relevance sorting breaks ties by confidence, and confidence sorting
+/// breaks ties by relevance.
+fn sort_findings(findings: &mut [AnalystFinding], sort_by: &str) {
+    match sort_by {
+        "relevance" => findings.sort_by(|a, b| {
+            relevance_order(&a.relevance)
+                .cmp(&relevance_order(&b.relevance))
+                .then_with(|| confidence_order(a.confidence, b.confidence))
+        }),
+        "confidence" => findings.sort_by(|a, b| {
+            confidence_order(a.confidence, b.confidence)
+                .then_with(|| relevance_order(&a.relevance).cmp(&relevance_order(&b.relevance)))
+        }),
+        "chunk_id" => findings.sort_by_key(|f| f.chunk_id),
+        "findings_count" => findings.sort_by_key(|f| std::cmp::Reverse(f.findings.len())),
+        _ => {}
     }
 }
 
+/// Sorts findings in document order: by buffer, then chunk index.
+///
+/// `positions` maps chunk IDs to `(buffer_id, index)`; findings for chunks
+/// not in it sort last, by chunk ID. Relevance is ignored.
+fn sort_findings_temporal(
+    findings: &mut [AnalystFinding],
+    positions: &std::collections::HashMap<i64, (i64, usize)>,
+) {
+    findings.sort_by_key(|f| {
+        let position = positions.get(&f.chunk_id).copied();
+        (position.is_none(), position, f.chunk_id)
+    });
+}
+
 /// Check if relevance meets minimum threshold.
 fn meets_relevance_threshold(relevance: &str, min_relevance: &str) -> bool {
     relevance_order(relevance) <= relevance_order(min_relevance)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_aggregate(
     db_path: &std::path::Path,
     buffer: Option<&str>,
@@ -751,6 +2110,7 @@ fn cmd_aggregate(
     group_by: &str,
     sort_by: &str,
     output_buffer: Option<&str>,
+    embed: bool,
     format: OutputFormat,
 ) -> Result<String> {
     let mut storage = open_storage(db_path)?;
@@ -779,11 +2139,16 @@ fn cmd_aggregate(
 
     // Sort findings
     let mut sorted = filtered;
-    match sort_by {
-        "relevance" => sorted.sort_by_key(|f| relevance_order(&f.relevance)),
-        "chunk_id" => sorted.sort_by_key(|f| f.chunk_id),
-        "findings_count" => sorted.sort_by_key(|f| std::cmp::Reverse(f.findings.len())),
-        _ => {}
+    if sort_by == "temporal" {
+        let mut positions = std::collections::HashMap::new();
+        for f in &sorted {
+            if let Some(chunk) = storage.get_chunk(f.chunk_id)? {
+                positions.insert(f.chunk_id, (chunk.buffer_id, chunk.index));
+            }
+        }
+        sort_findings_temporal(&mut sorted, &positions);
+    } else {
+        sort_findings(&mut sorted, sort_by);
     }
 
     // Group findings
@@ -825,25 +2190,40 @@ fn cmd_aggregate(
 
     // Build summary stats
     let total_findings = sorted.len();
+    let critical_count = sorted.iter().filter(|f| f.relevance == "critical").count();
     let high_count = sorted.iter().filter(|f| f.relevance == "high").count();
     let medium_count = sorted.iter().filter(|f| f.relevance == "medium").count();
     let low_count = sorted.iter().filter(|f| f.relevance == "low").count();
     let unique_findings_count = all_findings.len();
 
-    // Store in output buffer if requested
-    if let Some(out_name) = output_buffer {
-        let output_content = serde_json::to_string_pretty(&sorted).unwrap_or_default();
-        let out_buffer = Buffer::from_named(out_name.to_string(), output_content);
-        storage.add_buffer(&out_buffer)?;
-    }
+    // Store in output buffer if requested, chunked along finding boundaries
+    let stored = match output_buffer {
+        Some(out_name) => {
+            let output_content = serde_json::to_string_pretty(&sorted).unwrap_or_default();
+            let out_buffer = Buffer::from_named(out_name.to_string(), output_content);
+            let options = LoadOptions::new().with_chunker("json");
+            let embedder = if embed {
+                Some(create_embedder()?)
+            } else {
+                None
+            };
+            Some(ingest_buffer(
+                &mut storage,
+                embedder.as_deref(),
+                out_buffer,
+                &options,
+            )?)
+        }
+        None => None,
+    };
 
     match format {
         OutputFormat::Text => {
             let mut output = String::new();
             output.push_str(&format!("Aggregated {} analyst findings\n", total_findings));
             output.push_str(&format!(
-                "Relevance: {} high, {} medium, {} low\n",
-                high_count, medium_count, low_count
+                "Relevance: {} critical, {} high, {} medium, {} low\n",
+                critical_count, high_count, medium_count, low_count
             ));
             output.push_str(&format!("Unique findings: {}\n\n", unique_findings_count));
 
@@ -861,10 +2241,10 @@ fn cmd_aggregate(
                 output.push('\n');
             }
 
-            if output_buffer.is_some() {
+            if let (Some(name), Some(stored)) = (output_buffer, stored) {
                 output.push_str(&format!(
-                    "Results stored in buffer '{}'\n",
-                    output_buffer.unwrap_or("")
+                    "Results stored in buffer '{}' (ID: {}) with {} chunks ({} embedded)\n",
+                    name, stored.buffer_id, stored.chunk_count, stored.embedded_count
                 ));
             }
 
@@ -874,6 +2254,7 @@ fn cmd_aggregate(
             let json = serde_json::json!({
                 "summary": {
                     "total_findings": total_findings,
+                    "critical_relevance": critical_count,
                     "high_relevance": high_count,
                     "medium_relevance": medium_count,
                     "low_relevance": low_count,
@@ -882,33 +2263,337 @@ fn cmd_aggregate(
                 "grouped": grouped,
                 "findings": sorted,
                 "all_findings_deduplicated": all_findings,
-                "output_buffer": output_buffer
+                "output_buffer": output_buffer,
+                "output_buffer_id": stored.map(|s| s.buffer_id),
+                "output_chunk_count": stored.map(|s| s.chunk_count),
+                "output_embedded_count": stored.map(|s| s.embedded_count)
             });
             Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
         }
     }
 }
 
+/// Serialization used by `export-buffers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    /// Buffer contents separated by blank lines.
+    Raw,
+    /// Array of buffer objects.
+    Json,
+    /// One chunk object per line.
+    Jsonl,
+    /// Markdown document with a heading per buffer and chunk.
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Maps the `--as` value, or the global `--format` when `--as` is not
+    /// given.
+    const fn resolve(as_format: Option<&str>, format: OutputFormat) -> Self {
+        match as_format {
+            Some("json") => Self::Json,
+            Some("jsonl") => Self::Jsonl,
+            Some("markdown") => Self::Markdown,
+            // `text`; clap rejects anything else
+            Some(_) => Self::Raw,
+            None => match format {
+                OutputFormat::Text => Self::Raw,
+                OutputFormat::Json => Self::Json,
+                OutputFormat::Ndjson => Self::Jsonl,
+            },
+        }
+    }
+}
+
 fn cmd_export_buffers(
     db_path: &std::path::Path,
     output: Option<&std::path::Path>,
-    _pretty: bool,
-    _format: OutputFormat,
+    identifier: Option<&str>,
+    pretty: bool,
+    format: ExportFormat,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
-    let content = storage.export_buffers()?;
+    let selected = || -> Result<Vec<Buffer>> {
+        match identifier {
+            Some(identifier) => Ok(vec![resolve_buffer(&storage, identifier)?]),
+            None => storage.list_buffers(),
+        }
+    };
+    let content = match format {
+        ExportFormat::Raw => match identifier {
+            Some(identifier) => resolve_buffer(&storage, identifier)?.content,
+            None => storage.export_buffers()?,
+        },
+        ExportFormat::Json => export_buffers_json(&storage, &selected()?, pretty)?,
+        ExportFormat::Jsonl => export_chunks_jsonl(&storage, &selected()?)?,
+        ExportFormat::Markdown => export_chunks_markdown(&storage, &selected()?)?,
+    };
 
     if let Some(path) = output {
         write_file(path, &content)?;
         Ok(format!("Exported buffers to: {}\n", path.display()))
     } else {
-        // Write to stdout
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        handle.write_all(content.as_bytes()).map_err(|e| {
-            CommandError::ExecutionFailed(format!("Failed to write to stdout: {e}"))
-        })?;
-        Ok(String::new()) // Content already written
+        Ok(content)
+    }
+}
+
+fn export_buffers_json(
+    storage: &SqliteStorage,
+    buffers: &[Buffer],
+    pretty: bool,
+) -> Result<String> {
+    let mut json = Vec::with_capacity(buffers.len());
+    for buffer in buffers {
+        let chunks: Vec<_> = storage
+            .get_chunks(buffer.id.unwrap_or(0))?
+            .iter()
+            .map(|chunk| {
+                let mut json = chunk_json(chunk);
+                json["strategy"] = serde_json::json!(chunk.metadata.strategy);
+                json
+            })
+            .collect();
+        json.push(serde_json::json!({
+            "id": buffer.id,
+            "name": buffer.name,
+            "source": buffer.source,
+            "content_type": buffer.metadata.content_type,
+            "size": buffer.metadata.size,
+            "content": buffer.content,
+            "chunks": chunks
+        }));
+    }
+    let mut output = if pretty {
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    } else {
+        serde_json::to_string(&json).unwrap_or_default()
+    };
+    output.push('\n');
+    Ok(output)
+}
+
+fn export_chunks_jsonl(storage: &SqliteStorage, buffers: &[Buffer]) -> Result<String> {
+    let mut output = String::new();
+    for buffer in buffers {
+        for chunk in storage.get_chunks(buffer.id.unwrap_or(0))? {
+            let mut json = chunk_json(&chunk);
+            json["buffer_name"] = serde_json::json!(buffer.name);
+            let _ = writeln!(output, "{json}");
+        }
+    }
+    Ok(output)
+}
+
+fn export_chunks_markdown(storage: &SqliteStorage, buffers: &[Buffer]) -> Result<String> {
+    let mut output = String::new();
+    for buffer in buffers {
+        let id = buffer.id.unwrap_or(0);
+        let title = buffer
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Buffer {id}"));
+        let _ = writeln!(output, "# {title}\n");
+        if let Some(source) = &buffer.source {
+            let _ = writeln!(output, "Source: `{}`\n", source.display());
+        }
+
+        for chunk in storage.get_chunks(id)? {
+            let _ = writeln!(
+                output,
+                "## Chunk {} (bytes {}..{})\n\n{}\n",
+                chunk.index,
+                chunk.byte_range.start,
+                chunk.byte_range.end,
+                chunk.content.trim_end()
+            );
+        }
+    }
+    Ok(output)
+}
+
+/// A buffer as written by `export-buffers --as json`.
+#[derive(Debug, serde::Deserialize)]
+struct ExportedBuffer {
+    name: Option<String>,
+    source: Option<std::path::PathBuf>,
+    content_type: Option<String>,
+    content: String,
+    /// Missing in hand-written files; the buffer is then re-chunked.
+    chunks: Option<Vec<ExportedChunk>>,
+}
+
+/// A chunk within an [`ExportedBuffer`].
+#[derive(Debug, serde::Deserialize)]
+struct ExportedChunk {
+    index: usize,
+    byte_range: ExportedRange,
+    content: String,
+    strategy: Option<String>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ExportedRange {
+    start: usize,
+    end: usize,
+}
+
+/// Parses and validates an export file.
+fn parse_export(text: &str) -> std::result::Result<Vec<ExportedBuffer>, String> {
+    let buffers: Vec<ExportedBuffer> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    for (i, buffer) in buffers.iter().enumerate() {
+        for chunk in buffer.chunks.iter().flatten() {
+            let ExportedRange { start, end } = chunk.byte_range;
+            if start > end
+                || end > buffer.content.len()
+                || !buffer.content.is_char_boundary(start)
+                || !buffer.content.is_char_boundary(end)
+            {
+                return Err(format!(
+                    "buffer {i}: chunk {} has invalid byte range {start}..{end}",
+                    chunk.index
+                ));
+            }
+            if chunk.content != buffer.content[start..end] {
+                return Err(format!(
+                    "buffer {i}: chunk {} content does not match byte range {start}..{end}",
+                    chunk.index
+                ));
+            }
+        }
+    }
+    Ok(buffers)
+}
+
+/// Returns `name` with the first free `-N` suffix.
+fn free_buffer_name(storage: &SqliteStorage, name: &str) -> Result<String> {
+    let mut n = 2;
+    loop {
+        let candidate = format!("{name}-{n}");
+        if storage.find_buffer_id(&candidate)?.is_none() {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+fn cmd_import_buffers(
+    db_path: &std::path::Path,
+    file: &std::path::Path,
+    embed: bool,
+    rename: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let text = read_file(file)?;
+    let exported = parse_export(&text).map_err(|reason| {
+        CommandError::InvalidArgument(format!("invalid export file {}: {reason}", file.display()))
+    })?;
+
+    let mut storage = open_storage(db_path)?;
+    let embedder = if embed {
+        Some(create_embedder()?)
+    } else {
+        None
+    };
+    let options = LoadOptions::new();
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for buffer in exported {
+        let mut name = buffer.name;
+        if let Some(existing) = &name
+            && storage.find_buffer_id(existing)?.is_some()
+        {
+            if !rename {
+                skipped.push(existing.clone());
+                continue;
+            }
+            name = Some(free_buffer_name(&storage, existing)?);
+        }
+
+        let mut new_buffer = Buffer::from_content(buffer.content);
+        new_buffer.name.clone_from(&name);
+        new_buffer.source = buffer.source;
+        new_buffer.metadata.content_type = buffer.content_type;
+
+        let embedder = embedder.as_deref();
+        let loaded = match buffer.chunks {
+            Some(chunks) => {
+                let chunks = chunks
+                    .into_iter()
+                    .map(|c| {
+                        let mut chunk =
+                            Chunk::new(0, c.content, c.byte_range.start..c.byte_range.end, c.index);
+                        chunk.metadata.strategy = c.strategy;
+                        if let Some(metadata) = &c.metadata {
+                            chunk.set_custom_metadata(metadata);
+                        }
+                        chunk
+                    })
+                    .collect();
+                ingest_chunked_buffer(
+                    &mut storage,
+                    embedder,
+                    new_buffer,
+                    chunks,
+                    options.embed_workers,
+                )?
+            }
+            None => ingest_buffer(&mut storage, embedder, new_buffer, &options)?,
+        };
+        imported.push((name, loaded));
+    }
+
+    let chunk_count: usize = imported.iter().map(|(_, l)| l.chunk_count).sum();
+    let embedded_count: usize = imported.iter().map(|(_, l)| l.embedded_count).sum();
+    match format {
+        OutputFormat::Text => {
+            let mut output = format!(
+                "Imported {} buffers ({chunk_count} chunks, {embedded_count} embedded) from {}\n",
+                imported.len(),
+                file.display()
+            );
+            for (name, loaded) in &imported {
+                let _ = writeln!(
+                    output,
+                    "  {} (ID: {}): {} chunks",
+                    name.as_deref().unwrap_or("unnamed"),
+                    loaded.buffer_id,
+                    loaded.chunk_count
+                );
+            }
+            if !skipped.is_empty() {
+                let _ = writeln!(
+                    output,
+                    "Skipped {} existing: {}",
+                    skipped.len(),
+                    skipped.join(", ")
+                );
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let buffers: Vec<_> = imported
+                .iter()
+                .map(|(name, loaded)| {
+                    serde_json::json!({
+                        "buffer_id": loaded.buffer_id,
+                        "name": name,
+                        "chunk_count": loaded.chunk_count,
+                        "embedded_count": loaded.embedded_count
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "imported": buffers,
+                "skipped": skipped,
+                "buffer_count": imported.len(),
+                "chunk_count": chunk_count,
+                "embedded_count": embedded_count
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
     }
 }
 
@@ -978,6 +2663,43 @@ fn cmd_global(
     }
 }
 
+fn cmd_vars(db_path: &std::path::Path, scope: &str, format: OutputFormat) -> Result<String> {
+    let storage = open_storage(db_path)?;
+    let context = storage.load_context()?.unwrap_or_else(Context::new);
+
+    // (JSON key, text heading, values sorted by name)
+    let mut sections: Vec<(&str, &str, std::collections::BTreeMap<_, _>)> = Vec::new();
+    if scope != "global" {
+        sections.push(("variables", "Variables", context.variables.iter().collect()));
+    }
+    if scope != "var" {
+        sections.push(("globals", "Globals", context.globals.iter().collect()));
+    }
+
+    match format {
+        OutputFormat::Text => {
+            let mut output = String::new();
+            for (_, heading, values) in &sections {
+                let _ = writeln!(output, "{heading}:");
+                if values.is_empty() {
+                    output.push_str("  (none)\n");
+                }
+                for (name, value) in values {
+                    let _ = writeln!(output, "  {name} = {value:?}");
+                }
+            }
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json: serde_json::Map<String, serde_json::Value> = sections
+                .into_iter()
+                .map(|(key, _, values)| (key.to_string(), serde_json::json!(values)))
+                .collect();
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
 // ==================== Dispatch Command ====================
 
 #[allow(clippy::too_many_arguments)]
@@ -989,6 +2711,7 @@ fn cmd_dispatch(
     query: Option<&str>,
     mode: &str,
     threshold: f32,
+    manifest: Option<&std::path::Path>,
     format: OutputFormat,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
@@ -1056,6 +2779,14 @@ fn cmd_dispatch(
         .map(|chunk| chunk.to_vec())
         .collect();
 
+    if let Some(path) = manifest {
+        let manifest = dispatch_manifest(&storage, buffer_id, &buffer_name, query, mode, &batches)?;
+        write_file(
+            path,
+            &serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+        )?;
+    }
+
     match format {
         OutputFormat::Text => {
             let mut output = String::new();
@@ -1085,6 +2816,9 @@ fn cmd_dispatch(
 
             output
                 .push_str("\nUsage: Feed each batch to a subagent with 'rlm-cli chunk get <id>'\n");
+            if let Some(path) = manifest {
+                let _ = writeln!(output, "Manifest written to: {}", path.display());
+            }
             Ok(output)
         }
         OutputFormat::Json | OutputFormat::Ndjson => {
@@ -1095,6 +2829,7 @@ fn cmd_dispatch(
                 "batch_count": batches.len(),
                 "batch_size": effective_batch_size,
                 "query_filter": query,
+                "manifest": manifest.map(|p| p.display().to_string()),
                 "batches": batches.iter().enumerate().map(|(i, batch)| {
                     serde_json::json!({
                         "batch_index": i,
@@ -1108,6 +2843,50 @@ fn cmd_dispatch(
     }
 }
 
+/// Version of the `dispatch --manifest` file layout.
+const DISPATCH_MANIFEST_VERSION: u32 = 1;
+
+/// Builds the `dispatch --manifest` document.
+///
+/// Each batch lists its chunk IDs and, where known, their stable keys so a
+/// runner can still fetch the chunks after the buffer is reloaded. Runners
+/// retrieve chunks with `chunk get`, analyze them against `query`, and
+/// feed the findings to `aggregate`.
+fn dispatch_manifest(
+    storage: &SqliteStorage,
+    buffer_id: i64,
+    buffer_name: &str,
+    query: Option<&str>,
+    mode: &str,
+    batches: &[Vec<i64>],
+) -> Result<serde_json::Value> {
+    let chunk_count: usize = batches.iter().map(Vec::len).sum();
+    let batches = batches
+        .iter()
+        .enumerate()
+        .map(|(i, batch)| {
+            let keys = batch
+                .iter()
+                .map(|&id| storage.chunk_stable_key(id))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(serde_json::json!({
+                "batch_index": i,
+                "chunk_ids": batch,
+                "chunk_keys": keys
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serde_json::json!({
+        "version": DISPATCH_MANIFEST_VERSION,
+        "buffer_id": buffer_id,
+        "buffer_name": buffer_name,
+        "query": query,
+        "mode": mode,
+        "chunk_count": chunk_count,
+        "batches": batches
+    }))
+}
+
 // ==================== Search Commands ====================
 
 #[allow(clippy::too_many_arguments)]
@@ -1115,14 +2894,43 @@ fn cmd_search(
     db_path: &std::path::Path,
     query: &str,
     top_k: usize,
+    offset: usize,
     threshold: f32,
     mode: &str,
     rrf_k: u32,
+    fusion: &str,
+    alpha: f64,
+    metric: &str,
+    mmr_lambda: Option<f32>,
     buffer_filter: Option<&str>,
+    tag: Option<&str>,
     preview: bool,
     preview_len: usize,
+    context: usize,
+    enrich: bool,
+    fail_on_empty: bool,
     format: OutputFormat,
+    style: TextStyle,
 ) -> Result<String> {
+    let fusion = FusionMethod::parse(fusion, rrf_k, alpha).ok_or_else(|| {
+        CommandError::InvalidArgument(format!(
+            "unknown fusion method '{fusion}' (expected: rrf, weighted)"
+        ))
+    })?;
+    let metric = SimilarityMetric::parse(metric).ok_or_else(|| {
+        CommandError::InvalidArgument(format!(
+            "unknown similarity metric '{metric}' (expected: cosine, dot, euclidean)"
+        ))
+    })?;
+    if let Some(lambda) = mmr_lambda
+        && !(0.0..=1.0).contains(&lambda)
+    {
+        return Err(CommandError::InvalidArgument(format!(
+            "--mmr-lambda must be between 0.0 and 1.0, got {lambda}"
+        ))
+        .into());
+    }
+
     let storage = open_storage(db_path)?;
     let embedder = create_embedder()?;
 
@@ -1133,44 +2941,88 @@ fn cmd_search(
         _ => (true, true), // hybrid is default
     };
 
+    // MMR re-ranks a larger candidate pool, so it pages after re-ranking
+    let (candidates, search_offset) = if mmr_lambda.is_some() {
+        (
+            offset
+                .saturating_add(top_k)
+                .saturating_mul(MMR_CANDIDATE_FACTOR),
+            0,
+        )
+    } else {
+        (top_k, offset)
+    };
+
     let config = SearchConfig::new()
-        .with_top_k(top_k)
+        .with_top_k(candidates)
+        .with_offset(search_offset)
         .with_threshold(threshold)
         .with_rrf_k(rrf_k)
+        .with_fusion(fusion)
+        .with_metric(metric)
         .with_semantic(use_semantic)
         .with_bm25(use_bm25);
 
-    // If buffer filter is specified, validate it exists
-    let buffer_id = if let Some(identifier) = buffer_filter {
-        let buffer = resolve_buffer(&storage, identifier)?;
-        buffer.id
-    } else {
-        None
-    };
+    // If buffer filter is specified, validate each buffer exists.
+    // A name that itself contains commas is matched whole before splitting.
+    let mut buffer_ids = Vec::new();
+    if let Some(identifiers) = buffer_filter {
+        if let Ok(buffer) = resolve_buffer(&storage, identifiers) {
+            buffer_ids.extend(buffer.id);
+        } else {
+            for identifier in identifiers
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+            {
+                buffer_ids.extend(resolve_buffer(&storage, identifier)?.id);
+            }
+        }
+    }
+    if let Some(tag) = tag {
+        buffer_ids = storage.buffer_ids_with_tag(tag)?;
+        if buffer_ids.is_empty() {
+            return Err(CommandError::InvalidArgument(format!("no buffers tagged '{tag}'")).into());
+        }
+    }
+    let config = config.with_buffer_ids(buffer_ids);
 
-    let results = hybrid_search(&storage, embedder.as_ref(), query, &config)?;
+    let mut results: Vec<SearchResult> =
+        hybrid_search(&storage, embedder.as_ref(), query, &config)?;
 
-    // Filter by buffer if specified
-    let mut results: Vec<SearchResult> = if let Some(bid) = buffer_id {
-        let buffer_chunks: std::collections::HashSet<i64> = storage
-            .get_chunks(bid)?
-            .iter()
-            .filter_map(|c| c.id)
-            .collect();
-        results
-            .into_iter()
-            .filter(|r| buffer_chunks.contains(&r.chunk_id))
-            .collect()
-    } else {
-        results
-    };
+    if let Some(lambda) = mmr_lambda {
+        results = mmr_rerank(&storage, results, lambda, offset.saturating_add(top_k))?;
+        results.drain(..offset.min(results.len()));
+    }
 
     // Populate content previews if requested
     if preview {
         crate::search::populate_previews(&storage, &mut results, preview_len)?;
     }
 
-    Ok(format_search_results(&results, query, mode, format))
+    if enrich {
+        crate::search::enrich_results(&storage, &mut results)?;
+    }
+
+    let groups = if context > 0 {
+        Some(expand_context(&storage, &results, context)?)
+    } else {
+        None
+    };
+
+    let output = format_search_results(
+        &results,
+        groups.as_deref(),
+        preview_len,
+        query,
+        mode,
+        format,
+        style,
+    );
+    if fail_on_empty && results.is_empty() {
+        return Err(CommandError::NoResults(output).into());
+    }
+    Ok(output)
 }
 
 /// Formats a score for display, using scientific notation for very small values.
@@ -1184,11 +3036,35 @@ fn format_score(score: f64) -> String {
     }
 }
 
+/// Truncates chunk content to a single-line preview.
+fn context_preview(content: &str, preview_len: usize) -> String {
+    let end = crate::io::find_char_boundary(content, preview_len);
+    let mut preview = content[..end].replace('\n', " ");
+    if end < content.len() {
+        preview.push_str("...");
+    }
+    preview
+}
+
+/// Shortens `text` to at most `width` characters, ending in "..." if cut.
+fn fit_width(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(3)).collect();
+    fitted.push_str("...");
+    fitted
+}
+
+#[allow(clippy::too_many_lines)]
 fn format_search_results(
     results: &[SearchResult],
+    groups: Option<&[ContextGroup]>,
+    preview_len: usize,
     query: &str,
     mode: &str,
     format: OutputFormat,
+    style: TextStyle,
 ) -> String {
     match format {
         OutputFormat::Text => {
@@ -1196,62 +3072,126 @@ fn format_search_results(
                 return format!("No results found for query: \"{query}\"\n");
             }
 
+            let best = results.iter().map(|r| r.score).fold(0.0, f64::max);
+            let best_semantic = results
+                .iter()
+                .filter_map(|r| r.semantic_score)
+                .map(f64::from)
+                .fold(0.0, f64::max);
+            let best_bm25 = results
+                .iter()
+                .filter_map(|r| r.bm25_score)
+                .fold(0.0, f64::max);
+
             let mut output = String::new();
             let _ = writeln!(
                 output,
                 "Search results for \"{query}\" ({mode} mode, {} results):\n",
                 results.len()
             );
-            let _ = writeln!(
-                output,
+            let header = format!(
                 "{:<10} {:<12} {:<12} {:<12}",
                 "Chunk ID", "Score", "Semantic", "BM25"
             );
+            let _ = writeln!(output, "{}", style.bold(&header));
             output.push_str(&"-".repeat(50));
             output.push('\n');
 
-            for result in results {
-                let semantic = result
-                    .semantic_score
-                    .map_or_else(|| "-".to_string(), |s| format_score(f64::from(s)));
-                let bm25 = result
-                    .bm25_score
-                    .map_or_else(|| "-".to_string(), format_score);
-
-                let _ = writeln!(
-                    output,
-                    "{:<10} {:<12.4} {:<12} {:<12}",
-                    result.chunk_id, result.score, semantic, bm25
+            for (i, result) in results.iter().enumerate() {
+                let score = style.score(&format!("{:<12.4}", result.score), result.score, best);
+                let semantic = result.semantic_score.map_or_else(
+                    || format!("{:<12}", "-"),
+                    |s| {
+                        let s = f64::from(s);
+                        style.score(&format!("{:<12}", format_score(s)), s, best_semantic)
+                    },
                 );
+                let bm25 = result.bm25_score.map_or_else(
+                    || "-".to_string(),
+                    |s| style.score(&format_score(s), s, best_bm25),
+                );
+
+                let _ = writeln!(output, "{:<10} {score} {semantic} {bm25}", result.chunk_id);
+
+                if let Some(ref name) = result.buffer_name {
+                    let _ = writeln!(output, "  Buffer: {name}");
+                }
+                if let Some(ref metadata) = result.metadata {
+                    let _ = writeln!(output, "  Metadata: {metadata}");
+                }
 
                 // Show content preview if available
                 if let Some(ref preview) = result.content_preview {
+                    let preview = fit_width(preview, style.remaining(11, usize::MAX));
                     let _ = writeln!(output, "  Preview: {preview}");
                 }
+
+                if let Some(group) = groups.and_then(|g| g.get(i)) {
+                    for chunk in &group.chunks {
+                        let marker = if chunk.is_hit { ">" } else { " " };
+                        let prefix = format!("  {marker} [{}] #{}: ", chunk.chunk_id, chunk.index);
+                        let preview = fit_width(
+                            &context_preview(&chunk.content, preview_len),
+                            style.remaining(prefix.len(), usize::MAX),
+                        );
+                        let _ = writeln!(output, "{prefix}{preview}");
+                    }
+                }
             }
 
             output.push_str("\nUse 'rlm-cli chunk get <id>' to retrieve chunk content.\n");
             output
         }
         OutputFormat::Json | OutputFormat::Ndjson => {
+            let records = results.iter().enumerate().map(|(i, r)| {
+                let mut obj = serde_json::json!({
+                    "chunk_id": r.chunk_id,
+                    "buffer_id": r.buffer_id,
+                    "index": r.index,
+                    "score": r.score,
+                    "semantic_score": r.semantic_score,
+                    "bm25_score": r.bm25_score
+                });
+                if let Some(ref name) = r.buffer_name {
+                    obj["buffer_name"] = serde_json::json!(name);
+                }
+                if let Some(ref metadata) = r.metadata {
+                    obj["metadata"] = metadata.clone();
+                }
+                if let Some(ref preview) = r.content_preview {
+                    obj["content_preview"] = serde_json::json!(preview);
+                }
+                if let Some(group) = groups.and_then(|g| g.get(i)) {
+                    obj["context"] = group
+                        .chunks
+                        .iter()
+                        .map(|c| {
+                            serde_json::json!({
+                                "chunk_id": c.chunk_id,
+                                "index": c.index,
+                                "is_hit": c.is_hit,
+                                "content": c.content
+                            })
+                        })
+                        .collect::<serde_json::Value>();
+                }
+                obj
+            });
+            if format.is_streaming() {
+                return format_ndjson(
+                    records,
+                    serde_json::json!({
+                        "query": query,
+                        "mode": mode,
+                        "count": results.len(),
+                    }),
+                );
+            }
             let json = serde_json::json!({
                 "query": query,
                 "mode": mode,
                 "count": results.len(),
-                "results": results.iter().map(|r| {
-                    let mut obj = serde_json::json!({
-                        "chunk_id": r.chunk_id,
-                        "buffer_id": r.buffer_id,
-                        "index": r.index,
-                        "score": r.score,
-                        "semantic_score": r.semantic_score,
-                        "bm25_score": r.bm25_score
-                    });
-                    if let Some(ref preview) = r.content_preview {
-                        obj["content_preview"] = serde_json::json!(preview);
-                    }
-                    obj
-                }).collect::<Vec<_>>()
+                "results": records.collect::<Vec<_>>()
             });
             serde_json::to_string_pretty(&json).unwrap_or_default()
         }
@@ -1260,65 +3200,210 @@ fn format_search_results(
 
 // ==================== Chunk Commands ====================
 
+/// Maximum number of chunk IDs a single `chunk get` range may expand to.
+const MAX_CHUNK_GET_IDS: usize = 10_000;
+
+/// Parses `chunk get` arguments: chunk IDs and inclusive `start-end` ranges.
+fn parse_chunk_ids(args: &[String]) -> Result<Vec<i64>> {
+    let invalid = |arg: &str| -> crate::error::Error {
+        CommandError::InvalidArgument(format!(
+            "invalid chunk ID or range '{arg}' (expected e.g. 42 or 10-14)"
+        ))
+        .into()
+    };
+
+    let mut ids = Vec::new();
+    for arg in args {
+        let arg = arg.trim();
+        if let Ok(id) = arg.parse::<i64>() {
+            ids.push(id);
+            continue;
+        }
+
+        let (start, end) = arg.split_once('-').ok_or_else(|| invalid(arg))?;
+        let start: i64 = start.trim().parse().map_err(|_| invalid(arg))?;
+        let end: i64 = end.trim().parse().map_err(|_| invalid(arg))?;
+        if start > end {
+            return Err(invalid(arg));
+        }
+        if !usize::try_from(end - start)
+            .is_ok_and(|span| ids.len().saturating_add(span) < MAX_CHUNK_GET_IDS)
+        {
+            return Err(CommandError::InvalidArgument(format!(
+                "chunk range '{arg}' exceeds {MAX_CHUNK_GET_IDS} IDs"
+            ))
+            .into());
+        }
+        ids.extend(start..=end);
+    }
+    Ok(ids)
+}
+
 fn cmd_chunk_get(
     db_path: &std::path::Path,
-    chunk_id: i64,
+    args: &[String],
     include_metadata: bool,
     format: OutputFormat,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
 
-    let chunk = storage
-        .get_chunk(chunk_id)?
-        .ok_or(StorageError::ChunkNotFound { id: chunk_id })?;
+    // Stable chunk keys resolve to the chunk's current ID
+    let args = args
+        .iter()
+        .map(|arg| {
+            let key = arg.trim();
+            if Chunk::parse_stable_key(key).is_none() {
+                return Ok(arg.clone());
+            }
+            storage
+                .find_chunk_by_stable_key(key)?
+                .map(|id| id.to_string())
+                .ok_or_else(|| {
+                    CommandError::InvalidArgument(format!("no chunk with stable key '{key}'"))
+                        .into()
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ids = parse_chunk_ids(&args)?;
+    let stable_key = |chunk: &Chunk| -> Result<Option<String>> {
+        if format == OutputFormat::Text && !include_metadata {
+            return Ok(None);
+        }
+        storage.chunk_stable_key(chunk.id.unwrap_or(0))
+    };
+
+    // A single plain ID keeps the original output and fails if missing
+    if let ([id], [arg]) = (ids.as_slice(), args.as_slice())
+        && arg.trim().parse::<i64>().is_ok()
+    {
+        let chunk = storage
+            .get_chunk(*id)?
+            .ok_or(StorageError::ChunkNotFound { id: *id })?;
+        let key = stable_key(&chunk)?;
+        return Ok(match format {
+            OutputFormat::Text => format_chunk_text(&chunk, include_metadata, key.as_deref()),
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                serde_json::to_string_pretty(&chunk_get_json(&chunk, key.as_deref()))
+                    .unwrap_or_default()
+            }
+        });
+    }
+
+    let chunks = ids
+        .iter()
+        .map(|&id| {
+            let chunk = storage.get_chunk(id)?;
+            let key = chunk.as_ref().map(stable_key).transpose()?.flatten();
+            Ok((id, chunk, key))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     match format {
         OutputFormat::Text => {
-            if include_metadata {
-                let mut output = String::new();
-                let _ = writeln!(output, "Chunk ID: {}", chunk.id.unwrap_or(0));
-                let _ = writeln!(output, "Buffer ID: {}", chunk.buffer_id);
-                let _ = writeln!(output, "Index: {}", chunk.index);
-                let _ = writeln!(
-                    output,
-                    "Byte range: {}..{}",
-                    chunk.byte_range.start, chunk.byte_range.end
-                );
-                let _ = writeln!(output, "Size: {} bytes", chunk.size());
-                output.push_str("---\n");
-                output.push_str(&chunk.content);
-                if !chunk.content.ends_with('\n') {
+            let mut output = String::new();
+            for (i, (id, chunk, key)) in chunks.iter().enumerate() {
+                if i > 0 {
                     output.push('\n');
                 }
-                Ok(output)
-            } else {
-                // Plain content output for pass-by-reference use case
-                Ok(chunk.content)
+                let _ = writeln!(output, "==> chunk {id} <==");
+                match chunk {
+                    Some(chunk) => {
+                        output.push_str(&format_chunk_text(
+                            chunk,
+                            include_metadata,
+                            key.as_deref(),
+                        ));
+                        if !output.ends_with('\n') {
+                            output.push('\n');
+                        }
+                    }
+                    None => output.push_str("(not found)\n"),
+                }
             }
+            Ok(output)
         }
         OutputFormat::Json | OutputFormat::Ndjson => {
-            let json = serde_json::json!({
-                "chunk_id": chunk.id,
-                "buffer_id": chunk.buffer_id,
-                "index": chunk.index,
-                "byte_range": {
-                    "start": chunk.byte_range.start,
-                    "end": chunk.byte_range.end
-                },
-                "size": chunk.size(),
-                "content": chunk.content
-            });
+            let json: Vec<serde_json::Value> = chunks
+                .iter()
+                .map(|(_, chunk, key)| {
+                    chunk.as_ref().map_or(serde_json::Value::Null, |chunk| {
+                        chunk_get_json(chunk, key.as_deref())
+                    })
+                })
+                .collect();
             Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
         }
     }
 }
 
+/// Formats a chunk for `chunk get` text output.
+fn format_chunk_text(chunk: &Chunk, include_metadata: bool, stable_key: Option<&str>) -> String {
+    if !include_metadata {
+        // Plain content output for pass-by-reference use case
+        return chunk.content.clone();
+    }
+
+    let mut output = String::new();
+    let _ = writeln!(output, "Chunk ID: {}", chunk.id.unwrap_or(0));
+    if let Some(key) = stable_key {
+        let _ = writeln!(output, "Stable key: {key}");
+    }
+    let _ = writeln!(output, "Buffer ID: {}", chunk.buffer_id);
+    let _ = writeln!(output, "Index: {}", chunk.index);
+    let _ = writeln!(
+        output,
+        "Byte range: {}..{}",
+        chunk.byte_range.start, chunk.byte_range.end
+    );
+    let _ = writeln!(output, "Size: {} bytes", chunk.size());
+    if let Some(metadata) = chunk.custom_metadata() {
+        let _ = writeln!(output, "Metadata: {metadata}");
+    }
+    output.push_str("---\n");
+    output.push_str(&chunk.content);
+    if !chunk.content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+/// Builds the `chunk get` JSON object, adding the stable key when known.
+fn chunk_get_json(chunk: &Chunk, stable_key: Option<&str>) -> serde_json::Value {
+    let mut json = chunk_json(chunk);
+    if let Some(key) = stable_key {
+        json["stable_key"] = serde_json::json!(key);
+    }
+    json
+}
+
+/// Builds the `chunk get` JSON object for a chunk.
+///
+/// Chunker-specific metadata is included as `metadata` when present.
+fn chunk_json(chunk: &Chunk) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "chunk_id": chunk.id,
+        "buffer_id": chunk.buffer_id,
+        "index": chunk.index,
+        "byte_range": {
+            "start": chunk.byte_range.start,
+            "end": chunk.byte_range.end
+        },
+        "size": chunk.size(),
+        "content": chunk.content
+    });
+    if let Some(metadata) = chunk.custom_metadata() {
+        json["metadata"] = metadata;
+    }
+    json
+}
+
 fn cmd_chunk_list(
     db_path: &std::path::Path,
     identifier: &str,
     show_preview: bool,
     preview_len: usize,
     format: OutputFormat,
+    style: TextStyle,
 ) -> Result<String> {
     let storage = open_storage(db_path)?;
     let buffer = resolve_buffer(&storage, identifier)?;
@@ -1344,14 +3429,17 @@ fn cmd_chunk_list(
             );
 
             if show_preview {
-                let _ = writeln!(
-                    output,
+                let header = format!(
                     "{:<8} {:<6} {:<12} {:<12} Preview",
                     "ID", "Index", "Start", "Size"
                 );
+                let _ = writeln!(output, "{}", style.bold(&header));
                 output.push_str(&"-".repeat(70));
                 output.push('\n');
 
+                // Keep each row on one terminal line: 42 columns precede the
+                // preview and 3 more are reserved for the ellipsis
+                let preview_len = preview_len.min(style.remaining(45, preview_len));
                 for chunk in &chunks {
                     let preview: String = chunk
                         .content
@@ -1376,11 +3464,8 @@ fn cmd_chunk_list(
                     );
                 }
             } else {
-                let _ = writeln!(
-                    output,
-                    "{:<8} {:<6} {:<12} {:<12}",
-                    "ID", "Index", "Start", "Size"
-                );
+                let header = format!("{:<8} {:<6} {:<12} {:<12}", "ID", "Index", "Start", "Size");
+                let _ = writeln!(output, "{}", style.bold(&header));
                 output.push_str(&"-".repeat(40));
                 output.push('\n');
 
@@ -1399,26 +3484,37 @@ fn cmd_chunk_list(
             Ok(output)
         }
         OutputFormat::Json | OutputFormat::Ndjson => {
+            let records = chunks.iter().map(|c| {
+                let mut obj = serde_json::json!({
+                    "id": c.id,
+                    "index": c.index,
+                    "byte_range": {
+                        "start": c.byte_range.start,
+                        "end": c.byte_range.end
+                    },
+                    "size": c.size()
+                });
+                if show_preview {
+                    let preview: String = c.content.chars().take(preview_len).collect();
+                    obj["preview"] = serde_json::Value::String(preview);
+                }
+                obj
+            });
+            if format.is_streaming() {
+                return Ok(format_ndjson(
+                    records,
+                    serde_json::json!({
+                        "buffer_id": buffer_id,
+                        "buffer_name": buffer.name,
+                        "chunk_count": chunks.len(),
+                    }),
+                ));
+            }
             let json = serde_json::json!({
                 "buffer_id": buffer_id,
                 "buffer_name": buffer.name,
                 "chunk_count": chunks.len(),
-                "chunks": chunks.iter().map(|c| {
-                    let mut obj = serde_json::json!({
-                        "id": c.id,
-                        "index": c.index,
-                        "byte_range": {
-                            "start": c.byte_range.start,
-                            "end": c.byte_range.end
-                        },
-                        "size": c.size()
-                    });
-                    if show_preview {
-                        let preview: String = c.content.chars().take(preview_len).collect();
-                        obj["preview"] = serde_json::Value::String(preview);
-                    }
-                    obj
-                }).collect::<Vec<_>>()
+                "chunks": records.collect::<Vec<_>>()
             });
             Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
         }
@@ -1429,6 +3525,7 @@ fn cmd_chunk_embed(
     db_path: &std::path::Path,
     identifier: &str,
     force: bool,
+    discard_other_dimensions: bool,
     format: OutputFormat,
 ) -> Result<String> {
     let mut storage = open_storage(db_path)?;
@@ -1438,12 +3535,28 @@ fn cmd_chunk_embed(
 
     let embedder = create_embedder()?;
 
-    // Use incremental embedding (force_reembed = force flag)
-    let result = crate::search::embed_buffer_chunks_incremental(
+    // Discarding mismatched embeddings database-wide is opt-in and reported
+    let discarded = if discard_other_dimensions {
+        crate::search::ensure_embedding_dimensions(
+            &mut storage,
+            embedder.as_ref(),
+            crate::search::DimensionMismatch::ReplaceAll,
+        )?
+    } else {
+        0
+    };
+
+    // Use incremental embedding (force_reembed = force flag), committing
+    // in batches and reporting progress on stderr
+    let mut stderr = io::stderr();
+    let result = crate::search::embed_buffer_chunks_incremental_with_progress(
         &mut storage,
         embedder.as_ref(),
         buffer_id,
         force,
+        &mut |done, total| {
+            let _ = writeln!(stderr, "embedded {done}/{total}");
+        },
     )?;
 
     // Check for model version mismatch warning
@@ -1470,6 +3583,24 @@ fn cmd_chunk_embed(
                 output.push_str(warning);
                 output.push('\n');
             }
+            if discarded > 0 {
+                output.push_str(&format!(
+                    "Warning: Deleted {discarded} embeddings with a different dimension across \
+                     all buffers. Re-embed the other buffers with 'chunk embed'.\n"
+                ));
+            }
+            if !result.invalid_chunk_ids.is_empty() {
+                let ids: Vec<String> = result
+                    .invalid_chunk_ids
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                output.push_str(&format!(
+                    "Warning: Skipped {} chunks with zero or NaN embeddings (IDs: {}).\n",
+                    ids.len(),
+                    ids.join(", ")
+                ));
+            }
 
             if !result.had_changes() {
                 output.push_str(&format!(
@@ -1489,6 +3620,12 @@ fn cmd_chunk_embed(
                         result.replaced_count
                     ));
                 }
+                if result.cached_count > 0 {
+                    output.push_str(&format!(
+                        "Reused {} cached embeddings for previously seen content.\n",
+                        result.cached_count
+                    ));
+                }
                 if result.skipped_count > 0 {
                     output.push_str(&format!(
                         "Skipped {} chunks (already embedded with current model).\n",
@@ -1504,7 +3641,11 @@ fn cmd_chunk_embed(
                 "buffer_name": buffer_name,
                 "embedded_count": result.embedded_count,
                 "replaced_count": result.replaced_count,
+                "cached_count": result.cached_count,
                 "skipped_count": result.skipped_count,
+                "invalid_count": result.invalid_chunk_ids.len(),
+                "invalid_chunk_ids": result.invalid_chunk_ids,
+                "discarded_count": discarded,
                 "total_chunks": result.total_chunks,
                 "model": result.model_name,
                 "had_changes": result.had_changes(),
@@ -1516,11 +3657,97 @@ fn cmd_chunk_embed(
     }
 }
 
+fn cmd_chunk_delete(
+    db_path: &std::path::Path,
+    chunk_id: i64,
+    yes: bool,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut storage = open_storage(db_path)?;
+    if storage.get_chunk(chunk_id)?.is_none() {
+        return Err(StorageError::ChunkNotFound { id: chunk_id }.into());
+    }
+    confirm(yes, &chunk_id.to_string(), "Use --yes to confirm deletion.")?;
+    let buffer_id = storage.delete_chunk(chunk_id)?;
+
+    match format {
+        OutputFormat::Text => Ok(format!(
+            "Deleted chunk {chunk_id} from buffer {buffer_id}\n"
+        )),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "chunk_id": chunk_id,
+                "buffer_id": buffer_id,
+                "deleted": true
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+fn cmd_chunk_embedding(
+    db_path: &std::path::Path,
+    chunk_id: i64,
+    format: OutputFormat,
+) -> Result<String> {
+    let storage = open_storage(db_path)?;
+    if storage.get_chunk(chunk_id)?.is_none() {
+        return Err(StorageError::ChunkNotFound { id: chunk_id }.into());
+    }
+    let (values, model) = storage.get_embedding_with_model(chunk_id)?.ok_or_else(|| {
+        CommandError::ExecutionFailed(format!(
+            "Chunk {chunk_id} has no embedding. Run: rlm-cli chunk embed <buffer>"
+        ))
+    })?;
+
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let zero_count = values.iter().filter(|v| **v == 0.0).count();
+    let nan_count = values.iter().filter(|v| v.is_nan()).count();
+
+    match format {
+        OutputFormat::Text => {
+            let mut output = format!(
+                "Chunk {chunk_id} embedding: {} dimensions (model: {})\n",
+                values.len(),
+                model.as_deref().unwrap_or("unknown")
+            );
+            let _ = writeln!(
+                output,
+                "Norm: {norm:.4}, zero values: {zero_count}, NaN values: {nan_count}"
+            );
+            let values: Vec<String> = values.iter().map(|v| format!("{v:.6}")).collect();
+            let _ = writeln!(output, "[{}]", values.join(", "));
+            Ok(output)
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let json = serde_json::json!({
+                "chunk_id": chunk_id,
+                "model": model,
+                "dimensions": values.len(),
+                "norm": norm,
+                "zero_count": zero_count,
+                "nan_count": nan_count,
+                "values": values
+            });
+            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+        }
+    }
+}
+
+/// Embedding coverage of one buffer, as reported by `chunk status`.
+struct BufferEmbeddingStatus {
+    id: i64,
+    name: String,
+    chunks: usize,
+    embedded: usize,
+    models: Vec<String>,
+}
+
 fn cmd_chunk_status(db_path: &std::path::Path, format: OutputFormat) -> Result<String> {
     let storage = open_storage(db_path)?;
     let buffers = storage.list_buffers()?;
 
-    let mut buffer_stats: Vec<(String, i64, usize, usize)> = Vec::new();
+    let mut buffer_stats: Vec<BufferEmbeddingStatus> = Vec::new();
 
     for buffer in &buffers {
         let buffer_id = buffer.id.unwrap_or(0);
@@ -1537,11 +3764,17 @@ fn cmd_chunk_status(db_path: &std::path::Path, format: OutputFormat) -> Result<S
             }
         }
 
-        buffer_stats.push((buffer_name, buffer_id, chunk_count, embedded_count));
+        buffer_stats.push(BufferEmbeddingStatus {
+            id: buffer_id,
+            name: buffer_name,
+            chunks: chunk_count,
+            embedded: embedded_count,
+            models: storage.get_embedding_models(buffer_id)?,
+        });
     }
 
-    let total_chunks: usize = buffer_stats.iter().map(|(_, _, c, _)| c).sum();
-    let total_embedded: usize = buffer_stats.iter().map(|(_, _, _, e)| e).sum();
+    let total_chunks: usize = buffer_stats.iter().map(|b| b.chunks).sum();
+    let total_embedded: usize = buffer_stats.iter().map(|b| b.embedded).sum();
 
     match format {
         OutputFormat::Text => {
@@ -1556,29 +3789,38 @@ fn cmd_chunk_status(db_path: &std::path::Path, format: OutputFormat) -> Result<S
             if !buffer_stats.is_empty() {
                 let _ = writeln!(
                     output,
-                    "{:<6} {:<20} {:<10} {:<10} Status",
-                    "ID", "Name", "Chunks", "Embedded"
+                    "{:<6} {:<20} {:<10} {:<10} {:<12} Models",
+                    "ID", "Name", "Chunks", "Embedded", "Status"
                 );
-                output.push_str(&"-".repeat(60));
+                output.push_str(&"-".repeat(72));
                 output.push('\n');
 
-                for (name, id, chunks, embedded) in &buffer_stats {
-                    let status = if *embedded == *chunks {
+                for buffer in &buffer_stats {
+                    let status = if buffer.embedded == buffer.chunks {
                         "✓ complete"
-                    } else if *embedded > 0 {
+                    } else if buffer.embedded > 0 {
                         "◐ partial"
                     } else {
                         "○ none"
                     };
+                    let mut models = if buffer.models.is_empty() {
+                        "-".to_string()
+                    } else {
+                        buffer.models.join(", ")
+                    };
+                    if buffer.models.len() > 1 {
+                        models.push_str(" (mixed)");
+                    }
 
                     let _ = writeln!(
                         output,
-                        "{:<6} {:<20} {:<10} {:<10} {}",
-                        id,
-                        truncate_str(name, 20),
-                        chunks,
-                        embedded,
-                        status
+                        "{:<6} {:<20} {:<10} {:<10} {:<12} {}",
+                        buffer.id,
+                        truncate_str(&buffer.name, 20),
+                        buffer.chunks,
+                        buffer.embedded,
+                        status,
+                        models
                     );
                 }
             }
@@ -1589,13 +3831,15 @@ fn cmd_chunk_status(db_path: &std::path::Path, format: OutputFormat) -> Result<S
             let json = serde_json::json!({
                 "total_chunks": total_chunks,
                 "total_embedded": total_embedded,
-                "buffers": buffer_stats.iter().map(|(name, id, chunks, embedded)| {
+                "buffers": buffer_stats.iter().map(|b| {
                     serde_json::json!({
-                        "buffer_id": id,
-                        "name": name,
-                        "chunk_count": chunks,
-                        "embedded_count": embedded,
-                        "fully_embedded": chunks == embedded
+                        "buffer_id": b.id,
+                        "name": b.name,
+                        "chunk_count": b.chunks,
+                        "embedded_count": b.embedded,
+                        "fully_embedded": b.chunks == b.embedded,
+                        "models": b.models,
+                        "mixed_models": b.models.len() > 1
                     })
                 }).collect::<Vec<_>>()
             });
@@ -1726,6 +3970,129 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn finding(chunk_id: i64, relevance: &str, confidence: Option<f32>) -> AnalystFinding {
+        AnalystFinding {
+            chunk_id,
+            relevance: relevance.to_string(),
+            findings: Vec::new(),
+            summary: None,
+            follow_up: Vec::new(),
+            confidence,
+        }
+    }
+
+    fn chunk_ids(findings: &[AnalystFinding]) -> Vec<i64> {
+        findings.iter().map(|f| f.chunk_id).collect()
+    }
+
+    #[test]
+    fn test_finding_confidence_parsing() {
+        let parsed: Vec<AnalystFinding> = serde_json::from_str(
+            r#"[
+                {"chunk_id": 1, "relevance": "high", "confidence": 0.9},
+                {"chunk_id": 2, "relevance": "low"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(parsed[0].confidence, Some(0.9));
+        assert_eq!(parsed[1].confidence, None);
+
+        // Findings without confidence serialize as before
+        let json = serde_json::to_value(&parsed[1]).unwrap();
+        assert!(json.get("confidence").is_none());
+    }
+
+    #[test]
+    fn test_sort_findings_relevance_uses_confidence_tiebreak() {
+        let mut findings = vec![
+            finding(1, "medium", Some(0.9)),
+            finding(2, "high", None),
+            finding(3, "high", Some(0.4)),
+            finding(4, "high", Some(0.8)),
+        ];
+        sort_findings(&mut findings, "relevance");
+        assert_eq!(chunk_ids(&findings), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_critical_relevance_ranks_above_high() {
+        assert!(relevance_order("critical") < relevance_order("high"));
+        assert!(relevance_order("CRITICAL") < relevance_order("high"));
+        assert!(meets_relevance_threshold("critical", "critical"));
+        assert!(!meets_relevance_threshold("high", "critical"));
+        assert!(meets_relevance_threshold("critical", "low"));
+        assert!(meets_relevance_threshold("high", "high"));
+
+        let mut findings = vec![
+            finding(1, "high", Some(0.9)),
+            finding(2, "critical", None),
+            finding(3, "medium", None),
+        ];
+        sort_findings(&mut findings, "relevance");
+        assert_eq!(chunk_ids(&findings), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn test_sort_findings_temporal_ignores_relevance() {
+        let mut findings = vec![
+            finding(30, "high", Some(0.9)),
+            finding(10, "low", None),
+            finding(99, "critical", None),
+            finding(20, "medium", None),
+        ];
+        // Chunk 30 comes first in buffer 1; chunk 99 is unknown
+        let positions = std::collections::HashMap::from([(30, (1, 0)), (10, (1, 4)), (20, (2, 0))]);
+        sort_findings_temporal(&mut findings, &positions);
+        assert_eq!(chunk_ids(&findings), vec![30, 10, 20, 99]);
+    }
+
+    #[test]
+    fn test_sort_findings_by_confidence() {
+        let mut findings = vec![
+            finding(1, "high", None),
+            finding(2, "low", Some(0.7)),
+            finding(3, "medium", Some(0.95)),
+            finding(4, "high", Some(0.7)),
+        ];
+        sort_findings(&mut findings, "confidence");
+        assert_eq!(chunk_ids(&findings), vec![3, 4, 2, 1]);
+    }
+
+    #[test]
+    fn test_buffer_stats_compute() {
+        use crate::core::Chunk;
+
+        let mut buffer = Buffer::from_named(
+            "stats.txt".to_string(),
+            "alpha beta\ngamma\ndelta epsilon zeta\n".to_string(),
+        );
+        buffer.id = Some(7);
+        let chunks = vec![
+            Chunk::new(7, "alpha beta\n".to_string(), 0..11, 0),
+            Chunk::new(7, "gamma\ndelta epsilon zeta\n".to_string(), 11..36, 1),
+        ];
+
+        let stats = BufferStats::compute(&mut buffer, &chunks, 1);
+        assert_eq!(stats.bytes, 36);
+        assert_eq!(stats.lines, 3);
+        assert!(stats.estimated_tokens > 0);
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.min_chunk_size, 11);
+        assert_eq!(stats.max_chunk_size, 25);
+        assert!((stats.mean_chunk_size - 18.0).abs() < f64::EPSILON);
+        assert!((stats.embedding_coverage - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_buffer_stats_no_chunks() {
+        let mut buffer = Buffer::from_named("empty.txt".to_string(), String::new());
+        let stats = BufferStats::compute(&mut buffer, &[], 0);
+        assert_eq!(stats.chunk_count, 0);
+        assert_eq!(stats.min_chunk_size, 0);
+        assert!(stats.mean_chunk_size.abs() < f64::EPSILON);
+        assert!(stats.embedding_coverage.abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_truncate_str_short() {
         // String shorter than max_len should be returned as-is
@@ -1760,4 +4127,96 @@ mod tests {
         let result = truncate_str("hello", 4);
         assert_eq!(result, "h...");
     }
+
+    #[test]
+    fn test_parse_chunk_ids() {
+        let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(parse_chunk_ids(&args(&["7"])).unwrap(), vec![7]);
+        assert_eq!(
+            parse_chunk_ids(&args(&["3", "10-12", "5-5"])).unwrap(),
+            vec![3, 10, 11, 12, 5]
+        );
+        assert!(parse_chunk_ids(&args(&["12-10"])).is_err());
+        assert!(parse_chunk_ids(&args(&["1-"])).is_err());
+        assert!(parse_chunk_ids(&args(&["abc"])).is_err());
+        assert!(parse_chunk_ids(&args(&["1-100000"])).is_err());
+    }
+
+    #[test]
+    fn test_confirm_with() {
+        let mut output = Vec::new();
+        let confirmed = confirm_with(
+            &mut "notes.md\n".as_bytes(),
+            &mut output,
+            "Type: ",
+            "notes.md",
+        )
+        .unwrap();
+        assert!(confirmed);
+        assert_eq!(output, b"Type: ");
+
+        assert!(!confirm_with(&mut "notes\n".as_bytes(), &mut Vec::new(), "", "notes.md").unwrap());
+        assert!(!confirm_with(&mut "".as_bytes(), &mut Vec::new(), "", "notes.md").unwrap());
+    }
+
+    #[test]
+    fn test_confirm_yes_skips_prompt() {
+        assert!(confirm(true, "reset", "refused").is_ok());
+    }
+
+    #[test]
+    fn test_grep_buffer_streaming_matches_in_memory() {
+        let (_temp_dir, db_path) = setup();
+        let mut storage = SqliteStorage::open(&db_path).unwrap();
+        storage.init().unwrap();
+
+        let mut content = String::new();
+        for i in 0..6000 {
+            let _ = writeln!(content, "line {i}: word café TODO {i} 😀 ëxtra zzz");
+        }
+        assert!(content.len() > 3 * GREP_MAX_MATCH_LEN);
+        let buffer = Buffer::from_named("big".to_string(), content.clone());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+
+        for pattern in [
+            r"TODO \d+",
+            "é[a-z]*",
+            r"\bword\b",
+            "z*",
+            "(?m)^line",
+            "😀 ë",
+        ] {
+            let regex = Regex::new(pattern).unwrap();
+            let expected: Vec<(usize, String, String)> = regex
+                .find_iter(&content)
+                .map(|m| {
+                    let start =
+                        crate::io::find_char_boundary(&content, m.start().saturating_sub(5));
+                    let end =
+                        crate::io::find_char_boundary(&content, (m.end() + 5).min(content.len()));
+                    (
+                        m.start(),
+                        m.as_str().to_string(),
+                        content[start..end].to_string(),
+                    )
+                })
+                .collect();
+
+            for read_size in [4093, GREP_READ_SIZE] {
+                let found: Vec<(usize, String, String)> =
+                    grep_buffer(&storage, buffer_id, &regex, 5, usize::MAX, read_size)
+                        .unwrap()
+                        .into_iter()
+                        .map(|m| (m.offset, m.matched, m.snippet))
+                        .collect();
+                assert_eq!(found, expected, "pattern {pattern}, read size {read_size}");
+            }
+        }
+
+        let regex = Regex::new("TODO").unwrap();
+        let limited = grep_buffer(&storage, buffer_id, &regex, 5, 3, 4093).unwrap();
+        assert_eq!(limited.len(), 3);
+        assert!(grep_buffer(&storage, buffer_id + 1, &regex, 5, 3, 4093).is_err());
+    }
 }
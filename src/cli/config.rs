@@ -0,0 +1,287 @@
+//! Configuration file support.
+//!
+//! Defaults for common CLI options can be kept in TOML files instead of
+//! environment variables. Files are read from the user config directory
+//! (`$XDG_CONFIG_HOME/rlm-rs/config.toml`, falling back to
+//! `~/.config/rlm-rs/config.toml`) and then from `./.rlm/config.toml`, with
+//! the project file winning. Keys are the `RLM_*` environment variable names
+//! in lowercase without the prefix:
+//!
+//! ```toml
+//! db_path = ".rlm/rlm-state.db"  # RLM_DB_PATH
+//! format = "json"                # RLM_FORMAT
+//! color = "never"                # RLM_COLOR
+//! chunker = "code"               # RLM_CHUNKER
+//! chunk_size = 2000              # RLM_CHUNK_SIZE
+//! overlap = 200                  # RLM_OVERLAP
+//! min_chunk_size = 0             # RLM_MIN_CHUNK_SIZE
+//! finding_sort = "temporal"      # RLM_FINDING_SORT
+//! ```
+//!
+//! Precedence is command-line flag, then environment variable, then config
+//! file, then the built-in default.
+
+use crate::cli::parser::{Cli, Commands};
+use crate::error::{CommandError, Result};
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Project config file path, relative to the current directory.
+pub const PROJECT_CONFIG_PATH: &str = ".rlm/config.toml";
+
+/// User config file path, relative to the user config directory.
+pub const USER_CONFIG_PATH: &str = "rlm-rs/config.toml";
+
+/// Option defaults read from a config file.
+///
+/// Every field is optional; unset fields leave the built-in default alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Database path (`RLM_DB_PATH`).
+    pub db_path: Option<PathBuf>,
+    /// Output format (`RLM_FORMAT`).
+    pub format: Option<String>,
+    /// Color choice (`RLM_COLOR`).
+    pub color: Option<String>,
+    /// Chunking strategy for `load` (`RLM_CHUNKER`).
+    pub chunker: Option<String>,
+    /// Chunk size for `load` (`RLM_CHUNK_SIZE`).
+    pub chunk_size: Option<usize>,
+    /// Chunk overlap for `load` (`RLM_OVERLAP`).
+    pub overlap: Option<usize>,
+    /// Minimum chunk size for `load` (`RLM_MIN_CHUNK_SIZE`).
+    pub min_chunk_size: Option<usize>,
+    /// Finding sort order for `aggregate` (`RLM_FINDING_SORT`).
+    pub finding_sort: Option<String>,
+}
+
+impl Config {
+    /// Reads a config file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid config
+    /// TOML (including unknown keys).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            CommandError::InvalidArgument(format!(
+                "cannot read config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&text).map_err(|reason| {
+            CommandError::InvalidArgument(format!(
+                "invalid config file {}: {reason}",
+                path.display()
+            ))
+            .into()
+        })
+    }
+
+    /// Parses config TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser message if `text` is not valid config TOML.
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.message().to_string())
+    }
+
+    /// Loads and merges the user and project config files, skipping any
+    /// that do not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an existing config file is invalid.
+    pub fn load() -> Result<Self> {
+        let mut config = Self::default();
+        for path in [Self::user_path(), Some(PathBuf::from(PROJECT_CONFIG_PATH))]
+            .into_iter()
+            .flatten()
+        {
+            if path.is_file() {
+                config = config.merge(Self::from_file(&path)?);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the user config file path, if a config directory is known.
+    #[must_use]
+    pub fn user_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join(USER_CONFIG_PATH))
+    }
+
+    /// Returns `self` with fields set in `other` taking precedence.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            db_path: other.db_path.or(self.db_path),
+            format: other.format.or(self.format),
+            color: other.color.or(self.color),
+            chunker: other.chunker.or(self.chunker),
+            chunk_size: other.chunk_size.or(self.chunk_size),
+            overlap: other.overlap.or(self.overlap),
+            min_chunk_size: other.min_chunk_size.or(self.min_chunk_size),
+            finding_sort: other.finding_sort.or(self.finding_sort),
+        }
+    }
+
+    /// Fills options in `cli` that were neither passed as flags nor set in
+    /// the environment.
+    pub fn apply(&self, cli: &mut Cli, matches: &ArgMatches) {
+        if matches.value_source("db_path").is_none() {
+            cli.db_path = self.db_path.clone().or_else(|| cli.db_path.take());
+        }
+        fill(&mut cli.format, self.format.as_ref(), matches, "format");
+        fill(&mut cli.color, self.color.as_ref(), matches, "color");
+
+        if let Commands::Load {
+            chunker,
+            chunk_size,
+            overlap,
+            min_chunk_size,
+            ..
+        } = &mut cli.command
+            && let Some(("load", load)) = matches.subcommand()
+        {
+            fill(chunker, self.chunker.as_ref(), load, "chunker");
+            // No built-in default: unset means neither flag nor environment
+            *chunk_size = chunk_size.or(self.chunk_size);
+            *overlap = overlap.or(self.overlap);
+            fill(
+                min_chunk_size,
+                self.min_chunk_size.as_ref(),
+                load,
+                "min_chunk_size",
+            );
+        }
+
+        if let Commands::Aggregate { sort_by, .. } = &mut cli.command
+            && let Some(("aggregate", aggregate)) = matches.subcommand()
+        {
+            fill(sort_by, self.finding_sort.as_ref(), aggregate, "sort_by");
+        }
+    }
+}
+
+/// Replaces `value` with `configured` if the argument `id` still holds its
+/// built-in default.
+fn fill<T: Clone>(value: &mut T, configured: Option<&T>, matches: &ArgMatches, id: &str) {
+    if let Some(configured) = configured
+        && matches.value_source(id) == Some(ValueSource::DefaultValue)
+    {
+        value.clone_from(configured);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches};
+
+    fn parse_args(args: &[&str], config: &Config) -> Cli {
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        config.apply(&mut cli, &matches);
+        cli
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse("format = \"json\"\nchunk_size = 2000\n").unwrap();
+        assert_eq!(config.format.as_deref(), Some("json"));
+        assert_eq!(config.chunk_size, Some(2000));
+        assert_eq!(config.chunker, None);
+
+        assert!(Config::parse("formatt = \"json\"").is_err());
+        assert!(Config::parse("chunk_size = \"big\"").is_err());
+    }
+
+    #[test]
+    fn test_merge_prefers_later_config() {
+        let user = Config::parse("format = \"json\"\ncolor = \"never\"").unwrap();
+        let project = Config::parse("format = \"ndjson\"").unwrap();
+        let merged = user.merge(project);
+        assert_eq!(merged.format.as_deref(), Some("ndjson"));
+        assert_eq!(merged.color.as_deref(), Some("never"));
+    }
+
+    #[test]
+    fn test_apply_fills_defaults_only() {
+        let config = Config::parse(
+            "db_path = \"cfg.db\"\nformat = \"json\"\nchunker = \"code\"\noverlap = 10\nmin_chunk_size = 0",
+        )
+        .unwrap();
+
+        let cli = parse_args(&["rlm-cli", "load", "a.rs"], &config);
+        assert_eq!(cli.db_path, Some(PathBuf::from("cfg.db")));
+        assert_eq!(cli.format, "json");
+        assert!(matches!(
+            cli.command,
+            Commands::Load { ref chunker, chunk_size: None, overlap: Some(10), min_chunk_size: 0, .. }
+                if chunker == "code"
+        ));
+
+        // Flags win over the config file
+        let cli = parse_args(
+            &[
+                "rlm-cli",
+                "--db-path",
+                "flag.db",
+                "--format",
+                "text",
+                "load",
+                "a.rs",
+                "--chunker",
+                "fixed",
+            ],
+            &config,
+        );
+        assert_eq!(cli.db_path, Some(PathBuf::from("flag.db")));
+        assert_eq!(cli.format, "text");
+        assert!(matches!(
+            cli.command,
+            Commands::Load { ref chunker, .. } if chunker == "fixed"
+        ));
+    }
+
+    #[test]
+    fn test_apply_fills_finding_sort() {
+        let config = Config::parse("finding_sort = \"temporal\"").unwrap();
+
+        let cli = parse_args(&["rlm-cli", "aggregate"], &config);
+        assert!(matches!(
+            cli.command,
+            Commands::Aggregate { ref sort_by, .. } if sort_by == "temporal"
+        ));
+
+        let cli = parse_args(
+            &["rlm-cli", "aggregate", "--sort-by", "confidence"],
+            &config,
+        );
+        assert!(matches!(
+            cli.command,
+            Commands::Aggregate { ref sort_by, .. } if sort_by == "confidence"
+        ));
+    }
+
+    #[test]
+    fn test_from_file_reports_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        std::fs::write(&path, "unknown = 1").unwrap();
+
+        let err = Config::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("invalid config file"));
+        assert!(Config::from_file(temp_dir.path().join("missing.toml")).is_err());
+    }
+}
@@ -4,9 +4,11 @@
 //! for initializing, managing, and querying RLM state.
 
 pub mod commands;
+pub mod config;
 pub mod output;
 pub mod parser;
 
 pub use commands::execute;
+pub use config::Config;
 pub use output::OutputFormat;
 pub use parser::{Cli, Commands};
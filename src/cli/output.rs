@@ -1,11 +1,14 @@
 //! Output formatting for CLI commands.
 //!
-//! Supports text and JSON output formats.
+//! Supports text, JSON, newline-delimited JSON (NDJSON) and YAML output
+//! formats. YAML is converted from the JSON output. Text output can be
+//! colorized and sized to the terminal width.
 
 use crate::core::{Buffer, Chunk, Context};
 use crate::storage::traits::StorageStats;
 use serde::Serialize;
 use std::fmt::Write;
+use std::io::IsTerminal;
 
 /// Output format options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,18 +40,172 @@ impl OutputFormat {
     }
 }
 
+/// Output format chosen with `--format`.
+///
+/// Commands only produce [`OutputFormat`] output; YAML is a conversion of
+/// the JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatChoice {
+    /// Output produced directly by the command.
+    Native(OutputFormat),
+    /// YAML, converted from the JSON output (see [`to_yaml`]).
+    Yaml,
+}
+
+impl FormatChoice {
+    /// Parses a format choice from string.
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "yaml" | "yml" => Self::Yaml,
+            _ => Self::Native(OutputFormat::parse(s)),
+        }
+    }
+
+    /// Returns the format commands should produce output in.
+    #[must_use]
+    pub const fn output_format(self) -> OutputFormat {
+        match self {
+            Self::Native(format) => format,
+            Self::Yaml => OutputFormat::Json,
+        }
+    }
+
+    /// Converts output produced in [`Self::output_format`] to this format.
+    #[must_use]
+    pub fn convert(self, output: String) -> String {
+        match self {
+            Self::Native(_) => output,
+            Self::Yaml => to_yaml(&output),
+        }
+    }
+}
+
+/// When to colorize text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colorize when stdout is a terminal and `NO_COLOR` is unset.
+    #[default]
+    Auto,
+    /// Always colorize.
+    Always,
+    /// Never colorize.
+    Never,
+}
+
+impl ColorChoice {
+    /// Parses a color choice from string, defaulting to [`Self::Auto`].
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "always" => Self::Always,
+            "never" => Self::Never,
+            _ => Self::Auto,
+        }
+    }
+}
+
+/// ANSI escape for green text.
+const GREEN: &str = "\x1b[32m";
+/// ANSI escape for yellow text.
+const YELLOW: &str = "\x1b[33m";
+/// ANSI escape for red text.
+const RED: &str = "\x1b[31m";
+/// ANSI escape for bold text.
+const BOLD: &str = "\x1b[1m";
+/// ANSI escape resetting all attributes.
+const RESET: &str = "\x1b[0m";
+
+/// Presentation settings for text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStyle {
+    /// Whether to emit ANSI color codes.
+    pub color: bool,
+    /// Terminal width in columns, or `None` to use fixed column widths.
+    pub width: Option<usize>,
+}
+
+impl TextStyle {
+    /// Plain style with fixed column widths.
+    pub const PLAIN: Self = Self {
+        color: false,
+        width: None,
+    };
+
+    /// Detects the style for stdout.
+    ///
+    /// Non-text formats and non-terminal output always use fixed widths, and
+    /// are only colorized with [`ColorChoice::Always`] (text formats only).
+    #[must_use]
+    pub fn detect(choice: ColorChoice, format: OutputFormat) -> Self {
+        if format != OutputFormat::Text {
+            return Self::PLAIN;
+        }
+
+        let is_terminal = std::io::stdout().is_terminal();
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && std::env::var_os("NO_COLOR").is_none(),
+        };
+        let width = is_terminal
+            .then(terminal_size::terminal_size)
+            .flatten()
+            .map(|(terminal_size::Width(w), _)| usize::from(w));
+
+        Self { color, width }
+    }
+
+    /// Wraps `text` in bold when color is enabled.
+    #[must_use]
+    pub fn bold(&self, text: &str) -> String {
+        self.paint(text, BOLD)
+    }
+
+    /// Colors `text` by how `score` compares with the best score shown:
+    /// green within 75% of it, yellow within 40%, red otherwise.
+    #[must_use]
+    pub fn score(&self, text: &str, score: f64, best: f64) -> String {
+        let ratio = if best > 0.0 { score / best } else { 0.0 };
+        let code = if ratio >= 0.75 {
+            GREEN
+        } else if ratio >= 0.4 {
+            YELLOW
+        } else {
+            RED
+        };
+        self.paint(text, code)
+    }
+
+    /// Returns the columns left on a line after `used` columns, or
+    /// `fallback` when the width is unknown.
+    #[must_use]
+    pub fn remaining(&self, used: usize, fallback: usize) -> usize {
+        self.width
+            .map_or(fallback, |width| width.saturating_sub(used).max(10))
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
 /// Formats a status response.
 #[must_use]
-pub fn format_status(stats: &StorageStats, format: OutputFormat) -> String {
+pub fn format_status(stats: &StorageStats, format: OutputFormat, style: TextStyle) -> String {
     match format {
-        OutputFormat::Text => format_status_text(stats),
+        OutputFormat::Text => format_status_text(stats, style),
         OutputFormat::Json | OutputFormat::Ndjson => format_json(stats),
     }
 }
 
-fn format_status_text(stats: &StorageStats) -> String {
+fn format_status_text(stats: &StorageStats, style: TextStyle) -> String {
     let mut output = String::new();
-    output.push_str("RLM-RS Status\n");
+    let _ = writeln!(output, "{}", style.bold("RLM-RS Status"));
     output.push_str("=============\n\n");
     let _ = writeln!(output, "  Buffers:       {}", stats.buffer_count);
     let _ = writeln!(output, "  Chunks:        {}", stats.chunk_count);
@@ -74,7 +231,11 @@ fn format_status_text(stats: &StorageStats) -> String {
 pub fn format_buffer_list(buffers: &[Buffer], format: OutputFormat) -> String {
     match format {
         OutputFormat::Text => format_buffer_list_text(buffers),
-        OutputFormat::Json | OutputFormat::Ndjson => format_json(&buffers),
+        OutputFormat::Json => format_json(&buffers),
+        OutputFormat::Ndjson => format_ndjson(
+            buffers.iter().filter_map(|b| serde_json::to_value(b).ok()),
+            serde_json::json!({ "count": buffers.len() }),
+        ),
     }
 }
 
@@ -242,7 +403,17 @@ fn format_grep_text(matches: &[GrepMatch], pattern: &str) -> String {
     );
 
     for (i, m) in matches.iter().enumerate() {
-        let _ = writeln!(output, "Match {} at byte {}:", i + 1, m.offset);
+        if let Some(id) = m.buffer_id {
+            let name = m.buffer_name.as_deref().unwrap_or("unnamed");
+            let _ = writeln!(
+                output,
+                "Match {} in {name} (ID {id}) at byte {}:",
+                i + 1,
+                m.offset
+            );
+        } else {
+            let _ = writeln!(output, "Match {} at byte {}:", i + 1, m.offset);
+        }
         let _ = writeln!(output, "  {}", m.snippet.replace('\n', "\\n"));
     }
 
@@ -306,6 +477,32 @@ pub struct GrepMatch {
     pub matched: String,
     /// Context snippet around the match.
     pub snippet: String,
+    /// Buffer ID, set when grepping across all buffers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buffer_id: Option<i64>,
+    /// Buffer name, set when grepping across all buffers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buffer_name: Option<String>,
+}
+
+/// Formats records as NDJSON: one compact JSON object per line.
+///
+/// A final summary line is appended with `"type": "summary"` added to
+/// `summary`, so streaming consumers can tell it apart from the records.
+#[must_use]
+pub fn format_ndjson<I>(records: I, mut summary: serde_json::Value) -> String
+where
+    I: IntoIterator<Item = serde_json::Value>,
+{
+    let mut output = String::new();
+    for record in records {
+        let _ = writeln!(output, "{record}");
+    }
+    if let Some(obj) = summary.as_object_mut() {
+        obj.insert("type".to_string(), serde_json::json!("summary"));
+    }
+    let _ = writeln!(output, "{summary}");
+    output
 }
 
 /// Formats a value as JSON.
@@ -336,6 +533,37 @@ pub fn format_error(error: &crate::Error, format: OutputFormat) -> String {
     }
 }
 
+/// Converts JSON command output to YAML.
+///
+/// A single JSON document becomes one YAML document and JSONL output becomes
+/// a stream of YAML documents. Anything else (empty output, plain messages,
+/// raw content) is returned unchanged.
+#[must_use]
+pub fn to_yaml(json: &str) -> String {
+    let to_string = |value: &serde_json::Value| serde_yaml::to_string(value).ok();
+
+    if json.trim().is_empty() {
+        return json.to_string();
+    }
+
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(json)
+        && let Some(yaml) = to_string(&value)
+    {
+        return yaml;
+    }
+
+    let documents: Option<Vec<String>> = json
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|value| to_string(&value))
+        })
+        .collect();
+    documents.map_or_else(|| json.to_string(), |documents| documents.join("---\n"))
+}
+
 /// Extracts error type and recovery suggestion from an error.
 const fn get_error_details(error: &crate::Error) -> (&'static str, Option<&'static str>) {
     use crate::error::{ChunkingError, CommandError, IoError, StorageError};
@@ -391,9 +619,12 @@ const fn get_error_details(error: &crate::Error) -> (&'static str, Option<&'stat
             ChunkingError::ParallelFailed { .. } => ("ParallelError", None),
             ChunkingError::SemanticFailed(_) => ("SemanticError", None),
             ChunkingError::Regex(_) => ("RegexError", None),
+            ChunkingError::Tokenizer(_) => ("TokenizerError", None),
             ChunkingError::UnknownStrategy { .. } => (
                 "UnknownStrategy",
-                Some("Valid strategies: fixed, semantic, parallel"),
+                Some(
+                    "Valid strategies: fixed, semantic, code, markdown, json, yaml, parallel, token",
+                ),
             ),
         },
         crate::Error::Command(e) => match e {
@@ -403,6 +634,7 @@ const fn get_error_details(error: &crate::Error) -> (&'static str, Option<&'stat
             CommandError::ExecutionFailed(_) => ("ExecutionFailed", None),
             CommandError::Cancelled => ("Cancelled", None),
             CommandError::OutputFormat(_) => ("OutputFormatError", None),
+            CommandError::NoResults(_) => ("NoResults", None),
         },
         crate::Error::InvalidState { .. } => ("InvalidState", None),
         crate::Error::Config { .. } => ("ConfigError", None),
@@ -448,6 +680,37 @@ mod tests {
         assert_eq!(OutputFormat::parse("unknown"), OutputFormat::Text);
     }
 
+    #[test]
+    fn test_color_choice_parse() {
+        assert_eq!(ColorChoice::parse("always"), ColorChoice::Always);
+        assert_eq!(ColorChoice::parse("NEVER"), ColorChoice::Never);
+        assert_eq!(ColorChoice::parse("auto"), ColorChoice::Auto);
+        assert_eq!(ColorChoice::parse("bogus"), ColorChoice::Auto);
+    }
+
+    #[test]
+    fn test_text_style() {
+        let color = TextStyle {
+            color: true,
+            width: Some(80),
+        };
+        assert_eq!(color.score("0.9", 0.9, 1.0), "\x1b[32m0.9\x1b[0m");
+        assert_eq!(color.score("0.5", 0.5, 1.0), "\x1b[33m0.5\x1b[0m");
+        assert_eq!(color.score("0.1", 0.1, 1.0), "\x1b[31m0.1\x1b[0m");
+        assert_eq!(color.remaining(30, 100), 50);
+
+        let plain = TextStyle::PLAIN;
+        assert_eq!(plain.score("0.9", 0.9, 1.0), "0.9");
+        assert_eq!(plain.bold("Title"), "Title");
+        assert_eq!(plain.remaining(30, 100), 100);
+
+        // Structured formats never use terminal styling
+        assert_eq!(
+            TextStyle::detect(ColorChoice::Always, OutputFormat::Json),
+            TextStyle::PLAIN
+        );
+    }
+
     #[test]
     fn test_output_format_ndjson() {
         assert_eq!(OutputFormat::parse("ndjson"), OutputFormat::Ndjson);
@@ -459,6 +722,27 @@ mod tests {
         assert!(!OutputFormat::Text.is_streaming());
     }
 
+    #[test]
+    fn test_output_format_yaml() {
+        assert_eq!(FormatChoice::parse("yaml"), FormatChoice::Yaml);
+        assert_eq!(FormatChoice::parse("YML"), FormatChoice::Yaml);
+        assert_eq!(FormatChoice::Yaml.output_format(), OutputFormat::Json);
+        assert_eq!(
+            FormatChoice::parse("ndjson"),
+            FormatChoice::Native(OutputFormat::Ndjson)
+        );
+
+        assert_eq!(
+            to_yaml("{\"count\": 2, \"names\": [\"a\"]}"),
+            "count: 2\nnames:\n- a\n"
+        );
+        // JSONL becomes a stream of documents
+        assert_eq!(to_yaml("{\"id\": 1}\n{\"id\": 2}\n"), "id: 1\n---\nid: 2\n");
+        // Empty and non-JSON output pass through unchanged
+        assert_eq!(to_yaml(""), "");
+        assert_eq!(to_yaml("Loaded 2 chunks\n"), "Loaded 2 chunks\n");
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(100), "100 B");
@@ -488,12 +772,12 @@ mod tests {
             db_size: Some(4096),
         };
 
-        let text = format_status(&stats, OutputFormat::Text);
+        let text = format_status(&stats, OutputFormat::Text, TextStyle::PLAIN);
         assert!(text.contains("Buffers:       2"));
         assert!(text.contains("Chunks:        10"));
         assert!(text.contains("DB size:"));
 
-        let json = format_status(&stats, OutputFormat::Json);
+        let json = format_status(&stats, OutputFormat::Json, TextStyle::PLAIN);
         assert!(json.contains("\"buffer_count\": 2"));
     }
 
@@ -508,11 +792,34 @@ mod tests {
             db_size: None,
         };
 
-        let text = format_status(&stats, OutputFormat::Text);
+        let text = format_status(&stats, OutputFormat::Text, TextStyle::PLAIN);
         assert!(text.contains("Context:       no"));
         assert!(!text.contains("DB size:"));
     }
 
+    #[test]
+    fn test_format_buffer_list_ndjson() {
+        let buffers = vec![
+            Buffer::from_named("a.txt".to_string(), "alpha".to_string()),
+            Buffer::from_named("b.txt".to_string(), "beta".to_string()),
+        ];
+        let output = format_buffer_list(&buffers, OutputFormat::Ndjson);
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["name"], "a.txt");
+        assert_eq!(lines[2]["type"], "summary");
+        assert_eq!(lines[2]["count"], 2);
+    }
+
+    #[test]
+    fn test_format_ndjson_empty_records() {
+        let output = format_ndjson(Vec::new(), serde_json::json!({ "count": 0 }));
+        assert_eq!(output, "{\"count\":0,\"type\":\"summary\"}\n");
+    }
+
     #[test]
     fn test_format_buffer_list_empty() {
         let buffers: Vec<Buffer> = vec![];
@@ -616,17 +923,22 @@ mod tests {
                 offset: 10,
                 matched: "hello".to_string(),
                 snippet: "say hello world".to_string(),
+                buffer_id: None,
+                buffer_name: None,
             },
             GrepMatch {
                 offset: 50,
                 matched: "hello".to_string(),
                 snippet: "another\nhello".to_string(),
+                buffer_id: Some(3),
+                buffer_name: Some("notes".to_string()),
             },
         ];
 
         let text = format_grep_matches(&matches, "hello", OutputFormat::Text);
         assert!(text.contains("Found 2 matches"));
         assert!(text.contains("Match 1 at byte 10"));
+        assert!(text.contains("Match 2 in notes (ID 3) at byte 50"));
         assert!(text.contains("another\\nhello"));
 
         let json = format_grep_matches(&matches, "hello", OutputFormat::Json);
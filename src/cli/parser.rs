@@ -2,10 +2,10 @@
 //!
 //! Defines the CLI structure using clap derive macros.
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::path::PathBuf;
 
-use crate::chunking::{DEFAULT_CHUNK_SIZE, DEFAULT_OVERLAP};
+use crate::chunking::DEFAULT_MIN_CHUNK_SIZE;
 
 /// RLM-RS: Recursive Language Model REPL for Claude Code.
 ///
@@ -26,10 +26,22 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
-    /// Output format (text, json).
-    #[arg(long, default_value = "text", global = true)]
+    /// Output format (text, json, ndjson, yaml).
+    #[arg(long, default_value = "text", global = true, env = "RLM_FORMAT")]
     pub format: String,
 
+    /// Colorize text output (auto, always, never).
+    ///
+    /// `auto` colorizes only when stdout is a terminal and `NO_COLOR` is unset.
+    #[arg(
+        long,
+        default_value = "auto",
+        global = true,
+        env = "RLM_COLOR",
+        value_parser = ["auto", "always", "never"]
+    )]
+    pub color: String,
+
     /// The subcommand to execute.
     #[command(subcommand)]
     pub command: Commands,
@@ -62,34 +74,111 @@ pub enum Commands {
         yes: bool,
     },
 
+    /// Apply pending schema migrations.
+    ///
+    /// Databases are also upgraded automatically when opened; this command
+    /// shows what would change or applies it explicitly.
+    #[command(after_help = r#"Examples:
+  rlm-cli migrate --dry-run       # List pending migrations
+  rlm-cli migrate                 # Apply pending migrations
+"#)]
+    Migrate {
+        /// List pending migrations without applying them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check the database and embedder for common setup problems.
+    ///
+    /// Prints a pass/warn/fail line per check with a hint for fixing each
+    /// problem found.
+    #[command(after_help = r#"Examples:
+  rlm-cli doctor                          # Quick checks
+  rlm-cli doctor --check-embedder         # Also load the embedding model
+  rlm-cli --format json doctor | jq '.ok'
+"#)]
+    Doctor {
+        /// Also load the embedding model and embed a probe text (may
+        /// download the model on first use).
+        #[arg(long)]
+        check_embedder: bool,
+    },
+
+    /// Back up the database to a file.
+    ///
+    /// Takes a consistent snapshot (including embeddings) that is safe to
+    /// run while the database is in use. The database is not migrated
+    /// first, so the snapshot keeps its schema version.
+    #[command(after_help = r#"Examples:
+  rlm-cli backup rlm-backup.db           # Snapshot the current database
+  rlm-cli restore rlm-backup.db --force  # Restore it later
+"#)]
+    Backup {
+        /// Destination file (must not exist).
+        path: PathBuf,
+    },
+
+    /// Restore the database from a backup file.
+    ///
+    /// The backup is validated before it replaces the current database.
+    Restore {
+        /// Backup file to restore from.
+        path: PathBuf,
+
+        /// Replace an existing database.
+        #[arg(short, long)]
+        force: bool,
+    },
+
     /// Load a context file into a buffer.
     #[command(after_help = r#"Examples:
   rlm-cli load large_file.txt                      # Load with semantic chunking
   rlm-cli load src/main.rs --name main-source      # Load with custom name
   rlm-cli load src/lib.rs --chunker code           # Code-aware chunking
   rlm-cli load doc.md --chunker fixed --chunk-size 2000
+  rlm-cli load README.md --chunker markdown        # Split at headings
+  rlm-cli load data.json --chunker json            # One chunk per element
   rlm-cli load big.log --chunker parallel          # Parallel for large files
+  rlm-cli load notes.txt --chunker token --chunk-size 500 --overlap 50
+  rlm-cli load report.pdf                          # Extract PDF text (pdf feature)
+  rlm-cli load notes.txt --dedup                   # Skip if already loaded
+  rlm-cli load notes.txt -n notes --update-if-exists  # Rename if already loaded
   rlm-cli --format json load file.txt | jq '.buffer_id'
 "#)]
     Load {
-        /// Path to the context file.
+        /// Path to the context file (PDF text is extracted with the `pdf` feature).
         file: PathBuf,
 
         /// Optional name for the buffer.
         #[arg(short, long)]
         name: Option<String>,
 
-        /// Chunking strategy (fixed, semantic, code, parallel).
-        #[arg(short, long, default_value = "semantic")]
+        /// Chunking strategy (fixed, semantic, code, markdown, json, yaml, parallel, token).
+        #[arg(short, long, default_value = "semantic", env = "RLM_CHUNKER")]
         chunker: String,
 
-        /// Chunk size in characters.
-        #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
-        chunk_size: usize,
+        /// Chunk size in characters, or tokens for the token chunker
+        /// [default: 3000, or 750 tokens].
+        #[arg(long, env = "RLM_CHUNK_SIZE")]
+        chunk_size: Option<usize>,
+
+        /// Overlap between chunks in characters, or tokens for the token
+        /// chunker [default: 500, or 125 tokens].
+        #[arg(long, env = "RLM_OVERLAP")]
+        overlap: Option<usize>,
 
-        /// Overlap between chunks in characters.
-        #[arg(long, default_value_t = DEFAULT_OVERLAP)]
-        overlap: usize,
+        /// Merge chunks smaller than this into their predecessor (0 disables merging).
+        #[arg(long, default_value_t = DEFAULT_MIN_CHUNK_SIZE, env = "RLM_MIN_CHUNK_SIZE")]
+        min_chunk_size: usize,
+
+        /// Skip loading if a buffer with identical content already exists.
+        #[arg(long)]
+        dedup: bool,
+
+        /// If a buffer with identical content already exists, give it this
+        /// load's name and source instead of loading (implies `--dedup`).
+        #[arg(long)]
+        update_if_exists: bool,
     },
 
     /// List all buffers.
@@ -118,8 +207,29 @@ pub enum Commands {
         chunks: bool,
     },
 
+    /// Show size, line, token and chunk statistics for a buffer.
+    ///
+    /// Token counts are estimates from a word and punctuation heuristic.
+    #[command(name = "stats")]
+    #[command(after_help = r#"Examples:
+  rlm-cli stats main-source               # Stats for a buffer
+  rlm-cli --format json stats 1 | jq '.estimated_tokens'
+"#)]
+    BufferStats {
+        /// Buffer ID or name.
+        buffer: String,
+    },
+
     /// Delete a buffer.
+    ///
+    /// With `--trash` the buffer is kept, hidden, until `buffer restore`
+    /// brings it back or `buffer empty-trash` removes it for good.
     #[command(name = "delete", alias = "rm")]
+    #[command(after_help = r#"Examples:
+  rlm-cli delete old-logs --yes           # Delete permanently
+  rlm-cli delete old-logs --trash         # Move to the trash
+  rlm-cli buffer restore 7                # Bring it back
+"#)]
     DeleteBuffer {
         /// Buffer ID or name.
         buffer: String,
@@ -127,6 +237,72 @@ pub enum Commands {
         /// Skip confirmation prompt.
         #[arg(short = 'y', long)]
         yes: bool,
+
+        /// Move the buffer to the trash instead of deleting it (no
+        /// confirmation needed).
+        #[arg(long)]
+        trash: bool,
+    },
+
+    /// Merge buffers into a new buffer.
+    ///
+    /// Concatenates sources in the given order (with a separator marker
+    /// between them), then chunks and embeds the result.
+    #[command(name = "merge")]
+    #[command(after_help = r#"Examples:
+  rlm-cli merge api-docs guide faq --name all-docs
+  rlm-cli merge 1 2 --name combined --chunker fixed --no-embed
+  rlm-cli --format json merge a b -n ab | jq '.buffer_id'
+"#)]
+    MergeBuffers {
+        /// Source buffer IDs or names (in order).
+        #[arg(required = true, num_args = 2..)]
+        sources: Vec<String>,
+
+        /// Name for the merged buffer.
+        #[arg(short, long)]
+        name: String,
+
+        /// Chunking strategy (fixed, semantic, code, markdown, json, yaml, parallel, token).
+        #[arg(short, long, default_value = "semantic")]
+        chunker: String,
+
+        /// Chunk size in characters, or tokens for the token chunker
+        /// [default: 3000, or 750 tokens].
+        #[arg(long)]
+        chunk_size: Option<usize>,
+
+        /// Overlap between chunks in characters, or tokens for the token
+        /// chunker [default: 500, or 125 tokens].
+        #[arg(long)]
+        overlap: Option<usize>,
+
+        /// Merge chunks smaller than this into their predecessor (0 disables merging).
+        #[arg(long, default_value_t = DEFAULT_MIN_CHUNK_SIZE)]
+        min_chunk_size: usize,
+
+        /// Skip embedding generation for the merged buffer.
+        #[arg(long)]
+        no_embed: bool,
+    },
+
+    /// Compare two buffers line by line.
+    #[command(name = "diff")]
+    #[command(after_help = r#"Examples:
+  rlm-cli diff notes-v1 notes-v2          # Unified diff of two buffers
+  rlm-cli diff 1 2 --context 0            # Only changed lines
+  rlm-cli --format json diff 1 2          # Added/removed/changed summary
+"#)]
+    DiffBuffers {
+        /// Old buffer ID or name.
+        a: String,
+
+        /// New buffer ID or name.
+        b: String,
+
+        /// Unchanged lines of context around each change.
+        #[arg(short, long, default_value = "3")]
+        context: usize,
     },
 
     /// Peek at buffer content.
@@ -144,9 +320,11 @@ pub enum Commands {
     },
 
     /// Search buffer content with regex.
+    #[command(allow_missing_positional = true)]
     Grep {
-        /// Buffer ID or name.
-        buffer: String,
+        /// Buffer ID or name (omit with --all).
+        #[arg(required_unless_present = "all")]
+        buffer: Option<String>,
 
         /// Search pattern (regex).
         pattern: String,
@@ -162,6 +340,10 @@ pub enum Commands {
         /// Case-insensitive search.
         #[arg(short, long)]
         ignore_case: bool,
+
+        /// Search every buffer; --max-matches caps the total.
+        #[arg(short, long, conflicts_with = "buffer")]
+        all: bool,
     },
 
     /// Get chunk indices for a buffer.
@@ -169,13 +351,13 @@ pub enum Commands {
         /// Buffer ID or name.
         buffer: String,
 
-        /// Chunk size in characters.
-        #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
-        chunk_size: usize,
+        /// Chunk size in characters [default: 3000].
+        #[arg(long)]
+        chunk_size: Option<usize>,
 
-        /// Overlap between chunks in characters.
-        #[arg(long, default_value_t = DEFAULT_OVERLAP)]
-        overlap: usize,
+        /// Overlap between chunks in characters [default: 500].
+        #[arg(long)]
+        overlap: Option<usize>,
     },
 
     /// Write chunks to files.
@@ -187,13 +369,17 @@ pub enum Commands {
         #[arg(short, long, default_value = ".rlm/chunks")]
         out_dir: PathBuf,
 
-        /// Chunk size in characters.
-        #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
-        chunk_size: usize,
+        /// Chunk size in characters [default: 3000].
+        #[arg(long)]
+        chunk_size: Option<usize>,
+
+        /// Overlap between chunks in characters [default: 500].
+        #[arg(long)]
+        overlap: Option<usize>,
 
-        /// Overlap between chunks in characters.
-        #[arg(long, default_value_t = DEFAULT_OVERLAP)]
-        overlap: usize,
+        /// Merge chunks smaller than this into their predecessor (0 disables merging).
+        #[arg(long, default_value_t = DEFAULT_MIN_CHUNK_SIZE)]
+        min_chunk_size: usize,
 
         /// Filename prefix.
         #[arg(long, default_value = "chunk")]
@@ -216,6 +402,7 @@ pub enum Commands {
   cat updated.txt | rlm-cli update main-source   # Update from stdin
   rlm-cli update my-buffer "new content"         # Update with inline content
   rlm-cli update my-buffer --embed               # Update and generate embeddings
+  rlm-cli update my-buffer --incremental --embed # Re-embed only changed chunks
   rlm-cli update my-buffer --chunk-size 500      # Custom chunk size"#)]
     #[command(alias = "update")]
     UpdateBuffer {
@@ -229,30 +416,71 @@ pub enum Commands {
         #[arg(short, long)]
         embed: bool,
 
+        /// Keep unchanged chunks and their embeddings instead of replacing all chunks.
+        #[arg(long)]
+        incremental: bool,
+
         /// Chunking strategy (semantic, fixed, parallel).
         #[arg(long, default_value = "semantic")]
         strategy: String,
 
-        /// Chunk size in characters.
-        #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
-        chunk_size: usize,
+        /// Chunk size in characters, or tokens for the token strategy
+        /// [default: 3000, or 750 tokens].
+        #[arg(long)]
+        chunk_size: Option<usize>,
 
-        /// Chunk overlap in characters.
-        #[arg(long, default_value_t = DEFAULT_OVERLAP)]
-        overlap: usize,
+        /// Chunk overlap in characters, or tokens for the token strategy
+        /// [default: 500, or 125 tokens].
+        #[arg(long)]
+        overlap: Option<usize>,
+
+        /// Merge chunks smaller than this into their predecessor (0 disables merging).
+        #[arg(long, default_value_t = DEFAULT_MIN_CHUNK_SIZE)]
+        min_chunk_size: usize,
     },
 
-    /// Export all buffers to a file.
+    /// Export buffers to a file.
+    ///
+    /// `--as` picks the serialization: `text` concatenates buffer contents,
+    /// `json` writes an array of buffers, `jsonl` writes one chunk per line,
+    /// and `markdown` writes a heading per chunk. Without `--as`, the global
+    /// `--format` is used (`text`, `json`/`yaml`, or `ndjson` as `jsonl`).
     ExportBuffers {
         /// Output file path (stdout if not specified).
         #[arg(short, long)]
         output: Option<PathBuf>,
 
+        /// Export only this buffer (ID or name).
+        #[arg(short, long)]
+        buffer: Option<String>,
+
+        /// Export serialization (text, json, jsonl, markdown).
+        #[arg(
+            long = "as",
+            value_name = "FORMAT",
+            value_parser = ["text", "json", "jsonl", "markdown"]
+        )]
+        as_format: Option<String>,
+
         /// Pretty-print if JSON format.
         #[arg(short, long)]
         pretty: bool,
     },
 
+    /// Import buffers from a file written by `export-buffers --as json`.
+    ImportBuffers {
+        /// Export file to read.
+        file: PathBuf,
+
+        /// Generate embeddings for the imported chunks.
+        #[arg(short, long)]
+        embed: bool,
+
+        /// What to do when a buffer name already exists (skip, rename).
+        #[arg(long, default_value = "skip", value_parser = ["skip", "rename"])]
+        on_conflict: String,
+    },
+
     /// Set or get context variables.
     #[command(name = "var")]
     Variable {
@@ -280,26 +508,50 @@ pub enum Commands {
         delete: bool,
     },
 
+    /// List all context variables and globals.
+    #[command(after_help = r#"Examples:
+  rlm-cli vars                            # Variables and globals
+  rlm-cli vars --scope global             # Globals only
+  rlm-cli --format json vars | jq '.variables'
+"#)]
+    Vars {
+        /// Which values to list (var, global, all).
+        #[arg(long, default_value = "all", value_parser = ["var", "global", "all"])]
+        scope: String,
+    },
+
     /// Search chunks using hybrid semantic + BM25 search.
     ///
     /// Returns chunk IDs and scores. Use `chunk get <id>` to retrieve content.
     #[command(after_help = r#"Examples:
   rlm-cli search "error handling"                  # Hybrid search (default)
   rlm-cli search "authentication" -k 5             # Top 5 results
+  rlm-cli search "authentication" -k 5 --offset 5  # Next 5 results
   rlm-cli search "config" --mode bm25              # BM25 keyword search only
+  rlm-cli search '"exact phrase" -excluded'        # Phrase match, drop a term
   rlm-cli search "API" --mode semantic             # Semantic search only
   rlm-cli search "bug fix" --buffer main-source    # Filter by buffer
+  rlm-cli search "auth" --buffer api,docs,tests    # Search several buffers
+  rlm-cli search "auth" --tag project:alpha        # Buffers with a tag
   rlm-cli search "auth" --preview                  # Include content preview
+  rlm-cli search "auth" --fusion weighted --alpha 0.7  # Blend normalized scores
+  rlm-cli search "API" --metric dot                # Dot-product similarity
+  rlm-cli search "retry logic" --mmr-lambda 0.5    # Diversify near-duplicate hits
+  rlm-cli search "parser" --context 1              # Include neighboring chunks
   rlm-cli --format json search "test" | jq '.results[].chunk_id'
 "#)]
     Search {
-        /// Search query text.
+        /// Search query text ("quoted phrases" and -term exclusions supported).
         query: String,
 
         /// Maximum number of results.
         #[arg(short = 'k', long, default_value = "10")]
         top_k: usize,
 
+        /// Skip this many ranked results (for paging with --top-k).
+        #[arg(long, default_value = "0")]
+        offset: usize,
+
         /// Minimum similarity threshold (0.0-1.0).
         #[arg(short, long, default_value = "0.3")]
         threshold: f32,
@@ -312,10 +564,30 @@ pub enum Commands {
         #[arg(long, default_value = "60")]
         rrf_k: u32,
 
-        /// Filter by buffer ID or name.
+        /// Fusion method for hybrid mode: rrf, weighted.
+        #[arg(long, default_value = "rrf")]
+        fusion: String,
+
+        /// Semantic weight for weighted fusion (0.0-1.0).
+        #[arg(long, default_value = "0.5")]
+        alpha: f64,
+
+        /// Similarity metric for semantic search: cosine, dot, euclidean.
+        #[arg(long, default_value = "cosine")]
+        metric: String,
+
+        /// Re-rank with Maximal Marginal Relevance (0.0-1.0, lower = more diverse).
+        #[arg(long)]
+        mmr_lambda: Option<f32>,
+
+        /// Filter by buffer ID or name (comma-separated for several).
         #[arg(short, long)]
         buffer: Option<String>,
 
+        /// Only search buffers carrying this tag.
+        #[arg(long, conflicts_with = "buffer")]
+        tag: Option<String>,
+
         /// Include content preview in results.
         #[arg(short, long)]
         preview: bool,
@@ -323,6 +595,18 @@ pub enum Commands {
         /// Preview length in characters.
         #[arg(long, default_value = "150")]
         preview_len: usize,
+
+        /// Include N neighboring chunks before and after each hit.
+        #[arg(long, default_value = "0")]
+        context: usize,
+
+        /// Include buffer names and chunk metadata in results.
+        #[arg(long)]
+        enrich: bool,
+
+        /// Exit with code 3 when nothing matches.
+        #[arg(long)]
+        fail_on_empty: bool,
     },
 
     /// Aggregate findings from analyst subagents.
@@ -333,19 +617,23 @@ pub enum Commands {
   cat findings.json | rlm-cli aggregate           # Aggregate from stdin
   rlm-cli aggregate --buffer findings             # Read from buffer
   rlm-cli aggregate --min-relevance medium        # Filter low relevance
+  rlm-cli aggregate --sort-by confidence          # Most confident first
+  rlm-cli aggregate -o report --embed             # Store searchable results
   rlm-cli --format json aggregate | jq '.findings'
 
 Input format (JSON array of analyst findings):
 [
-  {"chunk_id": 12, "relevance": "high", "findings": ["..."], "summary": "..."},
+  {"chunk_id": 12, "relevance": "high", "findings": ["..."], "summary": "...", "confidence": 0.9},
   {"chunk_id": 27, "relevance": "medium", "findings": ["..."], "summary": "..."}
-]"#)]
+]
+
+The optional "confidence" (0.0-1.0) ranks findings within a relevance level."#)]
     Aggregate {
         /// Read findings from a buffer instead of stdin.
         #[arg(short, long)]
         buffer: Option<String>,
 
-        /// Minimum relevance to include (none, low, medium, high).
+        /// Minimum relevance to include (none, low, medium, high, critical).
         #[arg(long, default_value = "low")]
         min_relevance: String,
 
@@ -353,13 +641,18 @@ Input format (JSON array of analyst findings):
         #[arg(long, default_value = "relevance")]
         group_by: String,
 
-        /// Sort findings by this field (`relevance`, `chunk_id`, `findings_count`).
-        #[arg(long, default_value = "relevance")]
+        /// Sort findings by this field (`relevance`, `confidence`, `temporal`,
+        /// `chunk_id`, `findings_count`).
+        #[arg(long, default_value = "relevance", env = "RLM_FINDING_SORT")]
         sort_by: String,
 
-        /// Store aggregated results in a new buffer with this name.
+        /// Store aggregated results in a new, chunked buffer with this name.
         #[arg(short, long)]
         output_buffer: Option<String>,
+
+        /// Embed the output buffer's chunks so they are semantically searchable.
+        #[arg(short, long, requires = "output_buffer")]
+        embed: bool,
     },
 
     /// Dispatch chunks for parallel subagent processing.
@@ -371,6 +664,7 @@ Input format (JSON array of analyst findings):
   rlm-cli dispatch my-buffer --batch-size 5      # 5 chunks per batch
   rlm-cli dispatch my-buffer --workers 4         # Split into 4 batches
   rlm-cli dispatch my-buffer --query "error"     # Only relevant chunks
+  rlm-cli dispatch my-buffer --manifest plan.json  # Write a runner manifest
   rlm-cli --format json dispatch my-buffer       # JSON for orchestrator"#)]
     Dispatch {
         /// Buffer ID or name.
@@ -395,28 +689,49 @@ Input format (JSON array of analyst findings):
         /// Minimum similarity threshold for query filtering.
         #[arg(long, default_value = "0.3")]
         threshold: f32,
+
+        /// Also write a JSON batch manifest for an external runner to this path.
+        #[arg(long)]
+        manifest: Option<PathBuf>,
     },
 
     /// Chunk operations (get, list, embed).
     #[command(subcommand)]
     Chunk(ChunkCommands),
+
+    /// Buffer operations (tag, trash, restore, empty-trash).
+    #[command(subcommand)]
+    Buffer(BufferCommands),
+
+    /// Database maintenance operations.
+    #[command(subcommand)]
+    Storage(StorageCommands),
 }
 
 /// Chunk subcommands for pass-by-reference retrieval.
 #[derive(Subcommand, Debug)]
 pub enum ChunkCommands {
-    /// Get a chunk by ID.
+    /// Get chunks by ID.
     ///
     /// Returns the chunk content and metadata. This is the primary
-    /// pass-by-reference retrieval mechanism for subagents.
+    /// pass-by-reference retrieval mechanism for subagents. Several IDs or
+    /// an inclusive range return every chunk in one call; missing IDs are
+    /// reported in place instead of failing the command. A stable chunk key
+    /// (shown with `--metadata`) stays valid when identical content is
+    /// reloaded.
     #[command(after_help = r#"Examples:
   rlm-cli chunk get 42                    # Get chunk content
   rlm-cli chunk get 42 --metadata         # Include byte range, token count
+  rlm-cli chunk get 10 11 12              # Several chunks at once
+  rlm-cli chunk get 10-14                 # Inclusive range
+  rlm-cli chunk get 3f2a9c41d07e8b65-1f4:0-500  # Stable key, valid across reloads
   rlm-cli --format json chunk get 42      # JSON output for programmatic use
+  rlm-cli --format json chunk get 10-14   # JSON array, null for missing IDs
 "#)]
     Get {
-        /// Chunk ID.
-        id: i64,
+        /// Chunk IDs, inclusive ranges, or stable keys (e.g., 42, 10-14).
+        #[arg(required = true, num_args = 1..)]
+        ids: Vec<String>,
 
         /// Include metadata in output.
         #[arg(short, long)]
@@ -451,13 +766,137 @@ pub enum ChunkCommands {
         /// Buffer ID or name.
         buffer: String,
 
-        /// Re-embed even if already embedded.
+        /// Re-embed even if already embedded, discarding this buffer's
+        /// embeddings whose dimension differs from the current model.
         #[arg(short, long)]
         force: bool,
+
+        /// Delete embeddings whose dimension differs from the current model
+        /// in every buffer, not just this one. The other buffers must be
+        /// re-embedded afterwards.
+        #[arg(long)]
+        discard_other_dimensions: bool,
+    },
+
+    /// Show the stored embedding vector of a chunk.
+    ///
+    /// Prints the model, dimension, norm and counts of zero and NaN values
+    /// alongside the raw values, to help diagnose semantic search.
+    #[command(after_help = r#"Examples:
+  rlm-cli chunk embedding 42              # Summary and values
+  rlm-cli --format json chunk embedding 42 | jq '.dimensions'
+"#)]
+    Embedding {
+        /// Chunk ID.
+        id: i64,
     },
 
     /// Show embedding status for buffers.
     Status,
+
+    /// Delete a single chunk by ID.
+    ///
+    /// Removes the chunk, its embedding and its search index entry. In a
+    /// terminal, asks you to type the chunk ID to confirm.
+    #[command(after_help = r#"Examples:
+  rlm-cli chunk delete 42                 # Type 42 to confirm
+  rlm-cli chunk delete 42 --yes           # Delete chunk 42 without asking
+  rlm-cli --format json chunk delete 42 -y | jq '.buffer_id'
+"#)]
+    Delete {
+        /// Chunk ID.
+        id: i64,
+
+        /// Skip confirmation prompt.
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+/// Buffer subcommands.
+#[derive(Subcommand, Debug)]
+pub enum BufferCommands {
+    /// Manage buffer tags.
+    ///
+    /// Tags are arbitrary labels, either plain strings (`logs`) or
+    /// `key:value` pairs (`project:alpha`), used to group buffers and to
+    /// scope `search --tag`.
+    #[command(subcommand)]
+    Tag(TagCommands),
+
+    /// List buffers in the trash.
+    Trash,
+
+    /// Restore a buffer from the trash.
+    Restore {
+        /// Trashed buffer ID.
+        id: i64,
+    },
+
+    /// Permanently delete every buffer in the trash.
+    EmptyTrash {
+        /// Skip confirmation prompt.
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+/// Buffer tag subcommands.
+#[derive(Subcommand, Debug)]
+pub enum TagCommands {
+    /// Add tags to a buffer.
+    #[command(after_help = r#"Examples:
+  rlm-cli buffer tag add app-logs project:alpha type:logs
+  rlm-cli search "timeout" --tag project:alpha
+"#)]
+    Add {
+        /// Buffer ID or name.
+        buffer: String,
+
+        /// Tags to add.
+        #[arg(required = true, num_args = 1..)]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from a buffer.
+    Remove {
+        /// Buffer ID or name.
+        buffer: String,
+
+        /// Tags to remove.
+        #[arg(required = true, num_args = 1..)]
+        tags: Vec<String>,
+    },
+
+    /// List a buffer's tags, or every tag with its buffer count.
+    #[command(after_help = r#"Examples:
+  rlm-cli buffer tag list                 # All tags in use
+  rlm-cli buffer tag list app-logs        # Tags on one buffer
+"#)]
+    List {
+        /// Buffer ID or name (omit to list all tags).
+        buffer: Option<String>,
+    },
+}
+
+/// Storage maintenance subcommands.
+#[derive(Subcommand, Debug)]
+pub enum StorageCommands {
+    /// Compact the database and rebuild the search index.
+    ///
+    /// Runs `VACUUM` to return free pages to the filesystem, rebuilds the
+    /// full-text index and drops embedding cache entries no chunk uses,
+    /// reporting the size before and after.
+    #[command(after_help = r#"Examples:
+  rlm-cli storage vacuum                  # Compact the database
+  rlm-cli storage vacuum --analyze        # Also refresh planner statistics
+  rlm-cli --format json storage vacuum | jq '.reclaimed'
+"#)]
+    Vacuum {
+        /// Also run ANALYZE to refresh query planner statistics.
+        #[arg(long)]
+        analyze: bool,
+    },
 }
 
 impl Cli {
@@ -468,12 +907,26 @@ impl Cli {
             .clone()
             .unwrap_or_else(|| PathBuf::from(crate::storage::DEFAULT_DB_PATH))
     }
+
+    /// Parses arguments from the process command line, then fills options
+    /// left at their defaults from the config files.
+    ///
+    /// Exits with a usage message if the arguments are invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a config file exists but is invalid.
+    pub fn parse_with_config() -> crate::error::Result<Self> {
+        let matches = Self::command().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        crate::cli::config::Config::load()?.apply(&mut cli, &matches);
+        Ok(cli)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
 
     #[test]
     fn test_cli_parse() {
@@ -487,6 +940,7 @@ mod tests {
             db_path: None,
             verbose: false,
             format: "text".to_string(),
+            color: "auto".to_string(),
             command: Commands::Status,
         };
         assert_eq!(
@@ -501,8 +955,78 @@ mod tests {
             db_path: Some(PathBuf::from("/custom/path.db")),
             verbose: false,
             format: "text".to_string(),
+            color: "auto".to_string(),
             command: Commands::Status,
         };
         assert_eq!(cli.get_db_path(), PathBuf::from("/custom/path.db"));
     }
+
+    #[test]
+    fn test_grep_buffer_optional_with_all() {
+        let cli = Cli::try_parse_from(["rlm-cli", "grep", "--all", "TODO"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Grep { buffer: None, ref pattern, all: true, .. } if pattern == "TODO"
+        ));
+
+        let cli = Cli::try_parse_from(["rlm-cli", "grep", "notes", "TODO"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Grep { buffer: Some(ref buffer), all: false, .. } if buffer == "notes"
+        ));
+
+        assert!(Cli::try_parse_from(["rlm-cli", "grep", "TODO"]).is_err());
+        assert!(Cli::try_parse_from(["rlm-cli", "grep", "--all", "notes", "TODO"]).is_err());
+    }
+
+    #[test]
+    fn test_export_buffers_as_format() {
+        let cli = Cli::try_parse_from(["rlm-cli", "export-buffers", "--as", "markdown"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::ExportBuffers { as_format: Some(ref f), .. } if f == "markdown"
+        ));
+
+        assert!(Cli::try_parse_from(["rlm-cli", "export-buffers", "--as", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_min_chunk_size_flag() {
+        let cli = Cli::try_parse_from(["rlm-cli", "load", "a.txt"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Load {
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["rlm-cli", "update", "a", "--min-chunk-size", "0"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::UpdateBuffer {
+                min_chunk_size: 0,
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from([
+            "rlm-cli",
+            "merge",
+            "a",
+            "b",
+            "-n",
+            "ab",
+            "--min-chunk-size",
+            "20",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::MergeBuffers {
+                min_chunk_size: 20,
+                ..
+            }
+        ));
+    }
 }
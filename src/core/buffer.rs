@@ -3,6 +3,7 @@
 //! Buffers represent text content loaded into the RLM system, typically
 //! from files or direct input. Each buffer can be chunked for processing.
 
+use crate::core::chunk::content_hash;
 use crate::io::{current_timestamp, find_char_boundary};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -59,8 +60,17 @@ pub struct BufferMetadata {
     /// Number of chunks (set after chunking).
     pub chunk_count: Option<usize>,
 
-    /// SHA-256 hash of content (for deduplication).
+    /// Stable hash of content (for deduplication and stable chunk keys).
     pub content_hash: Option<String>,
+
+    /// Byte offsets where each page starts (for paginated sources like PDF).
+    pub page_offsets: Option<Vec<usize>>,
+
+    /// Size of the source file before decompression (for compressed sources).
+    pub compressed_size: Option<usize>,
+
+    /// Encoding the source was decoded from (e.g., "utf-8", "utf-16le").
+    pub encoding: Option<String>,
 }
 
 impl Buffer {
@@ -247,19 +257,21 @@ impl Buffer {
         self.metadata.updated_at = current_timestamp();
     }
 
-    /// Computes and sets the content hash.
+    /// Computes and sets the content hash (see [`content_hash`]).
     pub fn compute_hash(&mut self) {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        self.content.hash(&mut hasher);
-        self.metadata.content_hash = Some(format!("{:016x}", hasher.finish()));
+        self.metadata.content_hash = Some(content_hash(&self.content));
     }
 }
 
 /// Infers content type from file extension.
+///
+/// Compression extensions are skipped, so `access.log.gz` is a `log` buffer.
 fn infer_content_type(path: &std::path::Path) -> Option<String> {
+    let path = if crate::io::Compression::from_extension(path).is_some() {
+        path.file_stem().map(std::path::Path::new)?
+    } else {
+        path
+    };
     path.extension()
         .and_then(|ext| ext.to_str())
         .map(str::to_lowercase)
@@ -286,6 +298,16 @@ mod tests {
         assert_eq!(buffer.name, Some("test.txt".to_string()));
     }
 
+    #[test]
+    fn test_buffer_from_compressed_file() {
+        let buffer = Buffer::from_file(PathBuf::from("access.log.gz"), "content".to_string());
+        assert_eq!(buffer.metadata.content_type, Some("log".to_string()));
+        assert_eq!(buffer.name, Some("access.log.gz".to_string()));
+
+        let buffer = Buffer::from_file(PathBuf::from("archive.zst"), "content".to_string());
+        assert_eq!(buffer.metadata.content_type, None);
+    }
+
     #[test]
     fn test_buffer_from_named() {
         let buffer = Buffer::from_named("my-buffer".to_string(), "content".to_string());
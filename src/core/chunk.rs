@@ -8,6 +8,38 @@ use crate::io::{current_timestamp, find_char_boundary};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
+/// Computes a stable content hash.
+///
+/// Uses 64-bit FNV-1a plus the byte length, which is stable across Rust
+/// versions (unlike `DefaultHasher`), so hashes can be persisted and
+/// compared between runs. Used for buffer content hashes.
+#[must_use]
+pub fn content_hash(text: &str) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = text.bytes().fold(FNV_OFFSET, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{hash:016x}-{:x}", text.len())
+}
+
+/// Computes the embedding cache key for a text.
+///
+/// Uses 128-bit FNV-1a plus the byte length. A key collision would serve
+/// another text's vector, so the cache uses a wider hash than
+/// [`content_hash`].
+#[must_use]
+pub fn embedding_cache_key(text: &str) -> String {
+    const FNV_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    let hash = text.bytes().fold(FNV_OFFSET, |h, b| {
+        (h ^ u128::from(b)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{hash:032x}-{:x}", text.len())
+}
+
 /// Estimates token count for a text string with improved accuracy.
 ///
 /// This uses a heuristic that accounts for word boundaries, punctuation,
@@ -251,6 +283,46 @@ impl Chunk {
         estimate_tokens_for_text(&self.content)
     }
 
+    /// Returns the chunker-specific metadata (heading path, code symbols,
+    /// JSON path, ...) stored in [`ChunkMetadata::custom`].
+    ///
+    /// Returns `None` if no metadata is set or it is not valid JSON.
+    #[must_use]
+    pub fn custom_metadata(&self) -> Option<serde_json::Value> {
+        self.metadata
+            .custom
+            .as_deref()
+            .and_then(|custom| serde_json::from_str(custom).ok())
+    }
+
+    /// Sets chunker-specific metadata, stored as JSON in
+    /// [`ChunkMetadata::custom`].
+    pub fn set_custom_metadata(&mut self, value: &serde_json::Value) {
+        self.metadata.custom = Some(value.to_string());
+    }
+
+    /// Builds the stable key for a chunk: the buffer content hash and the
+    /// chunk's byte range, as `<buffer hash>:<start>-<end>`.
+    ///
+    /// Unlike the chunk ID, the key is the same every time identical content
+    /// is loaded with the same chunking, so it stays valid across reloads.
+    #[must_use]
+    pub fn stable_key(buffer_hash: &str, byte_range: &Range<usize>) -> String {
+        format!("{buffer_hash}:{}-{}", byte_range.start, byte_range.end)
+    }
+
+    /// Splits a key built by [`Chunk::stable_key`] into the buffer content
+    /// hash and byte range.
+    ///
+    /// Returns `None` if `key` is not a stable chunk key.
+    #[must_use]
+    pub fn parse_stable_key(key: &str) -> Option<(&str, Range<usize>)> {
+        let (hash, range) = key.rsplit_once(':')?;
+        let (start, end) = range.split_once('-')?;
+        let range = start.parse().ok()?..end.parse().ok()?;
+        (!hash.is_empty() && range.start <= range.end).then_some((hash, range))
+    }
+
     /// Sets the line range in the original buffer.
     pub const fn set_line_range(&mut self, start_line: usize, end_line: usize) {
         self.metadata.line_range = Some(start_line..end_line);
@@ -422,6 +494,19 @@ mod tests {
         assert!(chunk.id.is_none());
     }
 
+    #[test]
+    fn test_chunk_custom_metadata() {
+        let mut chunk = Chunk::new(1, "content".to_string(), 0..7, 0);
+        assert!(chunk.custom_metadata().is_none());
+
+        let value = serde_json::json!({ "page": 3, "heading_path": ["Intro", "Setup"] });
+        chunk.set_custom_metadata(&value);
+        assert_eq!(chunk.custom_metadata(), Some(value));
+
+        chunk.metadata.custom = Some("not json".to_string());
+        assert!(chunk.custom_metadata().is_none());
+    }
+
     #[test]
     fn test_chunk_with_strategy() {
         let chunk = Chunk::with_strategy(1, "content".to_string(), 0..7, 0, "semantic");
@@ -507,6 +592,39 @@ mod tests {
         assert!(!chunk.contains_offset(5));
     }
 
+    #[test]
+    fn test_content_hash_stable() {
+        assert_eq!(content_hash("abc"), content_hash("abc"));
+        assert_ne!(content_hash("abc"), content_hash("abd"));
+        assert_eq!(content_hash(""), "cbf29ce484222325-0");
+    }
+
+    #[test]
+    fn test_embedding_cache_key_stable() {
+        assert_eq!(
+            embedding_cache_key(""),
+            "6c62272e07bb014262b821756295c58d-0"
+        );
+        assert_eq!(
+            embedding_cache_key("a"),
+            "d228cb696f1a8caf78912b704e4a8964-1"
+        );
+    }
+
+    #[test]
+    fn test_stable_key_round_trip() {
+        let key = Chunk::stable_key("cbf29ce484222325-0", &(10..42));
+        assert_eq!(key, "cbf29ce484222325-0:10-42");
+        assert_eq!(
+            Chunk::parse_stable_key(&key),
+            Some(("cbf29ce484222325-0", 10..42))
+        );
+        assert_eq!(Chunk::parse_stable_key("42"), None);
+        assert_eq!(Chunk::parse_stable_key("10-14"), None);
+        assert_eq!(Chunk::parse_stable_key(":1-2"), None);
+        assert_eq!(Chunk::parse_stable_key("abc:5-1"), None);
+    }
+
     #[test]
     fn test_chunk_hash() {
         let mut chunk1 = Chunk::new(1, "Hello".to_string(), 0..5, 0);
@@ -0,0 +1,348 @@
+//! Line-based text diff.
+//!
+//! Computes a line diff between two texts (used to compare buffers) and
+//! renders it as unified diff hunks or a summary of added, removed and
+//! changed lines.
+
+use std::fmt::Write;
+
+/// Maximum LCS table size (cells) before falling back to a block replace.
+///
+/// Common leading and trailing lines are trimmed first, so this only bounds
+/// the differing middle section.
+const MAX_LCS_CELLS: usize = 16 * 1024 * 1024;
+
+/// A single line-level diff operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    /// Line present in both texts.
+    Equal(&'a str),
+    /// Line only in the old text.
+    Removed(&'a str),
+    /// Line only in the new text.
+    Added(&'a str),
+}
+
+/// A contiguous region of changes with surrounding context lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk<'a> {
+    /// Starting line in the old text (1-based; 0 if the hunk is empty there).
+    pub old_start: usize,
+    /// Number of old-text lines covered.
+    pub old_len: usize,
+    /// Starting line in the new text (1-based; 0 if the hunk is empty there).
+    pub new_start: usize,
+    /// Number of new-text lines covered.
+    pub new_len: usize,
+    /// Operations in this hunk.
+    pub ops: Vec<DiffOp<'a>>,
+}
+
+/// Line counts describing a diff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffSummary {
+    /// Lines added without a corresponding removal.
+    pub added: usize,
+    /// Lines removed without a corresponding addition.
+    pub removed: usize,
+    /// Lines replaced (a removal paired with an addition in the same block).
+    pub changed: usize,
+}
+
+/// A line diff between two texts.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::core::diff::LineDiff;
+///
+/// let diff = LineDiff::new("a\nb\nc\n", "a\nB\nc\nd\n");
+/// let summary = diff.summary();
+/// assert_eq!(summary.changed, 1);
+/// assert_eq!(summary.added, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineDiff<'a> {
+    ops: Vec<DiffOp<'a>>,
+}
+
+impl<'a> LineDiff<'a> {
+    /// Computes the diff from `old` to `new`.
+    #[must_use]
+    pub fn new(old: &'a str, new: &'a str) -> Self {
+        let a: Vec<&str> = old.lines().collect();
+        let b: Vec<&str> = new.lines().collect();
+
+        let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        let mut ops = Vec::with_capacity(a.len().max(b.len()));
+        ops.extend(a[..prefix].iter().copied().map(DiffOp::Equal));
+        diff_middle(
+            &a[prefix..a.len() - suffix],
+            &b[prefix..b.len() - suffix],
+            &mut ops,
+        );
+        ops.extend(a[a.len() - suffix..].iter().copied().map(DiffOp::Equal));
+
+        Self { ops }
+    }
+
+    /// Returns the diff operations in order.
+    #[must_use]
+    pub fn ops(&self) -> &[DiffOp<'a>] {
+        &self.ops
+    }
+
+    /// Returns true if the texts have identical lines.
+    #[must_use]
+    pub fn is_identical(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, DiffOp::Equal(_)))
+    }
+
+    /// Summarizes the diff as added, removed and changed line counts.
+    #[must_use]
+    pub fn summary(&self) -> DiffSummary {
+        let mut summary = DiffSummary::default();
+        let (mut removed, mut added) = (0, 0);
+
+        let mut flush = |removed: &mut usize, added: &mut usize| {
+            let paired = (*removed).min(*added);
+            summary.changed += paired;
+            summary.removed += *removed - paired;
+            summary.added += *added - paired;
+            *removed = 0;
+            *added = 0;
+        };
+
+        for op in &self.ops {
+            match op {
+                DiffOp::Removed(_) => removed += 1,
+                DiffOp::Added(_) => added += 1,
+                DiffOp::Equal(_) => flush(&mut removed, &mut added),
+            }
+        }
+        flush(&mut removed, &mut added);
+
+        summary
+    }
+
+    /// Groups changes into hunks with `context` unchanged lines around each.
+    #[must_use]
+    pub fn hunks(&self, context: usize) -> Vec<DiffHunk<'a>> {
+        // Line positions (0-based) in the old and new text before each op
+        let mut old_pos = Vec::with_capacity(self.ops.len() + 1);
+        let mut new_pos = Vec::with_capacity(self.ops.len() + 1);
+        let (mut old_line, mut new_line) = (0, 0);
+        for op in &self.ops {
+            old_pos.push(old_line);
+            new_pos.push(new_line);
+            match op {
+                DiffOp::Equal(_) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                DiffOp::Removed(_) => old_line += 1,
+                DiffOp::Added(_) => new_line += 1,
+            }
+        }
+        old_pos.push(old_line);
+        new_pos.push(new_line);
+
+        // Merge change windows that overlap or touch
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (i, op) in self.ops.iter().enumerate() {
+            if matches!(op, DiffOp::Equal(_)) {
+                continue;
+            }
+            let start = i.saturating_sub(context);
+            let end = i.saturating_add(context + 1).min(self.ops.len());
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+        }
+
+        ranges
+            .into_iter()
+            .map(|(start, end)| {
+                let old_len = old_pos[end] - old_pos[start];
+                let new_len = new_pos[end] - new_pos[start];
+                DiffHunk {
+                    old_start: old_pos[start] + usize::from(old_len > 0),
+                    old_len,
+                    new_start: new_pos[start] + usize::from(new_len > 0),
+                    new_len,
+                    ops: self.ops[start..end].to_vec(),
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the diff in unified format.
+    ///
+    /// Returns an empty string if the texts are identical.
+    #[must_use]
+    pub fn unified(&self, old_name: &str, new_name: &str, context: usize) -> String {
+        let hunks = self.hunks(context);
+        if hunks.is_empty() {
+            return String::new();
+        }
+
+        let mut output = String::new();
+        let _ = writeln!(output, "--- {old_name}");
+        let _ = writeln!(output, "+++ {new_name}");
+        for hunk in &hunks {
+            let _ = writeln!(
+                output,
+                "@@ -{},{} +{},{} @@",
+                hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+            );
+            for op in &hunk.ops {
+                let (marker, line) = match op {
+                    DiffOp::Equal(line) => (' ', line),
+                    DiffOp::Removed(line) => ('-', line),
+                    DiffOp::Added(line) => ('+', line),
+                };
+                let _ = writeln!(output, "{marker}{line}");
+            }
+        }
+        output
+    }
+}
+
+/// Diffs the section between the common prefix and suffix using an LCS table.
+///
+/// Falls back to removing all old lines and adding all new ones when the
+/// table would exceed [`MAX_LCS_CELLS`].
+fn diff_middle<'a>(a: &[&'a str], b: &[&'a str], ops: &mut Vec<DiffOp<'a>>) {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 || n.saturating_mul(m) > MAX_LCS_CELLS {
+        ops.extend(a.iter().copied().map(DiffOp::Removed));
+        ops.extend(b.iter().copied().map(DiffOp::Added));
+        return;
+    }
+
+    // lcs[i * width + j] = LCS length of a[i..] and b[j..]
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            ops.push(DiffOp::Equal(a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push(DiffOp::Removed(a[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(b[j]));
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().copied().map(DiffOp::Removed));
+    ops.extend(b[j..].iter().copied().map(DiffOp::Added));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_identical() {
+        let diff = LineDiff::new("one\ntwo\n", "one\ntwo\n");
+        assert!(diff.is_identical());
+        assert_eq!(diff.summary(), DiffSummary::default());
+        assert!(diff.hunks(3).is_empty());
+        assert!(diff.unified("a", "b", 3).is_empty());
+    }
+
+    #[test]
+    fn test_diff_fully_different() {
+        let diff = LineDiff::new("a\nb\n", "x\ny\nz\n");
+        assert!(!diff.is_identical());
+        assert_eq!(
+            diff.summary(),
+            DiffSummary {
+                added: 1,
+                removed: 0,
+                changed: 2,
+            }
+        );
+        let hunks = diff.hunks(3);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!((hunks[0].old_start, hunks[0].old_len), (1, 2));
+        assert_eq!((hunks[0].new_start, hunks[0].new_len), (1, 3));
+    }
+
+    #[test]
+    fn test_diff_partial_overlap() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n8\n9\n10\n11\n";
+        let diff = LineDiff::new(old, new);
+        assert_eq!(
+            diff.summary(),
+            DiffSummary {
+                added: 1,
+                removed: 0,
+                changed: 1,
+            }
+        );
+
+        // Changes at lines 4 and 11 are far apart with 1 line of context
+        let hunks = diff.hunks(1);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_len), (3, 3));
+
+        // With wide context they merge into one hunk
+        assert_eq!(diff.hunks(10).len(), 1);
+
+        let unified = diff.unified("a", "b", 1);
+        assert!(unified.starts_with("--- a\n+++ b\n@@ -3,3 +3,3 @@\n 3\n-4\n+four\n 5\n"));
+        assert!(unified.contains("+11\n"));
+    }
+
+    #[test]
+    fn test_diff_pure_insertion_hunk_header() {
+        let diff = LineDiff::new("", "new\n");
+        let hunks = diff.hunks(3);
+        assert_eq!((hunks[0].old_start, hunks[0].old_len), (0, 0));
+        assert_eq!((hunks[0].new_start, hunks[0].new_len), (1, 1));
+    }
+
+    #[test]
+    fn test_diff_removed_lines() {
+        let diff = LineDiff::new("keep\ndrop\nkeep2\n", "keep\nkeep2\n");
+        assert_eq!(
+            diff.summary(),
+            DiffSummary {
+                added: 0,
+                removed: 1,
+                changed: 0,
+            }
+        );
+        assert_eq!(
+            diff.ops(),
+            &[
+                DiffOp::Equal("keep"),
+                DiffOp::Removed("drop"),
+                DiffOp::Equal("keep2"),
+            ]
+        );
+    }
+}
@@ -7,7 +7,11 @@
 pub mod buffer;
 pub mod chunk;
 pub mod context;
+pub mod diff;
 
 pub use buffer::{Buffer, BufferMetadata};
-pub use chunk::{Chunk, ChunkMetadata, estimate_tokens_for_text};
+pub use chunk::{
+    Chunk, ChunkMetadata, content_hash, embedding_cache_key, estimate_tokens_for_text,
+};
 pub use context::{Context, ContextValue};
+pub use diff::{DiffHunk, DiffOp, DiffSummary, LineDiff};
@@ -123,6 +123,10 @@ impl Embedder for FallbackEmbedder {
             .map(|text| self.generate_embedding(text))
             .collect())
     }
+
+    fn supports_batch(&self) -> bool {
+        true
+    }
 }
 
 // Note: FallbackEmbedder auto-derives Send + Sync because it only contains
@@ -168,6 +168,12 @@ impl Embedder for FastEmbedEmbedder {
                 )))
             })
     }
+
+    fn supports_batch(&self) -> bool {
+        // Every call locks the one shared model, so concurrent batches
+        // would only queue behind each other
+        false
+    }
 }
 
 #[cfg(test)]
@@ -67,6 +67,74 @@ pub trait Embedder: Send + Sync {
     fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         texts.iter().map(|t| self.embed(t)).collect()
     }
+
+    /// Returns true if concurrent `embed_batch` calls run in parallel.
+    ///
+    /// [`embed_parallel`] only submits batches concurrently for embedders
+    /// that return true. Embedders that serialize calls behind a single
+    /// model should keep the default of false.
+    fn supports_batch(&self) -> bool {
+        false
+    }
+}
+
+/// Number of texts submitted per batch by [`embed_parallel`].
+pub const PARALLEL_BATCH_SIZE: usize = 32;
+
+/// Returns the default worker count for parallel embedding (one per CPU).
+#[must_use]
+pub fn default_embed_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(std::num::NonZeroUsize::get)
+        .unwrap_or(4)
+}
+
+/// Generates embeddings for `texts` using up to `workers` concurrent batches.
+///
+/// Texts are split into batches of [`PARALLEL_BATCH_SIZE`]. If the embedder
+/// supports concurrent batches and more than one worker is allowed, the
+/// batches are split into `workers` groups that run on the global rayon
+/// pool; otherwise they run one after another. Results are returned in
+/// input order either way.
+///
+/// # Errors
+///
+/// Returns an error if embedding fails for any batch.
+pub fn embed_parallel(
+    embedder: &dyn Embedder,
+    texts: &[&str],
+    workers: usize,
+) -> Result<Vec<Vec<f32>>> {
+    use rayon::prelude::*;
+
+    let batches: Vec<&[&str]> = texts.chunks(PARALLEL_BATCH_SIZE).collect();
+    let mut embeddings = Vec::with_capacity(texts.len());
+
+    if workers <= 1 || batches.len() <= 1 || !embedder.supports_batch() {
+        for batch in batches {
+            embeddings.extend(embedder.embed_batch(batch)?);
+        }
+        return Ok(embeddings);
+    }
+
+    // Each group runs its batches in sequence, so at most `workers` batches
+    // are in flight at once
+    let per_worker = batches.len().div_ceil(workers);
+    let results: Vec<Result<Vec<Vec<f32>>>> = batches
+        .par_chunks(per_worker)
+        .map(|group| {
+            let mut group_embeddings = Vec::new();
+            for batch in group {
+                group_embeddings.extend(embedder.embed_batch(batch)?);
+            }
+            Ok(group_embeddings)
+        })
+        .collect();
+
+    for result in results {
+        embeddings.extend(result?);
+    }
+    Ok(embeddings)
 }
 
 /// Creates the default embedder based on available features.
@@ -183,6 +251,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_embed_parallel_matches_sequential() {
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let owned: Vec<String> = (0..PARALLEL_BATCH_SIZE * 3 + 5)
+            .map(|i| format!("text number {i}"))
+            .collect();
+        let texts: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        let sequential: Vec<Vec<f32>> = texts.iter().map(|t| embedder.embed(t).unwrap()).collect();
+        let parallel = embed_parallel(&embedder, &texts, 4).unwrap();
+        let single = embed_parallel(&embedder, &texts, 1).unwrap();
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(single, sequential);
+    }
+
+    #[test]
+    fn test_embed_parallel_empty() {
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        assert!(embed_parallel(&embedder, &[], 4).unwrap().is_empty());
+    }
+
     #[test]
     fn test_embed_batch_empty() {
         // Test embed_batch with empty slice
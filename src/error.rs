@@ -148,6 +148,10 @@ pub enum ChunkingError {
     #[error("regex error: {0}")]
     Regex(String),
 
+    /// Tokenizer error.
+    #[error("tokenizer error: {0}")]
+    Tokenizer(String),
+
     /// Unknown chunking strategy.
     #[error("unknown chunking strategy: {name}")]
     UnknownStrategy {
@@ -274,6 +278,12 @@ pub enum CommandError {
     /// Output format error.
     #[error("output format error: {0}")]
     OutputFormat(String),
+
+    /// Command ran but found nothing, with `--fail-on-empty`.
+    ///
+    /// Holds the command's normal output so it can still be shown.
+    #[error("no results")]
+    NoResults(String),
 }
 
 // Implement From traits for standard library errors
@@ -516,6 +526,9 @@ mod tests {
 
         let err = CommandError::OutputFormat("json error".to_string());
         assert!(err.to_string().contains("output format"));
+
+        let err = CommandError::NoResults("No results found\n".to_string());
+        assert_eq!(err.to_string(), "no results");
     }
 
     #[test]
@@ -0,0 +1,326 @@
+//! Source encoding detection and transcoding.
+//!
+//! Buffers are always stored as UTF-8. Input in UTF-16 (with or without a
+//! byte order mark) or Latin-1 is transcoded, and input that looks like
+//! binary data is rejected instead of being loaded as mojibake. Input that
+//! mixes valid multi-byte UTF-8 with invalid bytes is an error rather than
+//! being guessed as Latin-1.
+
+use std::fmt;
+
+/// Number of leading bytes inspected when classifying input.
+const SAMPLE_SIZE: usize = 8192;
+
+/// Fraction of control bytes above which input is treated as binary.
+const BINARY_CONTROL_RATIO: f64 = 0.1;
+
+/// Fraction of NUL bytes on one side of each byte pair that marks
+/// BOM-less UTF-16.
+const UTF16_NUL_RATIO: f64 = 0.4;
+
+/// Text encoding detected for an input file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// UTF-8, with any byte order mark removed.
+    #[default]
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// ISO-8859-1 (every byte maps to the code point of the same value).
+    Latin1,
+}
+
+impl TextEncoding {
+    /// Returns the encoding name (e.g., "utf-8", "utf-16le").
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+            Self::Latin1 => "latin-1",
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Decodes `bytes` as text, detecting the source encoding.
+///
+/// Detection order: byte order mark, BOM-less UTF-16 (ASCII-heavy text
+/// with NULs in alternating bytes), binary data, valid UTF-8, and finally
+/// Latin-1. Latin-1 is only assumed when the input holds no valid
+/// multi-byte UTF-8 sequence; otherwise the input is taken to be corrupt
+/// UTF-8.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the input appears to be binary,
+/// is malformed UTF-16, or is UTF-8 with invalid bytes.
+pub fn decode_text(bytes: Vec<u8>) -> std::result::Result<(String, TextEncoding), String> {
+    if let Some(rest) = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]) {
+        return String::from_utf8(rest.to_vec())
+            .map(|text| (text, TextEncoding::Utf8))
+            .map_err(|e| format!("invalid UTF-8 after byte order mark: {e}"));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        return decode_utf16(rest, TextEncoding::Utf16Le);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xfe, 0xff]) {
+        return decode_utf16(rest, TextEncoding::Utf16Be);
+    }
+
+    let sample = &bytes[..bytes.len().min(SAMPLE_SIZE)];
+    if let Some(encoding) = detect_utf16(sample) {
+        return decode_utf16(&bytes, encoding);
+    }
+    if looks_binary(sample) {
+        return Err("file appears to be binary".to_string());
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok((text, TextEncoding::Utf8)),
+        Err(e) if has_multibyte_utf8(e.as_bytes()) => Err(invalid_utf8(&e)),
+        Err(e) => Ok((decode_latin1(&e.into_bytes()), TextEncoding::Latin1)),
+    }
+}
+
+/// Decodes `bytes` as UTF-8 without guessing other encodings.
+///
+/// A leading byte order mark is removed.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the input appears to be binary
+/// or is not valid UTF-8.
+pub fn decode_utf8(bytes: Vec<u8>) -> std::result::Result<(String, TextEncoding), String> {
+    let bytes = match bytes.strip_prefix(&[0xef, 0xbb, 0xbf]) {
+        Some(rest) => rest.to_vec(),
+        None => bytes,
+    };
+    if looks_binary(&bytes[..bytes.len().min(SAMPLE_SIZE)]) {
+        return Err("file appears to be binary".to_string());
+    }
+    String::from_utf8(bytes)
+        .map(|text| (text, TextEncoding::Utf8))
+        .map_err(|e| invalid_utf8(&e))
+}
+
+/// Decodes `bytes` in the encoding named by `charset`, such as the charset
+/// parameter of an HTTP `Content-Type`.
+///
+/// UTF-8, UTF-16 and Latin-1 labels are honored (case-insensitively); a
+/// missing or unrecognized label falls back to [`decode_text`].
+///
+/// # Errors
+///
+/// Returns a description of the problem if the input appears to be binary
+/// or is not valid in the declared encoding.
+pub fn decode_with_charset(
+    bytes: Vec<u8>,
+    charset: Option<&str>,
+) -> std::result::Result<(String, TextEncoding), String> {
+    let label = charset.map(|c| c.trim().trim_matches('"').to_ascii_lowercase());
+    match label.as_deref() {
+        Some("utf-8" | "utf8" | "us-ascii" | "ascii") => decode_utf8(bytes),
+        Some("utf-16le") => decode_utf16(
+            bytes.strip_prefix(&[0xff, 0xfe]).unwrap_or(&bytes),
+            TextEncoding::Utf16Le,
+        ),
+        Some("utf-16be") => decode_utf16(
+            bytes.strip_prefix(&[0xfe, 0xff]).unwrap_or(&bytes),
+            TextEncoding::Utf16Be,
+        ),
+        Some("iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1") => {
+            if looks_binary(&bytes[..bytes.len().min(SAMPLE_SIZE)]) {
+                return Err("file appears to be binary".to_string());
+            }
+            Ok((decode_latin1(&bytes), TextEncoding::Latin1))
+        }
+        _ => decode_text(bytes),
+    }
+}
+
+/// Maps each byte to the code point of the same value.
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Returns true if `bytes` contains at least one valid multi-byte UTF-8
+/// sequence.
+fn has_multibyte_utf8(bytes: &[u8]) -> bool {
+    bytes.utf8_chunks().any(|chunk| !chunk.valid().is_ascii())
+}
+
+/// Describes an invalid UTF-8 error by the offset of the first bad byte.
+fn invalid_utf8(error: &std::string::FromUtf8Error) -> String {
+    format!(
+        "invalid UTF-8 at byte offset {}",
+        error.utf8_error().valid_up_to()
+    )
+}
+
+/// Detects BOM-less UTF-16 from the position of NUL bytes.
+#[allow(clippy::cast_precision_loss)]
+fn detect_utf16(sample: &[u8]) -> Option<TextEncoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+
+    let (mut even_nuls, mut odd_nuls) = (0usize, 0usize);
+    for pair in sample.chunks_exact(2) {
+        match (pair[0], pair[1]) {
+            (0, 0) => {}
+            (0, _) => even_nuls += 1,
+            (_, 0) => odd_nuls += 1,
+            _ => {}
+        }
+    }
+
+    let threshold = pairs as f64 * UTF16_NUL_RATIO;
+    if odd_nuls as f64 >= threshold && even_nuls == 0 {
+        Some(TextEncoding::Utf16Le)
+    } else if even_nuls as f64 >= threshold && odd_nuls == 0 {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Returns true if `sample` contains NUL bytes or mostly control bytes.
+#[allow(clippy::cast_precision_loss)]
+fn looks_binary(sample: &[u8]) -> bool {
+    if sample.is_empty() {
+        return false;
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let controls = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    controls as f64 / sample.len() as f64 > BINARY_CONTROL_RATIO
+}
+
+/// Decodes UTF-16 code units in the given byte order.
+fn decode_utf16(
+    bytes: &[u8],
+    encoding: TextEncoding,
+) -> std::result::Result<(String, TextEncoding), String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(format!("invalid {encoding}: odd number of bytes"));
+    }
+
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if encoding == TextEncoding::Utf16Be {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    });
+
+    char::decode_utf16(units)
+        .collect::<std::result::Result<String, _>>()
+        .map(|text| (text, encoding))
+        .map_err(|e| format!("invalid {encoding}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xff, 0xfe] } else { Vec::new() };
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let (text, encoding) = decode_text("héllo wörld".as_bytes().to_vec()).unwrap();
+        assert_eq!(text, "héllo wörld");
+        assert_eq!(encoding, TextEncoding::Utf8);
+
+        let (text, encoding) = decode_text(b"\xef\xbb\xbfwith bom".to_vec()).unwrap();
+        assert_eq!(text, "with bom");
+        assert_eq!(encoding, TextEncoding::Utf8);
+
+        assert_eq!(decode_text(Vec::new()).unwrap().0, "");
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let (text, encoding) = decode_text(utf16le("Grüße, world!\n", true)).unwrap();
+        assert_eq!(text, "Grüße, world!\n");
+        assert_eq!(encoding, TextEncoding::Utf16Le);
+
+        let (text, encoding) = decode_text(utf16le("no byte order mark here", false)).unwrap();
+        assert_eq!(text, "no byte order mark here");
+        assert_eq!(encoding, TextEncoding::Utf16Le);
+
+        let mut be = vec![0xfe, 0xff];
+        be.extend("big endian".encode_utf16().flat_map(u16::to_be_bytes));
+        let (text, encoding) = decode_text(be).unwrap();
+        assert_eq!(text, "big endian");
+        assert_eq!(encoding, TextEncoding::Utf16Be);
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        let (text, encoding) = decode_text(b"caf\xe9 cr\xe8me".to_vec()).unwrap();
+        assert_eq!(text, "café crème");
+        assert_eq!(encoding, TextEncoding::Latin1);
+        assert_eq!(encoding.to_string(), "latin-1");
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupt_utf8() {
+        // Valid multi-byte UTF-8 followed by a stray byte is not Latin-1
+        let err = decode_text(b"caf\xc3\xa9 \xff".to_vec()).unwrap_err();
+        assert_eq!(err, "invalid UTF-8 at byte offset 6");
+
+        let err = decode_utf8(b"caf\xe9".to_vec()).unwrap_err();
+        assert_eq!(err, "invalid UTF-8 at byte offset 3");
+        assert_eq!(decode_utf8(b"\xef\xbb\xbfok".to_vec()).unwrap().0, "ok");
+    }
+
+    #[test]
+    fn test_decode_with_charset() {
+        let (text, encoding) =
+            decode_with_charset(b"caf\xe9".to_vec(), Some("ISO-8859-1")).unwrap();
+        assert_eq!(text, "café");
+        assert_eq!(encoding, TextEncoding::Latin1);
+
+        let (text, encoding) = decode_with_charset(utf16le("hi", false), Some("utf-16le")).unwrap();
+        assert_eq!(text, "hi");
+        assert_eq!(encoding, TextEncoding::Utf16Le);
+
+        // A declared UTF-8 charset is strict
+        assert!(decode_with_charset(b"caf\xe9".to_vec(), Some("\"UTF-8\"")).is_err());
+
+        // Unknown labels fall back to detection
+        let (text, encoding) = decode_with_charset(b"caf\xe9".to_vec(), Some("x-unknown")).unwrap();
+        assert_eq!(text, "café");
+        assert_eq!(encoding, TextEncoding::Latin1);
+    }
+
+    #[test]
+    fn test_decode_rejects_binary() {
+        let blob: Vec<u8> = (0..=255u8).cycle().take(1024).collect();
+        let err = decode_text(blob).unwrap_err();
+        assert_eq!(err, "file appears to be binary");
+
+        let controls = b"\x01\x02\x03\x04text\x05\x06".to_vec();
+        assert!(decode_text(controls).is_err());
+    }
+}
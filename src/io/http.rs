@@ -0,0 +1,285 @@
+//! Fetching buffer content over HTTP.
+//!
+//! With the `http` feature enabled, `http://` and `https://` sources are
+//! downloaded with a timeout and a size cap; without it, fetching fails
+//! with a hint to enable the feature.
+
+use crate::error::{CommandError, Result};
+use crate::io::encoding::TextEncoding;
+use std::time::Duration;
+
+/// Maximum response body size accepted by default (64 MiB).
+pub const DEFAULT_MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Default timeout for the whole request.
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Content downloaded from a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    /// Final URL after redirects.
+    pub url: String,
+    /// Response body, decoded to UTF-8.
+    pub content: String,
+    /// Encoding the body was decoded from.
+    pub encoding: TextEncoding,
+    /// Content type derived from the response (e.g., "txt", "md", "json").
+    pub content_type: Option<String>,
+    /// Final path segment of the URL, if any.
+    pub file_name: Option<String>,
+}
+
+/// Returns true if `source` is an `http://` or `https://` URL.
+#[must_use]
+pub fn is_url(source: &str) -> bool {
+    let lower = source.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Returns the final path segment of `url`, ignoring query and fragment.
+#[must_use]
+pub fn url_file_name(url: &str) -> Option<String> {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split_once('/')
+        .map(|(_, path)| path)?;
+
+    path.rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty())
+        .map(String::from)
+}
+
+/// Maps a MIME type to the short content type used for buffers.
+///
+/// Unknown types are stored as their bare MIME type.
+#[must_use]
+pub fn content_type_from_mime(mime: &str) -> Option<String> {
+    let essence = mime.split(';').next().unwrap_or_default().trim();
+    if essence.is_empty() {
+        return None;
+    }
+
+    let short = match essence.to_ascii_lowercase().as_str() {
+        "text/plain" => "txt",
+        "text/markdown" | "text/x-markdown" => "md",
+        "application/json" => "json",
+        "text/html" | "application/xhtml+xml" => "html",
+        "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => "yaml",
+        "text/csv" => "csv",
+        "application/xml" | "text/xml" => "xml",
+        "application/pdf" => "pdf",
+        other => return Some(other.to_string()),
+    };
+    Some(short.to_string())
+}
+
+/// Returns the `charset` parameter of a `Content-Type` value, if present.
+#[must_use]
+pub fn mime_charset(mime: &str) -> Option<String> {
+    mime.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// Downloads `url` as text, decoded to UTF-8.
+///
+/// The body is decoded in the charset declared by the response, or with
+/// the same detection used for local files when none is declared.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or times out, the server responds
+/// with a non-2xx status, the body exceeds `max_size` bytes, appears to be
+/// binary or cannot be decoded, or the `http` feature is disabled.
+#[cfg(feature = "http")]
+pub fn fetch_url(url: &str, max_size: u64, timeout: Duration) -> Result<Download> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+    let response = agent.get(url).call().map_err(|e| match e {
+        ureq::Error::Status(status, response) => CommandError::ExecutionFailed(format!(
+            "failed to fetch {url}: HTTP {status} {}",
+            response.status_text()
+        )),
+        ureq::Error::Transport(transport) => {
+            CommandError::ExecutionFailed(format!("failed to fetch {url}: {transport}"))
+        }
+    })?;
+
+    let final_url = response.get_url().to_string();
+    let content_type = content_type_from_mime(response.content_type());
+    let charset = response.header("content-type").and_then(mime_charset);
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut body)
+        .map_err(|e| CommandError::ExecutionFailed(format!("failed to read {url}: {e}")))?;
+
+    if body.len() as u64 > max_size {
+        return Err(CommandError::ExecutionFailed(format!(
+            "{url} exceeds the maximum download size of {max_size} bytes"
+        ))
+        .into());
+    }
+
+    let (content, encoding) = crate::io::encoding::decode_with_charset(body, charset.as_deref())
+        .map_err(|reason| CommandError::ExecutionFailed(format!("{url}: {reason}")))?;
+
+    Ok(Download {
+        file_name: url_file_name(&final_url),
+        url: final_url,
+        content,
+        encoding,
+        content_type,
+    })
+}
+
+/// Downloads `url` as text, decoded to UTF-8.
+///
+/// # Errors
+///
+/// Always returns an error because the `http` feature is disabled.
+#[cfg(not(feature = "http"))]
+pub fn fetch_url(url: &str, _max_size: u64, _timeout: Duration) -> Result<Download> {
+    Err(CommandError::ExecutionFailed(format!(
+        "cannot fetch {url}: URL loading is not enabled (rebuild with --features http)"
+    ))
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/doc.txt"));
+        assert!(is_url("HTTP://example.com"));
+        assert!(!is_url("docs/http.txt"));
+        assert!(!is_url("ftp://example.com/file"));
+    }
+
+    #[test]
+    fn test_url_file_name() {
+        assert_eq!(
+            url_file_name("https://example.com/docs/guide.md?rev=2#intro").as_deref(),
+            Some("guide.md")
+        );
+        assert_eq!(url_file_name("https://example.com/docs/"), None);
+        assert_eq!(url_file_name("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_content_type_from_mime() {
+        assert_eq!(
+            content_type_from_mime("text/plain; charset=utf-8").as_deref(),
+            Some("txt")
+        );
+        assert_eq!(
+            content_type_from_mime("application/json").as_deref(),
+            Some("json")
+        );
+        assert_eq!(
+            content_type_from_mime("text/x-rust").as_deref(),
+            Some("text/x-rust")
+        );
+        assert_eq!(content_type_from_mime(""), None);
+    }
+
+    #[test]
+    fn test_mime_charset() {
+        assert_eq!(
+            mime_charset("text/plain; charset=ISO-8859-1").as_deref(),
+            Some("ISO-8859-1")
+        );
+        assert_eq!(
+            mime_charset("text/html; boundary=x; Charset=\"utf-8\"").as_deref(),
+            Some("utf-8")
+        );
+        assert_eq!(mime_charset("text/plain"), None);
+        assert_eq!(mime_charset("text/plain; charset="), None);
+    }
+
+    #[cfg(feature = "http")]
+    mod server {
+        use super::*;
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+        use std::thread;
+
+        /// Serves one canned response on a local port and returns its base URL.
+        fn serve_once(
+            status: &'static str,
+            content_type: &'static str,
+            body: &'static str,
+        ) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            });
+
+            format!("http://{addr}")
+        }
+
+        #[test]
+        fn test_fetch_url_success() {
+            let base = serve_once("200 OK", "text/markdown; charset=utf-8", "# Title\n\nBody");
+            let download =
+                fetch_url(&format!("{base}/docs/guide.md"), 1024, DEFAULT_HTTP_TIMEOUT).unwrap();
+
+            assert_eq!(download.content, "# Title\n\nBody");
+            assert_eq!(download.content_type.as_deref(), Some("md"));
+            assert_eq!(download.file_name.as_deref(), Some("guide.md"));
+            assert_eq!(download.encoding, TextEncoding::Utf8);
+        }
+
+        #[test]
+        fn test_fetch_url_declared_charset() {
+            let base = serve_once("200 OK", "text/plain; charset=utf-16le", "h\0i\0");
+            let download =
+                fetch_url(&format!("{base}/notes.txt"), 1024, DEFAULT_HTTP_TIMEOUT).unwrap();
+            assert_eq!(download.content, "hi");
+            assert_eq!(download.encoding, TextEncoding::Utf16Le);
+        }
+
+        #[test]
+        fn test_fetch_url_not_found() {
+            let base = serve_once("404 Not Found", "text/plain", "missing");
+            let err =
+                fetch_url(&format!("{base}/missing.txt"), 1024, DEFAULT_HTTP_TIMEOUT).unwrap_err();
+            assert!(err.to_string().contains("HTTP 404"));
+        }
+
+        #[test]
+        fn test_fetch_url_too_large() {
+            let base = serve_once("200 OK", "text/plain", "0123456789");
+            let err = fetch_url(&format!("{base}/big.txt"), 4, DEFAULT_HTTP_TIMEOUT).unwrap_err();
+            assert!(err.to_string().contains("maximum download size"));
+        }
+    }
+}
@@ -1,10 +1,24 @@
 //! I/O utilities for RLM-RS.
 //!
-//! Provides file reading with memory mapping support for efficient
-//! handling of large files, along with Unicode utilities.
+//! Provides file reading with memory mapping support, transparent
+//! decompression and encoding detection for efficient handling of large
+//! files, along with URL downloads, PDF text extraction, and Unicode
+//! utilities.
 
+pub mod encoding;
+pub mod http;
+pub mod pdf;
 pub mod reader;
 pub mod unicode;
 
-pub use reader::{FileReader, read_file, read_file_mmap, write_chunks, write_file};
+pub use encoding::{TextEncoding, decode_text, decode_utf8, decode_with_charset};
+pub use http::{
+    DEFAULT_HTTP_TIMEOUT, DEFAULT_MAX_DOWNLOAD_SIZE, Download, content_type_from_mime, fetch_url,
+    is_url, mime_charset, url_file_name,
+};
+pub use pdf::{PAGE_BREAK, PdfText, is_pdf, read_pdf};
+pub use reader::{
+    Compression, FileContent, FileReader, read_file, read_file_content, read_file_mmap,
+    write_chunks, write_file,
+};
 pub use unicode::{current_timestamp, find_char_boundary, validate_utf8};
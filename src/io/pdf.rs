@@ -0,0 +1,164 @@
+//! PDF text extraction.
+//!
+//! PDF files are recognized by their `.pdf` extension or `%PDF-` magic
+//! bytes. With the `pdf` feature enabled, text is extracted page by page
+//! and the pages are joined with form feeds so chunkers can break on them;
+//! without it, loading a PDF fails with a hint to enable the feature.
+
+use crate::error::{CommandError, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Separator inserted between pages of extracted text.
+pub const PAGE_BREAK: char = '\u{c}';
+
+/// Magic bytes at the start of every PDF file.
+const PDF_MAGIC: &[u8] = b"%PDF-";
+
+/// Text extracted from a PDF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfText {
+    /// Page texts in page order, separated by [`PAGE_BREAK`].
+    pub content: String,
+    /// Byte offset in `content` where each page starts.
+    pub page_offsets: Vec<usize>,
+}
+
+impl PdfText {
+    /// Joins page texts in order, recording where each page starts.
+    #[must_use]
+    pub fn from_pages<S: AsRef<str>>(pages: &[S]) -> Self {
+        let mut content = String::new();
+        let mut page_offsets = Vec::with_capacity(pages.len());
+
+        for (i, page) in pages.iter().enumerate() {
+            if i > 0 {
+                content.push(PAGE_BREAK);
+            }
+            page_offsets.push(content.len());
+            content.push_str(page.as_ref().trim_matches(PAGE_BREAK));
+        }
+
+        Self {
+            content,
+            page_offsets,
+        }
+    }
+
+    /// Returns the number of pages.
+    #[must_use]
+    pub const fn page_count(&self) -> usize {
+        self.page_offsets.len()
+    }
+}
+
+/// Returns true if `path` looks like a PDF, by extension or magic bytes.
+#[must_use]
+pub fn is_pdf<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    if path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+    {
+        return true;
+    }
+
+    let mut magic = [0u8; PDF_MAGIC.len()];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| magic == PDF_MAGIC)
+}
+
+/// Extracts the text of the PDF at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, the PDF is encrypted or
+/// malformed, it contains no extractable text, or the `pdf` feature is
+/// disabled.
+pub fn read_pdf<P: AsRef<Path>>(path: P) -> Result<PdfText> {
+    let path = path.as_ref();
+    let bytes = super::FileReader::open(path)?.read_to_bytes()?;
+    extract_pdf_text(&bytes).map_err(|reason| {
+        CommandError::ExecutionFailed(format!(
+            "cannot extract text from PDF {}: {reason}",
+            path.display()
+        ))
+        .into()
+    })
+}
+
+/// Extracts page texts from PDF bytes.
+#[cfg(feature = "pdf")]
+fn extract_pdf_text(bytes: &[u8]) -> std::result::Result<PdfText, String> {
+    let pages = pdf_extract::extract_text_from_mem_by_pages(bytes)
+        .map_err(|e| format!("{e} (the file may be encrypted or malformed)"))?;
+
+    if pages.iter().all(|page| page.trim().is_empty()) {
+        return Err("no extractable text (the file may be scanned or image-only)".to_string());
+    }
+
+    Ok(PdfText::from_pages(&pages))
+}
+
+/// Extracts page texts from PDF bytes.
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf_text(_bytes: &[u8]) -> std::result::Result<PdfText, String> {
+    Err("PDF support is not enabled (rebuild with --features pdf)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/two_pages.pdf");
+
+    #[test]
+    fn test_from_pages_records_offsets() {
+        let pdf = PdfText::from_pages(&["First page\n", "Second\n", ""]);
+        assert_eq!(pdf.content, "First page\n\u{c}Second\n\u{c}");
+        assert_eq!(pdf.page_offsets, vec![0, 12, 20]);
+        assert_eq!(pdf.page_count(), 3);
+        assert!(pdf.content[pdf.page_offsets[1]..].starts_with("Second"));
+    }
+
+    #[test]
+    fn test_is_pdf_by_extension_and_magic() {
+        let temp_dir = TempDir::new().unwrap();
+        let renamed = temp_dir.path().join("report.bin");
+        std::fs::write(&renamed, b"%PDF-1.4\n").unwrap();
+        let text = temp_dir.path().join("notes.txt");
+        std::fs::write(&text, "plain text").unwrap();
+
+        assert!(is_pdf("missing.PDF"));
+        assert!(is_pdf(&renamed));
+        assert!(is_pdf(FIXTURE));
+        assert!(!is_pdf(&text));
+        assert!(!is_pdf("missing.txt"));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn test_read_pdf_extracts_pages_in_order() {
+        let pdf = read_pdf(FIXTURE).unwrap();
+        assert_eq!(pdf.page_count(), 2);
+
+        let first = &pdf.content[pdf.page_offsets[0]..pdf.page_offsets[1]];
+        let second = &pdf.content[pdf.page_offsets[1]..];
+        assert!(first.contains("First page text"));
+        assert!(second.contains("Second page text"));
+    }
+
+    #[test]
+    fn test_read_pdf_rejects_invalid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.pdf");
+        std::fs::write(&path, b"%PDF-1.4\nnot really a pdf").unwrap();
+
+        let err = read_pdf(&path).unwrap_err();
+        assert!(err.to_string().contains("cannot extract text from PDF"));
+    }
+}
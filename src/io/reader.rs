@@ -7,9 +7,10 @@
 #![allow(unsafe_code)]
 
 use crate::error::{IoError, Result};
+use crate::io::encoding::{TextEncoding, decode_text, decode_utf8};
 use memmap2::Mmap;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
 /// Threshold for using memory mapping (1MB).
@@ -206,7 +207,7 @@ impl FileReader {
 ///
 /// # Errors
 ///
-/// Returns an error if the file cannot be read or is not valid UTF-8.
+/// Returns an error if the file cannot be read or appears to be binary.
 ///
 /// # Examples
 ///
@@ -216,7 +217,152 @@ impl FileReader {
 /// let content = read_file("example.txt").unwrap();
 /// ```
 pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String> {
-    FileReader::open(path)?.read_to_string()
+    Ok(read_file_content(path)?.content)
+}
+
+/// Compression format of a file read with [`read_file_content`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip (`.gz`).
+    Gzip,
+    /// Zstandard (`.zst`), decompressed with the `zstd` feature.
+    Zstd,
+}
+
+impl Compression {
+    /// Magic bytes at the start of a gzip stream.
+    const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+    /// Magic bytes at the start of a Zstandard frame.
+    const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+    /// Detects compression from a file's leading bytes, falling back to its
+    /// extension.
+    #[must_use]
+    pub fn detect(path: &Path, header: &[u8]) -> Option<Self> {
+        if header.starts_with(Self::GZIP_MAGIC) {
+            return Some(Self::Gzip);
+        }
+        if header.starts_with(Self::ZSTD_MAGIC) {
+            return Some(Self::Zstd);
+        }
+        Self::from_extension(path)
+    }
+
+    /// Detects compression from a file extension (`gz`, `zst` or `zstd`).
+    #[must_use]
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Decompresses `bytes`, failing if the output exceeds `limit` bytes.
+    fn decompress(self, bytes: &[u8], limit: u64) -> std::result::Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        let read = match self {
+            Self::Gzip => flate2::read::MultiGzDecoder::new(bytes)
+                .take(limit.saturating_add(1))
+                .read_to_end(&mut output),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::stream::read::Decoder::new(bytes).and_then(|decoder| {
+                decoder
+                    .take(limit.saturating_add(1))
+                    .read_to_end(&mut output)
+            }),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd => {
+                return Err(
+                    "zstd support is not enabled (rebuild with --features zstd)".to_string()
+                );
+            }
+        };
+        read.map_err(|e| format!("decompression failed: {e}"))?;
+
+        if output.len() as u64 > limit {
+            return Err(format!(
+                "decompressed size exceeds the maximum of {limit} bytes"
+            ));
+        }
+        Ok(output)
+    }
+}
+
+/// File content after transparent decompression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileContent {
+    /// Text content (decompressed if the file was compressed).
+    pub content: String,
+    /// Encoding the content was decoded from.
+    pub encoding: TextEncoding,
+    /// Compression format, if the file was compressed.
+    pub compression: Option<Compression>,
+    /// On-disk size in bytes, if the file was compressed.
+    pub compressed_size: Option<usize>,
+}
+
+/// Reads a file to string, decompressing gzip and zstd files and
+/// transcoding UTF-16 and Latin-1 text transparently.
+///
+/// Compression is detected by magic bytes or a `.gz`/`.zst` extension.
+/// The text encoding of plain files is detected with [`decode_text`];
+/// decompressed content must be UTF-8.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or decompressed, its
+/// (decompressed) content appears to be binary, or decompressed content is
+/// not valid UTF-8.
+#[allow(clippy::cast_possible_truncation)]
+pub fn read_file_content<P: AsRef<Path>>(path: P) -> Result<FileContent> {
+    let path = path.as_ref();
+    let reader = FileReader::open(path)?;
+
+    let mut header = [0u8; 4];
+    let header_len = (&reader.file)
+        .read(&mut header)
+        .map_err(|e| IoError::ReadFailed {
+            path: reader.path.clone(),
+            reason: e.to_string(),
+        })?;
+    (&reader.file)
+        .seek(SeekFrom::Start(0))
+        .map_err(|e| IoError::ReadFailed {
+            path: reader.path.clone(),
+            reason: e.to_string(),
+        })?;
+
+    let compression = Compression::detect(path, &header[..header_len]);
+    let mut bytes = reader.read_to_bytes()?;
+    if let Some(compression) = compression {
+        bytes = compression
+            .decompress(&bytes, MAX_FILE_SIZE)
+            .map_err(|reason| IoError::ReadFailed {
+                path: reader.path.clone(),
+                reason,
+            })?;
+    }
+
+    // Decompressed content must already be UTF-8; encodings are only
+    // detected for plain files
+    let decoded = if compression.is_some() {
+        decode_utf8(bytes).map_err(|reason| format!("decompressed content: {reason}"))
+    } else {
+        decode_text(bytes)
+    };
+    let (content, encoding) = decoded.map_err(|reason| IoError::ReadFailed {
+        path: reader.path.clone(),
+        reason,
+    })?;
+
+    Ok(FileContent {
+        content,
+        encoding,
+        compression,
+        compressed_size: compression.map(|_| reader.size as usize),
+    })
 }
 
 /// Reads a file using memory mapping.
@@ -331,6 +477,92 @@ mod tests {
         assert_eq!(content, "Hello, world!");
     }
 
+    #[test]
+    fn test_read_gzip_fixture() {
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let original = std::fs::read_to_string(format!("{fixtures}/sample.log")).unwrap();
+
+        let read = read_file_content(format!("{fixtures}/sample.log.gz")).unwrap();
+        assert_eq!(read.content, original);
+        assert_eq!(read.compression, Some(Compression::Gzip));
+        assert!(read.compressed_size.unwrap() < original.len());
+
+        let plain = read_file_content(format!("{fixtures}/sample.log")).unwrap();
+        assert_eq!(plain.compression, None);
+        assert_eq!(plain.compressed_size, None);
+    }
+
+    #[test]
+    fn test_read_gzip_detected_by_magic() {
+        use flate2::Compression as Level;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("renamed.txt");
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all("compressed text".as_bytes()).unwrap();
+        std::fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+        assert_eq!(read_file(&file_path).unwrap(), "compressed text");
+    }
+
+    #[test]
+    fn test_read_gzip_binary() {
+        use flate2::Compression as Level;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("binary.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(&[0x00, 0x01, 0x02, 0x03, 0x00]).unwrap();
+        std::fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+        let err = read_file(&file_path).unwrap_err();
+        assert!(err.to_string().contains("file appears to be binary"));
+
+        // Decompressed content is not transcoded from Latin-1
+        let latin1 = temp_dir.path().join("latin1.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(b"caf\xe9").unwrap();
+        std::fs::write(&latin1, encoder.finish().unwrap()).unwrap();
+        assert!(
+            read_file(&latin1)
+                .unwrap_err()
+                .to_string()
+                .contains("decompressed content: invalid UTF-8 at byte offset 3")
+        );
+
+        // A .gz extension on a file that is not gzip is a decompression error
+        let bogus = temp_dir.path().join("plain.gz");
+        std::fs::write(&bogus, "not compressed").unwrap();
+        assert!(
+            read_file(&bogus)
+                .unwrap_err()
+                .to_string()
+                .contains("decompression failed")
+        );
+    }
+
+    #[test]
+    fn test_compression_detect() {
+        let path = Path::new("data.bin");
+        assert_eq!(
+            Compression::detect(path, &[0x1f, 0x8b, 0x08]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::detect(path, &[0x28, 0xb5, 0x2f, 0xfd]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::detect(path, b"text"), None);
+        assert_eq!(
+            Compression::detect(Path::new("trace.ZST"), b""),
+            Some(Compression::Zstd)
+        );
+    }
+
     #[test]
     fn test_read_nonexistent_file() {
         let result = read_file("/nonexistent/path/file.txt");
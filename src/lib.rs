@@ -20,6 +20,7 @@
 // Note: unsafe is needed for memory-mapped I/O (memmap2)
 #![warn(unsafe_code)]
 
+pub mod api;
 pub mod chunking;
 pub mod cli;
 pub mod core;
@@ -32,6 +33,9 @@ pub mod storage;
 // Re-export commonly used types at crate root
 pub use error::{Error, Result};
 
+// Re-export the library facade
+pub use api::{LoadOptions, LoadResult, Rlm};
+
 // Re-export core domain types
 pub use core::{Buffer, BufferMetadata, Chunk, ChunkMetadata, Context, ContextValue};
 
@@ -49,11 +53,14 @@ pub use cli::{Cli, Commands, OutputFormat};
 pub use embedding::FastEmbedEmbedder;
 pub use embedding::{
     DEFAULT_DIMENSIONS, Embedder, FallbackEmbedder, cosine_similarity, create_embedder,
+    embed_parallel,
 };
 
 // Re-export search types
 pub use search::{
-    DEFAULT_SIMILARITY_THRESHOLD, DEFAULT_TOP_K, RrfConfig, SearchConfig, SearchResult,
-    buffer_fully_embedded, embed_buffer_chunks, hybrid_search, reciprocal_rank_fusion, search_bm25,
-    search_semantic, weighted_rrf,
+    ContextGroup, DEFAULT_SIMILARITY_THRESHOLD, DEFAULT_TOP_K, DimensionMismatch, FusionMethod,
+    ParsedQuery, RelaxedSearch, RrfConfig, SearchConfig, SearchResult, SimilarityMetric,
+    buffer_fully_embedded, embed_buffer_chunks, embed_buffer_chunks_with_workers,
+    ensure_embedding_dimensions, expand_context, hybrid_search, mmr_rerank, reciprocal_rank_fusion,
+    search_bm25, search_semantic, search_with_relaxation, weighted_rrf, weighted_score_fusion,
 };
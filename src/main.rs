@@ -4,15 +4,25 @@
 
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
-use clap::Parser;
-use rlm_rs::cli::output::{OutputFormat, format_error};
+use rlm_rs::cli::output::{FormatChoice, OutputFormat, format_error};
 use rlm_rs::cli::{Cli, execute};
+use rlm_rs::error::CommandError;
 use std::io::{self, Write};
 use std::process::ExitCode;
 
+/// Exit code for a successful command that found nothing (`--fail-on-empty`).
+const NO_RESULTS_EXIT_CODE: u8 = 3;
+
 fn main() -> ExitCode {
-    let cli = Cli::parse();
-    let format = OutputFormat::parse(&cli.format);
+    let cli = match Cli::parse_with_config() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let choice = FormatChoice::parse(&cli.format);
+    let format = choice.output_format();
 
     match execute(&cli) {
         Ok(output) => {
@@ -27,11 +37,16 @@ fn main() -> ExitCode {
             }
             ExitCode::SUCCESS
         }
+        Err(rlm_rs::Error::Command(CommandError::NoResults(output))) => {
+            // The normal output is still informative, so print it as usual
+            let _ = write!(io::stdout(), "{output}");
+            ExitCode::from(NO_RESULTS_EXIT_CODE)
+        }
         Err(e) => {
-            let error_output = format_error(&e, format);
+            let error_output = choice.convert(format_error(&e, format));
             match format {
                 OutputFormat::Json | OutputFormat::Ndjson => {
-                    // JSON errors go to stdout for programmatic parsing
+                    // Structured errors go to stdout for programmatic parsing
                     println!("{error_output}");
                 }
                 OutputFormat::Text => {
@@ -0,0 +1,179 @@
+//! Context-window expansion around search hits.
+//!
+//! Loads the chunks immediately before and after each hit (by buffer and
+//! chunk index) so callers can read a hit in continuity without issuing
+//! separate `chunk get` calls.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use super::SearchResult;
+use crate::core::Chunk;
+use crate::error::Result;
+use crate::storage::{SqliteStorage, Storage};
+
+/// A chunk shown as part of a context group.
+#[derive(Debug, Clone)]
+pub struct ContextChunk {
+    /// Chunk ID.
+    pub chunk_id: i64,
+    /// Sequential index within the buffer.
+    pub index: usize,
+    /// Whether this chunk is the search hit (as opposed to context).
+    pub is_hit: bool,
+    /// Chunk content.
+    pub content: String,
+}
+
+/// A search hit together with its neighboring chunks.
+#[derive(Debug, Clone)]
+pub struct ContextGroup {
+    /// Chunk ID of the hit this group belongs to.
+    pub hit_chunk_id: i64,
+    /// Chunks in index order, including the hit itself.
+    pub chunks: Vec<ContextChunk>,
+}
+
+/// Expands each search result with the `n` preceding and following chunks.
+///
+/// Groups are returned in the same order as `results`. Overlaps are
+/// deduplicated: a context chunk already shown in an earlier group is
+/// omitted, and a neighbor that is itself a hit is left to its own group.
+/// Windows are clamped at the start and end of each buffer.
+///
+/// # Arguments
+///
+/// * `storage` - The storage backend.
+/// * `results` - Search results to expand.
+/// * `n` - Number of chunks to include on each side of a hit.
+///
+/// # Errors
+///
+/// Returns an error if chunk retrieval fails.
+pub fn expand_context(
+    storage: &SqliteStorage,
+    results: &[SearchResult],
+    n: usize,
+) -> Result<Vec<ContextGroup>> {
+    let mut buffers: HashMap<i64, Vec<Chunk>> = HashMap::new();
+    let mut seen: HashSet<i64> = results.iter().map(|r| r.chunk_id).collect();
+    let mut groups = Vec::with_capacity(results.len());
+
+    for result in results {
+        let chunks = match buffers.entry(result.buffer_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(storage.get_chunks(result.buffer_id)?),
+        };
+
+        let Some(pos) = chunks.iter().position(|c| c.id == Some(result.chunk_id)) else {
+            groups.push(ContextGroup {
+                hit_chunk_id: result.chunk_id,
+                chunks: Vec::new(),
+            });
+            continue;
+        };
+
+        let start = pos.saturating_sub(n);
+        let end = pos.saturating_add(n).min(chunks.len() - 1);
+
+        let mut group = Vec::with_capacity(end - start + 1);
+        for chunk in &chunks[start..=end] {
+            let Some(chunk_id) = chunk.id else {
+                continue;
+            };
+            let is_hit = chunk_id == result.chunk_id;
+            if !is_hit && !seen.insert(chunk_id) {
+                continue;
+            }
+            group.push(ContextChunk {
+                chunk_id,
+                index: chunk.index,
+                is_hit,
+                content: chunk.content.clone(),
+            });
+        }
+
+        groups.push(ContextGroup {
+            hit_chunk_id: result.chunk_id,
+            chunks: group,
+        });
+    }
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Buffer;
+
+    fn setup_storage(count: usize) -> (SqliteStorage, Vec<Chunk>) {
+        let mut storage = SqliteStorage::in_memory().unwrap();
+        storage.init().unwrap();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named("ctx.txt".to_string(), String::new()))
+            .unwrap();
+        let chunks: Vec<Chunk> = (0..count)
+            .map(|i| Chunk::new(buffer_id, format!("chunk {i}"), i * 10..i * 10 + 7, i))
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let stored = storage.get_chunks(buffer_id).unwrap();
+        (storage, stored)
+    }
+
+    fn hit(chunk: &Chunk) -> SearchResult {
+        SearchResult {
+            chunk_id: chunk.id.unwrap(),
+            buffer_id: chunk.buffer_id,
+            index: chunk.index,
+            score: 1.0,
+            semantic_score: None,
+            bm25_score: None,
+            content_preview: None,
+            buffer_name: None,
+            metadata: None,
+        }
+    }
+
+    fn indices(group: &ContextGroup) -> Vec<usize> {
+        group.chunks.iter().map(|c| c.index).collect()
+    }
+
+    #[test]
+    fn test_expand_context_buffer_start() {
+        let (storage, chunks) = setup_storage(5);
+        let groups = expand_context(&storage, &[hit(&chunks[0])], 2).unwrap();
+        assert_eq!(indices(&groups[0]), vec![0, 1, 2]);
+        assert!(groups[0].chunks[0].is_hit);
+        assert!(!groups[0].chunks[1].is_hit);
+    }
+
+    #[test]
+    fn test_expand_context_buffer_end() {
+        let (storage, chunks) = setup_storage(5);
+        let groups = expand_context(&storage, &[hit(&chunks[4])], 2).unwrap();
+        assert_eq!(indices(&groups[0]), vec![2, 3, 4]);
+        assert!(groups[0].chunks[2].is_hit);
+        assert_eq!(groups[0].chunks[2].content, "chunk 4");
+    }
+
+    #[test]
+    fn test_expand_context_dedupes_overlaps() {
+        let (storage, chunks) = setup_storage(8);
+        let results = [hit(&chunks[2]), hit(&chunks[4])];
+        let groups = expand_context(&storage, &results, 2).unwrap();
+
+        // Chunk 4 is a hit, so it is not repeated as context of chunk 2
+        assert_eq!(indices(&groups[0]), vec![0, 1, 2, 3]);
+        // Chunks 2 and 3 were already shown in the first group
+        assert_eq!(indices(&groups[1]), vec![4, 5, 6]);
+        assert!(groups[1].chunks[0].is_hit);
+    }
+
+    #[test]
+    fn test_expand_context_zero() {
+        let (storage, chunks) = setup_storage(3);
+        let groups = expand_context(&storage, &[hit(&chunks[1])], 0).unwrap();
+        assert_eq!(indices(&groups[0]), vec![1]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Fusion strategies for combining semantic and BM25 rankings.
+//!
+//! Reciprocal Rank Fusion (see [`super::reciprocal_rank_fusion`]) uses only
+//! ranks; weighted fusion blends normalized scores so that genuinely high
+//! similarity is not flattened into a rank position.
+
+use std::collections::HashMap;
+
+/// Default alpha for weighted fusion (equal weight to both signals).
+pub const DEFAULT_FUSION_ALPHA: f64 = 0.5;
+
+/// Method used to fuse semantic and BM25 result lists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FusionMethod {
+    /// Reciprocal Rank Fusion with the given k parameter.
+    Rrf {
+        /// RRF k parameter (default 60).
+        k: u32,
+    },
+    /// Weighted blend of min-max normalized scores:
+    /// `alpha * semantic + (1 - alpha) * bm25`.
+    Weighted {
+        /// Weight given to the semantic score (0.0-1.0).
+        alpha: f64,
+    },
+}
+
+impl Default for FusionMethod {
+    fn default() -> Self {
+        Self::Rrf { k: 60 }
+    }
+}
+
+impl FusionMethod {
+    /// Parses a fusion method name ("rrf" or "weighted").
+    ///
+    /// Returns `None` for unknown names.
+    #[must_use]
+    pub fn parse(name: &str, rrf_k: u32, alpha: f64) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rrf" => Some(Self::Rrf { k: rrf_k }),
+            "weighted" | "alpha" => Some(Self::Weighted {
+                alpha: alpha.clamp(0.0, 1.0),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the method name.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Rrf { .. } => "rrf",
+            Self::Weighted { .. } => "weighted",
+        }
+    }
+}
+
+/// Min-max normalizes scores to [0, 1].
+///
+/// When all scores are equal, every item receives 1.0.
+fn normalize(scores: &[(i64, f64)]) -> HashMap<i64, f64> {
+    let (min, max) = scores
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, s)| {
+            (lo.min(*s), hi.max(*s))
+        });
+    let range = max - min;
+
+    scores
+        .iter()
+        .map(|(id, s)| {
+            let norm = if range > f64::EPSILON {
+                (s - min) / range
+            } else {
+                1.0
+            };
+            (*id, norm)
+        })
+        .collect()
+}
+
+/// Fuses semantic and BM25 results by a weighted blend of normalized scores.
+///
+/// Each list is min-max normalized independently; an item missing from a
+/// list contributes 0 for that signal.
+///
+/// # Arguments
+///
+/// * `semantic` - `(chunk_id, similarity)` pairs.
+/// * `bm25` - `(chunk_id, bm25_score)` pairs.
+/// * `alpha` - Weight of the semantic score (clamped to 0.0-1.0).
+///
+/// # Returns
+///
+/// A vector of (`item_id`, `fused_score`) tuples, sorted by score descending
+/// with ties broken by ascending ID.
+///
+/// # Examples
+///
+/// ```
+/// use rlm_rs::search::weighted_score_fusion;
+///
+/// let semantic = vec![(1, 0.9), (2, 0.2)];
+/// let bm25 = vec![(2, 5.0), (3, 1.0)];
+/// let fused = weighted_score_fusion(&semantic, &bm25, 0.8);
+/// assert_eq!(fused[0].0, 1);
+/// ```
+#[must_use]
+pub fn weighted_score_fusion(
+    semantic: &[(i64, f32)],
+    bm25: &[(i64, f64)],
+    alpha: f64,
+) -> Vec<(i64, f64)> {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let semantic: Vec<(i64, f64)> = semantic
+        .iter()
+        .map(|(id, s)| (*id, f64::from(*s)))
+        .collect();
+    let semantic_norm = normalize(&semantic);
+    let bm25_norm = normalize(bm25);
+
+    let mut scores: HashMap<i64, f64> = HashMap::new();
+    for (id, s) in &semantic_norm {
+        *scores.entry(*id).or_insert(0.0) += alpha * s;
+    }
+    for (id, s) in &bm25_norm {
+        *scores.entry(*id).or_insert(0.0) += (1.0 - alpha) * s;
+    }
+
+    let mut results: Vec<(i64, f64)> = scores.into_iter().collect();
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fusion_method_default_is_rrf() {
+        assert_eq!(FusionMethod::default(), FusionMethod::Rrf { k: 60 });
+    }
+
+    #[test]
+    fn test_fusion_method_parse() {
+        assert_eq!(
+            FusionMethod::parse("rrf", 30, 0.5),
+            Some(FusionMethod::Rrf { k: 30 })
+        );
+        assert_eq!(
+            FusionMethod::parse("Weighted", 60, 1.5),
+            Some(FusionMethod::Weighted { alpha: 1.0 })
+        );
+        assert_eq!(FusionMethod::parse("borda", 60, 0.5), None);
+    }
+
+    #[test]
+    fn test_weighted_fusion_alpha_extremes() {
+        let semantic = vec![(1, 0.9), (2, 0.5), (3, 0.1)];
+        let bm25 = vec![(3, 10.0), (2, 5.0), (1, 1.0)];
+
+        let semantic_only = weighted_score_fusion(&semantic, &bm25, 1.0);
+        assert_eq!(
+            semantic_only.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        let bm25_only = weighted_score_fusion(&semantic, &bm25, 0.0);
+        assert_eq!(
+            bm25_only.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_weighted_fusion_scores_in_range() {
+        let semantic = vec![(1, 0.8), (2, 0.4)];
+        let bm25 = vec![(2, 3.0), (4, 9.0)];
+        for (_, score) in weighted_score_fusion(&semantic, &bm25, 0.7) {
+            assert!((0.0..=1.0).contains(&score));
+        }
+    }
+
+    #[test]
+    fn test_weighted_fusion_preserves_magnitude_vs_rrf() {
+        // Chunk 1 is a near-perfect semantic match but absent from BM25;
+        // chunk 2 is mediocre in both lists. RRF favours chunk 2 (present in
+        // both lists) while a semantic-leaning blend keeps chunk 1 on top.
+        let semantic = vec![(1, 0.95), (2, 0.40), (3, 0.35)];
+        let bm25 = vec![(3, 4.0), (2, 3.9)];
+
+        let rrf = super::super::reciprocal_rank_fusion(
+            &[&[1, 2, 3], &[3, 2]],
+            &super::super::RrfConfig::new(60),
+        );
+        assert_ne!(rrf[0].0, 1);
+
+        let weighted = weighted_score_fusion(&semantic, &bm25, 0.7);
+        assert_eq!(weighted[0].0, 1);
+    }
+
+    #[test]
+    fn test_weighted_fusion_empty() {
+        assert!(weighted_score_fusion(&[], &[], 0.5).is_empty());
+    }
+}
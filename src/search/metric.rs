@@ -0,0 +1,120 @@
+//! Similarity metrics for semantic search.
+//!
+//! Every metric is expressed as a "higher is better" score so semantic
+//! results can be thresholded, sorted and fused the same way regardless of
+//! the metric in use.
+
+use crate::embedding::cosine_similarity;
+
+/// Metric used to compare a query embedding with stored chunk embeddings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SimilarityMetric {
+    /// Cosine similarity (-1.0 to 1.0). Ignores vector magnitude.
+    #[default]
+    Cosine,
+    /// Dot product. Equivalent to cosine for normalized embeddings, and
+    /// favours larger vectors otherwise.
+    Dot,
+    /// Euclidean (L2) distance, scored as `1 / (1 + distance)` so identical
+    /// vectors score 1.0 and the score approaches 0.0 as distance grows.
+    Euclidean,
+}
+
+impl SimilarityMetric {
+    /// Parses a metric name ("cosine", "dot" or "euclidean").
+    ///
+    /// Returns `None` for unknown names.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cosine" => Some(Self::Cosine),
+            "dot" | "dot-product" => Some(Self::Dot),
+            "euclidean" | "l2" => Some(Self::Euclidean),
+            _ => None,
+        }
+    }
+
+    /// Returns the metric name.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::Dot => "dot",
+            Self::Euclidean => "euclidean",
+        }
+    }
+
+    /// Scores the similarity of two vectors (higher is more similar).
+    ///
+    /// Returns 0.0 if the vectors have different lengths.
+    #[must_use]
+    pub fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() {
+            return 0.0;
+        }
+
+        match self {
+            Self::Cosine => cosine_similarity(a, b),
+            Self::Dot => a.iter().zip(b).map(|(x, y)| x * y).sum(),
+            Self::Euclidean => {
+                let distance = a
+                    .iter()
+                    .zip(b)
+                    .map(|(x, y)| (x - y) * (x - y))
+                    .sum::<f32>()
+                    .sqrt();
+                1.0 / (1.0 + distance)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_parse_and_name() {
+        for metric in [
+            SimilarityMetric::Cosine,
+            SimilarityMetric::Dot,
+            SimilarityMetric::Euclidean,
+        ] {
+            assert_eq!(SimilarityMetric::parse(metric.name()), Some(metric));
+        }
+        assert_eq!(
+            SimilarityMetric::parse("L2"),
+            Some(SimilarityMetric::Euclidean)
+        );
+        assert_eq!(SimilarityMetric::parse("manhattan"), None);
+        assert_eq!(SimilarityMetric::default(), SimilarityMetric::Cosine);
+    }
+
+    #[test]
+    fn test_metric_scores() {
+        let a = [3.0, 4.0];
+        assert!((SimilarityMetric::Cosine.score(&a, &a) - 1.0).abs() < 1e-6);
+        assert!((SimilarityMetric::Dot.score(&a, &a) - 25.0).abs() < 1e-6);
+        assert!((SimilarityMetric::Euclidean.score(&a, &a) - 1.0).abs() < 1e-6);
+
+        // Distance 5 from the origin scores 1 / 6
+        let origin = [0.0, 0.0];
+        assert!((SimilarityMetric::Euclidean.score(&a, &origin) - 1.0 / 6.0).abs() < 1e-6);
+        assert!(SimilarityMetric::Dot.score(&a, &[1.0]).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_cosine_and_dot_rank_differently() {
+        // `small` points the same way as the query; `large` is slightly off
+        // axis but much longer
+        let query = [1.0, 0.0];
+        let small = [0.5, 0.0];
+        let large = [3.0, 1.0];
+
+        let cosine = SimilarityMetric::Cosine;
+        assert!(cosine.score(&query, &small) > cosine.score(&query, &large));
+
+        let dot = SimilarityMetric::Dot;
+        assert!(dot.score(&query, &large) > dot.score(&query, &small));
+    }
+}
@@ -6,16 +6,31 @@
 //!
 //! - **Semantic Search**: Vector similarity using embeddings
 //! - **BM25 Search**: Full-text search using `SQLite` `FTS5`
-//! - **Hybrid Search**: Combines both using Reciprocal Rank Fusion
+//! - **Hybrid Search**: Combines both using Reciprocal Rank Fusion or weighted score fusion
+//! - **Query Operators**: `"quoted phrases"` and `-term` exclusions
+//! - **MMR Re-ranking**: Optional diversification of near-duplicate results
+//! - **Context Expansion**: Neighboring chunks around each hit
+//! - **Vector Index**: Optional `sqlite-vec` nearest-neighbor queries for cosine search (requires `vec` feature)
 //! - **HNSW Index**: Optional scalable approximate nearest neighbor search (requires `usearch-hnsw` feature)
 
+mod context;
+mod fusion;
 pub mod hnsw;
+mod metric;
+mod query;
 mod rrf;
 
+pub use context::{ContextChunk, ContextGroup, expand_context};
+pub use fusion::{DEFAULT_FUSION_ALPHA, FusionMethod, weighted_score_fusion};
 pub use hnsw::{HnswConfig, HnswIndex, HnswResult};
+pub use metric::SimilarityMetric;
+pub use query::ParsedQuery;
 pub use rrf::{RrfConfig, reciprocal_rank_fusion, weighted_rrf};
 
-use crate::embedding::{Embedder, cosine_similarity};
+use std::collections::{HashMap, HashSet};
+
+use crate::core::{Chunk, embedding_cache_key};
+use crate::embedding::{Embedder, cosine_similarity, default_embed_workers, embed_parallel};
 use crate::error::Result;
 use crate::storage::{SqliteStorage, Storage};
 
@@ -25,6 +40,13 @@ pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.3;
 /// Default number of results to return.
 pub const DEFAULT_TOP_K: usize = 10;
 
+/// Candidate pool multiplier used when re-ranking with MMR.
+pub const MMR_CANDIDATE_FACTOR: usize = 3;
+
+/// Amount the similarity threshold is lowered per relaxation step (see
+/// [`SearchConfig::min_results`]).
+pub const THRESHOLD_RELAXATION_STEP: f32 = 0.1;
+
 /// Search result with chunk ID and combined score.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -34,7 +56,7 @@ pub struct SearchResult {
     pub buffer_id: i64,
     /// Sequential index within the buffer (0-based, for temporal ordering).
     pub index: usize,
-    /// Combined RRF score (higher is better).
+    /// Combined fusion score (higher is better).
     pub score: f64,
     /// Semantic similarity score (if available).
     pub semantic_score: Option<f32>,
@@ -42,6 +64,10 @@ pub struct SearchResult {
     pub bm25_score: Option<f64>,
     /// Content preview (first N characters, if requested).
     pub content_preview: Option<String>,
+    /// Name of the buffer (if enriched, see [`enrich_results`]).
+    pub buffer_name: Option<String>,
+    /// Chunker-specific chunk metadata (if enriched and present).
+    pub metadata: Option<serde_json::Value>,
 }
 
 /// Configuration for hybrid search.
@@ -49,24 +75,41 @@ pub struct SearchResult {
 pub struct SearchConfig {
     /// Maximum number of results to return.
     pub top_k: usize,
-    /// Minimum similarity threshold for semantic results.
+    /// Number of ranked results to skip before returning `top_k`.
+    pub offset: usize,
+    /// Minimum similarity score for semantic results (in the units of
+    /// [`Self::metric`]).
     pub similarity_threshold: f32,
-    /// RRF k parameter (default 60).
+    /// RRF k parameter (default 60); mirrors `FusionMethod::Rrf { k }`.
     pub rrf_k: u32,
+    /// Method used to fuse semantic and BM25 results in hybrid mode.
+    pub fusion: FusionMethod,
+    /// Metric used to score semantic matches.
+    pub metric: SimilarityMetric,
     /// Whether to include semantic search.
     pub use_semantic: bool,
     /// Whether to include BM25 search.
     pub use_bm25: bool,
+    /// Restrict results to these buffers (empty = all buffers).
+    pub buffer_ids: Vec<i64>,
+    /// Lower the similarity threshold until the page holds at least this
+    /// many results (0 = never relax).
+    pub min_results: usize,
 }
 
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
             top_k: DEFAULT_TOP_K,
+            offset: 0,
             similarity_threshold: DEFAULT_SIMILARITY_THRESHOLD,
             rrf_k: 60,
+            fusion: FusionMethod::default(),
+            metric: SimilarityMetric::default(),
             use_semantic: true,
             use_bm25: true,
+            buffer_ids: Vec::new(),
+            min_results: 0,
         }
     }
 }
@@ -97,6 +140,8 @@ impl SearchResult {
                 semantic_score,
                 bm25_score,
                 content_preview: None,
+                buffer_name: None,
+                metadata: None,
             })
     }
 }
@@ -137,6 +182,112 @@ pub fn populate_previews(
     Ok(())
 }
 
+/// Adds buffer names and chunk metadata to search results.
+///
+/// The default results carry only IDs and scores; this fills
+/// [`SearchResult::buffer_name`] and [`SearchResult::metadata`] for
+/// human-readable output.
+///
+/// # Errors
+///
+/// Returns an error if a buffer or chunk lookup fails.
+pub fn enrich_results(storage: &SqliteStorage, results: &mut [SearchResult]) -> Result<()> {
+    let names: HashMap<i64, Option<String>> = storage.list_buffer_names()?.into_iter().collect();
+    for result in results.iter_mut() {
+        result.buffer_name = names.get(&result.buffer_id).cloned().flatten();
+        result.metadata = storage
+            .get_chunk(result.chunk_id)?
+            .and_then(|chunk| chunk.custom_metadata());
+    }
+    Ok(())
+}
+
+/// Re-ranks search results with Maximal Marginal Relevance (MMR).
+///
+/// Greedily selects results that balance relevance (the fused score,
+/// normalized to [0, 1]) against redundancy (maximum cosine similarity to
+/// any already-selected result): `lambda * relevance - (1 - lambda) * redundancy`.
+/// A `lambda` of 1.0 keeps the original order; lower values favour diversity.
+///
+/// If any result lacks a stored embedding, the original order is kept.
+///
+/// # Arguments
+///
+/// * `storage` - The storage backend.
+/// * `results` - Candidate results, ordered by relevance.
+/// * `lambda` - Relevance/diversity trade-off (0.0-1.0).
+/// * `top_k` - Maximum number of results to return.
+///
+/// # Errors
+///
+/// Returns an error if embedding retrieval fails.
+pub fn mmr_rerank(
+    storage: &SqliteStorage,
+    mut results: Vec<SearchResult>,
+    lambda: f32,
+    top_k: usize,
+) -> Result<Vec<SearchResult>> {
+    let mut embeddings = Vec::with_capacity(results.len());
+    for result in &results {
+        match storage.get_embedding(result.chunk_id)? {
+            Some(embedding) => embeddings.push(embedding),
+            None => {
+                results.truncate(top_k);
+                return Ok(results);
+            }
+        }
+    }
+
+    let lambda = f64::from(lambda.clamp(0.0, 1.0));
+    let (min, max) = results
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), r| {
+            (lo.min(r.score), hi.max(r.score))
+        });
+    let relevance: Vec<f64> = results
+        .iter()
+        .map(|r| {
+            if max - min > f64::EPSILON {
+                (r.score - min) / (max - min)
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    let mut selected: Vec<usize> = Vec::with_capacity(top_k.min(results.len()));
+    let mut remaining: Vec<usize> = (0..results.len()).collect();
+
+    while selected.len() < top_k && !remaining.is_empty() {
+        let best = remaining
+            .iter()
+            .enumerate()
+            .map(|(pos, &i)| {
+                let redundancy = selected
+                    .iter()
+                    .map(|&j| f64::from(cosine_similarity(&embeddings[i], &embeddings[j])))
+                    .fold(0.0, f64::max);
+                (
+                    pos,
+                    lambda.mul_add(relevance[i], -(1.0 - lambda) * redundancy),
+                )
+            })
+            // Prefer the higher-ranked candidate on ties
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+
+        match best {
+            Some((pos, _)) => selected.push(remaining.remove(pos)),
+            None => break,
+        }
+    }
+
+    let mut slots: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+    Ok(selected
+        .into_iter()
+        .filter_map(|i| slots.get_mut(i).and_then(Option::take))
+        .collect())
+}
+
 impl SearchConfig {
     /// Creates a new search config with default values.
     #[must_use]
@@ -151,6 +302,19 @@ impl SearchConfig {
         self
     }
 
+    /// Sets the number of ranked results to skip (for paging).
+    #[must_use]
+    pub const fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns the number of ranked results needed to fill the page
+    /// (`offset + top_k`).
+    const fn window(&self) -> usize {
+        self.offset.saturating_add(self.top_k)
+    }
+
     /// Sets the similarity threshold.
     #[must_use]
     pub const fn with_threshold(mut self, threshold: f32) -> Self {
@@ -159,9 +323,31 @@ impl SearchConfig {
     }
 
     /// Sets the RRF k parameter.
+    ///
+    /// Also updates the fusion method when it is RRF.
     #[must_use]
     pub const fn with_rrf_k(mut self, k: u32) -> Self {
         self.rrf_k = k;
+        if let FusionMethod::Rrf { k: rrf_k } = &mut self.fusion {
+            *rrf_k = k;
+        }
+        self
+    }
+
+    /// Sets the fusion method used in hybrid mode.
+    #[must_use]
+    pub const fn with_fusion(mut self, fusion: FusionMethod) -> Self {
+        if let FusionMethod::Rrf { k } = fusion {
+            self.rrf_k = k;
+        }
+        self.fusion = fusion;
+        self
+    }
+
+    /// Sets the similarity metric used for semantic search.
+    #[must_use]
+    pub const fn with_metric(mut self, metric: SimilarityMetric) -> Self {
+        self.metric = metric;
         self
     }
 
@@ -178,10 +364,113 @@ impl SearchConfig {
         self.use_bm25 = enabled;
         self
     }
+
+    /// Restricts search to a single buffer.
+    ///
+    /// Replaces any buffer set from [`Self::with_buffer_ids`].
+    #[must_use]
+    pub fn with_buffer_id(self, buffer_id: i64) -> Self {
+        self.with_buffer_ids(vec![buffer_id])
+    }
+
+    /// Restricts search to a set of buffers (empty = all buffers).
+    ///
+    /// Replaces any buffer set from [`Self::with_buffer_id`].
+    #[must_use]
+    pub fn with_buffer_ids(mut self, buffer_ids: Vec<i64>) -> Self {
+        self.buffer_ids = buffer_ids;
+        self
+    }
+
+    /// Sets the result count below which the similarity threshold is
+    /// relaxed (see [`search_with_relaxation`]).
+    #[must_use]
+    pub const fn with_min_results(mut self, min_results: usize) -> Self {
+        self.min_results = min_results;
+        self
+    }
+}
+
+/// Results of [`search_with_relaxation`].
+#[derive(Debug, Clone)]
+pub struct RelaxedSearch {
+    /// The search results.
+    pub results: Vec<SearchResult>,
+    /// Similarity threshold that produced `results`.
+    pub threshold: f32,
+    /// How far the threshold was lowered from the configured value.
+    pub relaxed_by: f32,
+}
+
+/// Performs [`hybrid_search`], lowering the similarity threshold when too
+/// few results come back.
+///
+/// While fewer than [`SearchConfig::min_results`] chunks match, the
+/// threshold is lowered by [`THRESHOLD_RELAXATION_STEP`] and the results are
+/// fused again, stopping once it reaches zero. The query is embedded and the
+/// semantic and BM25 candidates are collected once, at the lowest threshold
+/// relaxation may reach; each step only filters and re-fuses them. Matches
+/// are counted over the whole ranking before the page
+/// `[offset, offset + top_k)` is taken, so paging never triggers relaxation.
+/// The threshold only affects semantic matches, so BM25-only searches are
+/// never relaxed.
+///
+/// # Errors
+///
+/// Returns an error if search operations fail.
+pub fn search_with_relaxation(
+    storage: &SqliteStorage,
+    embedder: &dyn Embedder,
+    query: &str,
+    config: &SearchConfig,
+) -> Result<RelaxedSearch> {
+    let configured = config.similarity_threshold;
+    let floor = if config.use_semantic && config.min_results > 0 {
+        configured.min(0.0)
+    } else {
+        configured
+    };
+    let candidates = Candidates::collect(
+        storage,
+        embedder,
+        query,
+        &config.clone().with_threshold(floor),
+    )?;
+
+    let mut threshold = configured;
+    loop {
+        let ranked = candidates.rank(config, threshold);
+        if ranked.len() >= config.min_results || !config.use_semantic || threshold <= 0.0 {
+            let results = ranked
+                .into_iter()
+                .skip(config.offset)
+                .take(config.top_k)
+                .filter_map(|(chunk_id, score, semantic, bm25)| {
+                    SearchResult::from_chunk_id(storage, chunk_id, score, semantic, bm25)
+                })
+                .collect();
+            return Ok(RelaxedSearch {
+                results,
+                threshold,
+                relaxed_by: configured - threshold,
+            });
+        }
+        threshold = (threshold - THRESHOLD_RELAXATION_STEP).max(0.0);
+    }
 }
 
 /// Performs hybrid search combining semantic and BM25 results.
 ///
+/// The query is parsed with [`ParsedQuery`]: `"quoted phrases"` must match
+/// contiguously in BM25, and `-term` exclusions drop chunks containing the
+/// term from both result lists. Results are limited to
+/// [`SearchConfig::buffer_ids`] when it is non-empty.
+///
+/// Results are ranked after the similarity threshold is applied, and the
+/// page `[offset, offset + top_k)` of that ranking is returned. When
+/// [`SearchConfig::min_results`] is set, the threshold is relaxed as in
+/// [`search_with_relaxation`].
+///
 /// # Arguments
 ///
 /// * `storage` - The storage backend.
@@ -198,81 +487,151 @@ pub fn hybrid_search(
     query: &str,
     config: &SearchConfig,
 ) -> Result<Vec<SearchResult>> {
-    let mut semantic_results: Vec<(i64, f32)> = Vec::new();
-    let mut bm25_results: Vec<(i64, f64)> = Vec::new();
+    search_with_relaxation(storage, embedder, query, config).map(|search| search.results)
+}
 
-    // Semantic search
-    if config.use_semantic {
-        semantic_results = semantic_search(storage, embedder, query, config)?;
-    }
+/// A match before paging: chunk ID, combined score, and the semantic and
+/// BM25 scores it came from.
+type RankedMatch = (i64, f64, Option<f32>, Option<f64>);
 
-    // BM25 search
-    if config.use_bm25 {
-        bm25_results = storage.search_fts(query, config.top_k * 2)?;
-    }
+/// Semantic and BM25 candidates for one query, each sorted best first.
+struct Candidates {
+    semantic: Vec<(i64, f32)>,
+    bm25: Vec<(i64, f64)>,
+}
 
-    // If only one type of search is enabled, return those results directly
-    if !config.use_semantic {
-        return Ok(bm25_results
-            .into_iter()
-            .take(config.top_k)
-            .filter_map(|(chunk_id, score)| {
-                SearchResult::from_chunk_id(storage, chunk_id, score, None, Some(score))
-            })
-            .collect());
-    }
+impl Candidates {
+    /// Embeds the query and collects both candidate lists at the configured
+    /// threshold.
+    fn collect(
+        storage: &SqliteStorage,
+        embedder: &dyn Embedder,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Self> {
+        let parsed = ParsedQuery::parse(query);
+        let mut semantic = Vec::new();
+        let mut bm25 = Vec::new();
+
+        // Semantic search (embeds positive terms only; exclusions are filtered after)
+        if config.use_semantic && !parsed.is_empty() {
+            let allowed = if config.buffer_ids.is_empty() {
+                None
+            } else {
+                Some(
+                    storage
+                        .get_chunk_ids(&config.buffer_ids)?
+                        .into_iter()
+                        .collect::<HashSet<_>>(),
+                )
+            };
+            semantic = semantic_search(
+                storage,
+                embedder,
+                &parsed.semantic_text(),
+                config,
+                allowed.as_ref(),
+                &parsed,
+            )?;
+        }
 
-    if !config.use_bm25 {
-        return Ok(semantic_results
-            .into_iter()
-            .take(config.top_k)
-            .filter_map(|(chunk_id, score)| {
-                SearchResult::from_chunk_id(storage, chunk_id, f64::from(score), Some(score), None)
-            })
-            .collect());
-    }
+        // BM25 search (phrases and exclusions are enforced by FTS5)
+        if config.use_bm25
+            && let Some(fts_query) = parsed.to_fts_query()
+        {
+            bm25 = storage.search_fts_match(&fts_query, config.window() * 2, &config.buffer_ids)?;
+        }
 
-    // Combine using RRF
-    let rrf_config = RrfConfig::new(config.rrf_k);
+        Ok(Self { semantic, bm25 })
+    }
 
-    // Convert to ranked lists (already sorted by score descending)
-    let semantic_ranked: Vec<i64> = semantic_results.iter().map(|(id, _)| *id).collect();
-    let bm25_ranked: Vec<i64> = bm25_results.iter().map(|(id, _)| *id).collect();
+    /// Fuses the candidates whose semantic score reaches `threshold`,
+    /// returning the whole ranking without applying the page offset.
+    ///
+    /// The semantic list is sorted, so this matches collecting it at
+    /// `threshold` in the first place.
+    fn rank(&self, config: &SearchConfig, threshold: f32) -> Vec<RankedMatch> {
+        // If only one type of search is enabled, return those results directly
+        if !config.use_semantic {
+            return self
+                .bm25
+                .iter()
+                .map(|&(chunk_id, score)| (chunk_id, score, None, Some(score)))
+                .collect();
+        }
 
-    let fused = reciprocal_rank_fusion(&[&semantic_ranked, &bm25_ranked], &rrf_config);
+        let semantic_results: Vec<(i64, f32)> = self
+            .semantic
+            .iter()
+            .copied()
+            .filter(|(_, score)| *score >= threshold)
+            .collect();
+
+        if !config.use_bm25 {
+            return semantic_results
+                .into_iter()
+                .map(|(chunk_id, score)| (chunk_id, f64::from(score), Some(score), None))
+                .collect();
+        }
 
-    // Build result with original scores
-    let semantic_map: std::collections::HashMap<i64, f32> = semantic_results.into_iter().collect();
-    let bm25_map: std::collections::HashMap<i64, f64> = bm25_results.into_iter().collect();
+        let fused = match config.fusion {
+            FusionMethod::Rrf { k } => {
+                // Convert to ranked lists (already sorted by score descending)
+                let semantic_ranked: Vec<i64> =
+                    semantic_results.iter().map(|(id, _)| *id).collect();
+                let bm25_ranked: Vec<i64> = self.bm25.iter().map(|(id, _)| *id).collect();
+
+                reciprocal_rank_fusion(&[&semantic_ranked, &bm25_ranked], &RrfConfig::new(k))
+            }
+            FusionMethod::Weighted { alpha } => {
+                weighted_score_fusion(&semantic_results, &self.bm25, alpha)
+            }
+        };
 
-    let results: Vec<SearchResult> = fused
-        .into_iter()
-        .take(config.top_k)
-        .filter_map(|(chunk_id, rrf_score)| {
-            SearchResult::from_chunk_id(
-                storage,
-                chunk_id,
-                rrf_score,
-                semantic_map.get(&chunk_id).copied(),
-                bm25_map.get(&chunk_id).copied(),
-            )
-        })
-        .collect();
+        // Build result with original scores
+        let semantic_map: HashMap<i64, f32> = semantic_results.into_iter().collect();
+        let bm25_map: HashMap<i64, f64> = self.bm25.iter().copied().collect();
 
-    Ok(results)
+        fused
+            .into_iter()
+            .map(|(chunk_id, fused_score)| {
+                (
+                    chunk_id,
+                    fused_score,
+                    semantic_map.get(&chunk_id).copied(),
+                    bm25_map.get(&chunk_id).copied(),
+                )
+            })
+            .collect()
+    }
 }
 
 /// Performs semantic similarity search.
 ///
-/// Uses cosine similarity between query embedding and stored chunk embeddings.
+/// Scores stored chunk embeddings against the query embedding with
+/// [`SearchConfig::metric`]. Chunks matching an exclusion in `parsed` are
+/// dropped before the candidate list is truncated, so exclusions never
+/// shrink the result page. With the `vec` feature, cosine searches are
+/// answered from the `sqlite-vec` index where possible; otherwise every
+/// embedding is scanned.
 fn semantic_search(
     storage: &SqliteStorage,
     embedder: &dyn Embedder,
     query: &str,
     config: &SearchConfig,
+    allowed: Option<&HashSet<i64>>,
+    parsed: &ParsedQuery,
 ) -> Result<Vec<(i64, f32)>> {
     // Generate query embedding
     let query_embedding = embedder.embed(query)?;
+    let limit = config.window() * 2;
+
+    #[cfg(feature = "vec")]
+    if let Some(results) =
+        indexed_semantic_search(storage, &query_embedding, config, allowed, parsed, limit)?
+    {
+        return Ok(results);
+    }
 
     // Get all embeddings from storage
     let all_embeddings = storage.get_all_embeddings()?;
@@ -284,20 +643,106 @@ fn semantic_search(
     // Calculate similarities
     let mut similarities: Vec<(i64, f32)> = all_embeddings
         .iter()
+        .filter(|(chunk_id, embedding)| {
+            allowed.is_none_or(|ids| ids.contains(chunk_id)) && is_usable_embedding(embedding)
+        })
         .map(|(chunk_id, embedding)| {
-            let sim = cosine_similarity(&query_embedding, embedding);
+            let sim = config.metric.score(&query_embedding, embedding);
             (*chunk_id, sim)
         })
         .filter(|(_, sim)| *sim >= config.similarity_threshold)
         .collect();
 
-    // Sort by similarity descending
-    similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    // Sort by similarity descending, ties by chunk ID so results are stable
+    similarities.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    take_ranked(storage, similarities, limit, parsed)
+}
 
-    // Limit results
-    similarities.truncate(config.top_k * 2);
+/// Answers a cosine search from the `sqlite-vec` index.
+///
+/// Fetches nearest neighbors from the index, over-fetching so that
+/// trashed, disallowed, unusable and excluded chunks can be dropped, and
+/// rescores the survivors exactly. Returns `None` when the index cannot
+/// answer the query (another metric, a page too deep for one index query,
+/// or too few survivors while more neighbors exist), so the caller falls
+/// back to scanning every embedding. A missing index, or one built for
+/// another dimension, also falls back; searches never build it.
+#[cfg(feature = "vec")]
+fn indexed_semantic_search(
+    storage: &SqliteStorage,
+    query_embedding: &[f32],
+    config: &SearchConfig,
+    allowed: Option<&HashSet<i64>>,
+    parsed: &ParsedQuery,
+    limit: usize,
+) -> Result<Option<Vec<(i64, f32)>>> {
+    use crate::storage::sqlite::MAX_VECTOR_QUERY_K;
 
-    Ok(similarities)
+    if config.metric != SimilarityMetric::Cosine || limit > MAX_VECTOR_QUERY_K {
+        return Ok(None);
+    }
+    let k = limit.saturating_mul(4).clamp(64, MAX_VECTOR_QUERY_K);
+    let Some(ids) = storage.nearest_chunk_ids(query_embedding, k)? else {
+        return Ok(None);
+    };
+    // Neighbors come closest first, so one below the threshold ends the list
+    let mut exhausted = ids.len() < k;
+
+    let embeddings = storage.get_embeddings(&ids)?;
+    let mut similarities = Vec::with_capacity(ids.len());
+    for chunk_id in &ids {
+        let Some(embedding) = embeddings.get(chunk_id) else {
+            continue;
+        };
+        if !is_usable_embedding(embedding) {
+            continue;
+        }
+        let sim = config.metric.score(query_embedding, embedding);
+        if sim < config.similarity_threshold {
+            exhausted = true;
+            continue;
+        }
+        if allowed.is_none_or(|ids| ids.contains(chunk_id)) {
+            similarities.push((*chunk_id, sim));
+        }
+    }
+    similarities.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let kept = take_ranked(storage, similarities, limit, parsed)?;
+    Ok((kept.len() >= limit || exhausted).then_some(kept))
+}
+
+/// Returns the first `limit` of the ranked `similarities` that match no
+/// exclusion in `parsed`.
+fn take_ranked(
+    storage: &SqliteStorage,
+    mut similarities: Vec<(i64, f32)>,
+    limit: usize,
+    parsed: &ParsedQuery,
+) -> Result<Vec<(i64, f32)>> {
+    if parsed.excluded.is_empty() {
+        similarities.truncate(limit);
+        return Ok(similarities);
+    }
+
+    // Apply exclusions in ranked order, loading chunk text a batch at a time
+    let mut kept = Vec::with_capacity(limit);
+    for batch in similarities.chunks(limit.max(1)) {
+        let ids: Vec<i64> = batch.iter().map(|(chunk_id, _)| *chunk_id).collect();
+        let contents = storage.get_chunk_contents(&ids)?;
+        kept.extend(batch.iter().copied().filter(|(chunk_id, _)| {
+            contents
+                .get(chunk_id)
+                .is_some_and(|content| !parsed.excludes(content))
+        }));
+        if kept.len() >= limit {
+            break;
+        }
+    }
+    kept.truncate(limit);
+
+    Ok(kept)
 }
 
 /// Performs semantic-only search.
@@ -331,6 +776,8 @@ pub fn search_semantic(
 
 /// Performs BM25-only search.
 ///
+/// Supports `"quoted phrases"` and `-term` exclusions (see [`ParsedQuery`]).
+///
 /// # Arguments
 ///
 /// * `storage` - The storage backend.
@@ -345,7 +792,10 @@ pub fn search_bm25(
     query: &str,
     top_k: usize,
 ) -> Result<Vec<SearchResult>> {
-    let results = storage.search_fts(query, top_k)?;
+    let Some(fts_query) = ParsedQuery::parse(query).to_fts_query() else {
+        return Ok(Vec::new());
+    };
+    let results = storage.search_fts_match(&fts_query, top_k, &[])?;
 
     Ok(results
         .into_iter()
@@ -355,6 +805,76 @@ pub fn search_bm25(
         .collect())
 }
 
+/// Returns whether an embedding can be compared by similarity.
+///
+/// All-zero vectors (produced for empty or whitespace-only text by some
+/// embedders) and vectors with NaN or infinite values score every query the
+/// same, so they are neither stored nor searched.
+#[must_use]
+pub fn is_usable_embedding(embedding: &[f32]) -> bool {
+    embedding.iter().all(|v| v.is_finite()) && embedding.iter().any(|v| *v != 0.0)
+}
+
+/// Removes unusable embeddings from `batch`, recording their chunk IDs.
+fn drop_unusable(batch: Vec<(i64, Vec<f32>)>, invalid: &mut Vec<i64>) -> Vec<(i64, Vec<f32>)> {
+    let (usable, unusable): (Vec<_>, Vec<_>) = batch
+        .into_iter()
+        .partition(|(_, embedding)| is_usable_embedding(embedding));
+    invalid.extend(unusable.into_iter().map(|(id, _)| id));
+    usable
+}
+
+/// Embeds chunks, serving vectors from the content-hash cache when possible.
+///
+/// Cache misses are embedded with up to `workers` concurrent batches.
+/// Newly generated vectors are added to the cache unless they are unusable
+/// (see [`is_usable_embedding`]), and unusable cached vectors are never
+/// served. Returns the `(chunk_id, embedding)` batch and the IDs served
+/// from the cache.
+fn embed_with_cache(
+    storage: &mut SqliteStorage,
+    embedder: &dyn Embedder,
+    chunks: &[&Chunk],
+    workers: usize,
+) -> Result<(Vec<(i64, Vec<f32>)>, HashSet<i64>)> {
+    let model_name = embedder.model_name();
+    let mut batch = Vec::with_capacity(chunks.len());
+    let mut cached = HashSet::new();
+    let mut misses: Vec<(i64, &str, String)> = Vec::new();
+
+    for chunk in chunks {
+        let Some(id) = chunk.id else {
+            continue;
+        };
+        let hash = embedding_cache_key(&chunk.content);
+        if let Some(embedding) = storage
+            .get_cached_embedding(&hash, model_name)?
+            .filter(|e| e.len() == embedder.dimensions() && is_usable_embedding(e))
+        {
+            cached.insert(id);
+            batch.push((id, embedding));
+        } else {
+            misses.push((id, chunk.content.as_str(), hash));
+        }
+    }
+
+    if !misses.is_empty() {
+        let texts: Vec<&str> = misses.iter().map(|(_, text, _)| *text).collect();
+        let embeddings = embed_parallel(embedder, &texts, workers)?;
+
+        let mut entries = Vec::with_capacity(misses.len());
+        for ((id, _, hash), embedding) in misses.into_iter().zip(embeddings) {
+            if is_usable_embedding(&embedding) {
+                entries.push((hash, embedding.clone()));
+            }
+            batch.push((id, embedding));
+        }
+        storage.cache_embeddings_batch(&entries, model_name)?;
+    }
+
+    Ok((batch, cached))
+}
+
 /// Generates and stores embeddings for all chunks in a buffer.
 ///
 /// # Arguments
@@ -375,24 +895,35 @@ pub fn embed_buffer_chunks(
     embedder: &dyn Embedder,
     buffer_id: i64,
 ) -> Result<usize> {
+    embed_buffer_chunks_with_workers(storage, embedder, buffer_id, default_embed_workers())
+}
+
+/// Generates and stores embeddings for all chunks in a buffer using up to
+/// `workers` concurrent embedding batches.
+///
+/// Produces the same vectors as [`embed_buffer_chunks`]; `workers` only
+/// bounds concurrency (1 embeds sequentially).
+///
+/// # Errors
+///
+/// Returns an error if embedding generation or storage fails.
+pub fn embed_buffer_chunks_with_workers(
+    storage: &mut SqliteStorage,
+    embedder: &dyn Embedder,
+    buffer_id: i64,
+    workers: usize,
+) -> Result<usize> {
+    ensure_embedding_dimensions(storage, embedder, DimensionMismatch::Reject)?;
     let chunks = storage.get_chunks(buffer_id)?;
 
     if chunks.is_empty() {
         return Ok(0);
     }
 
-    // Collect chunk texts for batch embedding
-    let texts: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
-
-    // Generate embeddings in batch
-    let embeddings = embedder.embed_batch(&texts)?;
-
-    // Prepare batch for storage
-    let batch: Vec<(i64, Vec<f32>)> = chunks
-        .iter()
-        .zip(embeddings)
-        .filter_map(|(chunk, embedding)| chunk.id.map(|id| (id, embedding)))
-        .collect();
+    // Generate embeddings in batch, reusing cached vectors for known content
+    let chunk_refs: Vec<&Chunk> = chunks.iter().collect();
+    let (batch, _) = embed_with_cache(storage, embedder, &chunk_refs, workers)?;
+    let batch = drop_unusable(batch, &mut Vec::new());
 
     let count = batch.len();
 
@@ -402,6 +933,101 @@ pub fn embed_buffer_chunks(
     Ok(count)
 }
 
+/// Metadata key recording the embedding dimension used by the database.
+pub const EMBEDDING_DIMENSIONS_KEY: &str = "embedding_dimensions";
+
+/// Metadata key recording the model that produced the database's embeddings.
+pub const EMBEDDING_MODEL_KEY: &str = "embedding_model";
+
+/// How [`ensure_embedding_dimensions`] handles stored embeddings whose
+/// dimension differs from the embedder's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionMismatch {
+    /// Return an error.
+    Reject,
+    /// Delete the mismatched embeddings of one buffer. Mismatched
+    /// embeddings in any other buffer are still an error.
+    ReplaceBuffer(i64),
+    /// Delete the mismatched embeddings of every buffer.
+    ReplaceAll,
+}
+
+/// Ensures `embedder` matches the embedding dimension used by the database.
+///
+/// The dimension and model are recorded in storage metadata on first use.
+/// Mixing dimensions would make similarity scores meaningless, so stored
+/// embeddings with a different dimension are handled according to
+/// `mismatch`: rejected, deleted for one buffer, or deleted everywhere so
+/// they can be regenerated with the new embedder.
+///
+/// Returns the number of embeddings deleted.
+///
+/// # Errors
+///
+/// Returns an error if a mismatch is rejected by `mismatch`, or if storage
+/// access fails.
+pub fn ensure_embedding_dimensions(
+    storage: &mut SqliteStorage,
+    embedder: &dyn Embedder,
+    mismatch: DimensionMismatch,
+) -> Result<usize> {
+    let dimensions = embedder.dimensions();
+    let model = embedder.model_name();
+
+    let existing = storage
+        .embedding_dimensions()?
+        .into_iter()
+        .find(|&d| d != dimensions);
+    let mut deleted = 0;
+    if let Some(existing) = existing {
+        match mismatch {
+            DimensionMismatch::Reject => {
+                return Err(crate::Error::InvalidState {
+                    message: format!(
+                        "embedder '{model}' produces {dimensions}-dimensional vectors, but this \
+                         database has {existing}-dimensional embeddings. Use --force to re-embed \
+                         with the new model."
+                    ),
+                });
+            }
+            DimensionMismatch::ReplaceBuffer(buffer_id) => {
+                let others: Vec<String> = storage
+                    .buffers_with_other_dimensions(dimensions)?
+                    .into_iter()
+                    .filter(|&id| id != buffer_id)
+                    .map(|id| id.to_string())
+                    .collect();
+                if !others.is_empty() {
+                    return Err(crate::Error::InvalidState {
+                        message: format!(
+                            "embedder '{model}' produces {dimensions}-dimensional vectors, but \
+                             other buffers (IDs: {}) have {existing}-dimensional embeddings. Use \
+                             --discard-other-dimensions to delete them in every buffer, then \
+                             re-embed those buffers.",
+                            others.join(", ")
+                        ),
+                    });
+                }
+                deleted = storage
+                    .delete_buffer_embeddings_with_other_dimensions(buffer_id, dimensions)?;
+            }
+            DimensionMismatch::ReplaceAll => {
+                deleted = storage.delete_embeddings_with_other_dimensions(dimensions)?;
+            }
+        }
+    }
+
+    let recorded = storage.get_metadata(EMBEDDING_DIMENSIONS_KEY)?;
+    if recorded.as_deref() != Some(dimensions.to_string().as_str()) {
+        storage.set_metadata(EMBEDDING_DIMENSIONS_KEY, &dimensions.to_string())?;
+    }
+    if storage.get_metadata(EMBEDDING_MODEL_KEY)?.as_deref() != Some(model) {
+        storage.set_metadata(EMBEDDING_MODEL_KEY, model)?;
+    }
+
+    Ok(deleted)
+}
+
 /// Checks if a buffer has all chunks embedded.
 ///
 /// # Errors
@@ -499,17 +1125,22 @@ pub struct IncrementalEmbedResult {
     pub skipped_count: usize,
     /// Number of embeddings that were replaced (different model).
     pub replaced_count: usize,
+    /// Number of embeddings served from the content-hash cache.
+    pub cached_count: usize,
     /// Total chunks in the buffer.
     pub total_chunks: usize,
     /// Model name used for embedding.
     pub model_name: String,
+    /// Chunks whose embedding was all zeros or not finite and was not
+    /// stored (see [`is_usable_embedding`]).
+    pub invalid_chunk_ids: Vec<i64>,
 }
 
 impl IncrementalEmbedResult {
     /// Returns true if any embeddings were created or updated.
     #[must_use]
     pub const fn had_changes(&self) -> bool {
-        self.embedded_count > 0 || self.replaced_count > 0
+        self.embedded_count > 0 || self.replaced_count > 0 || self.cached_count > 0
     }
 
     /// Returns the percentage of chunks now embedded.
@@ -519,18 +1150,29 @@ impl IncrementalEmbedResult {
         if self.total_chunks == 0 {
             100.0
         } else {
-            let completed = self.embedded_count + self.skipped_count + self.replaced_count;
+            let completed =
+                self.embedded_count + self.skipped_count + self.replaced_count + self.cached_count;
             (completed as f64 / self.total_chunks as f64) * 100.0
         }
     }
 }
 
+/// Number of chunks embedded and committed together by
+/// [`embed_buffer_chunks_incremental`].
+///
+/// Each batch is stored before the next one starts, so an interrupted run
+/// keeps its completed batches and a re-run only embeds the remainder.
+pub const EMBED_BATCH_SIZE: usize = 64;
+
 /// Incrementally embeds chunks in a buffer.
 ///
 /// Only embeds chunks that:
 /// - Have no embedding, OR
 /// - Have an embedding from a different model (if `force_reembed` is true)
 ///
+/// Chunks whose content was already embedded with the same model (in any
+/// buffer) reuse the cached vector instead of calling the embedder.
+///
 /// This is more efficient than `embed_buffer_chunks` for large buffers
 /// where only a few chunks have changed.
 ///
@@ -554,6 +1196,42 @@ pub fn embed_buffer_chunks_incremental(
     buffer_id: i64,
     force_reembed: bool,
 ) -> Result<IncrementalEmbedResult> {
+    embed_buffer_chunks_incremental_with_progress(
+        storage,
+        embedder,
+        buffer_id,
+        force_reembed,
+        &mut |_, _| {},
+    )
+}
+
+/// Incrementally embeds chunks in a buffer, reporting progress.
+///
+/// Works like [`embed_buffer_chunks_incremental`], committing embeddings
+/// every [`EMBED_BATCH_SIZE`] chunks. After each batch is stored,
+/// `progress` is called with the number of chunks processed so far and
+/// the number that needed embedding.
+///
+/// # Errors
+///
+/// Returns an error if embedding generation or storage fails, or if the
+/// embedder's dimension differs from existing embeddings and either
+/// `force_reembed` is false or the mismatch extends to other buffers (see
+/// [`ensure_embedding_dimensions`]). Batches stored before a failure are
+/// kept.
+pub fn embed_buffer_chunks_incremental_with_progress(
+    storage: &mut SqliteStorage,
+    embedder: &dyn Embedder,
+    buffer_id: i64,
+    force_reembed: bool,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<IncrementalEmbedResult> {
+    let mismatch = if force_reembed {
+        DimensionMismatch::ReplaceBuffer(buffer_id)
+    } else {
+        DimensionMismatch::Reject
+    };
+    ensure_embedding_dimensions(storage, embedder, mismatch)?;
     let current_model = embedder.model_name();
     let stats = storage.get_embedding_stats(buffer_id)?;
     let total_chunks = stats.total_chunks;
@@ -572,8 +1250,10 @@ pub fn embed_buffer_chunks_incremental(
             embedded_count: 0,
             skipped_count: total_chunks,
             replaced_count: 0,
+            cached_count: 0,
             total_chunks,
             model_name: current_model.to_string(),
+            invalid_chunk_ids: Vec::new(),
         });
     }
 
@@ -584,39 +1264,45 @@ pub fn embed_buffer_chunks_incremental(
         .filter(|c| c.id.is_some_and(|id| chunk_ids_to_embed.contains(&id)))
         .collect();
 
-    // Count how many are replacements (had embeddings before)
+    let pending = chunks_to_embed.len();
+    let mut done = 0;
+    let mut stored_count = 0;
     let mut replaced_count = 0;
-    for chunk in &chunks_to_embed {
-        if let Some(id) = chunk.id
-            && storage.has_embedding(id)?
-        {
-            replaced_count += 1;
+    let mut cached_count = 0;
+    let mut invalid_chunk_ids = Vec::new();
+
+    for chunks in chunks_to_embed.chunks(EMBED_BATCH_SIZE) {
+        // Generate embeddings, reusing cached vectors for known content
+        let (batch, cached) = embed_with_cache(storage, embedder, chunks, default_embed_workers())?;
+        let batch = drop_unusable(batch, &mut invalid_chunk_ids);
+
+        // Count how many generated embeddings are replacements (had embeddings before)
+        for (id, _) in &batch {
+            if !cached.contains(id) && storage.has_embedding(*id)? {
+                replaced_count += 1;
+            }
         }
-    }
 
-    // Generate embeddings
-    let texts: Vec<&str> = chunks_to_embed.iter().map(|c| c.content.as_str()).collect();
-    let embeddings = embedder.embed_batch(&texts)?;
+        // Commit this batch before starting the next
+        storage.store_embeddings_batch(&batch, Some(current_model))?;
+        stored_count += batch.len();
+        cached_count += batch.iter().filter(|(id, _)| cached.contains(id)).count();
 
-    // Store embeddings
-    let batch: Vec<(i64, Vec<f32>)> = chunks_to_embed
-        .iter()
-        .zip(embeddings)
-        .filter_map(|(chunk, embedding)| chunk.id.map(|id| (id, embedding)))
-        .collect();
-
-    let embedded_count = batch.len();
-    storage.store_embeddings_batch(&batch, Some(current_model))?;
+        done += chunks.len();
+        progress(done, pending);
+    }
 
-    let new_embeddings = embedded_count - replaced_count;
-    let skipped_count = total_chunks - embedded_count;
+    let new_embeddings = stored_count - replaced_count - cached_count;
+    let skipped_count = total_chunks - stored_count - invalid_chunk_ids.len();
 
     Ok(IncrementalEmbedResult {
         embedded_count: new_embeddings,
         skipped_count,
         replaced_count,
+        cached_count,
         total_chunks,
         model_name: current_model.to_string(),
+        invalid_chunk_ids,
     })
 }
 
@@ -707,6 +1393,147 @@ mod tests {
         assert!(results[0].semantic_score.is_none());
     }
 
+    #[test]
+    fn test_search_bm25_phrase() {
+        let storage = setup_storage_with_chunks();
+
+        // Contiguous phrase matches
+        let results = search_bm25(&storage, "\"lazy dog\"", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].index, 0);
+
+        // Same words out of order do not
+        let results = search_bm25(&storage, "\"dog lazy\"", 10).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_bm25_exclusion() {
+        let storage = setup_storage_with_chunks();
+
+        let results = search_bm25(&storage, "fox rust", 10).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results = search_bm25(&storage, "fox rust -lazy", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].index, 2);
+    }
+
+    #[test]
+    fn test_hybrid_search_exclusion_filters_semantic() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, 1).unwrap();
+
+        let config = SearchConfig::new().with_threshold(0.0).with_bm25(false);
+        let results = hybrid_search(&storage, &embedder, "programming -rust", &config).unwrap();
+        assert!(results.iter().all(|r| r.index != 2));
+    }
+
+    #[test]
+    fn test_exclusions_match_whole_words_in_both_modes() {
+        let mut storage = setup_storage();
+        let buffer = Buffer::from_named("pets.txt".to_string(), String::new());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let chunks = vec![
+            Chunk::new(buffer_id, "The fox chased the cat".to_string(), 0..22, 0),
+            Chunk::new(
+                buffer_id,
+                "A fox in a category of its own".to_string(),
+                22..52,
+                1,
+            ),
+        ];
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, buffer_id).unwrap();
+
+        let semantic = SearchConfig::new().with_threshold(-1.0).with_bm25(false);
+        let bm25 = SearchConfig::new().with_semantic(false);
+        for config in [semantic, bm25] {
+            let results = hybrid_search(&storage, &embedder, "fox -cat", &config).unwrap();
+            let indices: Vec<usize> = results.iter().map(|r| r.index).collect();
+            assert_eq!(indices, vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_semantic_exclusions_apply_before_truncation() {
+        let mut storage = setup_storage();
+        let buffer = Buffer::from_named("notes.txt".to_string(), String::new());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let chunks: Vec<Chunk> = (0..6)
+            .map(|i| {
+                let content = if i < 5 {
+                    "legacy parsing notes"
+                } else {
+                    "parsing notes"
+                };
+                Chunk::new(buffer_id, content.to_string(), i * 30..i * 30 + 20, i)
+            })
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+
+        // Identical embeddings tie every chunk, so the excluded ones rank first
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let embedding = embedder.embed("parsing notes").unwrap();
+        let chunk_ids: Vec<i64> = storage
+            .get_chunks(buffer_id)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+        for id in &chunk_ids {
+            storage.store_embedding(*id, &embedding, None).unwrap();
+        }
+
+        let config = SearchConfig::new()
+            .with_threshold(-1.0)
+            .with_bm25(false)
+            .with_top_k(2);
+        let results = hybrid_search(&storage, &embedder, "parsing -legacy", &config).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, chunk_ids[5]);
+    }
+
+    #[test]
+    fn test_hybrid_search_buffer_ids() {
+        let mut storage = setup_storage_with_chunks();
+        let buffer = Buffer::from_named("other.txt".to_string(), String::new());
+        let other_id = storage.add_buffer(&buffer).unwrap();
+        let chunks = vec![Chunk::new(
+            other_id,
+            "Another fox in a different buffer".to_string(),
+            0..34,
+            0,
+        )];
+        storage.add_chunks(other_id, &chunks).unwrap();
+
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, 1).unwrap();
+        embed_buffer_chunks(&mut storage, &embedder, other_id).unwrap();
+
+        // Empty set searches everything
+        let config = SearchConfig::new().with_threshold(0.0).with_semantic(false);
+        let results = hybrid_search(&storage, &embedder, "fox", &config).unwrap();
+        assert_eq!(results.len(), 2);
+
+        // BM25 restricted to the other buffer
+        let config = config.with_buffer_ids(vec![other_id]);
+        let results = hybrid_search(&storage, &embedder, "fox", &config).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].buffer_id, other_id);
+
+        // Semantic restricted to buffer 1
+        let config = SearchConfig::new()
+            .with_threshold(0.0)
+            .with_bm25(false)
+            .with_buffer_id(1);
+        let results = hybrid_search(&storage, &embedder, "fox", &config).unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.buffer_id == 1));
+    }
+
     #[test]
     fn test_search_bm25_no_results() {
         let storage = setup_storage_with_chunks();
@@ -726,6 +1553,35 @@ mod tests {
         assert_eq!(count, 3); // We created 3 chunks
     }
 
+    #[test]
+    fn test_embed_buffer_chunks_parallel_matches_sequential() {
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let mut stored = Vec::new();
+        for workers in [1, 4] {
+            let mut storage = setup_storage();
+            let buffer = Buffer::from_named("big.txt".to_string(), String::new());
+            let buffer_id = storage.add_buffer(&buffer).unwrap();
+            let chunks: Vec<Chunk> = (0..100)
+                .map(|i| Chunk::new(buffer_id, format!("chunk number {i}"), i..i + 1, i))
+                .collect();
+            storage.add_chunks(buffer_id, &chunks).unwrap();
+
+            let count =
+                embed_buffer_chunks_with_workers(&mut storage, &embedder, buffer_id, workers)
+                    .unwrap();
+            assert_eq!(count, 100);
+
+            let vectors: Vec<Vec<f32>> = storage
+                .get_chunks(buffer_id)
+                .unwrap()
+                .iter()
+                .map(|c| storage.get_embedding(c.id.unwrap()).unwrap().unwrap())
+                .collect();
+            stored.push(vectors);
+        }
+        assert_eq!(stored[0], stored[1]);
+    }
+
     #[test]
     fn test_embed_buffer_chunks_empty() {
         let mut storage = setup_storage();
@@ -819,6 +1675,286 @@ mod tests {
         assert!(!results.is_empty());
     }
 
+    #[test]
+    fn test_hybrid_search_offset_pages() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, 1).unwrap();
+
+        for semantic_only in [true, false] {
+            let base = SearchConfig::new()
+                .with_threshold(-1.0)
+                .with_bm25(!semantic_only);
+            let ids = |config: &SearchConfig| -> Vec<i64> {
+                hybrid_search(&storage, &embedder, "the programming language", config)
+                    .unwrap()
+                    .iter()
+                    .map(|r| r.chunk_id)
+                    .collect()
+            };
+
+            let all = ids(&base);
+            assert_eq!(all.len(), 3);
+
+            let first = ids(&base.clone().with_top_k(2));
+            let second = ids(&base.clone().with_top_k(2).with_offset(2));
+            assert_eq!(first, all[..2]);
+            assert_eq!(second, all[2..]);
+            assert!(ids(&base.clone().with_offset(3)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_hybrid_search_breaks_ties_by_chunk_id() {
+        let mut storage = setup_storage();
+        let buffer = Buffer::from_named("dup.txt".to_string(), String::new());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let text = "duplicate paragraph about parsing";
+        let chunks: Vec<Chunk> = (0..4)
+            .map(|i| Chunk::new(buffer_id, text.to_string(), i * 40..i * 40 + 33, i))
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+
+        // Store embeddings newest chunk first so storage order disagrees
+        // with ID order
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let mut chunk_ids: Vec<i64> = storage
+            .get_chunks(buffer_id)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+        for id in chunk_ids.iter().rev() {
+            let embedding = embedder.embed(text).unwrap();
+            storage.store_embedding(*id, &embedding, None).unwrap();
+        }
+        chunk_ids.sort_unstable();
+
+        for (semantic, bm25) in [(true, false), (false, true), (true, true)] {
+            let config = SearchConfig::new()
+                .with_threshold(-1.0)
+                .with_semantic(semantic)
+                .with_bm25(bm25);
+            for _ in 0..5 {
+                let ids: Vec<i64> = hybrid_search(&storage, &embedder, "parsing", &config)
+                    .unwrap()
+                    .iter()
+                    .map(|r| r.chunk_id)
+                    .collect();
+                assert_eq!(ids, chunk_ids, "semantic={semantic} bm25={bm25}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_relaxes_threshold_to_min_results() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, 1).unwrap();
+
+        // No similarity reaches 1.5, so only relaxation can find the exact match
+        let query = "Rust is a systems programming language";
+        let strict = SearchConfig::new().with_threshold(1.5).with_bm25(false);
+        let search = search_with_relaxation(&storage, &embedder, query, &strict).unwrap();
+        assert!(search.results.is_empty());
+        assert!(search.relaxed_by.abs() < f32::EPSILON);
+
+        let relaxed = strict.clone().with_min_results(1);
+        let search = search_with_relaxation(&storage, &embedder, query, &relaxed).unwrap();
+        assert!(!search.results.is_empty());
+        assert!(search.threshold < 1.5);
+        assert!(search.threshold >= 0.0);
+        assert!((search.relaxed_by - (1.5 - search.threshold)).abs() < 1e-6);
+        assert!(
+            search
+                .results
+                .iter()
+                .all(|r| r.semantic_score >= Some(search.threshold))
+        );
+
+        // hybrid_search applies the same relaxation
+        let results = hybrid_search(&storage, &embedder, query, &relaxed).unwrap();
+        assert_eq!(results.len(), search.results.len());
+
+        // An empty later page is not a reason to relax
+        let paged = SearchConfig::new()
+            .with_threshold(0.99)
+            .with_bm25(false)
+            .with_min_results(1)
+            .with_offset(1);
+        let search = search_with_relaxation(&storage, &embedder, query, &paged).unwrap();
+        assert!(search.results.is_empty());
+        assert!((search.threshold - 0.99).abs() < f32::EPSILON);
+        assert!(search.relaxed_by.abs() < f32::EPSILON);
+    }
+
+    /// Embedder that counts the texts it embeds.
+    struct CountingEmbedder {
+        inner: FallbackEmbedder,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Embedder for CountingEmbedder {
+        fn dimensions(&self) -> usize {
+            self.inner.dimensions()
+        }
+
+        fn model_name(&self) -> &'static str {
+            self.inner.model_name()
+        }
+
+        fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.inner.embed(text)
+        }
+    }
+
+    #[test]
+    fn test_search_relaxation_embeds_query_once() {
+        let mut storage = setup_storage_with_chunks();
+        let inner = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &inner, 1).unwrap();
+        let embedder = CountingEmbedder {
+            inner,
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+
+        let query = "Rust is a systems programming language";
+        let config = SearchConfig::new()
+            .with_threshold(1.5)
+            .with_bm25(false)
+            .with_min_results(1);
+        let search = search_with_relaxation(&storage, &embedder, query, &config).unwrap();
+        assert!(search.relaxed_by > THRESHOLD_RELAXATION_STEP);
+        assert_eq!(embedder.calls.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        // Relaxing over cached scores ranks as a search at that threshold
+        let direct = hybrid_search(
+            &storage,
+            &embedder,
+            query,
+            &SearchConfig::new()
+                .with_threshold(search.threshold)
+                .with_bm25(false),
+        )
+        .unwrap();
+        let ids = |results: &[SearchResult]| results.iter().map(|r| r.chunk_id).collect::<Vec<_>>();
+        assert_eq!(ids(&search.results), ids(&direct));
+    }
+
+    #[test]
+    fn test_search_config_fusion() {
+        let config = SearchConfig::new();
+        assert_eq!(config.fusion, FusionMethod::Rrf { k: 60 });
+
+        let config = SearchConfig::new().with_rrf_k(30);
+        assert_eq!(config.fusion, FusionMethod::Rrf { k: 30 });
+
+        let config = SearchConfig::new().with_fusion(FusionMethod::Weighted { alpha: 0.7 });
+        assert_eq!(config.fusion, FusionMethod::Weighted { alpha: 0.7 });
+        assert_eq!(config.rrf_k, 60);
+    }
+
+    #[test]
+    fn test_hybrid_search_weighted_fusion() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, 1).unwrap();
+
+        let config = SearchConfig::new()
+            .with_threshold(0.0)
+            .with_fusion(FusionMethod::Weighted { alpha: 0.7 });
+
+        let results = hybrid_search(&storage, &embedder, "programming", &config).unwrap();
+        assert!(!results.is_empty());
+        for pair in results.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+        for result in &results {
+            assert!((0.0..=1.0).contains(&result.score));
+        }
+    }
+
+    #[test]
+    fn test_mmr_rerank_skips_duplicates() {
+        let mut storage = setup_storage();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named("dup.txt".to_string(), String::new()))
+            .unwrap();
+
+        let texts = [
+            "The quick brown fox jumps over the lazy dog",
+            "The quick brown fox jumps over the lazy dog",
+            "Rust is a systems programming language",
+            "Machine learning is a subset of artificial intelligence",
+        ];
+        let chunks: Vec<Chunk> = texts
+            .iter()
+            .enumerate()
+            .map(|(i, t)| Chunk::new(buffer_id, (*t).to_string(), i * 100..i * 100 + t.len(), i))
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        embed_buffer_chunks(&mut storage, &embedder, buffer_id).unwrap();
+
+        let stored = storage.get_chunks(buffer_id).unwrap();
+        let results: Vec<SearchResult> = stored
+            .iter()
+            .zip([1.0, 0.99, 0.5, 0.4])
+            .map(|(chunk, score)| SearchResult {
+                chunk_id: chunk.id.unwrap(),
+                buffer_id,
+                index: chunk.index,
+                score,
+                semantic_score: None,
+                bm25_score: None,
+                content_preview: None,
+                buffer_name: None,
+                metadata: None,
+            })
+            .collect();
+        let duplicate_ids = [stored[0].id.unwrap(), stored[1].id.unwrap()];
+
+        // Without MMR both duplicates are in the top 3
+        let top3: Vec<i64> = results.iter().take(3).map(|r| r.chunk_id).collect();
+        assert!(duplicate_ids.iter().all(|id| top3.contains(id)));
+
+        let reranked = mmr_rerank(&storage, results, 0.3, 3).unwrap();
+        assert_eq!(reranked.len(), 3);
+        assert_eq!(reranked[0].chunk_id, duplicate_ids[0]);
+        let both = duplicate_ids
+            .iter()
+            .all(|id| reranked.iter().any(|r| r.chunk_id == *id));
+        assert!(!both);
+    }
+
+    #[test]
+    fn test_mmr_rerank_without_embeddings_keeps_order() {
+        let storage = setup_storage_with_chunks();
+        let results: Vec<SearchResult> = storage
+            .get_chunks(1)
+            .unwrap()
+            .iter()
+            .map(|chunk| SearchResult {
+                chunk_id: chunk.id.unwrap(),
+                buffer_id: 1,
+                index: chunk.index,
+                score: 1.0,
+                semantic_score: None,
+                bm25_score: None,
+                content_preview: None,
+                buffer_name: None,
+                metadata: None,
+            })
+            .collect();
+        let original: Vec<i64> = results.iter().map(|r| r.chunk_id).collect();
+
+        let reranked = mmr_rerank(&storage, results, 0.5, 2).unwrap();
+        let ids: Vec<i64> = reranked.iter().map(|r| r.chunk_id).collect();
+        assert_eq!(ids, original[..2]);
+    }
+
     #[test]
     fn test_search_semantic() {
         let mut storage = setup_storage_with_chunks();
@@ -835,6 +1971,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_semantic_top_k_exact() {
+        let mut storage = setup_storage();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named("topk.txt".to_string(), String::new()))
+            .unwrap();
+        let chunks: Vec<Chunk> = (0..20)
+            .map(|i| {
+                let content = format!("document number {i} about topic {}", i % 4);
+                Chunk::new(buffer_id, content, i * 40..i * 40 + 35, i)
+            })
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks(&mut storage, &embedder, buffer_id).unwrap();
+
+        let query = "document number 7 about topic 3";
+        let results = search_semantic(&storage, &embedder, query, 5, 0.0).unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].index, 7);
+
+        // Scores match an exhaustive scan, in descending order
+        let query_embedding = embedder.embed(query).unwrap();
+        let mut expected: Vec<(i64, f32)> = storage
+            .get_all_embeddings()
+            .unwrap()
+            .iter()
+            .map(|(id, e)| (*id, cosine_similarity(&query_embedding, e)))
+            .collect();
+        expected.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (result, (_, sim)) in results.iter().zip(&expected) {
+            let score = result.semantic_score.unwrap();
+            assert!((score - sim).abs() < 1e-6);
+        }
+    }
+
+    /// Embedder that returns the same 2-dimensional vector for every text.
+    struct FixedEmbedder([f32; 2]);
+
+    impl Embedder for FixedEmbedder {
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        fn model_name(&self) -> &'static str {
+            "fixed-test"
+        }
+
+        fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(self.0.to_vec())
+        }
+    }
+
+    #[test]
+    fn test_search_metric_changes_ranking() {
+        let mut storage = setup_storage_with_chunks();
+        let ids: Vec<i64> = storage
+            .get_chunks(1)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+
+        // Non-normalized vectors: ids[0] is aligned with the query but
+        // short, ids[1] is off axis but long, ids[2] is far away
+        let vectors = vec![
+            (ids[0], vec![0.5, 0.0]),
+            (ids[1], vec![3.0, 1.0]),
+            (ids[2], vec![-2.0, 2.0]),
+        ];
+        storage
+            .store_embeddings_batch(&vectors, Some("fixed-test"))
+            .unwrap();
+        let embedder = FixedEmbedder([1.0, 0.0]);
+
+        let top = |metric: SimilarityMetric| {
+            let config = SearchConfig::new()
+                .with_threshold(f32::MIN)
+                .with_bm25(false)
+                .with_metric(metric);
+            let results = hybrid_search(&storage, &embedder, "query", &config).unwrap();
+            results.iter().map(|r| r.chunk_id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(top(SimilarityMetric::Cosine), vec![ids[0], ids[1], ids[2]]);
+        assert_eq!(top(SimilarityMetric::Dot), vec![ids[1], ids[0], ids[2]]);
+        assert_eq!(
+            top(SimilarityMetric::Euclidean),
+            vec![ids[0], ids[1], ids[2]]
+        );
+    }
+
     #[test]
     fn test_search_semantic_empty_embeddings() {
         let storage = setup_storage_with_chunks();
@@ -882,14 +2111,255 @@ mod tests {
         assert!(!result.had_changes());
     }
 
+    #[test]
+    fn test_incremental_embed_reuses_cache_for_reloaded_content() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks_incremental(&mut storage, &embedder, 1, false).unwrap();
+
+        // Reload the same content into a second buffer
+        let buffer = Buffer::from_named("copy.txt".to_string(), String::new());
+        let copy_id = storage.add_buffer(&buffer).unwrap();
+        let copies: Vec<Chunk> = storage
+            .get_chunks(1)
+            .unwrap()
+            .iter()
+            .map(|c| Chunk::new(copy_id, c.content.clone(), c.byte_range.clone(), c.index))
+            .collect();
+        storage.add_chunks(copy_id, &copies).unwrap();
+
+        let result =
+            embed_buffer_chunks_incremental(&mut storage, &embedder, copy_id, false).unwrap();
+        assert_eq!(result.embedded_count, 0);
+        assert_eq!(result.cached_count, 3);
+        assert_eq!(result.replaced_count, 0);
+        assert!(result.had_changes());
+        assert!(buffer_fully_embedded(&storage, copy_id).unwrap());
+
+        // Cached vectors match the originals
+        let original = storage.get_chunks(1).unwrap();
+        let copied = storage.get_chunks(copy_id).unwrap();
+        assert_eq!(
+            storage.get_embedding(original[0].id.unwrap()).unwrap(),
+            storage.get_embedding(copied[0].id.unwrap()).unwrap()
+        );
+    }
+
+    /// Embedder that fails once it has embedded `limit` texts.
+    struct FailAfter {
+        inner: FallbackEmbedder,
+        limit: usize,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Embedder for FailAfter {
+        fn dimensions(&self) -> usize {
+            self.inner.dimensions()
+        }
+
+        fn model_name(&self) -> &'static str {
+            self.inner.model_name()
+        }
+
+        fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let calls = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if calls >= self.limit {
+                return Err(
+                    crate::error::CommandError::ExecutionFailed("interrupted".to_string()).into(),
+                );
+            }
+            self.inner.embed(text)
+        }
+    }
+
+    #[test]
+    fn test_incremental_embed_resumes_after_interruption() {
+        let mut storage = setup_storage();
+        let buffer = Buffer::from_named("big.txt".to_string(), String::new());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let total = EMBED_BATCH_SIZE + 36;
+        let chunks: Vec<Chunk> = (0..total)
+            .map(|i| {
+                Chunk::new(
+                    buffer_id,
+                    format!("chunk number {i}"),
+                    i * 10..i * 10 + 10,
+                    i,
+                )
+            })
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+
+        // Fail partway through the second batch
+        let failing = FailAfter {
+            inner: FallbackEmbedder::new(DEFAULT_DIMENSIONS),
+            limit: EMBED_BATCH_SIZE + 10,
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let mut reported = Vec::new();
+        let result = embed_buffer_chunks_incremental_with_progress(
+            &mut storage,
+            &failing,
+            buffer_id,
+            false,
+            &mut |done, pending| reported.push((done, pending)),
+        );
+        assert!(result.is_err());
+        assert_eq!(reported, vec![(EMBED_BATCH_SIZE, total)]);
+
+        // The first batch was committed
+        let stats = storage.get_embedding_stats(buffer_id).unwrap();
+        assert_eq!(stats.embedded_chunks, EMBED_BATCH_SIZE);
+
+        // Re-running embeds only the remainder
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let mut reported = Vec::new();
+        let result = embed_buffer_chunks_incremental_with_progress(
+            &mut storage,
+            &embedder,
+            buffer_id,
+            false,
+            &mut |done, pending| reported.push((done, pending)),
+        )
+        .unwrap();
+        assert_eq!(result.embedded_count, total - EMBED_BATCH_SIZE);
+        assert_eq!(result.skipped_count, EMBED_BATCH_SIZE);
+        assert_eq!(
+            reported,
+            vec![(total - EMBED_BATCH_SIZE, total - EMBED_BATCH_SIZE)]
+        );
+        assert!(buffer_fully_embedded(&storage, buffer_id).unwrap());
+    }
+
+    #[test]
+    fn test_incremental_embed_rejects_dimension_change() {
+        let mut storage = setup_storage_with_chunks();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks_incremental(&mut storage, &embedder, 1, false).unwrap();
+        assert_eq!(
+            storage.get_metadata(EMBEDDING_DIMENSIONS_KEY).unwrap(),
+            Some(DEFAULT_DIMENSIONS.to_string())
+        );
+
+        // A different-dimension embedder is rejected without force
+        let smaller = FallbackEmbedder::new(384);
+        let err = embed_buffer_chunks_incremental(&mut storage, &smaller, 1, false).unwrap_err();
+        assert!(err.to_string().contains("384-dimensional"));
+        assert_eq!(
+            storage.embedding_dimensions().unwrap(),
+            vec![DEFAULT_DIMENSIONS]
+        );
+
+        // Force re-embeds everything with the new dimension
+        let result = embed_buffer_chunks_incremental(&mut storage, &smaller, 1, true).unwrap();
+        assert_eq!(result.embedded_count, 3);
+        assert_eq!(result.cached_count, 0);
+        assert_eq!(storage.embedding_dimensions().unwrap(), vec![384]);
+        assert_eq!(
+            storage.get_metadata(EMBEDDING_DIMENSIONS_KEY).unwrap(),
+            Some("384".to_string())
+        );
+    }
+
+    #[test]
+    fn test_forced_dimension_change_keeps_other_buffers() {
+        let mut storage = setup_storage_with_chunks();
+        let other_id = storage
+            .add_buffer(&Buffer::from_named(
+                "other".to_string(),
+                "other content".to_string(),
+            ))
+            .unwrap();
+        storage
+            .add_chunks(
+                other_id,
+                &[Chunk::new(other_id, "other content".to_string(), 0..13, 0)],
+            )
+            .unwrap();
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        embed_buffer_chunks_incremental(&mut storage, &embedder, 1, false).unwrap();
+        embed_buffer_chunks_incremental(&mut storage, &embedder, other_id, false).unwrap();
+
+        // Forcing one buffer must not wipe another buffer's embeddings
+        let smaller = FallbackEmbedder::new(384);
+        let err = embed_buffer_chunks_incremental(&mut storage, &smaller, 1, true).unwrap_err();
+        assert!(err.to_string().contains(&format!("IDs: {other_id}")));
+        assert!(buffer_fully_embedded(&storage, other_id).unwrap());
+        assert!(buffer_fully_embedded(&storage, 1).unwrap());
+
+        // Discarding across the database is explicit
+        let deleted =
+            ensure_embedding_dimensions(&mut storage, &smaller, DimensionMismatch::ReplaceAll)
+                .unwrap();
+        assert_eq!(deleted, 4);
+        let result = embed_buffer_chunks_incremental(&mut storage, &smaller, 1, false).unwrap();
+        assert_eq!(result.embedded_count, 3);
+        assert_eq!(storage.embedding_dimensions().unwrap(), vec![384]);
+        assert!(!buffer_fully_embedded(&storage, other_id).unwrap());
+    }
+
+    #[test]
+    fn test_unusable_embeddings_are_skipped() {
+        assert!(is_usable_embedding(&[0.0, 1.0]));
+        assert!(!is_usable_embedding(&[0.0, 0.0]));
+        assert!(!is_usable_embedding(&[f32::NAN, 1.0]));
+        assert!(!is_usable_embedding(&[]));
+
+        let mut storage = setup_storage();
+        let buffer = Buffer::from_named("ws.txt".to_string(), String::new());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let chunks = vec![
+            Chunk::new(buffer_id, "real words here".to_string(), 0..15, 0),
+            Chunk::new(buffer_id, "\t\n".to_string(), 15..17, 1),
+        ];
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let ids: Vec<i64> = storage
+            .get_chunks(buffer_id)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+
+        // The fallback embedder yields an all-zero vector for whitespace
+        let embedder = FallbackEmbedder::new(DEFAULT_DIMENSIONS);
+        let result =
+            embed_buffer_chunks_incremental(&mut storage, &embedder, buffer_id, false).unwrap();
+        assert_eq!(result.embedded_count, 1);
+        assert_eq!(result.invalid_chunk_ids, vec![ids[1]]);
+        assert_eq!(result.skipped_count, 0);
+        assert!(!storage.has_embedding(ids[1]).unwrap());
+
+        // Unusable vectors are not cached either
+        let model = embedder.model_name();
+        let key = embedding_cache_key("\t\n");
+        assert!(storage.get_cached_embedding(&key, model).unwrap().is_none());
+        assert!(
+            storage
+                .get_cached_embedding(&embedding_cache_key("real words here"), model)
+                .unwrap()
+                .is_some()
+        );
+
+        // Vectors stored by other means are ignored by semantic search
+        storage
+            .store_embedding(ids[1], &[0.0; DEFAULT_DIMENSIONS], None)
+            .unwrap();
+        let config = SearchConfig::new().with_threshold(-1.0).with_bm25(false);
+        let results = hybrid_search(&storage, &embedder, "words", &config).unwrap();
+        let found: Vec<i64> = results.iter().map(|r| r.chunk_id).collect();
+        assert_eq!(found, vec![ids[0]]);
+    }
+
     #[test]
     fn test_incremental_embed_result_completion() {
         let result = IncrementalEmbedResult {
             embedded_count: 2,
             skipped_count: 3,
             replaced_count: 0,
+            cached_count: 0,
             total_chunks: 5,
             model_name: "test".to_string(),
+            invalid_chunk_ids: Vec::new(),
         };
         assert!(result.had_changes());
         assert!((result.completion_percentage() - 100.0).abs() < f64::EPSILON);
@@ -0,0 +1,230 @@
+//! Search query parsing.
+//!
+//! Recognizes double-quoted phrases (`"exact phrase"`) and exclusions
+//! (`-term` or `-"some phrase"`) in addition to plain terms, and renders
+//! them as an FTS5 `MATCH` expression for BM25 search.
+
+/// A parsed search query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// Plain terms; at least one must match. A trailing `*` marks a prefix.
+    pub terms: Vec<String>,
+    /// Quoted phrases; each must match contiguously.
+    pub phrases: Vec<String>,
+    /// Excluded terms or phrases; chunks containing any are dropped.
+    pub excluded: Vec<String>,
+}
+
+impl ParsedQuery {
+    /// Parses a query string.
+    ///
+    /// An unterminated quote extends the phrase to the end of the query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rlm_rs::search::ParsedQuery;
+    ///
+    /// let parsed = ParsedQuery::parse(r#""error handling" retry -panic"#);
+    /// assert_eq!(parsed.phrases, vec!["error handling"]);
+    /// assert_eq!(parsed.terms, vec!["retry"]);
+    /// assert_eq!(parsed.excluded, vec!["panic"]);
+    /// ```
+    #[must_use]
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut rest = query.trim_start();
+
+        while !rest.is_empty() {
+            let (negated, body) = match rest.strip_prefix('-') {
+                Some(body) if body.starts_with(|c: char| !c.is_whitespace()) => (true, body),
+                _ => (false, rest),
+            };
+
+            let (item, is_phrase, remainder) = if let Some(inner) = body.strip_prefix('"') {
+                match inner.find('"') {
+                    Some(end) => (&inner[..end], true, &inner[end + 1..]),
+                    None => (inner, true, ""),
+                }
+            } else {
+                let end = body.find(char::is_whitespace).unwrap_or(body.len());
+                (&body[..end], false, &body[end..])
+            };
+
+            let item = item.trim();
+            if !item.is_empty() {
+                let item = item.to_string();
+                if negated {
+                    parsed.excluded.push(item);
+                } else if is_phrase {
+                    parsed.phrases.push(item);
+                } else {
+                    parsed.terms.push(item);
+                }
+            }
+
+            rest = remainder.trim_start();
+        }
+
+        parsed
+    }
+
+    /// Returns true if the query has no positive terms or phrases.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty()
+    }
+
+    /// Returns the positive terms and phrases as plain text for embedding.
+    #[must_use]
+    pub fn semantic_text(&self) -> String {
+        self.phrases
+            .iter()
+            .chain(&self.terms)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Renders the query as an FTS5 `MATCH` expression.
+    ///
+    /// Phrases are AND-ed, plain terms are OR-ed, and exclusions become
+    /// `NOT` clauses. Every item is quoted so FTS5 special characters are
+    /// treated as literals. Returns `None` if there is nothing positive to
+    /// match (FTS5 cannot evaluate a bare `NOT`).
+    #[must_use]
+    pub fn to_fts_query(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut clauses: Vec<String> = self.phrases.iter().map(|p| quote(p)).collect();
+        if !self.terms.is_empty() {
+            let terms = self
+                .terms
+                .iter()
+                .map(|term| match term.strip_suffix('*') {
+                    Some(prefix) if !prefix.is_empty() => format!("{}*", quote(prefix)),
+                    _ => quote(term),
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            clauses.push(format!("({terms})"));
+        }
+
+        let mut expr = clauses.join(" AND ");
+        for excluded in &self.excluded {
+            expr.push_str(" NOT ");
+            expr.push_str(&quote(excluded));
+        }
+        Some(expr)
+    }
+
+    /// Returns true if `content` contains any excluded term or phrase.
+    ///
+    /// Matching is case-insensitive and on whole words, like the FTS5 `NOT`
+    /// clauses: `-cat` drops "the cat" but not "category", and a phrase
+    /// matches only its words in sequence.
+    #[must_use]
+    pub fn excludes(&self, content: &str) -> bool {
+        if self.excluded.is_empty() {
+            return false;
+        }
+        let content = words(content);
+        self.excluded.iter().any(|excluded| {
+            let needle = words(excluded);
+            !needle.is_empty() && content.windows(needle.len()).any(|window| window == needle)
+        })
+    }
+}
+
+/// Splits text into lowercase words on non-alphanumeric characters.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Quotes a string as an FTS5 string literal.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_terms() {
+        let parsed = ParsedQuery::parse("  CLI   tool? ");
+        assert_eq!(parsed.terms, vec!["CLI", "tool?"]);
+        assert!(parsed.phrases.is_empty());
+        assert_eq!(
+            parsed.to_fts_query().as_deref(),
+            Some(r#"("CLI" OR "tool?")"#)
+        );
+    }
+
+    #[test]
+    fn test_parse_phrase_and_exclusion() {
+        let parsed = ParsedQuery::parse(r#""lazy dog" fox -cat -"big bird""#);
+        assert_eq!(parsed.phrases, vec!["lazy dog"]);
+        assert_eq!(parsed.terms, vec!["fox"]);
+        assert_eq!(parsed.excluded, vec!["cat", "big bird"]);
+        assert_eq!(
+            parsed.to_fts_query().as_deref(),
+            Some(r#""lazy dog" AND ("fox") NOT "cat" NOT "big bird""#)
+        );
+    }
+
+    #[test]
+    fn test_parse_unterminated_quote() {
+        let parsed = ParsedQuery::parse(r#"alpha "beta gamma"#);
+        assert_eq!(parsed.terms, vec!["alpha"]);
+        assert_eq!(parsed.phrases, vec!["beta gamma"]);
+    }
+
+    #[test]
+    fn test_parse_lone_dash_is_term() {
+        let parsed = ParsedQuery::parse("a - b");
+        assert_eq!(parsed.terms, vec!["a", "-", "b"]);
+        assert!(parsed.excluded.is_empty());
+    }
+
+    #[test]
+    fn test_only_exclusions_has_no_fts_query() {
+        let parsed = ParsedQuery::parse("-secret");
+        assert!(parsed.is_empty());
+        assert_eq!(parsed.to_fts_query(), None);
+    }
+
+    #[test]
+    fn test_prefix_term() {
+        let parsed = ParsedQuery::parse("auth*");
+        assert_eq!(parsed.to_fts_query().as_deref(), Some(r#"("auth"*)"#));
+    }
+
+    #[test]
+    fn test_excludes_case_insensitive() {
+        let parsed = ParsedQuery::parse("fox -DOG");
+        assert!(parsed.excludes("The lazy dog"));
+        assert!(!parsed.excludes("The quick fox"));
+    }
+
+    #[test]
+    fn test_excludes_whole_words() {
+        let parsed = ParsedQuery::parse(r#"fox -cat -"big bird""#);
+        assert!(parsed.excludes("The cat sat."));
+        assert!(parsed.excludes("cat-like"));
+        assert!(!parsed.excludes("A category of concatenated words"));
+        assert!(parsed.excludes("A BIG  bird!"));
+        assert!(!parsed.excludes("big birds and a bird"));
+    }
+
+    #[test]
+    fn test_semantic_text() {
+        let parsed = ParsedQuery::parse(r#"fox "lazy dog" -cat"#);
+        assert_eq!(parsed.semantic_text(), "lazy dog fox");
+    }
+}
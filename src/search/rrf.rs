@@ -44,7 +44,8 @@ impl RrfConfig {
 ///
 /// # Returns
 ///
-/// A vector of (`item_id`, `rrf_score`) tuples, sorted by score descending.
+/// A vector of (`item_id`, `rrf_score`) tuples, sorted by score descending
+/// with ties broken by ascending ID.
 ///
 /// # Examples
 ///
@@ -76,7 +77,7 @@ pub fn reciprocal_rank_fusion(ranked_lists: &[&[i64]], config: &RrfConfig) -> Ve
 
     // Sort by score descending
     let mut results: Vec<(i64, f64)> = scores.into_iter().collect();
-    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
 
     results
 }
@@ -92,7 +93,8 @@ pub fn reciprocal_rank_fusion(ranked_lists: &[&[i64]], config: &RrfConfig) -> Ve
 ///
 /// # Returns
 ///
-/// A vector of (`item_id`, `weighted_rrf_score`) tuples, sorted by score descending.
+/// A vector of (`item_id`, `weighted_rrf_score`) tuples, sorted by score
+/// descending with ties broken by ascending ID.
 #[must_use]
 #[allow(clippy::cast_possible_truncation)]
 pub fn weighted_rrf(ranked_lists: &[(&[i64], f64)], config: &RrfConfig) -> Vec<(i64, f64)> {
@@ -106,7 +108,7 @@ pub fn weighted_rrf(ranked_lists: &[(&[i64], f64)], config: &RrfConfig) -> Vec<(
     }
 
     let mut results: Vec<(i64, f64)> = scores.into_iter().collect();
-    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
 
     results
 }
@@ -159,6 +161,10 @@ mod tests {
         let score1 = results.iter().find(|(id, _)| *id == 1).unwrap().1;
         let score3 = results.iter().find(|(id, _)| *id == 3).unwrap().1;
         assert!((score1 - score3).abs() < f64::EPSILON);
+
+        // Ties are ordered by ID so paging is stable
+        let ids: Vec<i64> = results.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 3, 2, 4]);
     }
 
     #[test]
@@ -3,7 +3,7 @@
 //! Contains SQL schema and migration logic for the RLM `SQLite` database.
 
 /// Current schema version.
-pub const CURRENT_SCHEMA_VERSION: u32 = 3;
+pub const CURRENT_SCHEMA_VERSION: u32 = 11;
 
 /// SQL schema for initial database setup.
 pub const SCHEMA_SQL: &str = r"
@@ -32,6 +32,10 @@ CREATE TABLE IF NOT EXISTS buffers (
     size INTEGER NOT NULL,
     line_count INTEGER,
     chunk_count INTEGER,
+    page_offsets TEXT,  -- JSON array of page start offsets (v6)
+    compressed_size INTEGER,  -- On-disk size of compressed sources (v7)
+    encoding TEXT,  -- Source text encoding (v8)
+    deleted_at INTEGER,  -- Set while the buffer is in the trash (v10)
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL
 );
@@ -106,6 +110,34 @@ CREATE TRIGGER IF NOT EXISTS chunks_au AFTER UPDATE ON chunks BEGIN
     INSERT INTO chunks_fts(chunks_fts, rowid, content) VALUES('delete', old.id, old.content);
     INSERT INTO chunks_fts(chunks_fts, rowid, content) VALUES (new.id, new.content);
 END;
+
+-- Embedding cache keyed by content hash and model (v4)
+CREATE TABLE IF NOT EXISTS embedding_cache (
+    hash TEXT NOT NULL,
+    model TEXT NOT NULL,
+    vector BLOB NOT NULL,  -- f32 array serialized as bytes
+    dimensions INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (hash, model)
+);
+
+-- Buffer tags, plain strings or key:value labels (v9)
+CREATE TABLE IF NOT EXISTS buffer_tags (
+    buffer_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (buffer_id, tag),
+    FOREIGN KEY (buffer_id) REFERENCES buffers(id) ON DELETE CASCADE
+);
+
+-- Index for buffer lookup by tag
+CREATE INDEX IF NOT EXISTS idx_buffer_tags_tag ON buffer_tags(tag);
+
+-- Applied migrations, one row per schema version reached (v5)
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    applied_at INTEGER NOT NULL
+);
 ";
 
 /// SQL to check if schema is initialized.
@@ -124,12 +156,31 @@ pub const SET_VERSION_SQL: &str = r"
 INSERT OR REPLACE INTO schema_info (key, value) VALUES ('version', ?);
 ";
 
+/// SQL to create the applied-migrations table if it is missing.
+///
+/// Runs before any migration step so that databases older than v5 can
+/// record the steps applied to them.
+pub const CREATE_MIGRATIONS_TABLE_SQL: &str = r"
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    applied_at INTEGER NOT NULL
+);
+";
+
+/// SQL to record an applied migration.
+pub const RECORD_MIGRATION_SQL: &str = r"
+INSERT OR REPLACE INTO schema_migrations (version, applied_at) VALUES (?, ?);
+";
+
 /// Migrations from older schema versions.
+#[derive(Debug)]
 pub struct Migration {
     /// Version this migration upgrades from.
     pub from_version: u32,
     /// Version this migration upgrades to.
     pub to_version: u32,
+    /// Short description of the change.
+    pub description: &'static str,
     /// SQL statements to execute.
     pub sql: &'static str,
 }
@@ -182,18 +233,129 @@ const MIGRATION_V2_TO_V3: &str = r"
 DELETE FROM chunk_embeddings;
 ";
 
+/// SQL for v3 to v4 migration (adds content-hash embedding cache).
+const MIGRATION_V3_TO_V4: &str = r"
+CREATE TABLE IF NOT EXISTS embedding_cache (
+    hash TEXT NOT NULL,
+    model TEXT NOT NULL,
+    vector BLOB NOT NULL,
+    dimensions INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (hash, model)
+);
+";
+
+/// SQL for v4 to v5 migration (adds applied-migrations tracking).
+const MIGRATION_V4_TO_V5: &str = CREATE_MIGRATIONS_TABLE_SQL;
+
+/// SQL for v5 to v6 migration (adds buffer page offsets).
+const MIGRATION_V5_TO_V6: &str = r"
+ALTER TABLE buffers ADD COLUMN page_offsets TEXT;
+";
+
+/// SQL for v6 to v7 migration (adds buffer compressed size).
+const MIGRATION_V6_TO_V7: &str = r"
+ALTER TABLE buffers ADD COLUMN compressed_size INTEGER;
+";
+
+/// SQL for v7 to v8 migration (adds buffer source encoding).
+const MIGRATION_V7_TO_V8: &str = r"
+ALTER TABLE buffers ADD COLUMN encoding TEXT;
+";
+
+/// SQL for v8 to v9 migration (adds buffer tags).
+const MIGRATION_V8_TO_V9: &str = r"
+CREATE TABLE IF NOT EXISTS buffer_tags (
+    buffer_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (buffer_id, tag),
+    FOREIGN KEY (buffer_id) REFERENCES buffers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_buffer_tags_tag ON buffer_tags(tag);
+";
+
+/// SQL for v9 to v10 migration (adds buffer soft-delete timestamp).
+const MIGRATION_V9_TO_V10: &str = r"
+ALTER TABLE buffers ADD COLUMN deleted_at INTEGER;
+";
+
+/// SQL function computing [`content_hash`](crate::core::content_hash),
+/// registered on the connection while migrations run.
+pub const CONTENT_HASH_FUNCTION: &str = "rlm_content_hash";
+
+/// SQL for v10 to v11 migration (recomputes buffer content hashes).
+///
+/// Buffers stored by older releases carry hashes from an earlier algorithm
+/// (or none), so their stable chunk keys would never match a reload of the
+/// same content.
+const MIGRATION_V10_TO_V11: &str = r"
+UPDATE buffers SET content_hash = rlm_content_hash(content);
+";
+
 /// Available migrations.
 pub const MIGRATIONS: &[Migration] = &[
     Migration {
         from_version: 1,
         to_version: 2,
+        description: "Add chunk embeddings and FTS5 index",
         sql: MIGRATION_V1_TO_V2,
     },
     Migration {
         from_version: 2,
         to_version: 3,
+        description: "Clear embeddings for the BGE-M3 model switch",
         sql: MIGRATION_V2_TO_V3,
     },
+    Migration {
+        from_version: 3,
+        to_version: 4,
+        description: "Add content-hash embedding cache",
+        sql: MIGRATION_V3_TO_V4,
+    },
+    Migration {
+        from_version: 4,
+        to_version: 5,
+        description: "Track applied migrations",
+        sql: MIGRATION_V4_TO_V5,
+    },
+    Migration {
+        from_version: 5,
+        to_version: 6,
+        description: "Add buffer page offsets",
+        sql: MIGRATION_V5_TO_V6,
+    },
+    Migration {
+        from_version: 6,
+        to_version: 7,
+        description: "Add buffer compressed size",
+        sql: MIGRATION_V6_TO_V7,
+    },
+    Migration {
+        from_version: 7,
+        to_version: 8,
+        description: "Add buffer source encoding",
+        sql: MIGRATION_V7_TO_V8,
+    },
+    Migration {
+        from_version: 8,
+        to_version: 9,
+        description: "Add buffer tags",
+        sql: MIGRATION_V8_TO_V9,
+    },
+    Migration {
+        from_version: 9,
+        to_version: 10,
+        description: "Add buffer trash",
+        sql: MIGRATION_V9_TO_V10,
+    },
+    Migration {
+        from_version: 10,
+        to_version: 11,
+        description: "Recompute buffer content hashes",
+        sql: MIGRATION_V10_TO_V11,
+    },
 ];
 
 /// Gets migrations needed to upgrade from a version.
@@ -227,10 +389,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_migrations_contiguous() {
+        for pair in MIGRATIONS.windows(2) {
+            assert_eq!(pair[0].to_version, pair[1].from_version);
+        }
+        assert_eq!(
+            MIGRATIONS.last().map(|m| m.to_version),
+            Some(CURRENT_SCHEMA_VERSION)
+        );
+    }
+
     #[test]
     fn test_get_migrations_from() {
         let migrations = get_migrations_from(0);
         // Should return all migrations for fresh install
         assert!(migrations.len() <= MIGRATIONS.len());
     }
+
+    #[test]
+    fn test_embedding_cache_migration() {
+        let migrations = get_migrations_from(3);
+        assert_eq!(migrations[0].to_version, 4);
+        assert!(migrations[0].sql.contains("embedding_cache"));
+        assert!(SCHEMA_SQL.contains("embedding_cache"));
+    }
+
+    #[test]
+    fn test_content_hash_migration_uses_registered_function() {
+        let migrations = get_migrations_from(10);
+        assert_eq!(migrations[0].to_version, 11);
+        assert!(migrations[0].sql.contains(CONTENT_HASH_FUNCTION));
+    }
 }
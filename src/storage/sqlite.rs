@@ -8,14 +8,23 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss)]
 
-use crate::core::{Buffer, BufferMetadata, Chunk, ChunkMetadata, Context};
+use crate::core::{
+    Buffer, BufferMetadata, Chunk, ChunkMetadata, Context, content_hash, embedding_cache_key,
+};
 use crate::error::{Result, StorageError};
 use crate::storage::schema::{
-    CHECK_SCHEMA_SQL, CURRENT_SCHEMA_VERSION, GET_VERSION_SQL, SCHEMA_SQL, SET_VERSION_SQL,
+    CHECK_SCHEMA_SQL, CONTENT_HASH_FUNCTION, CREATE_MIGRATIONS_TABLE_SQL, CURRENT_SCHEMA_VERSION,
+    GET_VERSION_SQL, Migration, RECORD_MIGRATION_SQL, SCHEMA_SQL, SET_VERSION_SQL,
+    get_migrations_from,
 };
 use crate::storage::traits::{Storage, StorageStats};
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::backup::Backup;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, MAIN_DB, OpenFlags, OptionalExtension, params};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::Duration;
 
 /// SQLite-based storage implementation.
 ///
@@ -56,6 +65,8 @@ impl SqliteStorage {
             std::fs::create_dir_all(parent).map_err(|e| StorageError::Database(e.to_string()))?;
         }
 
+        #[cfg(feature = "vec")]
+        register_vector_extension();
         let conn = Connection::open(&path).map_err(StorageError::from)?;
 
         // Enable foreign keys
@@ -67,12 +78,71 @@ impl SqliteStorage {
             .query_row("PRAGMA journal_mode = WAL;", [], |row| row.get(0))
             .map_err(StorageError::from)?;
 
+        #[cfg(not(feature = "vec"))]
+        drop_vector_triggers(&conn)?;
+
+        Ok(Self {
+            conn,
+            path: Some(path),
+        })
+    }
+
+    /// Opens an existing database read-only.
+    ///
+    /// Read-only handles can run alongside the single writer connection
+    /// (the database uses WAL mode), so lookups such as `get_chunk` can be
+    /// served from several threads at once. Any write through this handle
+    /// fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to an existing database file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database does not exist or cannot be opened.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        #[cfg(feature = "vec")]
+        register_vector_extension();
+        let conn = Connection::open_with_flags(
+            &path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(StorageError::from)?;
+
         Ok(Self {
             conn,
             path: Some(path),
         })
     }
 
+    /// Opens a pool of `size` read-only handles on the same database.
+    ///
+    /// The pool can be shared between threads; each checkout borrows one
+    /// handle until the guard is dropped. The write path stays on the
+    /// connection from [`Self::open`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to an existing database file.
+    /// * `size` - Number of handles to open (at least one is opened).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any connection cannot be opened.
+    pub fn open_read_pool<P: AsRef<Path>>(path: P, size: usize) -> Result<ReadPool> {
+        let path = path.as_ref();
+        let handles = (0..size.max(1))
+            .map(|_| Self::open_read_only(path).map(Mutex::new))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ReadPool {
+            handles,
+            next: AtomicUsize::new(0),
+        })
+    }
+
     /// Creates an in-memory `SQLite` database.
     ///
     /// Useful for testing.
@@ -81,6 +151,8 @@ impl SqliteStorage {
     ///
     /// Returns an error if the database cannot be created.
     pub fn in_memory() -> Result<Self> {
+        #[cfg(feature = "vec")]
+        register_vector_extension();
         let conn = Connection::open_in_memory().map_err(StorageError::from)?;
         conn.execute("PRAGMA foreign_keys = ON;", [])
             .map_err(StorageError::from)?;
@@ -113,6 +185,177 @@ impl SqliteStorage {
         Ok(())
     }
 
+    /// Returns the migrations not yet applied to this database.
+    ///
+    /// Empty if the database is uninitialized (`init` creates the current
+    /// schema directly) or already at [`CURRENT_SCHEMA_VERSION`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema version cannot be read.
+    pub fn pending_migrations(&self) -> Result<Vec<&'static Migration>> {
+        if !self.is_initialized()? {
+            return Ok(Vec::new());
+        }
+        Ok(match self.get_schema_version()? {
+            Some(current) if current < CURRENT_SCHEMA_VERSION => get_migrations_from(current),
+            _ => Vec::new(),
+        })
+    }
+
+    /// Applies pending migrations in order.
+    ///
+    /// Each step runs in its own transaction that also bumps the stored
+    /// schema version and records the step in `schema_migrations`, so an
+    /// interrupted upgrade resumes from the last completed step. With the
+    /// `vec` feature, the vector index is then synced with the stored
+    /// embeddings (see `sync_vector_index`).
+    ///
+    /// # Returns
+    ///
+    /// The migrations that were applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a migration step fails.
+    pub fn migrate(&mut self) -> Result<Vec<&'static Migration>> {
+        let pending = self.pending_migrations()?;
+        if !pending.is_empty() {
+            self.apply_migrations(&pending)?;
+        }
+
+        #[cfg(feature = "vec")]
+        self.sync_vector_index()?;
+
+        Ok(pending)
+    }
+
+    /// Applies `pending` migrations in order, one transaction each.
+    fn apply_migrations(&mut self, pending: &[&'static Migration]) -> Result<()> {
+        self.conn
+            .execute_batch(CREATE_MIGRATIONS_TABLE_SQL)
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+        self.conn
+            .create_scalar_function(
+                CONTENT_HASH_FUNCTION,
+                1,
+                FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+                |ctx| Ok(content_hash(&ctx.get::<String>(0)?)),
+            )
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+
+        for migration in pending {
+            let tx = self
+                .conn
+                .transaction()
+                .map_err(|e| StorageError::Migration(e.to_string()))?;
+            tx.execute_batch(migration.sql)
+                .map_err(|e| StorageError::Migration(e.to_string()))?;
+            tx.execute(SET_VERSION_SQL, params![migration.to_version.to_string()])
+                .map_err(|e| StorageError::Migration(e.to_string()))?;
+            tx.execute(
+                RECORD_MIGRATION_SQL,
+                params![migration.to_version, Self::now()],
+            )
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+            tx.commit()
+                .map_err(|e| StorageError::Migration(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a consistent snapshot of the database to `dest`.
+    ///
+    /// Uses the SQLite online backup API, copying every page in a single
+    /// step so the snapshot is consistent even while other connections are
+    /// open. Embeddings and the embedding cache are included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dest` already exists or the snapshot fails.
+    pub fn backup_to<P: AsRef<Path>>(&self, dest: P) -> Result<()> {
+        let dest = dest.as_ref();
+        if dest.exists() {
+            return Err(StorageError::Database(format!(
+                "backup destination already exists: {}",
+                dest.display()
+            ))
+            .into());
+        }
+
+        let result = Connection::open(dest).and_then(|mut target| {
+            Backup::new(&self.conn, &mut target)?.run_to_completion(-1, Duration::ZERO, None)
+        });
+        if let Err(e) = result {
+            // Don't leave a partial snapshot behind
+            let _ = std::fs::remove_file(dest);
+            return Err(StorageError::from(e).into());
+        }
+        Ok(())
+    }
+
+    /// Checks that this is an RLM database this build can open.
+    ///
+    /// # Returns
+    ///
+    /// The stored schema version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if expected tables are missing, the version is
+    /// unreadable, or the database is newer than [`CURRENT_SCHEMA_VERSION`].
+    pub fn validate(&self) -> Result<u32> {
+        for table in ["schema_info", "context", "buffers", "chunks", "metadata"] {
+            let exists: i64 = self
+                .conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                    params![table],
+                    |row| row.get(0),
+                )
+                .map_err(StorageError::from)?;
+            if exists == 0 {
+                return Err(StorageError::Database(format!(
+                    "not an rlm database: missing table '{table}'"
+                ))
+                .into());
+            }
+        }
+
+        let version = self.get_schema_version()?.ok_or_else(|| {
+            StorageError::Database("not an rlm database: missing schema version".to_string())
+        })?;
+        if version > CURRENT_SCHEMA_VERSION {
+            return Err(StorageError::Database(format!(
+                "database schema version {version} is newer than supported version {CURRENT_SCHEMA_VERSION}"
+            ))
+            .into());
+        }
+        Ok(version)
+    }
+
+    /// Counts all buffer and chunk rows, trashed buffers included.
+    ///
+    /// Unlike [`Storage::stats`], this reads no column added by a migration,
+    /// so it works on databases that have not been upgraded yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the counts cannot be read.
+    pub fn row_counts(&self) -> Result<(usize, usize)> {
+        let count = |table: &str| -> Result<usize> {
+            let count: i64 = self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })
+                .map_err(StorageError::from)?;
+            Ok(count as usize)
+        };
+        Ok((count("buffers")?, count("chunks")?))
+    }
+
     /// Returns current Unix timestamp.
     #[allow(clippy::cast_possible_wrap)]
     fn now() -> i64 {
@@ -121,6 +364,16 @@ impl SqliteStorage {
             .map(|d| d.as_secs() as i64)
             .unwrap_or(0)
     }
+
+    /// Serializes buffer page offsets as a JSON array.
+    fn encode_page_offsets(offsets: Option<&[usize]>) -> Option<String> {
+        offsets.and_then(|offsets| serde_json::to_string(offsets).ok())
+    }
+
+    /// Parses buffer page offsets stored as a JSON array.
+    fn decode_page_offsets(value: Option<String>) -> Option<Vec<usize>> {
+        value.and_then(|json| serde_json::from_str(&json).ok())
+    }
 }
 
 impl Storage for SqliteStorage {
@@ -137,17 +390,14 @@ impl Storage for SqliteStorage {
                 .execute_batch(SCHEMA_SQL)
                 .map_err(StorageError::from)?;
             self.set_schema_version(CURRENT_SCHEMA_VERSION)?;
-        } else if let Some(current) = self.get_schema_version()?
-            && current < CURRENT_SCHEMA_VERSION
-        {
-            // Run migrations
-            let migrations = crate::storage::schema::get_migrations_from(current);
-            for migration in migrations {
-                self.conn
-                    .execute_batch(migration.sql)
-                    .map_err(|e| StorageError::Migration(e.to_string()))?;
-            }
-            self.set_schema_version(CURRENT_SCHEMA_VERSION)?;
+            self.conn
+                .execute(
+                    RECORD_MIGRATION_SQL,
+                    params![CURRENT_SCHEMA_VERSION, Self::now()],
+                )
+                .map_err(StorageError::from)?;
+        } else {
+            self.migrate()?;
         }
 
         Ok(())
@@ -166,7 +416,9 @@ impl Storage for SqliteStorage {
             .execute_batch(
                 r"
             DELETE FROM chunk_embeddings;
+            DELETE FROM embedding_cache;
             DELETE FROM chunks;
+            DELETE FROM buffer_tags;
             DELETE FROM buffers;
             DELETE FROM context;
             DELETE FROM metadata;
@@ -231,8 +483,9 @@ impl Storage for SqliteStorage {
                 r"
             INSERT INTO buffers (
                 name, source_path, content, content_type, content_hash,
-                size, line_count, chunk_count, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                size, line_count, chunk_count, page_offsets, compressed_size,
+                encoding, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ",
                 params![
                     buffer.name,
@@ -242,10 +495,13 @@ impl Storage for SqliteStorage {
                         .map(|p| p.to_string_lossy().to_string()),
                     buffer.content,
                     buffer.metadata.content_type,
-                    buffer.metadata.content_hash,
+                    content_hash(&buffer.content),
                     buffer.metadata.size as i64,
                     buffer.metadata.line_count.map(|c| c as i64),
                     buffer.metadata.chunk_count.map(|c| c as i64),
+                    Self::encode_page_offsets(buffer.metadata.page_offsets.as_deref()),
+                    buffer.metadata.compressed_size.map(|s| s as i64),
+                    buffer.metadata.encoding,
                     now,
                     now,
                 ],
@@ -261,8 +517,9 @@ impl Storage for SqliteStorage {
             .query_row(
                 r"
             SELECT id, name, source_path, content, content_type, content_hash,
-                   size, line_count, chunk_count, created_at, updated_at
-            FROM buffers WHERE id = ?
+                   size, line_count, chunk_count, page_offsets, compressed_size,
+                   encoding, created_at, updated_at
+            FROM buffers WHERE id = ? AND deleted_at IS NULL
         ",
                 params![id],
                 |row| {
@@ -277,8 +534,11 @@ impl Storage for SqliteStorage {
                             size: row.get::<_, i64>(6)? as usize,
                             line_count: row.get::<_, Option<i64>>(7)?.map(|c| c as usize),
                             chunk_count: row.get::<_, Option<i64>>(8)?.map(|c| c as usize),
-                            created_at: row.get(9)?,
-                            updated_at: row.get(10)?,
+                            page_offsets: Self::decode_page_offsets(row.get(9)?),
+                            compressed_size: row.get::<_, Option<i64>>(10)?.map(|s| s as usize),
+                            encoding: row.get(11)?,
+                            created_at: row.get(12)?,
+                            updated_at: row.get(13)?,
                         },
                     })
                 },
@@ -290,17 +550,8 @@ impl Storage for SqliteStorage {
     }
 
     fn get_buffer_by_name(&self, name: &str) -> Result<Option<Buffer>> {
-        let id: Option<i64> = self
-            .conn
-            .query_row(
-                "SELECT id FROM buffers WHERE name = ?",
-                params![name],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(StorageError::from)?;
-
-        id.map_or(Ok(None), |id| self.get_buffer(id))
+        self.find_buffer_id(name)?
+            .map_or(Ok(None), |id| self.get_buffer(id))
     }
 
     fn list_buffers(&self) -> Result<Vec<Buffer>> {
@@ -309,8 +560,9 @@ impl Storage for SqliteStorage {
             .prepare(
                 r"
             SELECT id, name, source_path, content, content_type, content_hash,
-                   size, line_count, chunk_count, created_at, updated_at
-            FROM buffers ORDER BY id
+                   size, line_count, chunk_count, page_offsets, compressed_size,
+                   encoding, created_at, updated_at
+            FROM buffers WHERE deleted_at IS NULL ORDER BY id
         ",
             )
             .map_err(StorageError::from)?;
@@ -328,8 +580,11 @@ impl Storage for SqliteStorage {
                         size: row.get::<_, i64>(6)? as usize,
                         line_count: row.get::<_, Option<i64>>(7)?.map(|c| c as usize),
                         chunk_count: row.get::<_, Option<i64>>(8)?.map(|c| c as usize),
-                        created_at: row.get(9)?,
-                        updated_at: row.get(10)?,
+                        page_offsets: Self::decode_page_offsets(row.get(9)?),
+                        compressed_size: row.get::<_, Option<i64>>(10)?.map(|s| s as usize),
+                        encoding: row.get(11)?,
+                        created_at: row.get(12)?,
+                        updated_at: row.get(13)?,
                     },
                 })
             })
@@ -354,7 +609,7 @@ impl Storage for SqliteStorage {
             UPDATE buffers SET
                 name = ?, source_path = ?, content = ?, content_type = ?,
                 content_hash = ?, size = ?, line_count = ?, chunk_count = ?,
-                updated_at = ?
+                page_offsets = ?, compressed_size = ?, encoding = ?, updated_at = ?
             WHERE id = ?
         ",
                 params![
@@ -365,10 +620,13 @@ impl Storage for SqliteStorage {
                         .map(|p| p.to_string_lossy().to_string()),
                     buffer.content,
                     buffer.metadata.content_type,
-                    buffer.metadata.content_hash,
+                    content_hash(&buffer.content),
                     buffer.metadata.size as i64,
                     buffer.metadata.line_count.map(|c| c as i64),
                     buffer.metadata.chunk_count.map(|c| c as i64),
+                    Self::encode_page_offsets(buffer.metadata.page_offsets.as_deref()),
+                    buffer.metadata.compressed_size.map(|s| s as i64),
+                    buffer.metadata.encoding,
                     now,
                     id,
                 ],
@@ -389,7 +647,11 @@ impl Storage for SqliteStorage {
     fn buffer_count(&self) -> Result<usize> {
         let count: i64 = self
             .conn
-            .query_row("SELECT COUNT(*) FROM buffers", [], |row| row.get(0))
+            .query_row(
+                "SELECT COUNT(*) FROM buffers WHERE deleted_at IS NULL",
+                [],
+                |row| row.get(0),
+            )
             .map_err(StorageError::from)?;
         Ok(count as usize)
     }
@@ -561,6 +823,39 @@ impl Storage for SqliteStorage {
         Ok(())
     }
 
+    fn delete_chunk(&mut self, id: i64) -> Result<i64> {
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+
+        let buffer_id: i64 = tx
+            .query_row(
+                "SELECT buffer_id FROM chunks WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?
+            .ok_or(StorageError::ChunkNotFound { id })?;
+
+        // FTS row is removed by the chunks_ad trigger
+        tx.execute(
+            "DELETE FROM chunk_embeddings WHERE chunk_id = ?",
+            params![id],
+        )
+        .map_err(StorageError::from)?;
+        tx.execute("DELETE FROM chunks WHERE id = ?", params![id])
+            .map_err(StorageError::from)?;
+
+        // Decrement chunk count on buffer
+        tx.execute(
+            "UPDATE buffers SET chunk_count = MAX(COALESCE(chunk_count, 1) - 1, 0) WHERE id = ?",
+            params![buffer_id],
+        )
+        .map_err(StorageError::from)?;
+
+        tx.commit().map_err(StorageError::from)?;
+        Ok(buffer_id)
+    }
+
     fn chunk_count(&self, buffer_id: i64) -> Result<usize> {
         let count: i64 = self
             .conn
@@ -624,6 +919,232 @@ impl Storage for SqliteStorage {
     }
 }
 
+/// Subquery selecting the chunk IDs of trashed buffers.
+const TRASHED_CHUNK_IDS_SQL: &str =
+    "SELECT c.id FROM chunks c JOIN buffers b ON b.id = c.buffer_id WHERE b.deleted_at IS NOT NULL";
+
+// ==================== Vector Index ====================
+
+/// Metadata key recording the embedding dimension the vector index was
+/// built for.
+const VECTOR_INDEX_KEY: &str = "vector_index_dimensions";
+
+/// SQL dropping the triggers that mirror `chunk_embeddings` into the
+/// vector index.
+const DROP_VECTOR_TRIGGERS_SQL: &str = r"
+DROP TRIGGER IF EXISTS chunk_vectors_ai;
+DROP TRIGGER IF EXISTS chunk_vectors_ad;
+";
+
+/// Largest `k` a `sqlite-vec` nearest-neighbor query accepts.
+#[cfg(feature = "vec")]
+pub const MAX_VECTOR_QUERY_K: usize = 4096;
+
+/// Registers the `sqlite-vec` extension for every connection opened
+/// afterwards.
+#[cfg(feature = "vec")]
+#[allow(unsafe_code, clippy::missing_transmute_annotations)]
+fn register_vector_extension() {
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+    REGISTER.call_once(|| {
+        // SAFETY: `sqlite3_vec_init` is the extension entry point that
+        // sqlite-vec exports for static linking, with the signature
+        // `sqlite3_auto_extension` expects.
+        unsafe {
+            rusqlite::ffi::sqlite3_auto_extension(Some(std::mem::transmute(
+                sqlite_vec::sqlite3_vec_init as *const (),
+            )));
+        }
+    });
+}
+
+/// Drops vector index triggers left behind by a build with the `vec`
+/// feature.
+///
+/// The triggers write to a `sqlite-vec` table this build cannot load, so
+/// they would make every embedding write fail. The index is marked stale
+/// and rebuilt the next time a `vec` build migrates the database.
+#[cfg(not(feature = "vec"))]
+fn drop_vector_triggers(conn: &Connection) -> Result<()> {
+    let triggers: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'trigger' AND name = 'chunk_vectors_ai'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(StorageError::from)?;
+    if triggers > 0 {
+        conn.execute_batch(DROP_VECTOR_TRIGGERS_SQL)
+            .map_err(StorageError::from)?;
+        conn.execute(
+            "DELETE FROM metadata WHERE key = ?",
+            params![VECTOR_INDEX_KEY],
+        )
+        .map_err(StorageError::from)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "vec")]
+impl SqliteStorage {
+    /// Returns the embedding dimension the vector index was built for, or
+    /// `None` if there is no usable index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn vector_index_dimensions(&self) -> Result<Option<usize>> {
+        let built: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = ?",
+                params![VECTOR_INDEX_KEY],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(built.and_then(|value| value.parse().ok()))
+    }
+
+    /// Builds or rebuilds the vector index so it covers the stored
+    /// embeddings.
+    ///
+    /// The index is built for the most common embedding dimension. Nothing
+    /// is written when the index already covers a dimension that still has
+    /// embeddings, or when there are no embeddings. [`Self::migrate`] runs
+    /// this, so every database opened through `init` or the CLI is synced;
+    /// call it directly after storing embeddings to use the index in the
+    /// same session.
+    ///
+    /// Returns the indexed dimension, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be built.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn sync_vector_index(&self) -> Result<Option<usize>> {
+        let built = self.vector_index_dimensions()?;
+        if let Some(dimensions) = built {
+            let covered: bool = self
+                .conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM chunk_embeddings WHERE dimensions = ?)",
+                    params![dimensions as i64],
+                    |row| row.get(0),
+                )
+                .map_err(StorageError::from)?;
+            if covered {
+                return Ok(built);
+            }
+        }
+
+        let dominant: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT dimensions FROM chunk_embeddings
+                 GROUP BY dimensions ORDER BY COUNT(*) DESC, dimensions LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        match dominant {
+            Some(dimensions) => {
+                let dimensions = dimensions as usize;
+                self.build_vector_index(dimensions)?;
+                Ok(Some(dimensions))
+            }
+            None => Ok(built),
+        }
+    }
+
+    /// Builds the vector index for `dimensions`-wide embeddings, replacing
+    /// any existing index.
+    ///
+    /// The index is a `sqlite-vec` `vec0` table that triggers keep in step
+    /// with `chunk_embeddings`. It is back-filled from the stored
+    /// embeddings; embeddings of other dimensions are not indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be built.
+    fn build_vector_index(&self, dimensions: usize) -> Result<()> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(StorageError::from)?;
+        tx.execute_batch(&format!(
+            r"
+            {DROP_VECTOR_TRIGGERS_SQL}
+            DROP TABLE IF EXISTS chunk_vectors;
+            CREATE VIRTUAL TABLE chunk_vectors USING vec0(
+                chunk_id INTEGER PRIMARY KEY,
+                embedding float[{dimensions}] distance_metric=cosine
+            );
+            INSERT INTO chunk_vectors (chunk_id, embedding)
+                SELECT chunk_id, embedding FROM chunk_embeddings WHERE dimensions = {dimensions};
+            CREATE TRIGGER chunk_vectors_ai AFTER INSERT ON chunk_embeddings BEGIN
+                DELETE FROM chunk_vectors WHERE chunk_id = new.chunk_id;
+                INSERT INTO chunk_vectors (chunk_id, embedding)
+                    SELECT new.chunk_id, new.embedding WHERE new.dimensions = {dimensions};
+            END;
+            CREATE TRIGGER chunk_vectors_ad AFTER DELETE ON chunk_embeddings BEGIN
+                DELETE FROM chunk_vectors WHERE chunk_id = old.chunk_id;
+            END;
+            "
+        ))
+        .map_err(StorageError::from)?;
+        let now = Self::now();
+        tx.execute(
+            r"
+            INSERT OR REPLACE INTO metadata (key, value, created_at, updated_at)
+            VALUES (?, ?, ?, ?)
+        ",
+            params![VECTOR_INDEX_KEY, dimensions.to_string(), now, now],
+        )
+        .map_err(StorageError::from)?;
+        tx.commit().map_err(StorageError::from)?;
+        Ok(())
+    }
+
+    /// Returns the IDs of up to `k` chunks nearest to `query` by cosine
+    /// distance, closest first.
+    ///
+    /// Only reads: the index is built by [`Self::sync_vector_index`].
+    /// Chunks of trashed buffers are included; callers filter them.
+    /// Returns `None` if the index is missing or was built for another
+    /// dimension, or if `k` exceeds [`MAX_VECTOR_QUERY_K`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index cannot be queried.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn nearest_chunk_ids(&self, query: &[f32], k: usize) -> Result<Option<Vec<i64>>> {
+        if k > MAX_VECTOR_QUERY_K || self.vector_index_dimensions()? != Some(query.len()) {
+            return Ok(None);
+        }
+        if k == 0 {
+            return Ok(Some(Vec::new()));
+        }
+
+        let bytes: Vec<u8> = query.iter().flat_map(|f| f.to_le_bytes()).collect();
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT chunk_id FROM chunk_vectors
+                 WHERE embedding MATCH ? AND k = ?
+                 ORDER BY distance",
+            )
+            .map_err(StorageError::from)?;
+        let ids = stmt
+            .query_map(params![bytes, k as i64], |row| row.get::<_, i64>(0))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(Some(ids))
+    }
+}
+
 // ==================== Embedding & Search Operations ====================
 
 impl SqliteStorage {
@@ -669,28 +1190,44 @@ impl SqliteStorage {
     ///
     /// Returns an error if the query fails.
     pub fn get_embedding(&self, chunk_id: i64) -> Result<Option<Vec<f32>>> {
-        let result: Option<Vec<u8>> = self
+        Ok(self
+            .get_embedding_with_model(chunk_id)?
+            .map(|(embedding, _)| embedding))
+    }
+
+    /// Retrieves the embedding for a chunk along with the name of the model
+    /// that produced it (`None` for embeddings stored without a model).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_embedding_with_model(
+        &self,
+        chunk_id: i64,
+    ) -> Result<Option<(Vec<f32>, Option<String>)>> {
+        let result: Option<(Vec<u8>, Option<String>)> = self
             .conn
             .query_row(
-                "SELECT embedding FROM chunk_embeddings WHERE chunk_id = ?",
+                "SELECT embedding, model_name FROM chunk_embeddings WHERE chunk_id = ?",
                 params![chunk_id],
-                |row| row.get(0),
+                |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .optional()
             .map_err(StorageError::from)?;
 
-        Ok(result.map(|bytes| {
-            bytes
+        Ok(result.map(|(bytes, model)| {
+            let embedding = bytes
                 .chunks_exact(4)
                 .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                .collect()
+                .collect();
+            (embedding, model)
         }))
     }
 
     /// Gets the distinct model names used for embeddings in a buffer.
     ///
     /// Returns the set of model names used to generate embeddings for
-    /// chunks belonging to the specified buffer.
+    /// chunks belonging to the specified buffer, sorted by name.
     ///
     /// # Errors
     ///
@@ -704,6 +1241,7 @@ impl SqliteStorage {
                 FROM chunk_embeddings ce
                 JOIN chunks c ON ce.chunk_id = c.id
                 WHERE c.buffer_id = ? AND ce.model_name IS NOT NULL
+                ORDER BY ce.model_name
                 ",
             )
             .map_err(StorageError::from)?;
@@ -791,62 +1329,251 @@ impl SqliteStorage {
         Ok(())
     }
 
-    /// Deletes the embedding for a chunk.
+    /// Looks up a cached embedding by content hash and model name.
     ///
     /// # Errors
     ///
-    /// Returns an error if deletion fails.
-    pub fn delete_embedding(&mut self, chunk_id: i64) -> Result<()> {
-        self.conn
-            .execute(
-                "DELETE FROM chunk_embeddings WHERE chunk_id = ?",
-                params![chunk_id],
+    /// Returns an error if the query fails.
+    pub fn get_cached_embedding(&self, hash: &str, model_name: &str) -> Result<Option<Vec<f32>>> {
+        let result: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT vector FROM embedding_cache WHERE hash = ? AND model = ?",
+                params![hash, model_name],
+                |row| row.get(0),
             )
+            .optional()
             .map_err(StorageError::from)?;
-        Ok(())
+
+        Ok(result.map(|bytes| {
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect()
+        }))
     }
 
-    /// Performs FTS5 BM25 full-text search.
-    ///
-    /// Returns chunk IDs and their BM25 scores (lower is better match).
+    /// Stores embeddings in the content-hash cache in a single transaction.
     ///
     /// # Arguments
     ///
-    /// * `query` - The search query (supports FTS5 query syntax).
-    /// * `limit` - Maximum number of results to return.
+    /// * `entries` - Vector of (`content_hash`, embedding) pairs.
+    /// * `model_name` - Model that produced the embeddings.
     ///
     /// # Errors
     ///
-    /// Returns an error if the search fails.
+    /// Returns an error if storage fails.
     #[allow(clippy::cast_possible_wrap)]
-    pub fn search_fts(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
-        // FTS5 bm25() returns negative scores, more negative = better match
-        // We negate it so higher scores = better match
+    pub fn cache_embeddings_batch(
+        &mut self,
+        entries: &[(String, Vec<f32>)],
+        model_name: &str,
+    ) -> Result<()> {
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+        let now = Self::now();
 
-        // Convert space-separated terms to OR query for more forgiving search
-        // Each term is quoted to escape FTS5 special characters (?, *, ^, etc.)
-        // "CLI tool?" becomes '"CLI" OR "tool?"' so special chars are treated as literals
-        let fts_query = query
-            .split_whitespace()
-            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
-            .collect::<Vec<_>>()
+        {
+            let mut stmt = tx
+                .prepare(
+                    r"
+                    INSERT OR REPLACE INTO embedding_cache (hash, model, vector, dimensions, created_at)
+                    VALUES (?, ?, ?, ?, ?)
+                ",
+                )
+                .map_err(StorageError::from)?;
+
+            for (hash, embedding) in entries {
+                let bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+                stmt.execute(params![
+                    hash,
+                    model_name,
+                    bytes,
+                    embedding.len() as i64,
+                    now
+                ])
+                .map_err(StorageError::from)?;
+            }
+        }
+
+        tx.commit().map_err(StorageError::from)?;
+        Ok(())
+    }
+
+    /// Deletes embedding cache entries whose key matches no stored chunk.
+    ///
+    /// Chunks of trashed buffers still count as stored, so restoring a
+    /// buffer can reuse its cached vectors.
+    ///
+    /// Returns the number of entries deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query or deletion fails.
+    pub fn prune_embedding_cache(&mut self) -> Result<usize> {
+        let mut live = std::collections::HashSet::new();
+        {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT content FROM chunks")
+                .map_err(StorageError::from)?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(StorageError::from)?;
+            for row in rows {
+                live.insert(embedding_cache_key(&row.map_err(StorageError::from)?));
+            }
+        }
+
+        let stale: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT DISTINCT hash FROM embedding_cache")
+                .map_err(StorageError::from)?;
+            stmt.query_map([], |row| row.get::<_, String>(0))
+                .map_err(StorageError::from)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(StorageError::from)?
+                .into_iter()
+                .filter(|hash| !live.contains(hash))
+                .collect()
+        };
+
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+        let mut deleted = 0;
+        {
+            let mut stmt = tx
+                .prepare("DELETE FROM embedding_cache WHERE hash = ?")
+                .map_err(StorageError::from)?;
+            for hash in &stale {
+                deleted += stmt.execute(params![hash]).map_err(StorageError::from)?;
+            }
+        }
+        tx.commit().map_err(StorageError::from)?;
+        Ok(deleted)
+    }
+
+    /// Deletes the embedding for a chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deletion fails.
+    pub fn delete_embedding(&mut self, chunk_id: i64) -> Result<()> {
+        self.conn
+            .execute(
+                "DELETE FROM chunk_embeddings WHERE chunk_id = ?",
+                params![chunk_id],
+            )
+            .map_err(StorageError::from)?;
+        Ok(())
+    }
+
+    /// Performs FTS5 BM25 full-text search.
+    ///
+    /// Returns chunk IDs and their negated FTS5 `bm25()` rank (higher is
+    /// better match). Terms are stemmed with the Porter tokenizer, so
+    /// "running" matches "run"; a trailing `*` makes a term a prefix query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Whitespace-separated search terms (OR-ed together).
+    /// * `limit` - Maximum number of results to return.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails.
+    pub fn search_fts(&self, query: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
+        // Convert space-separated terms to OR query for more forgiving search
+        // Each term is quoted to escape FTS5 special characters (?, *, ^, etc.)
+        // "CLI tool?" becomes '"CLI" OR "tool?"' so special chars are treated as literals
+        // A trailing '*' is kept outside the quotes as an FTS5 prefix query: "auth"*
+        let fts_query = query
+            .split_whitespace()
+            .map(|term| match term.strip_suffix('*') {
+                Some(prefix) if !prefix.is_empty() => {
+                    format!("\"{}\"*", prefix.replace('"', "\"\""))
+                }
+                _ => format!("\"{}\"", term.replace('"', "\"\"")),
+            })
+            .collect::<Vec<_>>()
             .join(" OR ");
 
-        let mut stmt = self
-            .conn
-            .prepare(
-                r"
+        self.search_fts_match(&fts_query, limit, &[])
+    }
+
+    /// Performs FTS5 BM25 search with a raw `MATCH` expression.
+    ///
+    /// Unlike [`Self::search_fts`], the expression is passed to FTS5 as-is,
+    /// so phrase, `AND`/`OR`/`NOT` and prefix syntax are all available.
+    ///
+    /// # Arguments
+    ///
+    /// * `fts_query` - An FTS5 query expression.
+    /// * `limit` - Maximum number of results to return.
+    /// * `buffer_ids` - Restrict results to these buffers (empty = all
+    ///   buffers). Buffers in the trash are always excluded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is invalid or the search fails.
+    pub fn search_fts_match(
+        &self,
+        fts_query: &str,
+        limit: usize,
+        buffer_ids: &[i64],
+    ) -> Result<Vec<(i64, f64)>> {
+        if buffer_ids.is_empty() {
+            let filter = format!("AND rowid NOT IN ({TRASHED_CHUNK_IDS_SQL})");
+            return self.query_fts_match(fts_query, limit, &filter, &[]);
+        }
+
+        // Stay well below SQLite's bound-parameter limit. bm25() scores use
+        // statistics from the whole index, so batches merge by score.
+        let mut results = Vec::new();
+        for ids in buffer_ids.chunks(500) {
+            let filter = format!(
+                "AND rowid IN (SELECT c.id FROM chunks c JOIN buffers b ON b.id = c.buffer_id
+                 WHERE c.buffer_id IN ({}) AND b.deleted_at IS NULL)",
+                vec!["?"; ids.len()].join(", ")
+            );
+            results.extend(self.query_fts_match(fts_query, limit, &filter, ids)?);
+        }
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Runs one FTS5 `MATCH` query with an extra rowid `filter` whose
+    /// placeholders are bound to `buffer_ids`.
+    #[allow(clippy::cast_possible_wrap)]
+    fn query_fts_match(
+        &self,
+        fts_query: &str,
+        limit: usize,
+        filter: &str,
+        buffer_ids: &[i64],
+    ) -> Result<Vec<(i64, f64)>> {
+        // FTS5 bm25() returns negative scores, more negative = better match
+        // We negate it so higher scores = better match
+        let sql = format!(
+            r"
                 SELECT rowid, -bm25(chunks_fts) as score
                 FROM chunks_fts
-                WHERE chunks_fts MATCH ?
-                ORDER BY score DESC
+                WHERE chunks_fts MATCH ? {filter}
+                ORDER BY score DESC, rowid
                 LIMIT ?
-            ",
-            )
-            .map_err(StorageError::from)?;
+            "
+        );
+
+        let mut values: Vec<rusqlite::types::Value> = Vec::with_capacity(buffer_ids.len() + 2);
+        values.push(fts_query.to_string().into());
+        values.extend(buffer_ids.iter().map(|id| (*id).into()));
+        values.push((limit as i64).into());
+
+        let mut stmt = self.conn.prepare(&sql).map_err(StorageError::from)?;
 
         let results = stmt
-            .query_map(params![fts_query, limit as i64], |row| {
+            .query_map(rusqlite::params_from_iter(values), |row| {
                 Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
             })
             .map_err(StorageError::from)?
@@ -856,7 +1583,8 @@ impl SqliteStorage {
         Ok(results)
     }
 
-    /// Returns all chunk embeddings for vector similarity search.
+    /// Returns all chunk embeddings for vector similarity search, except
+    /// those of trashed buffers.
     ///
     /// # Errors
     ///
@@ -864,7 +1592,10 @@ impl SqliteStorage {
     pub fn get_all_embeddings(&self) -> Result<Vec<(i64, Vec<f32>)>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT chunk_id, embedding FROM chunk_embeddings")
+            .prepare(&format!(
+                "SELECT chunk_id, embedding FROM chunk_embeddings
+                 WHERE chunk_id NOT IN ({TRASHED_CHUNK_IDS_SQL})"
+            ))
             .map_err(StorageError::from)?;
 
         let results = stmt
@@ -884,6 +1615,103 @@ impl SqliteStorage {
         Ok(results)
     }
 
+    /// Returns the IDs of every chunk in the listed buffers.
+    ///
+    /// Buffers in the trash contribute no chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_chunk_ids(&self, buffer_ids: &[i64]) -> Result<Vec<i64>> {
+        let mut chunk_ids = Vec::new();
+        // Stay well below SQLite's bound-parameter limit
+        for ids in buffer_ids.chunks(500) {
+            let sql = format!(
+                "SELECT c.id FROM chunks c JOIN buffers b ON b.id = c.buffer_id
+                 WHERE c.buffer_id IN ({}) AND b.deleted_at IS NULL",
+                vec!["?"; ids.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql).map_err(StorageError::from)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(ids), |row| row.get::<_, i64>(0))
+                .map_err(StorageError::from)?;
+            for row in rows {
+                chunk_ids.push(row.map_err(StorageError::from)?);
+            }
+        }
+        Ok(chunk_ids)
+    }
+
+    /// Returns the content of each listed chunk, keyed by chunk ID.
+    ///
+    /// IDs that do not exist are absent from the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_chunk_contents(
+        &self,
+        chunk_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, String>> {
+        let mut contents = std::collections::HashMap::with_capacity(chunk_ids.len());
+        // Stay well below SQLite's bound-parameter limit
+        for ids in chunk_ids.chunks(500) {
+            let sql = format!(
+                "SELECT id, content FROM chunks WHERE id IN ({})",
+                vec!["?"; ids.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql).map_err(StorageError::from)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(ids), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })
+                .map_err(StorageError::from)?;
+            for row in rows {
+                let (id, content) = row.map_err(StorageError::from)?;
+                contents.insert(id, content);
+            }
+        }
+        Ok(contents)
+    }
+
+    /// Returns the embeddings of the listed chunks, keyed by chunk ID.
+    ///
+    /// Chunks without an embedding and chunks of trashed buffers are
+    /// absent from the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_embeddings(
+        &self,
+        chunk_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<f32>>> {
+        let mut embeddings = std::collections::HashMap::with_capacity(chunk_ids.len());
+        // Stay well below SQLite's bound-parameter limit
+        for ids in chunk_ids.chunks(500) {
+            let sql = format!(
+                "SELECT chunk_id, embedding FROM chunk_embeddings
+                 WHERE chunk_id IN ({}) AND chunk_id NOT IN ({TRASHED_CHUNK_IDS_SQL})",
+                vec!["?"; ids.len()].join(", ")
+            );
+            let mut stmt = self.conn.prepare(&sql).map_err(StorageError::from)?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(ids), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+                })
+                .map_err(StorageError::from)?;
+            for row in rows {
+                let (id, bytes) = row.map_err(StorageError::from)?;
+                let embedding = bytes
+                    .chunks_exact(4)
+                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                    .collect();
+                embeddings.insert(id, embedding);
+            }
+        }
+        Ok(embeddings)
+    }
+
     /// Counts chunks with embeddings.
     ///
     /// # Errors
@@ -899,6 +1727,96 @@ impl SqliteStorage {
         Ok(count as usize)
     }
 
+    /// Returns the distinct dimensions of stored embeddings, in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn embedding_dimensions(&self) -> Result<Vec<usize>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT dimensions FROM chunk_embeddings ORDER BY dimensions")
+            .map_err(StorageError::from)?;
+
+        let dimensions = stmt
+            .query_map([], |row| row.get::<_, i64>(0))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+
+        Ok(dimensions.into_iter().map(|d| d as usize).collect())
+    }
+
+    /// Deletes every embedding whose dimension differs from `dimensions`.
+    ///
+    /// Returns the number of embeddings deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deletion fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn delete_embeddings_with_other_dimensions(&mut self, dimensions: usize) -> Result<usize> {
+        let deleted = self
+            .conn
+            .execute(
+                "DELETE FROM chunk_embeddings WHERE dimensions != ?",
+                params![dimensions as i64],
+            )
+            .map_err(StorageError::from)?;
+        Ok(deleted)
+    }
+
+    /// Deletes the embeddings of one buffer whose dimension differs from
+    /// `dimensions`, leaving other buffers untouched.
+    ///
+    /// Returns the number of embeddings deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deletion fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn delete_buffer_embeddings_with_other_dimensions(
+        &mut self,
+        buffer_id: i64,
+        dimensions: usize,
+    ) -> Result<usize> {
+        let deleted = self
+            .conn
+            .execute(
+                "DELETE FROM chunk_embeddings WHERE dimensions != ?1
+                 AND chunk_id IN (SELECT id FROM chunks WHERE buffer_id = ?2)",
+                params![dimensions as i64, buffer_id],
+            )
+            .map_err(StorageError::from)?;
+        Ok(deleted)
+    }
+
+    /// Returns the IDs of buffers holding embeddings whose dimension differs
+    /// from `dimensions`, in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn buffers_with_other_dimensions(&self, dimensions: usize) -> Result<Vec<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT DISTINCT c.buffer_id FROM chunk_embeddings e
+                 JOIN chunks c ON c.id = e.chunk_id
+                 WHERE e.dimensions != ?
+                 ORDER BY c.buffer_id",
+            )
+            .map_err(StorageError::from)?;
+
+        let ids = stmt
+            .query_map(params![dimensions as i64], |row| row.get(0))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(ids)
+    }
+
     /// Checks if a chunk has an embedding.
     ///
     /// # Errors
@@ -1088,32 +2006,722 @@ impl SqliteStorage {
             model_counts,
         })
     }
-}
 
-/// Statistics about embeddings for a buffer.
-#[derive(Debug, Clone)]
-pub struct EmbeddingStats {
-    /// Total number of chunks in the buffer.
-    pub total_chunks: usize,
-    /// Number of chunks with embeddings.
-    pub embedded_chunks: usize,
-    /// Count of embeddings by model (`model_name`, count).
-    pub model_counts: Vec<(Option<String>, i64)>,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::core::ContextValue;
+    // ==================== Buffer Content ====================
 
-    fn setup() -> SqliteStorage {
-        let mut storage = SqliteStorage::in_memory().unwrap();
-        storage.init().unwrap();
-        storage
+    /// Looks up a buffer ID by name without loading the content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn find_buffer_id(&self, name: &str) -> Result<Option<i64>> {
+        let id = self
+            .conn
+            .query_row(
+                "SELECT id FROM buffers WHERE name = ? AND deleted_at IS NULL",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(id)
     }
 
-    #[test]
-    fn test_init() {
+    /// Finds the oldest live buffer whose content is identical to `content`.
+    ///
+    /// The content hash narrows the lookup through its index; size and the
+    /// content itself are compared too, so a hash collision is never reported
+    /// as a match. Returns the buffer's ID and name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn find_buffer_by_content(&self, content: &str) -> Result<Option<(i64, Option<String>)>> {
+        let buffer = self
+            .conn
+            .query_row(
+                "SELECT id, name FROM buffers
+                 WHERE content_hash = ?1 AND size = ?2 AND content = ?3
+                   AND deleted_at IS NULL
+                 ORDER BY id LIMIT 1",
+                params![content_hash(content), content.len() as i64, content],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(buffer)
+    }
+
+    /// Lists buffer IDs and names, in ID order, without loading content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_buffer_names(&self) -> Result<Vec<(i64, Option<String>)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name FROM buffers WHERE deleted_at IS NULL ORDER BY id")
+            .map_err(StorageError::from)?;
+        let names = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(names)
+    }
+
+    /// Returns the byte length of a buffer's content, or `None` if the
+    /// buffer does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn buffer_content_len(&self, buffer_id: i64) -> Result<Option<usize>> {
+        let exists = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM buffers WHERE id = ? AND deleted_at IS NULL",
+                params![buffer_id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map_err(StorageError::from)?
+            .is_some();
+        if !exists {
+            return Ok(None);
+        }
+
+        let blob = self
+            .conn
+            .blob_open(MAIN_DB, "buffers", "content", buffer_id, true)
+            .map_err(StorageError::from)?;
+        Ok(Some(blob.len()))
+    }
+
+    /// Reads up to `len` bytes of a buffer's content starting at byte
+    /// `offset`.
+    ///
+    /// Uses incremental blob I/O, so only the requested range is read into
+    /// memory. The range may split a UTF-8 character; callers must trim
+    /// partial characters at either end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not exist or the read fails.
+    pub fn read_buffer_content(
+        &self,
+        buffer_id: i64,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let blob = self
+            .conn
+            .blob_open(MAIN_DB, "buffers", "content", buffer_id, true)
+            .map_err(StorageError::from)?;
+        let end = offset.saturating_add(len).min(blob.len());
+        if offset >= end {
+            return Ok(Vec::new());
+        }
+
+        let mut bytes = vec![0; end - offset];
+        blob.read_at_exact(&mut bytes, offset)
+            .map_err(StorageError::from)?;
+        Ok(bytes)
+    }
+
+    // ==================== Buffer Trash ====================
+
+    /// Moves a buffer to the trash.
+    ///
+    /// Trashed buffers keep their chunks, embeddings and tags but are hidden
+    /// from lookups, listings and search until restored or purged. Returns
+    /// `false` if no live buffer has this ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update fails.
+    pub fn trash_buffer(&mut self, buffer_id: i64) -> Result<bool> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE buffers SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+                params![Self::now(), buffer_id],
+            )
+            .map_err(StorageError::from)?;
+        Ok(updated > 0)
+    }
+
+    /// Restores a trashed buffer.
+    ///
+    /// Returns `false` if no trashed buffer has this ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update fails.
+    pub fn restore_buffer(&mut self, buffer_id: i64) -> Result<bool> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE buffers SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+                params![buffer_id],
+            )
+            .map_err(StorageError::from)?;
+        Ok(updated > 0)
+    }
+
+    /// Lists trashed buffers as `(id, name, deleted_at)`, in ID order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_trashed_buffers(&self) -> Result<Vec<(i64, Option<String>, i64)>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT id, name, deleted_at FROM buffers WHERE deleted_at IS NOT NULL ORDER BY id",
+            )
+            .map_err(StorageError::from)?;
+        let buffers = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(buffers)
+    }
+
+    /// Permanently deletes every trashed buffer with its chunks and
+    /// embeddings, returning how many were deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn empty_trash(&mut self) -> Result<usize> {
+        // Chunks, embeddings and tags are deleted automatically via CASCADE
+        let deleted = self
+            .conn
+            .execute("DELETE FROM buffers WHERE deleted_at IS NOT NULL", [])
+            .map_err(StorageError::from)?;
+        Ok(deleted)
+    }
+
+    // ==================== Buffer Tags ====================
+
+    /// Adds tags to a buffer, ignoring tags it already carries.
+    ///
+    /// Returns the number of tags newly added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not exist or the insert fails.
+    pub fn add_buffer_tags(&mut self, buffer_id: i64, tags: &[String]) -> Result<usize> {
+        let now = Self::now();
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+        let mut added = 0;
+        for tag in tags {
+            added += tx
+                .execute(
+                    "INSERT OR IGNORE INTO buffer_tags (buffer_id, tag, created_at) VALUES (?, ?, ?)",
+                    params![buffer_id, tag, now],
+                )
+                .map_err(StorageError::from)?;
+        }
+        tx.commit().map_err(StorageError::from)?;
+        Ok(added)
+    }
+
+    /// Removes tags from a buffer.
+    ///
+    /// Returns the number of tags actually removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails.
+    pub fn remove_buffer_tags(&mut self, buffer_id: i64, tags: &[String]) -> Result<usize> {
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+        let mut removed = 0;
+        for tag in tags {
+            removed += tx
+                .execute(
+                    "DELETE FROM buffer_tags WHERE buffer_id = ? AND tag = ?",
+                    params![buffer_id, tag],
+                )
+                .map_err(StorageError::from)?;
+        }
+        tx.commit().map_err(StorageError::from)?;
+        Ok(removed)
+    }
+
+    /// Returns a buffer's tags in sorted order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_buffer_tags(&self, buffer_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM buffer_tags WHERE buffer_id = ? ORDER BY tag")
+            .map_err(StorageError::from)?;
+        let tags = stmt
+            .query_map(params![buffer_id], |row| row.get(0))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(tags)
+    }
+
+    /// Lists every tag in use with the number of buffers carrying it.
+    ///
+    /// Buffers in the trash are not counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT t.tag, COUNT(*) FROM buffer_tags t
+                 JOIN buffers b ON b.id = t.buffer_id
+                 WHERE b.deleted_at IS NULL
+                 GROUP BY t.tag ORDER BY t.tag",
+            )
+            .map_err(StorageError::from)?;
+        let tags = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(tags)
+    }
+
+    /// Returns the IDs of buffers carrying `tag`, in ID order.
+    ///
+    /// Buffers in the trash are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn buffer_ids_with_tag(&self, tag: &str) -> Result<Vec<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT t.buffer_id FROM buffer_tags t
+                 JOIN buffers b ON b.id = t.buffer_id
+                 WHERE t.tag = ? AND b.deleted_at IS NULL
+                 ORDER BY t.buffer_id",
+            )
+            .map_err(StorageError::from)?;
+        let ids = stmt
+            .query_map(params![tag], |row| row.get(0))
+            .map_err(StorageError::from)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(StorageError::from)?;
+        Ok(ids)
+    }
+
+    // ==================== Stable Chunk Keys ====================
+
+    /// Returns the stable key of a chunk (see [`Chunk::stable_key`]).
+    ///
+    /// Returns `None` if the chunk does not exist or its buffer has no
+    /// content hash (buffers stored before hashes were recorded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn chunk_stable_key(&self, chunk_id: i64) -> Result<Option<String>> {
+        let row: Option<(Option<String>, i64, i64)> = self
+            .conn
+            .query_row(
+                "SELECT b.content_hash, c.byte_start, c.byte_end
+                 FROM chunks c JOIN buffers b ON b.id = c.buffer_id
+                 WHERE c.id = ?",
+                params![chunk_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(row.and_then(|(hash, start, end)| {
+            hash.map(|hash| Chunk::stable_key(&hash, &(start as usize..end as usize)))
+        }))
+    }
+
+    /// Finds the chunk with a stable key.
+    ///
+    /// If several live buffers hold identical content, the most recently
+    /// stored chunk wins. Returns `None` if `key` is malformed or matches no
+    /// chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn find_chunk_by_stable_key(&self, key: &str) -> Result<Option<i64>> {
+        let Some((hash, range)) = Chunk::parse_stable_key(key) else {
+            return Ok(None);
+        };
+        let id = self
+            .conn
+            .query_row(
+                "SELECT c.id FROM chunks c JOIN buffers b ON b.id = c.buffer_id
+                 WHERE b.content_hash = ? AND b.deleted_at IS NULL
+                   AND c.byte_start = ? AND c.byte_end = ?
+                 ORDER BY c.id DESC LIMIT 1",
+                params![hash, range.start as i64, range.end as i64],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(id)
+    }
+
+    // ==================== Metadata ====================
+
+    /// Gets a value from the metadata key-value store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn get_metadata(&self, key: &str) -> Result<Option<String>> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = ?",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(value)
+    }
+
+    /// Sets a value in the metadata key-value store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be stored.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<()> {
+        let now = Self::now();
+        self.conn
+            .execute(
+                r"
+                INSERT INTO metadata (key, value, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?3)
+                ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+                ",
+                params![key, value, now],
+            )
+            .map_err(StorageError::from)?;
+        Ok(())
+    }
+
+    // ==================== Maintenance ====================
+
+    /// Returns the database size in bytes (page count times page size).
+    ///
+    /// Pages still sitting in the WAL file are included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn database_size(&self) -> Result<u64> {
+        let size: i64 = self
+            .conn
+            .query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(StorageError::from)?;
+        Ok(size as u64)
+    }
+
+    /// Compacts the database.
+    ///
+    /// Prunes embedding cache entries no stored chunk uses (see
+    /// [`Self::prune_embedding_cache`]), rebuilds the FTS index from the
+    /// chunks table (dropping stale entries), optionally refreshes query
+    /// planner statistics with `ANALYZE`, then runs `VACUUM` and truncates
+    /// the WAL file.
+    ///
+    /// # Arguments
+    ///
+    /// * `analyze` - Also run `ANALYZE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any step fails.
+    pub fn vacuum(&mut self, analyze: bool) -> Result<VacuumStats> {
+        let size_before = self.database_size()?;
+
+        let cache_pruned = self.prune_embedding_cache()?;
+        self.conn
+            .execute("INSERT INTO chunks_fts(chunks_fts) VALUES('rebuild')", [])
+            .map_err(StorageError::from)?;
+        if analyze {
+            self.conn
+                .execute_batch("ANALYZE;")
+                .map_err(StorageError::from)?;
+        }
+        self.conn
+            .execute_batch("VACUUM;")
+            .map_err(StorageError::from)?;
+        if self.path.is_some() {
+            let _: (i64, i64, i64) = self
+                .conn
+                .query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })
+                .map_err(StorageError::from)?;
+        }
+
+        Ok(VacuumStats {
+            size_before,
+            size_after: self.database_size()?,
+            analyzed: analyze,
+            cache_pruned,
+        })
+    }
+
+    /// Replaces a buffer's chunks, keeping the rows of chunks whose content
+    /// is unchanged.
+    ///
+    /// Each new chunk is matched, in order, to an existing chunk with
+    /// identical content. Matched rows keep their ID and embedding and are
+    /// updated with the new byte range, index and metadata; unmatched new
+    /// chunks are inserted and unmatched old chunks are deleted along with
+    /// their embeddings. The whole replacement runs in one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any statement fails.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn replace_chunks_incremental(
+        &mut self,
+        buffer_id: i64,
+        chunks: &[Chunk],
+    ) -> Result<ChunkReuseStats> {
+        let old_chunks = self.get_chunks(buffer_id)?;
+        let mut by_content: std::collections::HashMap<&str, std::collections::VecDeque<i64>> =
+            std::collections::HashMap::new();
+        for chunk in &old_chunks {
+            if let Some(id) = chunk.id {
+                by_content
+                    .entry(chunk.content.as_str())
+                    .or_default()
+                    .push_back(id);
+            }
+        }
+
+        let tx = self.conn.transaction().map_err(StorageError::from)?;
+        let now = Self::now();
+        let mut stats = ChunkReuseStats::default();
+
+        {
+            let mut update = tx
+                .prepare(
+                    r"
+                UPDATE chunks SET
+                    byte_start = ?, byte_end = ?, chunk_index = ?, strategy = ?,
+                    token_count = ?, line_start = ?, line_end = ?, has_overlap = ?,
+                    content_hash = ?, custom_metadata = ?
+                WHERE id = ?
+            ",
+                )
+                .map_err(StorageError::from)?;
+            let mut insert = tx
+                .prepare(
+                    r"
+                INSERT INTO chunks (
+                    buffer_id, content, byte_start, byte_end, chunk_index,
+                    strategy, token_count, line_start, line_end, has_overlap,
+                    content_hash, custom_metadata, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+                )
+                .map_err(StorageError::from)?;
+
+            for chunk in chunks {
+                let (line_start, line_end) = chunk
+                    .metadata
+                    .line_range
+                    .as_ref()
+                    .map_or((None, None), |r| (Some(r.start as i64), Some(r.end as i64)));
+                let reused = by_content
+                    .get_mut(chunk.content.as_str())
+                    .and_then(std::collections::VecDeque::pop_front);
+
+                if let Some(id) = reused {
+                    update
+                        .execute(params![
+                            chunk.byte_range.start as i64,
+                            chunk.byte_range.end as i64,
+                            chunk.index as i64,
+                            chunk.metadata.strategy,
+                            chunk.metadata.token_count.map(|c| c as i64),
+                            line_start,
+                            line_end,
+                            i64::from(chunk.metadata.has_overlap),
+                            chunk.metadata.content_hash,
+                            chunk.metadata.custom,
+                            id,
+                        ])
+                        .map_err(StorageError::from)?;
+                    stats.reused += 1;
+                } else {
+                    insert
+                        .execute(params![
+                            buffer_id,
+                            chunk.content,
+                            chunk.byte_range.start as i64,
+                            chunk.byte_range.end as i64,
+                            chunk.index as i64,
+                            chunk.metadata.strategy,
+                            chunk.metadata.token_count.map(|c| c as i64),
+                            line_start,
+                            line_end,
+                            i64::from(chunk.metadata.has_overlap),
+                            chunk.metadata.content_hash,
+                            chunk.metadata.custom,
+                            now,
+                        ])
+                        .map_err(StorageError::from)?;
+                    stats.added += 1;
+                }
+            }
+
+            // FTS rows are removed by the chunks_ad trigger
+            for id in by_content.into_values().flatten() {
+                tx.execute(
+                    "DELETE FROM chunk_embeddings WHERE chunk_id = ?",
+                    params![id],
+                )
+                .map_err(StorageError::from)?;
+                tx.execute("DELETE FROM chunks WHERE id = ?", params![id])
+                    .map_err(StorageError::from)?;
+                stats.removed += 1;
+            }
+
+            tx.execute(
+                "UPDATE buffers SET chunk_count = ? WHERE id = ?",
+                params![chunks.len() as i64, buffer_id],
+            )
+            .map_err(StorageError::from)?;
+        }
+
+        tx.commit().map_err(StorageError::from)?;
+        Ok(stats)
+    }
+}
+
+/// Outcome of [`SqliteStorage::replace_chunks_incremental`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkReuseStats {
+    /// Existing chunks kept (with their embeddings) because their content
+    /// was unchanged.
+    pub reused: usize,
+    /// New chunks inserted.
+    pub added: usize,
+    /// Old chunks deleted.
+    pub removed: usize,
+}
+
+/// A fixed-size pool of read-only handles on one database.
+///
+/// Created by [`SqliteStorage::open_read_pool`]. The pool is `Sync`, so one
+/// pool can serve lookups from many threads; at most [`Self::size`] of them
+/// hold a handle at once and the rest wait for one to be returned.
+pub struct ReadPool {
+    handles: Vec<Mutex<SqliteStorage>>,
+    next: AtomicUsize,
+}
+
+impl std::fmt::Debug for ReadPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadPool")
+            .field("size", &self.handles.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReadPool {
+    /// Checks out a handle, waiting if every handle is in use.
+    ///
+    /// The handle returns to the pool when the guard is dropped.
+    pub fn get(&self) -> MutexGuard<'_, SqliteStorage> {
+        if let Some(guard) = self.try_get() {
+            return guard;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.handles.len();
+        self.handles[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Checks out a handle if one is free, without waiting.
+    pub fn try_get(&self) -> Option<MutexGuard<'_, SqliteStorage>> {
+        self.handles
+            .iter()
+            .find_map(|handle| match handle.try_lock() {
+                Ok(guard) => Some(guard),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            })
+    }
+
+    /// Returns the number of handles in the pool.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.handles.len()
+    }
+}
+
+/// Result of compacting the database with [`SqliteStorage::vacuum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumStats {
+    /// Database size in bytes before compaction.
+    pub size_before: u64,
+    /// Database size in bytes after compaction.
+    pub size_after: u64,
+    /// Whether planner statistics were refreshed.
+    pub analyzed: bool,
+    /// Number of unused embedding cache entries deleted.
+    pub cache_pruned: usize,
+}
+
+impl VacuumStats {
+    /// Returns the number of bytes reclaimed (0 if the database grew).
+    #[must_use]
+    pub const fn reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Statistics about embeddings for a buffer.
+#[derive(Debug, Clone)]
+pub struct EmbeddingStats {
+    /// Total number of chunks in the buffer.
+    pub total_chunks: usize,
+    /// Number of chunks with embeddings.
+    pub embedded_chunks: usize,
+    /// Count of embeddings by model (`model_name`, count).
+    pub model_counts: Vec<(Option<String>, i64)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ContextValue;
+
+    fn setup() -> SqliteStorage {
+        let mut storage = SqliteStorage::in_memory().unwrap();
+        storage.init().unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_init() {
         let mut storage = SqliteStorage::in_memory().unwrap();
         assert!(storage.init().is_ok());
         assert!(storage.is_initialized().unwrap());
@@ -1126,6 +2734,465 @@ mod tests {
         assert!(storage.init().is_ok()); // Second init should be fine
     }
 
+    /// Creates a database with the original v1 schema and one chunk.
+    fn setup_v1() -> SqliteStorage {
+        let storage = SqliteStorage::in_memory().unwrap();
+        storage
+            .conn
+            .execute_batch(
+                r"
+            CREATE TABLE schema_info (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            CREATE TABLE context (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                data TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE buffers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT,
+                source_path TEXT,
+                content TEXT NOT NULL,
+                content_type TEXT,
+                content_hash TEXT,
+                size INTEGER NOT NULL,
+                line_count INTEGER,
+                chunk_count INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                buffer_id INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                byte_start INTEGER NOT NULL,
+                byte_end INTEGER NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                strategy TEXT,
+                token_count INTEGER,
+                line_start INTEGER,
+                line_end INTEGER,
+                has_overlap INTEGER NOT NULL DEFAULT 0,
+                content_hash TEXT,
+                custom_metadata TEXT,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (buffer_id) REFERENCES buffers(id) ON DELETE CASCADE
+            );
+            CREATE TABLE metadata (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            INSERT INTO schema_info (key, value) VALUES ('version', '1');
+            INSERT INTO buffers (name, content, size, chunk_count, created_at, updated_at)
+                VALUES ('old.txt', 'legacy migration content', 24, 1, 0, 0);
+            INSERT INTO chunks (buffer_id, content, byte_start, byte_end, chunk_index, created_at)
+                VALUES (1, 'legacy migration content', 0, 24, 0, 0);
+        ",
+            )
+            .unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_pending_migrations_v1() {
+        let storage = setup_v1();
+        let pending = storage.pending_migrations().unwrap();
+        assert_eq!(pending.first().map(|m| m.from_version), Some(1));
+        assert_eq!(
+            pending.last().map(|m| m.to_version),
+            Some(CURRENT_SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_init_migrates_v1_database() {
+        let mut storage = setup_v1();
+        storage.init().unwrap();
+
+        assert_eq!(
+            storage.get_schema_version().unwrap(),
+            Some(CURRENT_SCHEMA_VERSION)
+        );
+        assert!(storage.pending_migrations().unwrap().is_empty());
+
+        // Existing data survives and is indexed by the v2 FTS table
+        assert_eq!(storage.get_chunks(1).unwrap().len(), 1);
+        assert_eq!(storage.search_fts("legacy", 10).unwrap().len(), 1);
+
+        let recorded: i64 = storage
+            .conn
+            .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(recorded, i64::from(CURRENT_SCHEMA_VERSION - 1));
+
+        // Running again is a no-op
+        assert!(storage.migrate().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migration_recomputes_buffer_hashes() {
+        // A v3 database whose buffer hash came from an older algorithm
+        let mut storage = setup_v1();
+        for migration in crate::storage::schema::MIGRATIONS
+            .iter()
+            .filter(|m| m.to_version <= 3)
+        {
+            storage.conn.execute_batch(migration.sql).unwrap();
+        }
+        storage.conn.execute(SET_VERSION_SQL, params!["3"]).unwrap();
+        storage
+            .conn
+            .execute("UPDATE buffers SET content_hash = '9f86d081884c7d65'", [])
+            .unwrap();
+        storage.init().unwrap();
+
+        let content = "legacy migration content";
+        let legacy = storage.find_buffer_by_content(content).unwrap();
+        assert_eq!(legacy.map(|(id, _)| id), Some(1));
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_content(content.to_string()))
+            .unwrap();
+        storage
+            .add_chunks(
+                buffer_id,
+                &[Chunk::new(buffer_id, content.to_string(), 0..24, 0)],
+            )
+            .unwrap();
+        let reloaded = storage.get_chunks(buffer_id).unwrap()[0].id.unwrap();
+
+        let key = storage.chunk_stable_key(1).unwrap().unwrap();
+        assert!(key.starts_with(&content_hash(content)));
+        assert_eq!(storage.chunk_stable_key(reloaded).unwrap(), Some(key));
+    }
+
+    #[test]
+    fn test_search_fts_match_batches_buffer_ids() {
+        let mut storage = setup();
+        for (i, content) in ["needle", "needle needle", "needle haystack"]
+            .iter()
+            .enumerate()
+        {
+            let buffer_id = storage
+                .add_buffer(&Buffer::from_named(format!("b{i}"), (*content).to_string()))
+                .unwrap();
+            storage
+                .add_chunks(
+                    buffer_id,
+                    &[Chunk::new(
+                        buffer_id,
+                        (*content).to_string(),
+                        0..content.len(),
+                        0,
+                    )],
+                )
+                .unwrap();
+        }
+
+        // More IDs than SQLite accepts as bound parameters in one statement
+        let ids: Vec<i64> = (1..=40_000).collect();
+        let all = storage.search_fts_match("needle", 10, &[]).unwrap();
+        let batched = storage.search_fts_match("needle", 10, &ids).unwrap();
+        assert_eq!(batched.len(), 3);
+        assert_eq!(batched, all);
+
+        let top = storage.search_fts_match("needle", 2, &ids).unwrap();
+        assert_eq!(top, all[..2]);
+    }
+
+    #[test]
+    fn test_find_buffer_by_content_ignores_hash_collisions() {
+        let mut storage = setup();
+        let original = storage
+            .add_buffer(&Buffer::from_named("a".to_string(), "alpha".to_string()))
+            .unwrap();
+        let colliding = storage
+            .add_buffer(&Buffer::from_named("b".to_string(), "bravo".to_string()))
+            .unwrap();
+        storage
+            .conn
+            .execute(
+                "UPDATE buffers SET content_hash = ? WHERE id = ?",
+                params![content_hash("alpha"), colliding],
+            )
+            .unwrap();
+
+        let found = storage.find_buffer_by_content("alpha").unwrap();
+        assert_eq!(found, Some((original, Some("a".to_string()))));
+
+        // Only the colliding buffer is left with the hash of "alpha"
+        storage.trash_buffer(original).unwrap();
+        assert_eq!(storage.find_buffer_by_content("alpha").unwrap(), None);
+    }
+
+    #[test]
+    fn test_buffer_tags() {
+        let mut storage = setup();
+        let a = storage
+            .add_buffer(&Buffer::from_named("a".to_string(), "a".to_string()))
+            .unwrap();
+        let b = storage
+            .add_buffer(&Buffer::from_named("b".to_string(), "b".to_string()))
+            .unwrap();
+
+        let tags = vec!["project:alpha".to_string(), "logs".to_string()];
+        assert_eq!(storage.add_buffer_tags(a, &tags).unwrap(), 2);
+        assert_eq!(storage.add_buffer_tags(a, &tags).unwrap(), 0);
+        storage
+            .add_buffer_tags(b, &["project:alpha".to_string()])
+            .unwrap();
+
+        assert_eq!(
+            storage.get_buffer_tags(a).unwrap(),
+            vec!["logs", "project:alpha"]
+        );
+        assert_eq!(
+            storage.buffer_ids_with_tag("project:alpha").unwrap(),
+            vec![a, b]
+        );
+        assert_eq!(
+            storage.list_tags().unwrap(),
+            vec![("logs".to_string(), 1), ("project:alpha".to_string(), 2)]
+        );
+
+        assert_eq!(storage.remove_buffer_tags(a, &tags).unwrap(), 2);
+        assert!(storage.get_buffer_tags(a).unwrap().is_empty());
+
+        // Tags go away with their buffer
+        storage.delete_buffer(b).unwrap();
+        assert!(storage.list_tags().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_metadata_get_set() {
+        let mut storage = setup();
+        assert_eq!(storage.get_metadata("embedding_dimensions").unwrap(), None);
+
+        storage
+            .set_metadata("embedding_dimensions", "1024")
+            .unwrap();
+        storage.set_metadata("embedding_dimensions", "384").unwrap();
+        assert_eq!(
+            storage.get_metadata("embedding_dimensions").unwrap(),
+            Some("384".to_string())
+        );
+    }
+
+    #[test]
+    fn test_delete_embeddings_with_other_dimensions() {
+        let mut storage = setup();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_content("a b".to_string()))
+            .unwrap();
+        let chunks = vec![
+            Chunk::new(buffer_id, "a".to_string(), 0..1, 0),
+            Chunk::new(buffer_id, "b".to_string(), 2..3, 1),
+        ];
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let ids: Vec<i64> = storage
+            .get_chunks(buffer_id)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+        storage.store_embedding(ids[0], &[1.0, 0.0], None).unwrap();
+        storage
+            .store_embedding(ids[1], &[1.0, 0.0, 0.0], None)
+            .unwrap();
+        assert_eq!(storage.embedding_dimensions().unwrap(), vec![2, 3]);
+
+        assert_eq!(
+            storage.delete_embeddings_with_other_dimensions(3).unwrap(),
+            1
+        );
+        assert_eq!(storage.embedding_dimensions().unwrap(), vec![3]);
+        assert!(storage.has_embedding(ids[1]).unwrap());
+    }
+
+    #[test]
+    fn test_delete_buffer_embeddings_with_other_dimensions() {
+        let mut storage = setup();
+        let mut ids = Vec::new();
+        for content in ["a", "b"] {
+            let buffer_id = storage
+                .add_buffer(&Buffer::from_content(content.to_string()))
+                .unwrap();
+            storage
+                .add_chunks(
+                    buffer_id,
+                    &[Chunk::new(buffer_id, content.to_string(), 0..1, 0)],
+                )
+                .unwrap();
+            let chunk_id = storage.get_chunks(buffer_id).unwrap()[0].id.unwrap();
+            storage
+                .store_embedding(chunk_id, &[1.0, 0.0], None)
+                .unwrap();
+            ids.push((buffer_id, chunk_id));
+        }
+        let (first, second) = (ids[0], ids[1]);
+        assert_eq!(
+            storage.buffers_with_other_dimensions(3).unwrap(),
+            vec![first.0, second.0]
+        );
+
+        assert_eq!(
+            storage
+                .delete_buffer_embeddings_with_other_dimensions(first.0, 3)
+                .unwrap(),
+            1
+        );
+        assert!(!storage.has_embedding(first.1).unwrap());
+        assert!(storage.has_embedding(second.1).unwrap());
+        assert_eq!(
+            storage.buffers_with_other_dimensions(3).unwrap(),
+            vec![second.0]
+        );
+    }
+
+    #[test]
+    fn test_vacuum_empty_database() {
+        let mut storage = setup();
+        let stats = storage.vacuum(true).unwrap();
+        assert!(stats.analyzed);
+        assert!(stats.size_after > 0);
+        assert_eq!(
+            stats.reclaimed(),
+            stats.size_before.saturating_sub(stats.size_after)
+        );
+    }
+
+    #[test]
+    fn test_vacuum_reclaims_deleted_content() {
+        let mut storage = setup();
+        let content = "x".repeat(256 * 1024);
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named("big.txt".to_string(), content.clone()))
+            .unwrap();
+        storage
+            .add_chunks(buffer_id, &[Chunk::new(buffer_id, content, 0..262_144, 0)])
+            .unwrap();
+        storage.delete_buffer(buffer_id).unwrap();
+
+        let stats = storage.vacuum(false).unwrap();
+        assert!(stats.reclaimed() > 0);
+        assert!(storage.search_fts("x", 10).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "vec")]
+    #[test]
+    fn test_vector_index_backfills_and_tracks_embeddings() {
+        let mut storage = setup();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named(
+                "v.txt".to_string(),
+                "a b c".to_string(),
+            ))
+            .unwrap();
+        let chunks: Vec<Chunk> = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Chunk::new(buffer_id, (*text).to_string(), i * 2..i * 2 + 1, i))
+            .collect();
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let ids: Vec<i64> = storage
+            .get_chunks(buffer_id)
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+
+        // Searching never builds the index
+        storage.store_embedding(ids[0], &[1.0, 0.0], None).unwrap();
+        storage.store_embedding(ids[1], &[0.0, 1.0], None).unwrap();
+        assert!(storage.nearest_chunk_ids(&[1.0, 0.1], 2).unwrap().is_none());
+
+        // Embeddings stored before the index exists are back-filled
+        assert_eq!(storage.sync_vector_index().unwrap(), Some(2));
+        assert_eq!(storage.vector_index_dimensions().unwrap(), Some(2));
+        let nearest = storage.nearest_chunk_ids(&[1.0, 0.1], 2).unwrap().unwrap();
+        assert_eq!(nearest, vec![ids[0], ids[1]]);
+
+        // Later writes and deletes are mirrored
+        storage.store_embedding(ids[2], &[1.0, 0.05], None).unwrap();
+        storage.delete_embedding(ids[0]).unwrap();
+        let nearest = storage.nearest_chunk_ids(&[1.0, 0.1], 3).unwrap().unwrap();
+        assert_eq!(nearest, vec![ids[2], ids[1]]);
+
+        assert!(
+            storage
+                .nearest_chunk_ids(&[1.0, 0.0], MAX_VECTOR_QUERY_K + 1)
+                .unwrap()
+                .is_none()
+        );
+
+        // A dimension change is picked up by the next migration pass
+        storage.delete_embedding(ids[1]).unwrap();
+        storage.delete_embedding(ids[2]).unwrap();
+        storage
+            .store_embedding(ids[0], &[1.0, 0.0, 0.0], None)
+            .unwrap();
+        assert!(
+            storage
+                .nearest_chunk_ids(&[1.0, 0.0, 0.0], 1)
+                .unwrap()
+                .is_none()
+        );
+        storage.migrate().unwrap();
+        assert_eq!(storage.vector_index_dimensions().unwrap(), Some(3));
+        let nearest = storage
+            .nearest_chunk_ids(&[1.0, 0.0, 0.0], 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(nearest, vec![ids[0]]);
+    }
+
+    #[test]
+    fn test_vacuum_prunes_unused_embedding_cache() {
+        let mut storage = setup();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_named("a.txt".to_string(), "kept".to_string()))
+            .unwrap();
+        storage
+            .add_chunks(
+                buffer_id,
+                &[Chunk::new(buffer_id, "kept".to_string(), 0..4, 0)],
+            )
+            .unwrap();
+        let entries = vec![
+            (embedding_cache_key("kept"), vec![1.0, 0.0]),
+            (embedding_cache_key("gone"), vec![0.0, 1.0]),
+        ];
+        storage.cache_embeddings_batch(&entries, "model").unwrap();
+
+        let stats = storage.vacuum(false).unwrap();
+        assert_eq!(stats.cache_pruned, 1);
+        assert!(
+            storage
+                .get_cached_embedding(&embedding_cache_key("kept"), "model")
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            storage
+                .get_cached_embedding(&embedding_cache_key("gone"), "model")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_fresh_init_has_no_pending_migrations() {
+        let storage = setup();
+        assert!(storage.pending_migrations().unwrap().is_empty());
+        assert_eq!(
+            storage.stats().unwrap().schema_version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+
     #[test]
     fn test_context_crud() {
         let mut storage = setup();
@@ -1185,6 +3252,48 @@ mod tests {
         assert!(storage.get_buffer(id).unwrap().is_none());
     }
 
+    #[test]
+    fn test_read_buffer_content() {
+        let mut storage = setup();
+        let buffer = Buffer::from_named("text".to_string(), "héllo world".to_string());
+        let id = storage.add_buffer(&buffer).unwrap();
+
+        assert_eq!(storage.find_buffer_id("text").unwrap(), Some(id));
+        assert_eq!(storage.find_buffer_id("missing").unwrap(), None);
+        assert_eq!(
+            storage.list_buffer_names().unwrap(),
+            vec![(id, Some("text".to_string()))]
+        );
+
+        // Lengths and offsets are in bytes, not characters
+        assert_eq!(storage.buffer_content_len(id).unwrap(), Some(12));
+        assert_eq!(storage.buffer_content_len(id + 1).unwrap(), None);
+        assert_eq!(
+            storage.read_buffer_content(id, 0, 3).unwrap(),
+            "hé".as_bytes()
+        );
+        assert_eq!(storage.read_buffer_content(id, 7, 100).unwrap(), b"world");
+        assert!(storage.read_buffer_content(id, 50, 10).unwrap().is_empty());
+        assert!(storage.read_buffer_content(id + 1, 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_buffer_page_offsets_round_trip() {
+        let mut storage = setup();
+
+        let mut buffer = Buffer::from_named("doc.pdf".to_string(), "one\u{c}two".to_string());
+        buffer.metadata.page_offsets = Some(vec![0, 4]);
+        let id = storage.add_buffer(&buffer).unwrap();
+
+        let mut loaded = storage.get_buffer(id).unwrap().unwrap();
+        assert_eq!(loaded.metadata.page_offsets, Some(vec![0, 4]));
+
+        loaded.metadata.page_offsets = None;
+        storage.update_buffer(&loaded).unwrap();
+        let listed = storage.list_buffers().unwrap();
+        assert_eq!(listed[0].metadata.page_offsets, None);
+    }
+
     #[test]
     fn test_chunk_crud() {
         let mut storage = setup();
@@ -1219,6 +3328,158 @@ mod tests {
         assert_eq!(storage.chunk_count(buffer_id).unwrap(), 0);
     }
 
+    #[test]
+    fn test_chunk_custom_metadata_round_trip() {
+        let mut storage = setup();
+        let buffer_id = storage
+            .add_buffer(&Buffer::from_content("a b".to_string()))
+            .unwrap();
+        let metadata = serde_json::json!({
+            "heading_path": ["Guide", "Install"],
+            "page": 2,
+            "nested": { "symbols": ["main"], "score": 0.5, "flag": null }
+        });
+        let mut chunk = Chunk::new(buffer_id, "a".to_string(), 0..1, 0);
+        chunk.set_custom_metadata(&metadata);
+        storage
+            .add_chunks(
+                buffer_id,
+                &[chunk, Chunk::new(buffer_id, "b".to_string(), 2..3, 1)],
+            )
+            .unwrap();
+
+        let chunks = storage.get_chunks(buffer_id).unwrap();
+        assert_eq!(chunks[0].custom_metadata(), Some(metadata.clone()));
+        assert!(chunks[1].custom_metadata().is_none());
+        let stored = storage.get_chunk(chunks[0].id.unwrap()).unwrap().unwrap();
+        assert_eq!(stored.custom_metadata(), Some(metadata));
+    }
+
+    #[test]
+    fn test_stable_chunk_keys_survive_reload() {
+        let mut storage = setup();
+        let load = |storage: &mut SqliteStorage| {
+            let buffer_id = storage
+                .add_buffer(&Buffer::from_content("alpha beta".to_string()))
+                .unwrap();
+            storage
+                .add_chunks(
+                    buffer_id,
+                    &[
+                        Chunk::new(buffer_id, "alpha ".to_string(), 0..6, 0),
+                        Chunk::new(buffer_id, "beta".to_string(), 6..10, 1),
+                    ],
+                )
+                .unwrap();
+            (buffer_id, storage.get_chunks(buffer_id).unwrap())
+        };
+
+        let (first_buffer, first) = load(&mut storage);
+        let first_id = first[1].id.unwrap();
+        let key = storage.chunk_stable_key(first_id).unwrap().unwrap();
+        storage.delete_buffer(first_buffer).unwrap();
+
+        let (_, second) = load(&mut storage);
+        let second_id = second[1].id.unwrap();
+        assert_ne!(first_id, second_id);
+        assert_eq!(
+            storage.chunk_stable_key(second_id).unwrap(),
+            Some(key.clone())
+        );
+        assert_eq!(
+            storage.find_chunk_by_stable_key(&key).unwrap(),
+            Some(second_id)
+        );
+
+        assert_eq!(storage.find_chunk_by_stable_key("bogus").unwrap(), None);
+        assert_eq!(storage.chunk_stable_key(9999).unwrap(), None);
+    }
+
+    #[test]
+    fn test_delete_single_chunk() {
+        let mut storage = setup();
+
+        let buffer = Buffer::from_content("Hello, world!".to_string());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let chunks = vec![
+            Chunk::new(buffer_id, "Hello, ".to_string(), 0..7, 0),
+            Chunk::new(buffer_id, "world!".to_string(), 7..13, 1),
+        ];
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+
+        let loaded = storage.get_chunks(buffer_id).unwrap();
+        let chunk_id = loaded[1].id.unwrap();
+        storage
+            .store_embedding(chunk_id, &[0.1, 0.2], None)
+            .unwrap();
+
+        assert_eq!(storage.delete_chunk(chunk_id).unwrap(), buffer_id);
+        assert!(storage.get_chunk(chunk_id).unwrap().is_none());
+        assert!(storage.get_embedding(chunk_id).unwrap().is_none());
+        assert!(storage.search_fts("world", 10).unwrap().is_empty());
+        assert_eq!(storage.chunk_count(buffer_id).unwrap(), 1);
+
+        let buffer = storage.get_buffer(buffer_id).unwrap().unwrap();
+        assert_eq!(buffer.metadata.chunk_count, Some(1));
+
+        // Deleting again reports the chunk as missing
+        assert!(storage.delete_chunk(chunk_id).is_err());
+    }
+
+    #[test]
+    fn test_replace_chunks_incremental() {
+        let mut storage = setup();
+
+        let buffer = Buffer::from_content("one\n\ntwo\n\nthree".to_string());
+        let buffer_id = storage.add_buffer(&buffer).unwrap();
+        let chunks = vec![
+            Chunk::new(buffer_id, "one\n\n".to_string(), 0..5, 0),
+            Chunk::new(buffer_id, "two\n\n".to_string(), 5..10, 1),
+            Chunk::new(buffer_id, "three".to_string(), 10..15, 2),
+        ];
+        storage.add_chunks(buffer_id, &chunks).unwrap();
+        let old = storage.get_chunks(buffer_id).unwrap();
+        for chunk in &old {
+            storage
+                .store_embedding(chunk.id.unwrap(), &[0.1, 0.2], None)
+                .unwrap();
+        }
+
+        // Insert a paragraph at the front and edit the last one
+        let new_chunks = vec![
+            Chunk::new(buffer_id, "zero\n\n".to_string(), 0..6, 0),
+            Chunk::new(buffer_id, "one\n\n".to_string(), 6..11, 1),
+            Chunk::new(buffer_id, "two\n\n".to_string(), 11..16, 2),
+            Chunk::new(buffer_id, "three!".to_string(), 16..22, 3),
+        ];
+        let stats = storage
+            .replace_chunks_incremental(buffer_id, &new_chunks)
+            .unwrap();
+        assert_eq!(
+            stats,
+            ChunkReuseStats {
+                reused: 2,
+                added: 2,
+                removed: 1
+            }
+        );
+
+        let current = storage.get_chunks(buffer_id).unwrap();
+        let contents: Vec<&str> = current.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, ["zero\n\n", "one\n\n", "two\n\n", "three!"]);
+
+        // Unchanged chunks keep their ID, embedding and get new ranges
+        assert_eq!(current[1].id, old[0].id);
+        assert_eq!(current[1].byte_range, 6..11);
+        assert!(storage.has_embedding(current[2].id.unwrap()).unwrap());
+        assert!(!storage.has_embedding(current[0].id.unwrap()).unwrap());
+        assert!(storage.get_embedding(old[2].id.unwrap()).unwrap().is_none());
+
+        assert_eq!(storage.search_fts("three", 10).unwrap().len(), 1);
+        let buffer = storage.get_buffer(buffer_id).unwrap().unwrap();
+        assert_eq!(buffer.metadata.chunk_count, Some(4));
+    }
+
     #[test]
     fn test_cascade_delete() {
         let mut storage = setup();
@@ -155,6 +155,19 @@ pub trait Storage {
     /// Returns an error if deletion fails.
     fn delete_chunks(&mut self, buffer_id: i64) -> Result<()>;
 
+    /// Deletes a single chunk, including its embedding and search index entry.
+    ///
+    /// Decrements the owning buffer's chunk count.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the buffer the chunk belonged to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk does not exist or deletion fails.
+    fn delete_chunk(&mut self, id: i64) -> Result<i64>;
+
     /// Returns the count of chunks for a buffer.
     ///
     /// # Errors
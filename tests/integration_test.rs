@@ -173,6 +173,70 @@ fn test_storage_reset() {
     assert_eq!(stats.buffer_count, 0);
 }
 
+#[test]
+fn test_read_pool_concurrent_get_chunk() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let db_path = temp_dir.path().join("pool.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("Failed to create storage");
+    storage.init().expect("Failed to init storage");
+
+    let buffer_id = storage
+        .add_buffer(&Buffer::from_content("pool content".to_string()))
+        .expect("add_buffer failed");
+    let chunks: Vec<Chunk> = (0..16)
+        .map(|i| Chunk::new(buffer_id, format!("chunk {i}"), i * 10..i * 10 + 7, i))
+        .collect();
+    storage
+        .add_chunks(buffer_id, &chunks)
+        .expect("add_chunks failed");
+    let ids: Vec<i64> = storage
+        .get_chunks(buffer_id)
+        .expect("get_chunks failed")
+        .iter()
+        .filter_map(|c| c.id)
+        .collect();
+
+    let pool = SqliteStorage::open_read_pool(&db_path, 4).expect("open_read_pool failed");
+    assert_eq!(pool.size(), 4);
+
+    // More threads than handles: checkouts wait for a free handle
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    ids.iter()
+                        .filter(|id| {
+                            pool.get()
+                                .get_chunk(**id)
+                                .expect("get_chunk failed")
+                                .is_some()
+                        })
+                        .count()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().expect("reader thread panicked"), ids.len());
+        }
+    });
+
+    // Every handle is back in the pool
+    let held: Vec<_> = (0..4).filter_map(|_| pool.try_get()).collect();
+    assert_eq!(held.len(), 4);
+    assert!(pool.try_get().is_none());
+    drop(held);
+
+    // The writer is unaffected and readers reject writes
+    let mut reader = SqliteStorage::open_read_only(&db_path).expect("open_read_only failed");
+    assert!(reader.delete_buffer(buffer_id).is_err());
+    assert!(
+        storage
+            .get_buffer(buffer_id)
+            .expect("get_buffer failed")
+            .is_some()
+    );
+}
+
 mod search_tests {
     use super::*;
     use rlm_rs::embedding::create_embedder;
@@ -381,6 +445,77 @@ mod search_tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn test_fts_search_stemming_and_prefix() {
+        let (mut storage, _temp) = create_test_storage();
+
+        let buffer = Buffer::from_content("The service keeps running.".to_string());
+        let buffer_id = storage.add_buffer(&buffer).expect("add_buffer failed");
+        let chunks = vec![
+            Chunk::new(
+                buffer_id,
+                "The service keeps running.".to_string(),
+                0..26,
+                0,
+            ),
+            Chunk::new(
+                buffer_id,
+                "Authentication tokens expire.".to_string(),
+                27..56,
+                1,
+            ),
+        ];
+        storage
+            .add_chunks(buffer_id, &chunks)
+            .expect("add_chunks failed");
+
+        // Porter stemming: "run" matches "running"
+        let results = storage.search_fts("run", 10).expect("search_fts failed");
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1 > 0.0);
+
+        // Prefix query
+        let results = storage.search_fts("auth*", 10).expect("search_fts failed");
+        assert_eq!(results.len(), 1);
+
+        // Without '*' the prefix does not match
+        let results = storage.search_fts("auth", 10).expect("search_fts failed");
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_fts_index_synced_on_delete() {
+        let (mut storage, _temp) = create_test_storage();
+
+        let buffer = Buffer::from_content("Searchable content".to_string());
+        let buffer_id = storage.add_buffer(&buffer).expect("add_buffer failed");
+        let chunks = vec![Chunk::new(
+            buffer_id,
+            "Searchable content".to_string(),
+            0..18,
+            0,
+        )];
+        storage
+            .add_chunks(buffer_id, &chunks)
+            .expect("add_chunks failed");
+        assert!(
+            !storage
+                .search_fts("searchable", 10)
+                .expect("search_fts failed")
+                .is_empty()
+        );
+
+        storage
+            .delete_chunks(buffer_id)
+            .expect("delete_chunks failed");
+        assert!(
+            storage
+                .search_fts("searchable", 10)
+                .expect("search_fts failed")
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_search_bm25_only() {
         let (mut storage, _temp) = create_test_storage();
@@ -682,8 +817,9 @@ mod property_tests {
 
 /// CLI command integration tests.
 mod cli_tests {
+    use rlm_rs::chunking::DEFAULT_MIN_CHUNK_SIZE;
     use rlm_rs::cli::commands::execute;
-    use rlm_rs::cli::parser::{ChunkCommands, Cli, Commands};
+    use rlm_rs::cli::parser::{BufferCommands, ChunkCommands, Cli, Commands, TagCommands};
     use std::path::PathBuf;
     use tempfile::TempDir;
 
@@ -693,6 +829,7 @@ mod cli_tests {
             db_path: Some(db_path),
             verbose: false,
             format: "text".to_string(),
+            color: "auto".to_string(),
             command,
         }
     }
@@ -703,6 +840,7 @@ mod cli_tests {
             db_path: Some(db_path),
             verbose: false,
             format: "json".to_string(),
+            color: "auto".to_string(),
             command,
         }
     }
@@ -772,6 +910,21 @@ mod cli_tests {
         assert!(output.contains("buffer_count"));
     }
 
+    #[test]
+    fn test_cmd_status_yaml() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let mut cli = make_cli(db_path, Commands::Status);
+        cli.format = "yaml".to_string();
+        let output = execute(&cli).expect("yaml output");
+        assert!(!output.contains('{'));
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&output).expect("valid yaml");
+        assert_eq!(yaml["buffer_count"], serde_yaml::Value::from(0));
+        assert!(yaml.get("chunk_count").is_some());
+    }
+
     #[test]
     fn test_cmd_status_not_initialized() {
         let temp_dir = TempDir::new().expect("temp dir");
@@ -826,8 +979,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("test-buffer".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 100,
+                chunk_size: Some(1000),
+                overlap: Some(100),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         let result = execute(&cli);
@@ -853,8 +1009,11 @@ mod cli_tests {
                 file: file_path,
                 name: None,
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 100,
+                chunk_size: Some(1000),
+                overlap: Some(100),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         let result = execute(&cli);
@@ -864,6 +1023,471 @@ mod cli_tests {
         assert!(output.contains("buffer_id"));
     }
 
+    #[test]
+    fn test_cmd_load_gzip_file() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let original = std::fs::read_to_string(fixtures.join("sample.log")).expect("fixture");
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: fixtures.join("sample.log.gz"),
+                name: None,
+                chunker: "semantic".to_string(),
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load gzip");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("sample.log.gz")
+            .expect("lookup")
+            .expect("buffer");
+        assert_eq!(buffer.content, original);
+        assert_eq!(buffer.metadata.content_type.as_deref(), Some("log"));
+        let compressed = std::fs::metadata(fixtures.join("sample.log.gz")).expect("stat");
+        assert_eq!(
+            buffer.metadata.compressed_size,
+            Some(usize::try_from(compressed.len()).expect("size"))
+        );
+    }
+
+    #[test]
+    fn test_cmd_aggregate_output_buffer_is_chunked() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let findings_path = temp_dir.path().join("findings.json");
+        std::fs::write(
+            &findings_path,
+            r#"[
+                {"chunk_id": 1, "relevance": "high", "findings": ["parser handles unicode"]},
+                {"chunk_id": 2, "relevance": "low", "findings": ["storage uses sqlite"]}
+            ]"#,
+        )
+        .expect("write findings");
+        let load = Commands::Load {
+            file: findings_path,
+            name: Some("findings".to_string()),
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+
+        let aggregate = Commands::Aggregate {
+            buffer: Some("findings".to_string()),
+            min_relevance: "low".to_string(),
+            group_by: "relevance".to_string(),
+            sort_by: "relevance".to_string(),
+            output_buffer: Some("report".to_string()),
+            embed: false,
+        };
+        let output = execute(&make_cli_json(db_path.clone(), aggregate)).expect("aggregate");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let chunk_count = json["output_chunk_count"].as_u64().expect("chunk count");
+        assert!(chunk_count >= 1);
+        assert_eq!(json["output_embedded_count"], 0);
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let report = storage
+            .get_buffer_by_name("report")
+            .expect("lookup")
+            .expect("report buffer");
+        let report_id = report.id.expect("id");
+        assert_eq!(json["output_buffer_id"], report_id);
+        let chunks = storage.get_chunks(report_id).expect("chunks");
+        assert_eq!(chunks.len(), usize::try_from(chunk_count).expect("count"));
+        assert!(
+            chunks
+                .iter()
+                .any(|c| c.content.contains("parser handles unicode"))
+        );
+        assert_eq!(report.metadata.chunk_count, Some(chunks.len()));
+    }
+
+    #[test]
+    fn test_cmd_dispatch_manifest_round_trips_through_runner() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let file_path = temp_dir.path().join("data.txt");
+        let content: String = (0..5)
+            .map(|i| format!("{:<19}\n", format!("row{i}")))
+            .collect();
+        std::fs::write(&file_path, content).expect("write file");
+        let load = Commands::Load {
+            file: file_path,
+            name: Some("data".to_string()),
+            chunker: "fixed".to_string(),
+            chunk_size: Some(20),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+
+        let manifest_path = temp_dir.path().join("plan.json");
+        let dispatch = Commands::Dispatch {
+            buffer: "data".to_string(),
+            batch_size: 2,
+            workers: None,
+            query: None,
+            mode: "hybrid".to_string(),
+            threshold: 0.3,
+            manifest: Some(manifest_path.clone()),
+        };
+        let output = execute(&make_cli(db_path.clone(), dispatch)).expect("dispatch");
+        assert!(output.contains("Manifest written to:"));
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path).expect("read"))
+                .expect("manifest json");
+        assert_eq!(manifest["version"], 1);
+        assert_eq!(manifest["buffer_name"], "data");
+        assert_eq!(manifest["chunk_count"], 5);
+        let batches = manifest["batches"].as_array().expect("batches");
+        assert_eq!(batches.len(), 3);
+
+        // A mock runner: fetch each chunk by stable key and report a finding
+        let mut findings = Vec::new();
+        for batch in batches {
+            for key in batch["chunk_keys"].as_array().expect("keys") {
+                let get = Commands::Chunk(ChunkCommands::Get {
+                    ids: vec![key.as_str().expect("key").to_string()],
+                    metadata: false,
+                });
+                let output = execute(&make_cli_json(db_path.clone(), get)).expect("chunk get");
+                let chunk: serde_json::Value = serde_json::from_str(&output).expect("json");
+                let text = chunk["content"]
+                    .as_str()
+                    .expect("content")
+                    .trim()
+                    .to_string();
+                findings.push(serde_json::json!({
+                    "chunk_id": chunk["chunk_id"],
+                    "relevance": "medium",
+                    "findings": [text]
+                }));
+            }
+        }
+
+        let findings_path = temp_dir.path().join("findings.json");
+        std::fs::write(&findings_path, serde_json::json!(findings).to_string())
+            .expect("write findings");
+        let load = Commands::Load {
+            file: findings_path,
+            name: Some("findings".to_string()),
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load findings");
+
+        let aggregate = Commands::Aggregate {
+            buffer: Some("findings".to_string()),
+            min_relevance: "low".to_string(),
+            group_by: "relevance".to_string(),
+            sort_by: "chunk_id".to_string(),
+            output_buffer: None,
+            embed: false,
+        };
+        let output = execute(&make_cli_json(db_path, aggregate)).expect("aggregate");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["summary"]["total_findings"], 5);
+        assert_eq!(json["findings"][4]["findings"][0], "row4");
+    }
+
+    #[test]
+    fn test_cmd_load_min_chunk_size_controls_tail_merge() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let file = temp_dir.path().join("tail.txt");
+        std::fs::write(&file, "x".repeat(110)).expect("write");
+        let load = |min_chunk_size: usize| Commands::Load {
+            file: file.clone(),
+            name: Some(format!("min-{min_chunk_size}")),
+            chunker: "fixed".to_string(),
+            chunk_size: Some(100),
+            overlap: Some(0),
+            min_chunk_size,
+            dedup: false,
+            update_if_exists: false,
+        };
+
+        // The 10-char tail is folded into the first chunk by default
+        let output = execute(&make_cli_json(
+            db_path.clone(),
+            load(DEFAULT_MIN_CHUNK_SIZE),
+        ))
+        .expect("load");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["chunk_count"], 1);
+
+        // 0 disables merging
+        let output = execute(&make_cli_json(db_path, load(0))).expect("load");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["chunk_count"], 2);
+    }
+
+    #[test]
+    fn test_cmd_load_token_chunker_defaults_to_token_sizes() {
+        use rlm_rs::chunking::token::{DEFAULT_TOKEN_CHUNK_SIZE, count_tokens};
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        // Roughly 4000 tokens, so the 3000-char default read as tokens would overshoot 750
+        let file = temp_dir.path().join("tokens.txt");
+        std::fs::write(&file, "alpha beta gamma delta ".repeat(1000)).expect("write");
+        let load = Commands::Load {
+            file,
+            name: Some("tokens".to_string()),
+            chunker: "token".to_string(),
+            chunk_size: None,
+            overlap: None,
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        let output = execute(&make_cli_json(db_path.clone(), load)).expect("load");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let buffer_id = json["buffer_id"].as_i64().expect("buffer id");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let chunks = storage.get_chunks(buffer_id).expect("chunks");
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            let tokens = count_tokens(&chunk.content).expect("count tokens");
+            assert!(
+                tokens <= DEFAULT_TOKEN_CHUNK_SIZE,
+                "chunk has {tokens} tokens"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cmd_load_dedup_skips_identical_content() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let first = temp_dir.path().join("a.txt");
+        let copy = temp_dir.path().join("copy-of-a.txt");
+        std::fs::write(&first, "same content\n").expect("write");
+        std::fs::write(&copy, "same content\n").expect("write");
+        let load = |file: &std::path::Path| Commands::Load {
+            file: file.to_path_buf(),
+            name: None,
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: true,
+            update_if_exists: false,
+        };
+
+        let output = execute(&make_cli_json(db_path.clone(), load(&first))).expect("load");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["created"], true);
+        let buffer_id = json["buffer_id"].clone();
+
+        let output = execute(&make_cli_json(db_path.clone(), load(&copy))).expect("reload");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["created"], false);
+        assert_eq!(json["buffer_id"], buffer_id);
+
+        let output = execute(&make_cli(db_path.clone(), load(&copy))).expect("reload");
+        assert!(output.contains("already loaded"));
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        assert_eq!(storage.list_buffers().expect("list").len(), 1);
+    }
+
+    #[test]
+    fn test_cmd_load_update_if_exists_renames_existing_buffer() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let first = temp_dir.path().join("a.txt");
+        let moved = temp_dir.path().join("moved.txt");
+        std::fs::write(&first, "same content\n").expect("write");
+        std::fs::write(&moved, "same content\n").expect("write");
+        let load = |file: &std::path::Path, name: Option<&str>| Commands::Load {
+            file: file.to_path_buf(),
+            name: name.map(str::to_string),
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: true,
+        };
+
+        let output = execute(&make_cli_json(db_path.clone(), load(&first, None))).expect("load");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["created"], true);
+        let buffer_id = json["buffer_id"].clone();
+
+        let reload = load(&moved, Some("renamed"));
+        let output = execute(&make_cli_json(db_path.clone(), reload)).expect("reload");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["created"], false);
+        assert_eq!(json["updated"], true);
+        assert_eq!(json["buffer_id"], buffer_id);
+        assert_eq!(json["name"], "renamed");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffers = storage.list_buffers().expect("list");
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(buffers[0].name.as_deref(), Some("renamed"));
+        assert_eq!(buffers[0].source.as_deref(), Some(moved.as_path()));
+    }
+
+    #[test]
+    fn test_cmd_load_utf16_and_binary_files() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let load = |file: PathBuf| Commands::Load {
+            file,
+            name: None,
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+
+        let utf16_path = temp_dir.path().join("notes.txt");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(
+            "Grüße aus UTF-16\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        std::fs::write(&utf16_path, bytes).expect("write utf-16");
+        execute(&make_cli(db_path.clone(), load(utf16_path))).expect("load utf-16");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("notes.txt")
+            .expect("lookup")
+            .expect("buffer");
+        assert_eq!(buffer.content, "Grüße aus UTF-16\n");
+        assert_eq!(buffer.metadata.encoding.as_deref(), Some("utf-16le"));
+
+        let binary_path = temp_dir.path().join("image.dat");
+        let blob: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        std::fs::write(&binary_path, blob).expect("write binary");
+        let err = execute(&make_cli(db_path, load(binary_path))).expect_err("binary rejected");
+        assert!(err.to_string().contains("file appears to be binary"));
+        assert!(
+            storage
+                .get_buffer_by_name("image.dat")
+                .expect("lookup")
+                .is_none()
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_cmd_load_url() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        std::thread::spawn(move || {
+            for status in ["200 OK", "404 Not Found"] {
+                let (stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let mut line = String::new();
+                while reader.read_line(&mut line).expect("read request") > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let body = "# Remote\n\nFetched over HTTP.";
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: text/markdown\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .expect("write response");
+            }
+        });
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let load = |path: &str| Commands::Load {
+            file: PathBuf::from(format!("http://{addr}{path}")),
+            name: None,
+            chunker: "semantic".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+
+        let cli = make_cli_json(db_path.clone(), load("/docs/remote.md"));
+        let output = execute(&cli).expect("load url");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["name"], "remote.md");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("remote.md")
+            .expect("lookup")
+            .expect("buffer");
+        assert_eq!(buffer.metadata.content_type.as_deref(), Some("md"));
+        assert!(buffer.content.contains("Fetched over HTTP."));
+
+        let cli = make_cli(db_path, load("/missing.md"));
+        let err = execute(&cli).expect_err("404 fails");
+        assert!(err.to_string().contains("HTTP 404"));
+    }
+
     #[test]
     fn test_cmd_list_buffers() {
         let temp_dir = TempDir::new().expect("temp dir");
@@ -880,8 +1504,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("mybuffer".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -924,8 +1551,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("showbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -992,8 +1622,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("deleteme".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1004,6 +1637,7 @@ mod cli_tests {
             Commands::DeleteBuffer {
                 buffer: "deleteme".to_string(),
                 yes: false,
+                trash: false,
             },
         );
         let result = execute(&cli);
@@ -1015,6 +1649,7 @@ mod cli_tests {
             Commands::DeleteBuffer {
                 buffer: "deleteme".to_string(),
                 yes: true,
+                trash: false,
             },
         );
         let result = execute(&cli);
@@ -1048,8 +1683,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("peekbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1100,8 +1738,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("grepbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1109,11 +1750,12 @@ mod cli_tests {
         let cli = make_cli(
             db_path.clone(),
             Commands::Grep {
-                buffer: "grepbuf".to_string(),
+                buffer: Some("grepbuf".to_string()),
                 pattern: "hello".to_string(),
                 max_matches: 10,
                 window: 50,
                 ignore_case: false,
+                all: false,
             },
         );
         let result = execute(&cli);
@@ -1125,11 +1767,12 @@ mod cli_tests {
         let cli = make_cli(
             db_path,
             Commands::Grep {
-                buffer: "grepbuf".to_string(),
+                buffer: Some("grepbuf".to_string()),
                 pattern: "HELLO".to_string(),
                 max_matches: 10,
                 window: 50,
                 ignore_case: true,
+                all: false,
             },
         );
         let result = execute(&cli);
@@ -1152,8 +1795,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("grepbuf2".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1161,11 +1807,12 @@ mod cli_tests {
         let cli = make_cli(
             db_path,
             Commands::Grep {
-                buffer: "grepbuf2".to_string(),
+                buffer: Some("grepbuf2".to_string()),
                 pattern: "notfound".to_string(),
                 max_matches: 10,
                 window: 50,
                 ignore_case: false,
+                all: false,
             },
         );
         let result = execute(&cli);
@@ -1174,6 +1821,84 @@ mod cli_tests {
         assert!(output.contains("No matches") || output.contains('0'));
     }
 
+    #[test]
+    fn test_cmd_grep_all_buffers() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        for (name, content) in [
+            ("first", "alpha TODO one\nTODO two\n"),
+            ("second", "beta TODO three\n"),
+            ("third", "no markers here\n"),
+        ] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(1000),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let grep_all = |max_matches| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Grep {
+                    buffer: None,
+                    pattern: "TODO".to_string(),
+                    max_matches,
+                    window: 10,
+                    ignore_case: false,
+                    all: true,
+                },
+            )
+        };
+
+        let output = execute(&grep_all(10)).expect("grep all");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        let names: Vec<&str> = json
+            .as_array()
+            .expect("array")
+            .iter()
+            .map(|m| m["buffer_name"].as_str().expect("buffer name"))
+            .collect();
+        assert_eq!(names, ["first", "first", "second"]);
+        assert!(json[2]["buffer_id"].is_i64());
+        assert_eq!(json[2]["offset"], 5);
+
+        // The match cap is global, not per buffer
+        let output = execute(&grep_all(2)).expect("grep all capped");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json.as_array().expect("array").len(), 2);
+
+        let cli = make_cli(
+            db_path,
+            Commands::Grep {
+                buffer: None,
+                pattern: "TODO".to_string(),
+                max_matches: 10,
+                window: 10,
+                ignore_case: false,
+                all: true,
+            },
+        );
+        let output = execute(&cli).expect("grep all text");
+        assert!(output.contains("Found 3 matches"));
+        assert!(output.contains("Match 3 in second (ID "));
+    }
+
     #[test]
     fn test_cmd_chunk_indices() {
         let temp_dir = TempDir::new().expect("temp dir");
@@ -1190,8 +1915,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("chunkbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1200,8 +1928,8 @@ mod cli_tests {
             db_path,
             Commands::ChunkIndices {
                 buffer: "chunkbuf".to_string(),
-                chunk_size: 50,
-                overlap: 10,
+                chunk_size: Some(50),
+                overlap: Some(10),
             },
         );
         let result = execute(&cli);
@@ -1225,8 +1953,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("writebuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1236,8 +1967,9 @@ mod cli_tests {
             Commands::WriteChunks {
                 buffer: "writebuf".to_string(),
                 out_dir,
-                chunk_size: 50,
-                overlap: 10,
+                chunk_size: Some(50),
+                overlap: Some(10),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
                 prefix: "test".to_string(),
             },
         );
@@ -1291,8 +2023,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("exportbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1302,6 +2037,8 @@ mod cli_tests {
             db_path.clone(),
             Commands::ExportBuffers {
                 output: None,
+                buffer: None,
+                as_format: None,
                 pretty: false,
             },
         );
@@ -1313,18 +2050,37 @@ mod cli_tests {
             db_path.clone(),
             Commands::ExportBuffers {
                 output: None,
+                buffer: None,
+                as_format: None,
                 pretty: true,
             },
         );
         let result = execute(&cli);
         assert!(result.is_ok());
 
+        // YAML selects the JSON export, which is not converted
+        let mut cli = make_cli(
+            db_path.clone(),
+            Commands::ExportBuffers {
+                output: None,
+                buffer: None,
+                as_format: None,
+                pretty: false,
+            },
+        );
+        cli.format = "yaml".to_string();
+        let output = execute(&cli).expect("yaml export");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert!(json.is_array());
+
         // Export to file
         let export_path = temp_dir.path().join("export.json");
         let cli = make_cli(
             db_path,
             Commands::ExportBuffers {
                 output: Some(export_path.clone()),
+                buffer: None,
+                as_format: None,
                 pretty: true,
             },
         );
@@ -1334,68 +2090,324 @@ mod cli_tests {
     }
 
     #[test]
-    fn test_cmd_variable() {
+    fn test_cmd_import_buffers_round_trip() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
         let temp_dir = TempDir::new().expect("temp dir");
         let db_path = temp_dir.path().join("test.db");
 
         let cli = make_cli(db_path.clone(), Commands::Init { force: false });
         execute(&cli).expect("init");
 
-        // Set variable
-        let cli = make_cli(
-            db_path.clone(),
-            Commands::Variable {
-                name: "myvar".to_string(),
-                value: Some("myvalue".to_string()),
-                delete: false,
-            },
-        );
-        let result = execute(&cli);
-        assert!(result.is_ok());
+        for (name, chunker) in [("notes.md", "semantic"), ("data.txt", "fixed")] {
+            let file_path = temp_dir.path().join(name);
+            let content = format!("# {name}\n\n{}\n\nSecond section é.\n", "word ".repeat(80));
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: chunker.to_string(),
+                    chunk_size: Some(120),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
 
-        // Get variable
-        let cli = make_cli(
+        let snapshot = |db_path: &PathBuf| {
+            let storage = SqliteStorage::open(db_path).expect("open");
+            storage
+                .list_buffers()
+                .expect("list")
+                .into_iter()
+                .map(|b| {
+                    let chunks: Vec<_> = storage
+                        .get_chunks(b.id.expect("id"))
+                        .expect("chunks")
+                        .into_iter()
+                        .map(|c| (c.index, c.byte_range, c.content, c.metadata.strategy))
+                        .collect();
+                    (b.name, b.content, b.metadata.chunk_count, chunks)
+                })
+                .collect::<Vec<_>>()
+        };
+        let before = snapshot(&db_path);
+        assert!(before.iter().all(|(_, _, _, chunks)| chunks.len() > 1));
+
+        let export_path = temp_dir.path().join("export.json");
+        let cli = make_cli_json(
             db_path.clone(),
-            Commands::Variable {
-                name: "myvar".to_string(),
-                value: None,
-                delete: false,
+            Commands::ExportBuffers {
+                output: Some(export_path.clone()),
+                buffer: None,
+                as_format: None,
+                pretty: true,
             },
         );
-        let result = execute(&cli);
-        assert!(result.is_ok());
-        let output = result.expect("var output");
-        assert!(output.contains("myvalue"));
+        execute(&cli).expect("export");
 
-        // Delete variable
-        let cli = make_cli(
-            db_path,
-            Commands::Variable {
-                name: "myvar".to_string(),
-                value: None,
-                delete: true,
-            },
-        );
-        let result = execute(&cli);
-        assert!(result.is_ok());
+        let cli = make_cli(db_path.clone(), Commands::Reset { yes: true });
+        execute(&cli).expect("reset");
+        assert!(snapshot(&db_path).is_empty());
+
+        let import = |on_conflict: &str| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::ImportBuffers {
+                    file: export_path.clone(),
+                    embed: false,
+                    on_conflict: on_conflict.to_string(),
+                },
+            )
+        };
+        let output = execute(&import("skip")).expect("import");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["buffer_count"], 2);
+        let chunk_total: usize = before.iter().map(|(_, _, _, c)| c.len()).sum();
+        assert_eq!(json["chunk_count"], chunk_total);
+        assert_eq!(snapshot(&db_path), before);
+
+        // Existing names are skipped by default, or renamed on request
+        let output = execute(&import("skip")).expect("import again");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["buffer_count"], 0);
+        assert_eq!(json["skipped"], serde_json::json!(["notes.md", "data.txt"]));
+
+        let output = execute(&import("rename")).expect("import renamed");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["imported"][0]["name"], "notes.md-2");
+        assert_eq!(json["imported"][1]["name"], "data.txt-2");
     }
 
     #[test]
-    fn test_cmd_global() {
+    fn test_cmd_import_buffers_rejects_bad_shape() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
         let temp_dir = TempDir::new().expect("temp dir");
         let db_path = temp_dir.path().join("test.db");
 
         let cli = make_cli(db_path.clone(), Commands::Init { force: false });
         execute(&cli).expect("init");
 
-        // Set global
-        let cli = make_cli(
-            db_path.clone(),
-            Commands::Global {
-                name: "globalvar".to_string(),
-                value: Some("globalvalue".to_string()),
-                delete: false,
-            },
+        let cases = [
+            r#"{"name": "not an array"}"#,
+            r#"[{"name": "missing content"}]"#,
+            r#"[{"content": "abc", "chunks": [
+                {"index": 0, "byte_range": {"start": 0, "end": 10}, "content": "abc"}
+            ]}]"#,
+            r#"[{"content": "abcdef", "chunks": [
+                {"index": 0, "byte_range": {"start": 0, "end": 3}, "content": "xyz"}
+            ]}]"#,
+        ];
+        for (i, case) in cases.iter().enumerate() {
+            let path = temp_dir.path().join(format!("bad{i}.json"));
+            std::fs::write(&path, case).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::ImportBuffers {
+                    file: path,
+                    embed: false,
+                    on_conflict: "skip".to_string(),
+                },
+            );
+            let err = execute(&cli).expect_err("invalid export");
+            assert!(err.to_string().contains("invalid export file"), "{err}");
+        }
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        assert_eq!(storage.buffer_count().expect("count"), 0);
+    }
+
+    #[test]
+    fn test_cmd_export_single_buffer_json_and_jsonl() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        for (name, content) in [("keep", "a".repeat(250)), ("skip", "b".repeat(50))] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(100),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let json_path = temp_dir.path().join("keep.json");
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::ExportBuffers {
+                output: Some(json_path.clone()),
+                buffer: Some("keep".to_string()),
+                as_format: None,
+                pretty: false,
+            },
+        );
+        execute(&cli).expect("export json");
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).expect("read json"))
+                .expect("valid JSON");
+        let buffers = json.as_array().expect("array");
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(buffers[0]["name"], "keep");
+        assert_eq!(buffers[0]["content"], "a".repeat(250));
+
+        let jsonl_path = temp_dir.path().join("keep.jsonl");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::ExportBuffers {
+                output: Some(jsonl_path.clone()),
+                buffer: Some("keep".to_string()),
+                as_format: Some("jsonl".to_string()),
+                pretty: false,
+            },
+        );
+        execute(&cli).expect("export jsonl");
+        let text = std::fs::read_to_string(&jsonl_path).expect("read jsonl");
+        let chunks: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid JSON line"))
+            .collect();
+        assert_eq!(chunks.len(), 3);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk["index"], i);
+            assert_eq!(chunk["buffer_name"], "keep");
+        }
+        assert_eq!(chunks[2]["byte_range"]["end"], 250);
+
+        let md_path = temp_dir.path().join("keep.md");
+        let cli = make_cli(
+            db_path,
+            Commands::ExportBuffers {
+                output: Some(md_path.clone()),
+                buffer: Some("keep".to_string()),
+                as_format: Some("markdown".to_string()),
+                pretty: false,
+            },
+        );
+        execute(&cli).expect("export markdown");
+        let markdown = std::fs::read_to_string(&md_path).expect("read markdown");
+        assert!(markdown.starts_with("# keep\n"));
+        assert!(markdown.contains("## Chunk 2 (bytes 200..250)"));
+        assert!(!markdown.contains("skip"));
+    }
+
+    #[test]
+    fn test_cmd_export_raw_yaml_unchanged() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        for (name, content) in [
+            (
+                "package",
+                "{\n  \"name\": \"pkg\",\n  \"version\": \"1.0.0\"\n}\n",
+            ),
+            ("answer", "42"),
+        ] {
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::AddBuffer {
+                    name: name.to_string(),
+                    content: Some(content.to_string()),
+                },
+            );
+            execute(&cli).expect("add buffer");
+
+            let mut cli = make_cli(
+                db_path.clone(),
+                Commands::ExportBuffers {
+                    output: None,
+                    buffer: Some(name.to_string()),
+                    as_format: Some("text".to_string()),
+                    pretty: false,
+                },
+            );
+            cli.format = "yaml".to_string();
+            assert_eq!(execute(&cli).expect("raw export"), content);
+        }
+    }
+
+    #[test]
+    fn test_cmd_variable() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        // Set variable
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Variable {
+                name: "myvar".to_string(),
+                value: Some("myvalue".to_string()),
+                delete: false,
+            },
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+
+        // Get variable
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Variable {
+                name: "myvar".to_string(),
+                value: None,
+                delete: false,
+            },
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+        let output = result.expect("var output");
+        assert!(output.contains("myvalue"));
+
+        // Delete variable
+        let cli = make_cli(
+            db_path,
+            Commands::Variable {
+                name: "myvar".to_string(),
+                value: None,
+                delete: true,
+            },
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_global() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        // Set global
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Global {
+                name: "globalvar".to_string(),
+                value: Some("globalvalue".to_string()),
+                delete: false,
+            },
         );
         let result = execute(&cli);
         assert!(result.is_ok());
@@ -1425,6 +2437,48 @@ mod cli_tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_cmd_vars_lists_variables_and_globals() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        for (name, value) in [("alpha", "1"), ("beta", "2")] {
+            let set = Commands::Variable {
+                name: name.to_string(),
+                value: Some(value.to_string()),
+                delete: false,
+            };
+            execute(&make_cli(db_path.clone(), set)).expect("set variable");
+        }
+        let set = Commands::Global {
+            name: "shared".to_string(),
+            value: Some("yes".to_string()),
+            delete: false,
+        };
+        execute(&make_cli(db_path.clone(), set)).expect("set global");
+
+        let vars = |scope: &str| Commands::Vars {
+            scope: scope.to_string(),
+        };
+        let output = execute(&make_cli(db_path.clone(), vars("all"))).expect("vars");
+        assert!(output.contains("alpha = "));
+        assert!(output.contains("beta = "));
+        assert!(output.contains("shared = "));
+
+        let output = execute(&make_cli_json(db_path.clone(), vars("var"))).expect("vars json");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let variables = json["variables"].as_object().expect("variables");
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables["alpha"]["value"], "1");
+        assert_eq!(variables["beta"]["value"], "2");
+        assert!(json.get("globals").is_none());
+
+        let output = execute(&make_cli(db_path, vars("global"))).expect("globals");
+        assert!(output.contains("shared = "));
+        assert!(!output.contains("alpha"));
+    }
+
     #[test]
     fn test_cmd_search_bm25() {
         let temp_dir = TempDir::new().expect("temp dir");
@@ -1445,8 +2499,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("searchbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1457,12 +2514,21 @@ mod cli_tests {
             Commands::Search {
                 query: "programming".to_string(),
                 top_k: 5,
+                offset: 0,
                 threshold: 0.3,
                 mode: "bm25".to_string(),
                 rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
                 buffer: None,
+                tag: None,
                 preview: false,
                 preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
             },
         );
         let result = execute(&cli);
@@ -1470,11 +2536,88 @@ mod cli_tests {
     }
 
     #[test]
-    fn test_cmd_chunk_get() {
+    fn test_cmd_color_choice() {
         let temp_dir = TempDir::new().expect("temp dir");
         let db_path = temp_dir.path().join("test.db");
         let file_path = temp_dir.path().join("content.txt");
-        std::fs::write(&file_path, "Chunk get test content").expect("write file");
+        std::fs::write(&file_path, "Rust programming\n\nMore programming notes").expect("write");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("colors".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        let with_color = |color: &str, command| Cli {
+            db_path: Some(db_path.clone()),
+            verbose: false,
+            format: "text".to_string(),
+            color: color.to_string(),
+            command,
+        };
+        let search = || Commands::Search {
+            query: "programming".to_string(),
+            top_k: 5,
+            offset: 0,
+            threshold: 0.3,
+            mode: "bm25".to_string(),
+            rrf_k: 60,
+            fusion: "rrf".to_string(),
+            alpha: 0.5,
+            metric: "cosine".to_string(),
+            mmr_lambda: None,
+            buffer: None,
+            tag: None,
+            preview: true,
+            preview_len: 150,
+            context: 0,
+            enrich: false,
+            fail_on_empty: false,
+        };
+        let chunk_list = || {
+            Commands::Chunk(ChunkCommands::List {
+                buffer: "colors".to_string(),
+                preview: true,
+                preview_len: 100,
+            })
+        };
+
+        for command in [search(), chunk_list(), Commands::Status] {
+            let output = execute(&with_color("never", command)).expect("plain output");
+            assert!(!output.contains('\x1b'), "unexpected ANSI codes: {output}");
+        }
+
+        let output = execute(&with_color("always", search())).expect("colored search");
+        assert!(output.contains("\x1b[32m"));
+        let output = execute(&with_color("always", Commands::Status)).expect("colored status");
+        assert!(output.contains("\x1b[1m"));
+
+        // Machine-readable output is never colorized
+        let mut cli = with_color("always", search());
+        cli.format = "json".to_string();
+        assert!(!execute(&cli).expect("json search").contains('\x1b'));
+    }
+
+    #[test]
+    fn test_cmd_search_with_context() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        // Each line is exactly one 40-byte chunk
+        let content: String = (0..6)
+            .map(|i| format!("{:<39}\n", format!("Section {i} about topic{i}.")))
+            .collect();
+        std::fs::write(&file_path, content).expect("write file");
 
         let cli = make_cli(db_path.clone(), Commands::Init { force: false });
         execute(&cli).expect("init");
@@ -1483,116 +2626,1060 @@ mod cli_tests {
             db_path.clone(),
             Commands::Load {
                 file: file_path,
-                name: Some("chunkgetbuf".to_string()),
+                name: Some("ctxbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
 
-        // Get chunk by ID (ID 1 should exist after load)
+        let cli = make_cli_json(
+            db_path,
+            Commands::Search {
+                query: "topic3".to_string(),
+                top_k: 1,
+                offset: 0,
+                threshold: 0.3,
+                mode: "bm25".to_string(),
+                rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
+                buffer: None,
+                tag: None,
+                preview: false,
+                preview_len: 150,
+                context: 1,
+                enrich: false,
+                fail_on_empty: false,
+            },
+        );
+        let output = execute(&cli).expect("search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let context = json["results"][0]["context"].as_array().expect("context");
+        assert!(context.len() > 1);
+        assert_eq!(
+            context
+                .iter()
+                .filter(|c| c["is_hit"].as_bool() == Some(true))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cmd_search_enrich_adds_buffer_name() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        for (name, text) in [("alpha", "apples and pears"), ("beta", "zebras grazing")] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, text).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(100),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let search = |enrich: bool| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Search {
+                    query: "zebras".to_string(),
+                    top_k: 5,
+                    offset: 0,
+                    threshold: 0.3,
+                    mode: "bm25".to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: None,
+                    buffer: None,
+                    tag: None,
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich,
+                    fail_on_empty: false,
+                },
+            )
+        };
+
+        let output = execute(&search(false)).expect("search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert!(json["results"][0].get("buffer_name").is_none());
+
+        let output = execute(&search(true)).expect("enriched search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let results = json["results"].as_array().expect("results");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["buffer_name"], "beta");
+    }
+
+    #[test]
+    fn test_cmd_search_fail_on_empty() {
+        use rlm_rs::error::{CommandError, Error};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+
+        let search = |fail_on_empty: bool| {
+            make_cli(
+                db_path.clone(),
+                Commands::Search {
+                    query: "nothing-matches-this".to_string(),
+                    top_k: 5,
+                    offset: 0,
+                    threshold: 0.3,
+                    mode: "bm25".to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: None,
+                    buffer: None,
+                    tag: None,
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich: false,
+                    fail_on_empty,
+                },
+            )
+        };
+
+        let output = execute(&search(false)).expect("empty search succeeds");
+        assert!(output.contains("No results found"));
+
+        let err = execute(&search(true)).expect_err("empty search reports no results");
+        assert!(matches!(
+            &err,
+            Error::Command(CommandError::NoResults(output)) if output.contains("No results found")
+        ));
+    }
+
+    #[test]
+    fn test_cmd_search_invalid_fusion() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
         let cli = make_cli(
-            db_path.clone(),
-            Commands::Chunk(ChunkCommands::Get {
-                id: 1,
-                metadata: false,
-            }),
+            db_path,
+            Commands::Search {
+                query: "anything".to_string(),
+                top_k: 5,
+                offset: 0,
+                threshold: 0.3,
+                mode: "hybrid".to_string(),
+                rrf_k: 60,
+                fusion: "borda".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
+                buffer: None,
+                tag: None,
+                preview: false,
+                preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
+            },
         );
         let result = execute(&cli);
-        assert!(result.is_ok());
+        assert!(result.is_err());
+    }
 
-        // Get chunk with metadata
+    #[test]
+    fn test_cmd_search_rejects_out_of_range_mmr_lambda() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        for lambda in [-0.1, 1.5, f32::NAN] {
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Search {
+                    query: "anything".to_string(),
+                    top_k: 5,
+                    offset: 0,
+                    threshold: 0.3,
+                    mode: "hybrid".to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: Some(lambda),
+                    buffer: None,
+                    tag: None,
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich: false,
+                    fail_on_empty: false,
+                },
+            );
+            let err = execute(&cli).expect_err("lambda should be rejected");
+            assert!(err.to_string().contains("--mmr-lambda must be between"));
+        }
+    }
+
+    #[test]
+    fn test_cmd_chunk_get() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        std::fs::write(&file_path, "Chunk get test content").expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("chunkgetbuf".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        // Get chunk by ID (ID 1 should exist after load)
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Get {
+                ids: vec!["1".to_string()],
+                metadata: false,
+            }),
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+
+        // Get chunk with metadata
+        let cli = make_cli(
+            db_path,
+            Commands::Chunk(ChunkCommands::Get {
+                ids: vec!["1".to_string()],
+                metadata: true,
+            }),
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+        let output = result.expect("chunk output");
+        assert!(output.contains("Chunk") || output.contains("content"));
+    }
+
+    #[test]
+    fn test_cmd_chunk_get_shows_chunker_metadata() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("guide.md");
+        std::fs::write(&file_path, "# Guide\n\nIntro.\n\n## Install\n\nRun it.\n").expect("write");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("guide".to_string()),
+                chunker: "markdown".to_string(),
+                chunk_size: Some(20),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        let get = |metadata| {
+            Commands::Chunk(ChunkCommands::Get {
+                ids: vec!["2".to_string()],
+                metadata,
+            })
+        };
+        let output = execute(&make_cli_json(db_path.clone(), get(false))).expect("chunk get");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(
+            json["metadata"]["heading_path"],
+            serde_json::json!(["Guide", "Install"])
+        );
+
+        let output = execute(&make_cli(db_path, get(true))).expect("chunk get");
+        assert!(output.contains("Metadata: {\"heading_path\":[\"Guide\",\"Install\"]}"));
+    }
+
+    #[test]
+    fn test_cmd_chunk_get_by_stable_key_after_reload() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("notes.txt");
+        std::fs::write(
+            &file_path,
+            format!("{}{}", "a".repeat(100), "b".repeat(100)),
+        )
+        .expect("write");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let load = || Commands::Load {
+            file: file_path.clone(),
+            name: Some("notes".to_string()),
+            chunker: "fixed".to_string(),
+            chunk_size: Some(100),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        let get = |id: &str| {
+            Commands::Chunk(ChunkCommands::Get {
+                ids: vec![id.to_string()],
+                metadata: false,
+            })
+        };
+
+        execute(&make_cli(db_path.clone(), load())).expect("load");
+        let output = execute(&make_cli_json(db_path.clone(), get("2"))).expect("chunk get");
+        let first: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let key = first["stable_key"]
+            .as_str()
+            .expect("stable key")
+            .to_string();
+
+        let delete = Commands::DeleteBuffer {
+            buffer: "notes".to_string(),
+            yes: true,
+            trash: false,
+        };
+        execute(&make_cli(db_path.clone(), delete)).expect("delete");
+        execute(&make_cli(db_path.clone(), load())).expect("reload");
+
+        let output = execute(&make_cli_json(db_path.clone(), get(&key))).expect("get by key");
+        let second: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_ne!(second["chunk_id"], first["chunk_id"]);
+        assert_eq!(second["stable_key"], first["stable_key"]);
+        assert_eq!(second["content"], "b".repeat(100));
+
+        let missing = format!("{}:0-1", key.split(':').next().expect("hash"));
+        assert!(execute(&make_cli(db_path, get(&missing))).is_err());
+    }
+
+    #[test]
+    fn test_cmd_chunk_get_multiple_ids_and_ranges() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("abc.txt");
+        let content = format!("{}{}{}", "a".repeat(200), "b".repeat(200), "c".repeat(200));
+        std::fs::write(&file_path, content).expect("write");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let load = Commands::Load {
+            file: file_path,
+            name: None,
+            chunker: "fixed".to_string(),
+            chunk_size: Some(200),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+        let delete = ChunkCommands::Delete { id: 2, yes: true };
+        execute(&make_cli(db_path.clone(), Commands::Chunk(delete))).expect("delete");
+
+        let get = |ids: &[&str]| {
+            Commands::Chunk(ChunkCommands::Get {
+                ids: ids.iter().map(ToString::to_string).collect(),
+                metadata: false,
+            })
+        };
+
+        // Several IDs, with a missing one in the middle
+        let output = execute(&make_cli_json(db_path.clone(), get(&["1", "2", "3"]))).expect("get");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let entries = json.as_array().expect("array");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["chunk_id"], 1);
+        assert!(entries[1].is_null());
+        assert!(
+            entries[2]["content"]
+                .as_str()
+                .expect("content")
+                .starts_with('c')
+        );
+
+        // An inclusive range gives the same result
+        let range = execute(&make_cli_json(db_path.clone(), get(&["1-3"]))).expect("range");
+        assert_eq!(range, output);
+
+        let text = execute(&make_cli(db_path.clone(), get(&["1-3"]))).expect("text");
+        assert!(text.contains("==> chunk 1 <==\naaaa"));
+        assert!(text.contains("==> chunk 2 <==\n(not found)"));
+        assert!(text.contains("==> chunk 3 <==\ncccc"));
+
+        assert!(execute(&make_cli(db_path.clone(), get(&["3-1"]))).is_err());
+        assert!(execute(&make_cli(db_path, get(&["x"]))).is_err());
+    }
+
+    #[test]
+    fn test_cmd_chunk_get_not_found() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path,
+            Commands::Chunk(ChunkCommands::Get {
+                ids: vec!["999".to_string()],
+                metadata: false,
+            }),
+        );
+        let result = execute(&cli);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cmd_chunk_list() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        std::fs::write(&file_path, "Chunk list content").expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("chunklistbuf".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        // List chunks
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::List {
+                buffer: "chunklistbuf".to_string(),
+                preview: false,
+                preview_len: 100,
+            }),
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+
+        // List with preview
+        let cli = make_cli(
+            db_path,
+            Commands::Chunk(ChunkCommands::List {
+                buffer: "chunklistbuf".to_string(),
+                preview: true,
+                preview_len: 50,
+            }),
+        );
+        let result = execute(&cli);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_chunk_status() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(db_path, Commands::Chunk(ChunkCommands::Status));
+        let result = execute(&cli);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_migrate() {
+        use rlm_rs::storage::CURRENT_SCHEMA_VERSION;
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(db_path.clone(), Commands::Migrate { dry_run: true });
+        let output = execute(&cli).expect("migrate dry run");
+        assert!(output.contains("up to date"));
+
+        // Roll the recorded version back to simulate a v4 database
+        {
+            let conn = rusqlite::Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "UPDATE schema_info SET value = '4' WHERE key = 'version';
+                 DROP TABLE schema_migrations;
+                 ALTER TABLE buffers DROP COLUMN page_offsets;
+                 ALTER TABLE buffers DROP COLUMN compressed_size;
+                 ALTER TABLE buffers DROP COLUMN encoding;
+                 ALTER TABLE buffers DROP COLUMN deleted_at;",
+            )
+            .expect("downgrade");
+        }
+
+        let cli = make_cli_json(db_path.clone(), Commands::Migrate { dry_run: true });
+        let output = execute(&cli).expect("migrate dry run");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["from_version"], 4);
+        let pending = json["migrations"].as_array().expect("migrations array");
+        assert_eq!(pending.len(), (CURRENT_SCHEMA_VERSION - 4) as usize);
+        assert_eq!(pending[0]["to_version"], 5);
+        assert_eq!(
+            pending.last().expect("last migration")["to_version"],
+            CURRENT_SCHEMA_VERSION
+        );
+
+        let cli = make_cli(db_path.clone(), Commands::Migrate { dry_run: false });
+        let output = execute(&cli).expect("migrate");
+        assert!(output.contains("Applied migrations"));
+        for version in 4..CURRENT_SCHEMA_VERSION {
+            assert!(output.contains(&format!("v{version} -> v{}", version + 1)));
+        }
+
+        let cli = make_cli_json(db_path, Commands::Migrate { dry_run: true });
+        let output = execute(&cli).expect("migrate dry run");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["from_version"], CURRENT_SCHEMA_VERSION);
+        assert!(json["migrations"].as_array().expect("array").is_empty());
+    }
+
+    #[test]
+    fn test_cmd_backup_restore_round_trip() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let backup_path = temp_dir.path().join("backup.db");
+        let file_path = temp_dir.path().join("content.txt");
+        let content: String = (0..4)
+            .map(|i| format!("{:<39}\n", format!("Line {i} of the backup test.")))
+            .collect();
+        std::fs::write(&file_path, content).expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("backed".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        let (chunks_before, embeddings_before) = {
+            let storage = SqliteStorage::open(&db_path).expect("open");
+            let stats = storage.stats().expect("stats");
+            (
+                stats.chunk_count,
+                storage.embedding_count().expect("embedding count"),
+            )
+        };
+        assert!(embeddings_before > 0);
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::Backup {
+                path: backup_path.clone(),
+            },
+        );
+        let output = execute(&cli).expect("backup");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["buffer_count"], 1);
+
+        // Refuses to overwrite an existing backup
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Backup {
+                path: backup_path.clone(),
+            },
+        );
+        assert!(execute(&cli).is_err());
+
+        let cli = make_cli(db_path.clone(), Commands::Reset { yes: true });
+        execute(&cli).expect("reset");
+
+        // Requires --force to replace the current database
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Restore {
+                path: backup_path.clone(),
+                force: false,
+            },
+        );
+        assert!(execute(&cli).is_err());
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Restore {
+                path: backup_path,
+                force: true,
+            },
+        );
+        let output = execute(&cli).expect("restore");
+        assert!(output.contains("Restored 1 buffer(s)"));
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("backed")
+            .expect("get buffer")
+            .expect("buffer restored");
+        let chunks = storage
+            .get_chunks(buffer.id.expect("buffer id"))
+            .expect("get chunks");
+        assert_eq!(chunks.len(), chunks_before);
+        assert!(chunks[0].content.starts_with("Line 0"));
+        assert_eq!(
+            storage.embedding_count().expect("embedding count"),
+            embeddings_before
+        );
+    }
+
+    #[test]
+    fn test_cmd_backup_keeps_schema_version() {
+        use rlm_rs::storage::SqliteStorage;
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let backup_path = temp_dir.path().join("backup.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::AddBuffer {
+                name: "old".to_string(),
+                content: Some("Stored by an older release.".to_string()),
+            },
+        );
+        execute(&cli).expect("add buffer");
+
+        // Roll the recorded version back to simulate a v9 database
+        {
+            let conn = rusqlite::Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "UPDATE schema_info SET value = '9' WHERE key = 'version';
+                 ALTER TABLE buffers DROP COLUMN deleted_at;",
+            )
+            .expect("downgrade");
+        }
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::Backup {
+                path: backup_path.clone(),
+            },
+        );
+        let output = execute(&cli).expect("backup");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["schema_version"], 9);
+        assert_eq!(json["buffer_count"], 1);
+
+        for path in [&db_path, &backup_path] {
+            let storage = SqliteStorage::open(path).expect("open");
+            assert_eq!(storage.validate().expect("version"), 9);
+        }
+    }
+
+    #[test]
+    fn test_cmd_restore_rejects_invalid_source() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let bogus_path = temp_dir.path().join("bogus.db");
+        std::fs::write(&bogus_path, "not a database").expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Restore {
+                path: bogus_path,
+                force: true,
+            },
+        );
+        assert!(execute(&cli).is_err());
+
+        // The current database is untouched
+        let cli = make_cli(db_path, Commands::Status);
+        execute(&cli).expect("status");
+    }
+
+    #[test]
+    fn test_cmd_storage_vacuum_empty_database() {
+        use rlm_rs::cli::parser::StorageCommands;
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::Storage(StorageCommands::Vacuum { analyze: true }),
+        );
+        let output = execute(&cli).expect("vacuum");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert!(json["size_before"].is_u64());
+        assert!(json["size_after"].as_u64().expect("size_after") > 0);
+        assert!(json["reclaimed"].is_u64());
+        assert_eq!(json["analyzed"], true);
+
+        let cli = make_cli(
+            db_path,
+            Commands::Storage(StorageCommands::Vacuum { analyze: false }),
+        );
+        let output = execute(&cli).expect("vacuum");
+        assert!(output.starts_with("Vacuumed database"));
+    }
+
+    #[test]
+    fn test_cmd_buffer_stats() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        let content: String = (0..5)
+            .map(|i| format!("{:<39}\n", format!("Line {i} of the stats test.")))
+            .collect();
+        std::fs::write(&file_path, &content).expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("statsbuf".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::BufferStats {
+                buffer: "statsbuf".to_string(),
+            },
+        );
+        let output = execute(&cli).expect("stats");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("valid JSON");
+        assert_eq!(json["name"], "statsbuf");
+        assert_eq!(json["bytes"], 200);
+        assert_eq!(json["lines"], 5);
+        assert_eq!(json["chunk_count"], 5);
+        assert_eq!(json["min_chunk_size"], 40);
+        assert_eq!(json["max_chunk_size"], 40);
+        assert_eq!(json["mean_chunk_size"], 40.0);
+        assert_eq!(json["embedding_coverage"], 1.0);
+        assert!(json["estimated_tokens"].as_u64().expect("tokens") > 0);
+
+        let cli = make_cli(
+            db_path,
+            Commands::BufferStats {
+                buffer: "statsbuf".to_string(),
+            },
+        );
+        let output = execute(&cli).expect("stats");
+        assert!(output.contains("Embedded:         5/5 (100%)"));
+    }
+
+    #[test]
+    fn test_cmd_chunk_delete() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        let content: String = (0..3)
+            .map(|i| format!("{:<39}\n", format!("Line {i} of chunk delete test.")))
+            .collect();
+        std::fs::write(&file_path, content).expect("write file");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Load {
+                file: file_path,
+                name: Some("delbuf".to_string()),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
+            },
+        );
+        execute(&cli).expect("load");
+
+        let (buffer_id, chunk_id) = {
+            let storage = SqliteStorage::open(&db_path).expect("open");
+            let buffer = storage
+                .get_buffer_by_name("delbuf")
+                .expect("get buffer")
+                .expect("buffer exists");
+            let buffer_id = buffer.id.expect("buffer id");
+            let chunks = storage.get_chunks(buffer_id).expect("get chunks");
+            assert_eq!(chunks.len(), 3);
+            (buffer_id, chunks[1].id.expect("chunk id"))
+        };
+
+        // Requires confirmation
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Delete {
+                id: chunk_id,
+                yes: false,
+            }),
+        );
+        assert!(execute(&cli).is_err());
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Delete {
+                id: chunk_id,
+                yes: true,
+            }),
+        );
+        let output = execute(&cli).expect("delete");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["buffer_id"].as_i64(), Some(buffer_id));
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        assert!(storage.get_chunk(chunk_id).expect("get chunk").is_none());
+        assert!(
+            storage
+                .get_embedding(chunk_id)
+                .expect("get embedding")
+                .is_none()
+        );
+        assert_eq!(storage.chunk_count(buffer_id).expect("count"), 2);
+        let buffer = storage
+            .get_buffer(buffer_id)
+            .expect("get buffer")
+            .expect("buffer exists");
+        assert_eq!(buffer.metadata.chunk_count, Some(2));
+
+        // A missing chunk is reported before asking for confirmation
         let cli = make_cli(
             db_path,
-            Commands::Chunk(ChunkCommands::Get {
-                id: 1,
-                metadata: true,
+            Commands::Chunk(ChunkCommands::Delete {
+                id: chunk_id,
+                yes: false,
             }),
         );
-        let result = execute(&cli);
-        assert!(result.is_ok());
-        let output = result.expect("chunk output");
-        assert!(output.contains("Chunk") || output.contains("content"));
+        let err = execute(&cli).expect_err("missing chunk");
+        assert!(err.to_string().contains("not found"), "{err}");
     }
 
     #[test]
-    fn test_cmd_chunk_get_not_found() {
+    fn test_cmd_merge_buffers() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
         let temp_dir = TempDir::new().expect("temp dir");
         let db_path = temp_dir.path().join("test.db");
 
         let cli = make_cli(db_path.clone(), Commands::Init { force: false });
         execute(&cli).expect("init");
 
+        for (name, word) in [("first", "alpha"), ("second", "omega")] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            let content: String = (0..4).map(|i| format!("{word} line {i:<28}\n")).collect();
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(40),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let cli = make_cli_json(
+            db_path.clone(),
+            Commands::MergeBuffers {
+                sources: vec!["first".to_string(), "second".to_string()],
+                name: "both".to_string(),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                no_embed: true,
+            },
+        );
+        let output = execute(&cli).expect("merge");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let chunk_count = json["chunk_count"].as_u64().expect("chunk_count");
+        assert!((8..=12).contains(&chunk_count));
+        assert_eq!(json["embedded_count"], 0);
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let merged = storage
+            .get_buffer_by_name("both")
+            .expect("get buffer")
+            .expect("buffer exists");
+        assert!(merged.content.starts_with("alpha line 0"));
+        assert!(merged.content.contains("--- merged from: second ---"));
+        assert!(merged.content.trim_end().ends_with("omega line 3"));
+        assert!(merged.content.find("alpha") < merged.content.find("omega"));
+
+        // An unknown source fails without creating a buffer
         let cli = make_cli(
-            db_path,
-            Commands::Chunk(ChunkCommands::Get {
-                id: 999,
-                metadata: false,
-            }),
+            db_path.clone(),
+            Commands::MergeBuffers {
+                sources: vec!["first".to_string(), "missing".to_string()],
+                name: "broken".to_string(),
+                chunker: "fixed".to_string(),
+                chunk_size: Some(40),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                no_embed: true,
+            },
+        );
+        assert!(execute(&cli).is_err());
+        assert!(
+            storage
+                .get_buffer_by_name("broken")
+                .expect("get buffer")
+                .is_none()
         );
-        let result = execute(&cli);
-        assert!(result.is_err());
     }
 
     #[test]
-    fn test_cmd_chunk_list() {
+    fn test_cmd_diff_buffers() {
         let temp_dir = TempDir::new().expect("temp dir");
         let db_path = temp_dir.path().join("test.db");
-        let file_path = temp_dir.path().join("content.txt");
-        std::fs::write(&file_path, "Chunk list content").expect("write file");
 
         let cli = make_cli(db_path.clone(), Commands::Init { force: false });
         execute(&cli).expect("init");
 
+        for (name, content) in [
+            ("v1", "alpha\nbeta\ngamma\n"),
+            ("v1copy", "alpha\nbeta\ngamma\n"),
+            ("v2", "alpha\nBETA\ngamma\ndelta\n"),
+        ] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(1000),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        // Identical buffers
         let cli = make_cli(
             db_path.clone(),
-            Commands::Load {
-                file: file_path,
-                name: Some("chunklistbuf".to_string()),
-                chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+            Commands::DiffBuffers {
+                a: "v1".to_string(),
+                b: "v1copy".to_string(),
+                context: 3,
             },
         );
-        execute(&cli).expect("load");
+        let output = execute(&cli).expect("diff");
+        assert!(output.contains("identical"));
 
-        // List chunks
+        // Unified diff
         let cli = make_cli(
             db_path.clone(),
-            Commands::Chunk(ChunkCommands::List {
-                buffer: "chunklistbuf".to_string(),
-                preview: false,
-                preview_len: 100,
-            }),
+            Commands::DiffBuffers {
+                a: "v1".to_string(),
+                b: "v2".to_string(),
+                context: 3,
+            },
         );
-        let result = execute(&cli);
-        assert!(result.is_ok());
+        let output = execute(&cli).expect("diff");
+        assert!(output.contains("-beta\n+BETA\n"));
+        assert!(output.contains("+delta\n"));
 
-        // List with preview
-        let cli = make_cli(
+        // JSON summary
+        let cli = make_cli_json(
             db_path,
-            Commands::Chunk(ChunkCommands::List {
-                buffer: "chunklistbuf".to_string(),
-                preview: true,
-                preview_len: 50,
-            }),
+            Commands::DiffBuffers {
+                a: "v1".to_string(),
+                b: "v2".to_string(),
+                context: 3,
+            },
         );
-        let result = execute(&cli);
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn test_cmd_chunk_status() {
-        let temp_dir = TempDir::new().expect("temp dir");
-        let db_path = temp_dir.path().join("test.db");
-
-        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
-        execute(&cli).expect("init");
-
-        let cli = make_cli(db_path, Commands::Chunk(ChunkCommands::Status));
-        let result = execute(&cli);
-        assert!(result.is_ok());
+        let output = execute(&cli).expect("diff");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["identical"], false);
+        assert_eq!(json["changed"], 1);
+        assert_eq!(json["added"], 1);
+        assert_eq!(json["removed"], 0);
     }
 
     #[test]
@@ -1611,8 +3698,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("jsonsearch".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1622,12 +3712,21 @@ mod cli_tests {
             Commands::Search {
                 query: "test".to_string(),
                 top_k: 5,
+                offset: 0,
                 threshold: 0.3,
                 mode: "bm25".to_string(),
                 rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
                 buffer: None,
+                tag: None,
                 preview: false,
                 preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
             },
         );
         let result = execute(&cli);
@@ -1656,8 +3755,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("semantic".to_string()),
                 chunker: "semantic".to_string(),
-                chunk_size: 1000,
-                overlap: 100,
+                chunk_size: Some(1000),
+                overlap: Some(100),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         let result = execute(&cli);
@@ -1680,8 +3782,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("parallel".to_string()),
                 chunker: "parallel".to_string(),
-                chunk_size: 100,
-                overlap: 10,
+                chunk_size: Some(100),
+                overlap: Some(10),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         let result = execute(&cli);
@@ -1726,8 +3831,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("embedbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1738,6 +3846,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "embedbuf".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         let result = execute(&cli);
@@ -1749,6 +3858,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "embedbuf".to_string(),
                 force: true,
+                discard_other_dimensions: false,
             }),
         );
         let result = execute(&cli);
@@ -1783,8 +3893,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("resolvebuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1817,8 +3930,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("filterbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1829,18 +3945,511 @@ mod cli_tests {
             Commands::Search {
                 query: "content".to_string(),
                 top_k: 5,
+                offset: 0,
                 threshold: 0.3,
                 mode: "bm25".to_string(),
                 rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
                 buffer: Some("filterbuf".to_string()),
+                tag: None,
                 preview: false,
                 preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
             },
         );
         let result = execute(&cli);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_cmd_search_with_buffer_list() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let cli = make_cli(db_path.clone(), Commands::Init { force: false });
+        execute(&cli).expect("init");
+
+        for name in ["alpha", "beta", "gamma"] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, format!("Shared keyword in {name}")).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(1000),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let search = |buffer: &str| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Search {
+                    query: "keyword".to_string(),
+                    top_k: 10,
+                    offset: 0,
+                    threshold: 0.3,
+                    mode: "bm25".to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: None,
+                    buffer: Some(buffer.to_string()),
+                    tag: None,
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich: false,
+                    fail_on_empty: false,
+                },
+            )
+        };
+
+        let output = execute(&search("alpha, gamma")).expect("search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["count"], 2);
+
+        // Unknown buffer in the list is an error
+        assert!(execute(&search("alpha,missing")).is_err());
+    }
+
+    #[test]
+    fn test_cmd_buffer_tags_scope_search() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        for name in ["alpha", "beta", "gamma"] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            std::fs::write(&file_path, format!("Shared keyword in {name}")).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(1000),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let tag = |command| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Buffer(BufferCommands::Tag(command)),
+            )
+        };
+        for name in ["alpha", "gamma"] {
+            let output = execute(&tag(TagCommands::Add {
+                buffer: name.to_string(),
+                tags: vec!["project:alpha".to_string(), "logs".to_string()],
+            }))
+            .expect("tag add");
+            let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+            assert_eq!(json["added"], 2);
+        }
+
+        let output = execute(&tag(TagCommands::Remove {
+            buffer: "gamma".to_string(),
+            tags: vec!["logs".to_string(), "unused".to_string()],
+        }))
+        .expect("tag remove");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["removed"], 1);
+        assert_eq!(json["tags"], serde_json::json!(["project:alpha"]));
+
+        let output = execute(&tag(TagCommands::List { buffer: None })).expect("tag list");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"tag": "logs", "buffer_count": 1},
+                {"tag": "project:alpha", "buffer_count": 2}
+            ])
+        );
+
+        let search = |tag: &str| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Search {
+                    query: "keyword".to_string(),
+                    top_k: 10,
+                    offset: 0,
+                    threshold: 0.3,
+                    mode: "bm25".to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: None,
+                    buffer: None,
+                    tag: Some(tag.to_string()),
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich: false,
+                    fail_on_empty: false,
+                },
+            )
+        };
+
+        let output = execute(&search("project:alpha")).expect("search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["count"], 2);
+
+        let output = execute(&search("logs")).expect("search");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["count"], 1);
+
+        // A tag no buffer carries is an error, not an unscoped search
+        assert!(execute(&search("missing")).is_err());
+    }
+
+    #[test]
+    fn test_cmd_delete_trash_and_restore() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        for name in ["keep", "trashme"] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            let content: String = (0..3)
+                .map(|i| format!("{:<39}\n", format!("Trash keyword {name} line {i}")))
+                .collect();
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(40),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Buffer(BufferCommands::Tag(TagCommands::Add {
+                    buffer: name.to_string(),
+                    tags: vec!["shared".to_string()],
+                })),
+            );
+            execute(&cli).expect("tag");
+        }
+
+        let (buffer_id, chunks_before, embeddings_before) = {
+            let storage = SqliteStorage::open(&db_path).expect("open");
+            let buffer = storage
+                .get_buffer_by_name("trashme")
+                .expect("query")
+                .expect("buffer");
+            let id = buffer.id.expect("id");
+            (
+                id,
+                storage.chunk_count(id).expect("chunks"),
+                storage.embedding_count().expect("count"),
+            )
+        };
+        assert!(chunks_before > 0);
+
+        let search = |mode: &str, tag: Option<&str>| {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Search {
+                    query: "keyword".to_string(),
+                    top_k: 20,
+                    offset: 0,
+                    threshold: -1.0,
+                    mode: mode.to_string(),
+                    rrf_k: 60,
+                    fusion: "rrf".to_string(),
+                    alpha: 0.5,
+                    metric: "cosine".to_string(),
+                    mmr_lambda: None,
+                    buffer: None,
+                    tag: tag.map(String::from),
+                    preview: false,
+                    preview_len: 150,
+                    context: 0,
+                    enrich: false,
+                    fail_on_empty: false,
+                },
+            )
+        };
+        let tagged_count = |mode: &str, tag: Option<&str>| {
+            let output = execute(&search(mode, tag)).expect("search");
+            let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+            json["count"].as_u64().expect("count")
+        };
+        let result_count = |mode: &str| tagged_count(mode, None);
+        let bm25_before = result_count("bm25");
+        let semantic_before = result_count("semantic");
+        let tagged_before = tagged_count("hybrid", Some("shared"));
+
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::DeleteBuffer {
+                buffer: "trashme".to_string(),
+                yes: false,
+                trash: true,
+            },
+        );
+        execute(&cli).expect("trash without --yes");
+
+        // Trashed buffers are hidden from lookups, listings and search
+        let show = || {
+            make_cli(
+                db_path.clone(),
+                Commands::ShowBuffer {
+                    buffer: "trashme".to_string(),
+                    chunks: false,
+                },
+            )
+        };
+        assert!(execute(&show()).is_err());
+        let output = execute(&make_cli_json(db_path.clone(), Commands::ListBuffers)).expect("list");
+        assert!(!output.contains("trashme"));
+        assert_eq!(result_count("bm25"), bm25_before - 3);
+        assert_eq!(result_count("semantic"), semantic_before - 3);
+
+        // ...including tag filters and tag listings
+        assert_eq!(tagged_count("bm25", Some("shared")), bm25_before - 3);
+        assert_eq!(
+            tagged_count("semantic", Some("shared")),
+            semantic_before - 3
+        );
+        assert!(tagged_count("hybrid", Some("shared")) < tagged_before);
+        {
+            let storage = SqliteStorage::open(&db_path).expect("open");
+            assert_eq!(
+                storage.list_tags().expect("tags"),
+                vec![("shared".to_string(), 1)]
+            );
+            assert!(
+                !storage
+                    .buffer_ids_with_tag("shared")
+                    .expect("tagged")
+                    .contains(&buffer_id)
+            );
+            assert!(
+                storage
+                    .search_fts_match("keyword", 20, &[buffer_id])
+                    .expect("fts")
+                    .is_empty()
+            );
+            assert!(storage.get_chunk_ids(&[buffer_id]).expect("ids").is_empty());
+        }
+
+        let output = execute(&make_cli_json(
+            db_path.clone(),
+            Commands::Buffer(BufferCommands::Trash),
+        ))
+        .expect("trash list");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json[0]["id"], buffer_id);
+
+        let restore = || {
+            make_cli(
+                db_path.clone(),
+                Commands::Buffer(BufferCommands::Restore { id: buffer_id }),
+            )
+        };
+        execute(&restore()).expect("restore");
+        assert!(execute(&restore()).is_err());
+        execute(&show()).expect("show restored");
+        assert_eq!(result_count("bm25"), bm25_before);
+        {
+            let storage = SqliteStorage::open(&db_path).expect("open");
+            assert_eq!(
+                storage.chunk_count(buffer_id).expect("chunks"),
+                chunks_before
+            );
+            assert_eq!(storage.embedding_count().expect("count"), embeddings_before);
+        }
+
+        // Emptying the trash deletes for good
+        let cli = make_cli(
+            db_path.clone(),
+            Commands::DeleteBuffer {
+                buffer: "trashme".to_string(),
+                yes: false,
+                trash: true,
+            },
+        );
+        execute(&cli).expect("trash again");
+        let output = execute(&make_cli_json(
+            db_path.clone(),
+            Commands::Buffer(BufferCommands::EmptyTrash { yes: true }),
+        ))
+        .expect("empty trash");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["deleted"], 1);
+        assert!(execute(&restore()).is_err());
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        assert_eq!(storage.chunk_count(buffer_id).expect("chunks"), 0);
+    }
+
+    #[test]
+    fn test_cmd_doctor_reports_database_problems() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let doctor = || {
+            make_cli_json(
+                db_path.clone(),
+                Commands::Doctor {
+                    check_embedder: false,
+                },
+            )
+        };
+        let status_of = |json: &serde_json::Value, name: &str| {
+            json["checks"]
+                .as_array()
+                .expect("checks")
+                .iter()
+                .find(|c| c["name"] == name)
+                .map(|c| c["status"].as_str().expect("status").to_string())
+        };
+
+        // Missing database
+        let output = execute(&doctor()).expect("doctor");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["ok"], false);
+        assert_eq!(status_of(&json, "database").as_deref(), Some("fail"));
+        assert_eq!(status_of(&json, "embedder").as_deref(), Some("pass"));
+
+        // Database file without the RLM schema
+        std::fs::write(&db_path, b"").expect("write");
+        let output = execute(&doctor()).expect("doctor");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(status_of(&json, "initialized").as_deref(), Some("fail"));
+
+        std::fs::remove_file(&db_path).expect("remove");
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let output = execute(&doctor()).expect("doctor");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["ok"], true, "{output}");
+        assert_eq!(status_of(&json, "schema").as_deref(), Some("pass"));
+
+        let output = execute(&make_cli(
+            db_path,
+            Commands::Doctor {
+                check_embedder: false,
+            },
+        ))
+        .expect("doctor");
+        assert!(output.contains("[pass] initialized"));
+    }
+
+    #[test]
+    fn test_ndjson_output_one_object_per_line() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        let make_cli_ndjson = |command| Cli {
+            db_path: Some(db_path.clone()),
+            verbose: false,
+            format: "ndjson".to_string(),
+            color: "auto".to_string(),
+            command,
+        };
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        for name in ["alpha", "beta"] {
+            let file_path = temp_dir.path().join(format!("{name}.txt"));
+            let content: String = (0..3)
+                .map(|i| format!("{:<39}\n", format!("Streaming keyword {name} line {i}")))
+                .collect();
+            std::fs::write(&file_path, content).expect("write file");
+            let cli = make_cli(
+                db_path.clone(),
+                Commands::Load {
+                    file: file_path,
+                    name: Some(name.to_string()),
+                    chunker: "fixed".to_string(),
+                    chunk_size: Some(40),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
+                },
+            );
+            execute(&cli).expect("load");
+        }
+
+        let parse_lines = |output: &str| -> Vec<serde_json::Value> {
+            output
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("each line is valid JSON"))
+                .collect()
+        };
+
+        let output = execute(&make_cli_ndjson(Commands::Search {
+            query: "keyword".to_string(),
+            top_k: 10,
+            offset: 0,
+            threshold: 0.3,
+            mode: "bm25".to_string(),
+            rrf_k: 60,
+            fusion: "rrf".to_string(),
+            alpha: 0.5,
+            metric: "cosine".to_string(),
+            mmr_lambda: None,
+            buffer: None,
+            tag: None,
+            preview: true,
+            preview_len: 20,
+            context: 0,
+            enrich: false,
+            fail_on_empty: false,
+        }))
+        .expect("search");
+        let lines = parse_lines(&output);
+        assert_eq!(lines.len(), 7);
+        assert!(lines[..6].iter().all(|l| l["chunk_id"].is_i64()));
+        assert_eq!(lines[6]["type"], "summary");
+        assert_eq!(lines[6]["count"], 6);
+
+        let output = execute(&make_cli_ndjson(Commands::Chunk(ChunkCommands::List {
+            buffer: "alpha".to_string(),
+            preview: false,
+            preview_len: 100,
+        })))
+        .expect("chunk list");
+        let lines = parse_lines(&output);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["index"], 0);
+        assert_eq!(lines[3]["chunk_count"], 3);
+
+        let output = execute(&make_cli_ndjson(Commands::ListBuffers)).expect("list");
+        let lines = parse_lines(&output);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2]["count"], 2);
+    }
+
     #[test]
     fn test_cmd_search_semantic_mode() {
         let temp_dir = TempDir::new().expect("temp dir");
@@ -1857,8 +4466,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("semanticbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1869,6 +4481,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "semanticbuf".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         execute(&cli).expect("embed");
@@ -1879,12 +4492,21 @@ mod cli_tests {
             Commands::Search {
                 query: "semantic".to_string(),
                 top_k: 5,
+                offset: 0,
                 threshold: 0.1, // Low threshold for test
                 mode: "semantic".to_string(),
                 rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
                 buffer: None,
+                tag: None,
                 preview: false,
                 preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
             },
         );
         let result = execute(&cli);
@@ -1896,12 +4518,21 @@ mod cli_tests {
             Commands::Search {
                 query: "content".to_string(),
                 top_k: 5,
+                offset: 0,
                 threshold: 0.1,
                 mode: "hybrid".to_string(),
                 rrf_k: 60,
+                fusion: "rrf".to_string(),
+                alpha: 0.5,
+                metric: "cosine".to_string(),
+                mmr_lambda: None,
                 buffer: None,
+                tag: None,
                 preview: false,
                 preview_len: 150,
+                context: 0,
+                enrich: false,
+                fail_on_empty: false,
             },
         );
         let result = execute(&cli);
@@ -1924,8 +4555,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("grepjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1933,11 +4567,12 @@ mod cli_tests {
         let cli = make_cli_json(
             db_path,
             Commands::Grep {
-                buffer: "grepjson".to_string(),
+                buffer: Some("grepjson".to_string()),
                 pattern: "pattern".to_string(),
                 max_matches: 10,
                 window: 50,
                 ignore_case: false,
+                all: false,
             },
         );
         let result = execute(&cli);
@@ -1962,8 +4597,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("peekjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -1996,8 +4634,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("chunklistjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2030,8 +4671,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("chunkgetjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2039,7 +4683,7 @@ mod cli_tests {
         let cli = make_cli_json(
             db_path,
             Commands::Chunk(ChunkCommands::Get {
-                id: 1,
+                ids: vec!["1".to_string()],
                 metadata: true,
             }),
         );
@@ -2065,8 +4709,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("indicesjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2075,8 +4722,8 @@ mod cli_tests {
             db_path,
             Commands::ChunkIndices {
                 buffer: "indicesjson".to_string(),
-                chunk_size: 50,
-                overlap: 10,
+                chunk_size: Some(50),
+                overlap: Some(10),
             },
         );
         let result = execute(&cli);
@@ -2100,8 +4747,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("writejson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2111,8 +4761,9 @@ mod cli_tests {
             Commands::WriteChunks {
                 buffer: "writejson".to_string(),
                 out_dir,
-                chunk_size: 50,
-                overlap: 10,
+                chunk_size: Some(50),
+                overlap: Some(10),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
                 prefix: "test".to_string(),
             },
         );
@@ -2136,8 +4787,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("deletejson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2147,6 +4801,7 @@ mod cli_tests {
             Commands::DeleteBuffer {
                 buffer: "deletejson".to_string(),
                 yes: true,
+                trash: false,
             },
         );
         let result = execute(&cli);
@@ -2169,8 +4824,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("statusbuf".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2188,6 +4846,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "statusbuf".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         execute(&cli).expect("embed");
@@ -2218,8 +4877,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("statusjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2232,6 +4894,197 @@ mod cli_tests {
         assert!(output.contains("total_chunks") || output.contains('{'));
     }
 
+    #[test]
+    fn test_cmd_update_incremental_reembeds_changed_chunks_only() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let section = |n: usize, body: &str| {
+            format!(
+                "# Section {n}\n\n{body} This paragraph is padded to a steady length of text.\n\n"
+            )
+        };
+        let document = |edited: &str| {
+            (1..=4)
+                .map(|n| section(n, if n == 3 { edited } else { "Original text." }))
+                .collect::<String>()
+        };
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("doc.md");
+        std::fs::write(&file_path, document("Original text.")).expect("write doc");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let load = Commands::Load {
+            file: file_path,
+            name: Some("doc".to_string()),
+            chunker: "markdown".to_string(),
+            chunk_size: Some(150),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer_id = storage
+            .get_buffer_by_name("doc")
+            .expect("lookup")
+            .expect("buffer")
+            .id
+            .expect("id");
+        let before = storage.get_chunks(buffer_id).expect("chunks");
+        assert_eq!(before.len(), 4);
+        drop(storage);
+
+        let update = Commands::UpdateBuffer {
+            buffer: "doc".to_string(),
+            content: Some(document("Edited text!!!")),
+            embed: true,
+            incremental: true,
+            strategy: "markdown".to_string(),
+            chunk_size: Some(150),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+        };
+        let output = execute(&make_cli_json(db_path.clone(), update)).expect("update");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["reused_chunks"], 3);
+        assert_eq!(json["added_chunks"], 1);
+        assert_eq!(json["removed_chunks"], 1);
+        assert_eq!(json["embedded"]["count"], 1);
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let after = storage.get_chunks(buffer_id).expect("chunks");
+        assert_eq!(after.len(), 4);
+        for (old, new) in before.iter().zip(&after) {
+            if new.content.contains("Edited") {
+                assert_ne!(old.id, new.id);
+            } else {
+                assert_eq!(old.id, new.id);
+                assert_eq!(old.byte_range, new.byte_range);
+            }
+            assert!(storage.has_embedding(new.id.expect("id")).expect("has"));
+        }
+    }
+
+    #[test]
+    fn test_cmd_chunk_status_reports_mixed_models() {
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        std::fs::write(&file_path, "x".repeat(400)).expect("write file");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let load = Commands::Load {
+            file: file_path,
+            name: Some("mixed".to_string()),
+            chunker: "fixed".to_string(),
+            chunk_size: Some(200),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+
+        // Re-embed the two chunks under different model names
+        let mut storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("mixed")
+            .expect("lookup")
+            .expect("buffer");
+        let chunks = storage.get_chunks(buffer.id.expect("id")).expect("chunks");
+        assert_eq!(chunks.len(), 2);
+        for (chunk, model) in chunks.iter().zip(["model-b", "model-a"]) {
+            let chunk_id = chunk.id.expect("chunk id");
+            let embedding = storage
+                .get_embedding(chunk_id)
+                .expect("get embedding")
+                .expect("embedding");
+            storage
+                .store_embedding(chunk_id, &embedding, Some(model))
+                .expect("store embedding");
+        }
+
+        let output = execute(&make_cli_json(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Status),
+        ))
+        .expect("json status");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let entry = &json["buffers"][0];
+        assert_eq!(entry["models"], serde_json::json!(["model-a", "model-b"]));
+        assert_eq!(entry["mixed_models"], true);
+
+        let text = execute(&make_cli(db_path, Commands::Chunk(ChunkCommands::Status)))
+            .expect("text status");
+        assert!(text.contains("model-a, model-b (mixed)"));
+    }
+
+    #[test]
+    fn test_cmd_chunk_embedding_returns_vector() {
+        use rlm_rs::embedding::{Embedder, create_embedder};
+        use rlm_rs::storage::{SqliteStorage, Storage};
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("content.txt");
+        std::fs::write(&file_path, "Embedding inspection content").expect("write file");
+
+        execute(&make_cli(db_path.clone(), Commands::Init { force: false })).expect("init");
+        let load = Commands::Load {
+            file: file_path,
+            name: Some("vec".to_string()),
+            chunker: "fixed".to_string(),
+            chunk_size: Some(1000),
+            overlap: Some(0),
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            dedup: false,
+            update_if_exists: false,
+        };
+        execute(&make_cli(db_path.clone(), load)).expect("load");
+
+        let storage = SqliteStorage::open(&db_path).expect("open");
+        let buffer = storage
+            .get_buffer_by_name("vec")
+            .expect("lookup")
+            .expect("buffer");
+        let chunk_id = storage.get_chunks(buffer.id.expect("id")).expect("chunks")[0]
+            .id
+            .expect("chunk id");
+        let dimensions = create_embedder().expect("embedder").dimensions();
+
+        let output = execute(&make_cli_json(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Embedding { id: chunk_id }),
+        ))
+        .expect("json embedding");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(json["chunk_id"], chunk_id);
+        assert_eq!(json["dimensions"], dimensions);
+        assert_eq!(json["values"].as_array().expect("values").len(), dimensions);
+        assert_eq!(json["nan_count"], 0);
+        assert!(json["model"].is_string());
+
+        let text = execute(&make_cli(
+            db_path.clone(),
+            Commands::Chunk(ChunkCommands::Embedding { id: chunk_id }),
+        ))
+        .expect("text embedding");
+        assert!(text.contains(&format!("{dimensions} dimensions")));
+
+        let err = execute(&make_cli(
+            db_path,
+            Commands::Chunk(ChunkCommands::Embedding { id: chunk_id + 100 }),
+        ))
+        .expect_err("missing chunk");
+        assert!(err.to_string().contains("not found"), "{err}");
+    }
+
     #[test]
     fn test_cmd_buffer_with_long_name() {
         // This test covers truncate_str function (lines 1047-1053)
@@ -2251,8 +5104,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some(long_name),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2309,8 +5165,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("alreadyembedded".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2321,6 +5180,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "alreadyembedded".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         execute(&cli).expect("first embed");
@@ -2331,6 +5191,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "alreadyembedded".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         let result = execute(&cli);
@@ -2355,8 +5216,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("embedjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2367,6 +5231,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "embedjson".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         let result = execute(&cli);
@@ -2464,8 +5329,11 @@ mod cli_tests {
                     file: file.clone(),
                     name: Some(name.to_string()),
                     chunker: "fixed".to_string(),
-                    chunk_size: 1000,
-                    overlap: 0,
+                    chunk_size: Some(1000),
+                    overlap: Some(0),
+                    min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                    dedup: false,
+                    update_if_exists: false,
                 },
             );
             execute(&cli).expect("load");
@@ -2477,6 +5345,7 @@ mod cli_tests {
             Commands::Chunk(ChunkCommands::Embed {
                 buffer: "buf1".to_string(),
                 force: false,
+                discard_other_dimensions: false,
             }),
         );
         execute(&cli).expect("embed buf1");
@@ -2511,8 +5380,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("showjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2547,8 +5419,11 @@ mod cli_tests {
                 file: file_path,
                 name: Some("listjson".to_string()),
                 chunker: "fixed".to_string(),
-                chunk_size: 1000,
-                overlap: 0,
+                chunk_size: Some(1000),
+                overlap: Some(0),
+                min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+                dedup: false,
+                update_if_exists: false,
             },
         );
         execute(&cli).expect("load");
@@ -2560,6 +5435,103 @@ mod cli_tests {
         let output = result.expect("json output");
         assert!(output.contains('{') || output.contains('['));
     }
+
+    #[test]
+    fn test_config_file_layered_under_env_and_flags() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        std::fs::create_dir_all(temp_dir.path().join(".rlm")).expect("config dir");
+        std::fs::write(
+            temp_dir.path().join(".rlm/config.toml"),
+            "format = \"json\"\nchunker = \"fixed\"\nchunk_size = 200\noverlap = 0\n",
+        )
+        .expect("write config");
+        std::fs::write(temp_dir.path().join("doc.txt"), "x".repeat(2000)).expect("write doc");
+
+        let run = |args: &[&str], env: &[(&str, &str)]| {
+            let mut cmd = assert_cmd::Command::cargo_bin("rlm-cli").expect("binary");
+            cmd.current_dir(temp_dir.path())
+                .env("HOME", temp_dir.path())
+                .env_remove("XDG_CONFIG_HOME");
+            for var in [
+                "RLM_DB_PATH",
+                "RLM_FORMAT",
+                "RLM_COLOR",
+                "RLM_CHUNKER",
+                "RLM_CHUNK_SIZE",
+                "RLM_OVERLAP",
+            ] {
+                cmd.env_remove(var);
+            }
+            cmd.envs(env.iter().copied());
+            let output = cmd.args(args).output().expect("run");
+            assert!(output.status.success(), "{args:?} failed");
+            String::from_utf8(output.stdout).expect("utf-8")
+        };
+        let chunk_count = |output: &str| {
+            let json: serde_json::Value = serde_json::from_str(output).expect("json output");
+            json["chunk_count"].as_u64().expect("chunk_count")
+        };
+
+        run(&["init"], &[]);
+
+        // Config file sets the format and chunking options
+        let from_config = chunk_count(&run(&["load", "doc.txt", "-n", "a"], &[]));
+        assert_eq!(from_config, 10);
+
+        // Environment overrides the config file
+        let from_env = chunk_count(&run(
+            &["load", "doc.txt", "-n", "b"],
+            &[("RLM_CHUNK_SIZE", "1000")],
+        ));
+        assert_eq!(from_env, 2);
+
+        // Flags override both
+        let from_flag = chunk_count(&run(
+            &["load", "doc.txt", "-n", "c", "--chunk-size", "500"],
+            &[("RLM_CHUNK_SIZE", "1000")],
+        ));
+        assert_eq!(from_flag, 4);
+
+        let text = run(&["--format", "text", "list"], &[]);
+        assert!(!text.trim_start().starts_with(['{', '[']));
+    }
+
+    #[test]
+    fn test_destructive_commands_refuse_without_tty() {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let db_path = temp_dir.path().join("test.db");
+        let file_path = temp_dir.path().join("notes.txt");
+        std::fs::write(&file_path, "some notes").expect("write");
+
+        let rlm = |args: &[&str]| {
+            let mut cmd = assert_cmd::Command::cargo_bin("rlm-cli").expect("binary");
+            cmd.arg("--db-path").arg(&db_path).args(args);
+            cmd
+        };
+        rlm(&["init"]).assert().success();
+        rlm(&["load", file_path.to_str().expect("path"), "-n", "notes"])
+            .assert()
+            .success();
+
+        // Piped stdin is not a terminal, so even the right answer is refused
+        rlm(&["delete", "notes"])
+            .write_stdin("notes\n")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("--yes"));
+        rlm(&["reset"])
+            .write_stdin("reset\n")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("--yes"));
+        rlm(&["list"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("notes"));
+
+        rlm(&["delete", "notes", "--yes"]).assert().success();
+        rlm(&["reset", "--yes"]).assert().success();
+    }
 }
 
 // =============================================================================